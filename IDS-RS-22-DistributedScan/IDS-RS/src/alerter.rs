@@ -5,6 +5,7 @@
 // Responsabilitati:
 //   1. Trimite alerte catre SIEM (ArcSight) prin UDP syslog
 //   2. Trimite notificari email catre echipa IT/Security
+//   3. Salveaza alerta, cu contextul ei, in istoricul local (history.rs)
 // CONCEPTE RUST EXPLICATE:
 //
 // 1. ASYNC/AWAIT (Asincronicitate)
@@ -34,8 +35,12 @@
 //
 // =============================================================================
 
-use crate::config::{AlertingConfig, DetectionConfig, EmailConfig, SubnetEntry};
+use crate::assets::AssetInventory;
+use crate::config::{AlertingConfig, DetectionConfig, EmailConfig, HistoryConfig, SubnetEntry};
 use crate::detector::{Alert, ScanType};
+use crate::history::{AlertHistory, AlertRecord};
+use crate::sessions::{SessionKind, SessionSummary};
+use crate::severity::Severity;
 use crate::display;
 use anyhow::{Context, Result};
 use arc_swap::ArcSwap;
@@ -96,14 +101,334 @@ fn sanitize_cef(input: &str) -> String {
         .replace('\r', "\\r")
 }
 
+/// Adauga rezumatul sesiunii ca o linie JSON la sfarsitul fisierului
+/// (creat la prima scriere, impreuna cu directorul).
+///
+/// NOTA RUST: `tokio::fs` ruleaza operatiile de fisier pe thread-pool-ul de
+/// blocking — main loop-ul nu asteapta dupa disc. `write_all` cu linia
+/// completa (inclusiv '\n') pastreaza liniile intregi la scrieri succesive.
+async fn append_session_json(path: &str, session: &SessionSummary) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    if let Some(dir) = std::path::Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Nu pot crea directorul {}", dir.display()))?;
+    }
+    let mut line = session.to_json().to_string();
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Nu pot deschide {}", path))?;
+    file.write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("Nu pot scrie in {}", path))?;
+    Ok(())
+}
+
+/// Hostname-ul unui IP: `[network.hostnames]` are prioritate, apoi inventarul.
+fn hostname<'a>(
+    hostnames: &'a HashMap<IpAddr, String>,
+    assets: &'a AssetInventory,
+    ip: &IpAddr,
+) -> Option<&'a str> {
+    hostnames
+        .get(ip)
+        .or_else(|| assets.lookup(ip).and_then(|a| a.hostname.as_ref()))
+        .map(|s| s.as_str())
+}
+
+/// Numarul maxim de elemente (porturi, IP-uri) listate in email.
+const MAX_EMAIL_LIST: usize = 30;
+
+/// Lista din email: primele `MAX_EMAIL_LIST` elemente, restul numarate
+/// (`22, 80, ... + 12 more`).
+fn email_list<T: ToString>(items: &[T]) -> String {
+    let list = items
+        .iter()
+        .take(MAX_EMAIL_LIST)
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > MAX_EMAIL_LIST {
+        format!("{} + {} more", list, items.len() - MAX_EMAIL_LIST)
+    } else {
+        list
+    }
+}
+
+/// Signature ID-ul SIEM, numele evenimentului CEF si descrierea alertei —
+/// descrierea deschide campul `msg` din CEF si este rezumatul din istoric.
+/// Update-urile si rezumatele "ongoing" pastreaza SigID-ul tipului, dar
+/// descrierea lor are totalurile cumulate, nu fereastra detectorului:
+/// `[ONGOING #2] Fast Scan in desfasurare de 12m 05s (din 08:12:40): 140 porturi`.
+pub fn describe(alert: &Alert, det: &DetectionConfig) -> (&'static str, &'static str, String) {
+    let (sig_id, name, text) = describe_detection(alert, det);
+    match alert.ongoing {
+        Some(ref o) => (sig_id, name, format!("[{}] {} {}", o.label(), alert.scan_type, o.summary())),
+        None => (sig_id, name, text),
+    }
+}
+
+fn describe_detection(alert: &Alert, det: &DetectionConfig) -> (&'static str, &'static str, String) {
+    match alert.scan_type {
+        ScanType::Fast => (
+            "1001",
+            "Fast Port Scan Detected",
+            format!(
+                "Fast Scan detectat: {} porturi unice in {} secunde",
+                alert.unique_ports.len(),
+                det.fast_scan.time_window_secs,
+            ),
+        ),
+        ScanType::Slow => (
+            "1002",
+            "Slow Port Scan Detected",
+            format!(
+                "Slow Scan detectat: {} porturi unice in {} minute",
+                alert.unique_ports.len(),
+                det.slow_scan.time_window_mins,
+            ),
+        ),
+        ScanType::AcceptScan => (
+            "1003",
+            "Accept Port Scan Detected",
+            format!(
+                "Accept Scan detectat: {} porturi deschise accesate in {} secunde",
+                alert.unique_ports.len(),
+                det.accept_scan.time_window_secs,
+            ),
+        ),
+        ScanType::LateralMovement => (
+            "1004",
+            "Lateral Movement Detected",
+            format!(
+                "Lateral Movement detectat: {} destinatii unice in {} secunde",
+                alert.unique_dests.len(),
+                det.lateral_movement.time_window_secs,
+            ),
+        ),
+        ScanType::DistributedScan => (
+            "1005",
+            "Distributed Port Scan Detected",
+            format!(
+                "Distributed Scan detectat: {} surse unice → tinta {} in {} secunde",
+                alert.unique_sources.len(),
+                alert.dest_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "N/A".to_string()),
+                det.distributed_scan.time_window_secs,
+            ),
+        ),
+        ScanType::KnownBadSource => (
+            "1006",
+            "Known Bad Source Detected",
+            format!(
+                "Sursa prezenta in feed-ul threat intel {}",
+                alert.threat_intel.as_ref().map(|m| m.feed.as_str()).unwrap_or("N/A"),
+            ),
+        ),
+        ScanType::KnownBadDestination => (
+            "1007",
+            "Known Bad Destination Contacted",
+            format!(
+                "Destinatie prezenta in feed-ul threat intel {}",
+                alert.threat_intel.as_ref().map(|m| m.feed.as_str()).unwrap_or("N/A"),
+            ),
+        ),
+        ScanType::Anomaly => (
+            "1008",
+            "Behavioral Anomaly Detected",
+            match alert.anomaly {
+                Some(ref a) => format!(
+                    "Anomalie fata de baseline {}: {} = {:.2} (medie {:.2}, stddev {:.2}, {:.1} sigma)",
+                    a.baseline_key, a.metric, a.observed, a.mean, a.stddev, a.sigmas,
+                ),
+                None => "Anomalie fata de baseline".to_string(),
+            },
+        ),
+        ScanType::Escalation => (
+            "1009",
+            "Risk Escalation",
+            match alert.risk {
+                Some(ref r) => format!(
+                    "Escaladare risc: scor {:.1} a depasit nivelul {} (prag {}) dupa {} alerte",
+                    r.score, r.tier, r.threshold, r.chain.len(),
+                ),
+                None => "Escaladare risc".to_string(),
+            },
+        ),
+        ScanType::AttackChain => (
+            "1010",
+            "Attack Chain Detected",
+            match alert.chain {
+                Some(ref c) => format!(
+                    "Lant de atac {} complet ({} etape), subiect {}",
+                    c.rule, c.steps.len(), c.subject,
+                ),
+                None => "Lant de atac complet".to_string(),
+            },
+        ),
+        ScanType::Beaconing => (
+            "1011",
+            "Beaconing Detected",
+            match alert.beacon {
+                Some(ref b) => format!(
+                    "Beaconing detectat: {} conexiuni in {} secunde, interval mediu {:.1}s, jitter {:.1}%",
+                    b.connections, b.duration_secs, b.mean_interval_secs, b.jitter * 100.0,
+                ),
+                None => "Beaconing detectat".to_string(),
+            },
+        ),
+        ScanType::BruteForce => (
+            "1012",
+            "Brute Force Detected",
+            match alert.flood {
+                Some(ref f) => format!(
+                    "Brute Force detectat: {} conexiuni {} pe portul {} in {} secunde",
+                    f.count,
+                    if f.accepted { "acceptate" } else { "blocate" },
+                    f.port,
+                    f.window_secs,
+                ),
+                None => "Brute Force detectat".to_string(),
+            },
+        ),
+        ScanType::PingSweep => (
+            "1013",
+            "Ping Sweep Detected",
+            format!(
+                "Ping Sweep detectat: {} destinatii unice sondate ICMP in {} secunde",
+                alert.unique_dests.len(),
+                det.ping_sweep.time_window_secs,
+            ),
+        ),
+        ScanType::PersistentScanner => (
+            "1014",
+            "Persistent Scanner Detected",
+            match alert.offence {
+                Some(ref o) => format!(
+                    "Scanner persistent: {} alerte in {}h, {} in {}h, prima {} ({})",
+                    o.nth,
+                    o.window_hours,
+                    o.total,
+                    o.horizon_hours,
+                    o.first_seen.format("%Y-%m-%d %H:%M"),
+                    o.scan_types.join(", "),
+                ),
+                None => "Scanner persistent".to_string(),
+            },
+        ),
+        ScanType::TestAlert => (
+            "1016",
+            "IDS-RS Test Alert",
+            "Alerta de test (ids-rs ctl test-alert) — nu este o detectie reala".to_string(),
+        ),
+    }
+}
+
+/// Randurile de context ale alertei (eticheta, valoare) — tabelul de detalii
+/// din email si detaliile salvate in istoric.
+fn detail_rows(alert: &Alert, assets: &AssetInventory) -> Vec<(&'static str, String)> {
+    let mut extra_rows: Vec<(&'static str, String)> = Vec::new();
+    if let Some(ref name) = alert.profile {
+        extra_rows.push(("Profil detectie", name.clone()));
+    }
+    if let Some(ref c) = alert.chain {
+        extra_rows.push(("Regula lant", c.rule.clone()));
+        extra_rows.push(("Etape", c.steps.len().to_string()));
+    }
+    if let Some(ref f) = alert.flood {
+        let action = if f.accepted { "acceptate" } else { "blocate" };
+        extra_rows.push(("Rata", format!("{} conexiuni {} in {} s", f.count, action, f.window_secs)));
+    }
+    if let Some(ref b) = alert.beacon {
+        extra_rows.push(("Interval mediu", format!("{:.1} s", b.mean_interval_secs)));
+        extra_rows.push(("Jitter", format!("{:.1}%", b.jitter * 100.0)));
+        extra_rows.push(("Conexiuni", format!("{} in {} s", b.connections, b.duration_secs)));
+    }
+    if let Some(ref r) = alert.risk {
+        extra_rows.push(("Scor risc", format!("{:.1}", r.score)));
+        extra_rows.push(("Nivel risc", format!("{} (prag {})", r.tier, r.threshold)));
+    }
+    if !alert.protocols.is_empty() {
+        extra_rows.push(("Protocoale", alert.protocol_summary(", ")));
+    }
+    if let Some(prefix) = alert.source_prefix {
+        extra_rows.push(("Sursa agregata", format!("IPv6 /{} — adrese rotite in prefix", prefix)));
+    }
+    if let Some(ref nat) = alert.nat {
+        extra_rows.push(("NAT", nat.summary()));
+    }
+    if let Some(asset) = assets.lookup(&alert.source_ip) {
+        extra_rows.push(("Activ sursa", asset.summary()));
+    }
+    if let Some(asset) = assets.most_critical(alert.targets()) {
+        extra_rows.push(("Activ tinta", asset.summary()));
+    }
+    if let Some(ref o) = alert.ongoing {
+        extra_rows.push(("Desfasurare", format!("{} — {}", o.label(), o.summary())));
+    }
+    if let Some(ref o) = alert.offence {
+        if o.is_repeat() {
+            extra_rows.push(("Recidiva", o.summary()));
+        }
+    }
+    if !alert.severity.factors.is_empty() {
+        extra_rows.push((
+            "Factori severitate",
+            format!("{} → {}", alert.severity.factors.join(", "), alert.severity.level),
+        ));
+    }
+    if !alert.rules.is_empty() {
+        extra_rows.push(("Reguli firewall", alert.rules.join(", ")));
+    }
+    if let Some(ref fp) = alert.fingerprint {
+        extra_rows.push(("Unealta probabila", format!("{} ({})", fp.tool, fp.evidence)));
+    }
+    if let Some(score) = alert.score {
+        extra_rows.push(("Scor ponderat", format!("{:.2}", score)));
+    }
+    if !alert.sensitive_ports.is_empty() {
+        let sensitive = alert
+            .sensitive_ports
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        extra_rows.push(("Porturi sensibile", sensitive));
+    }
+    if let Some(ref m) = alert.threat_intel {
+        extra_rows.push(("Feed threat intel", m.feed.clone()));
+        extra_rows.push(("Incredere", format!("{}%", m.confidence)));
+        extra_rows.push(("Indicator", m.indicator.clone()));
+    }
+    if let Some(ref a) = alert.anomaly {
+        extra_rows.push(("Baseline", a.baseline_key.clone()));
+        extra_rows.push(("Metrica", format!("{} = {:.2}", a.metric, a.observed)));
+        extra_rows.push((
+            "Normal",
+            format!("{:.2} ± {:.2} ({} intervale invatate)", a.mean, a.stddev, a.samples),
+        ));
+        extra_rows.push(("Abatere", format!("{:.1} sigma", a.sigmas)));
+    }
+    extra_rows
+}
+
 /// Construieste body-ul HTML al email-ului de alerta.
 ///
 /// Folosim template cu placeholder-e `__VAR__` in loc de `format!` pentru a evita
 /// escaping-ul acoladelor CSS (`{` → `{{`). Textul din `email_footer` este HTML-escapeat
 /// pentru a preveni injectia de tag-uri din valori controlate extern.
+///
+/// `extra_rows` = randuri suplimentare (eticheta, valoare) in tabelul de detalii,
+/// specifice tipului de alerta (ex: feed-ul si increderea IOC). Valorile sunt
+/// HTML-escapeate — pot proveni din fisiere externe (feed-uri threat intel).
+#[allow(clippy::too_many_arguments)]
 fn build_html_body(
     scan_type: &str,
-    severity: &str,
+    severity: &Severity,
     src_ip: &str,
     src_hostname: &str,
     src_location: &str,
@@ -113,6 +438,7 @@ fn build_html_body(
     port_count: usize,
     timestamp: &str,
     ports: &str,
+    extra_rows: &[(&str, String)],
     footer: &str,
 ) -> String {
     // HTML-escape pentru campuri care pot contine caractere speciale (footer ASCII art).
@@ -121,6 +447,14 @@ fn build_html_body(
         .replace('<', "&lt;")
         .replace('>', "&gt;");
 
+    // Culoarea header-ului urmeaza severitatea (aceleasi praguri ca eticheta).
+    let (hdr_color, hdr_color_dark) = match severity.level {
+        8.. => ("#c0392b", "#96281b"),
+        7 => ("#d35400", "#a04000"),
+        6 => ("#d4ac0d", "#9a7d0a"),
+        _ => ("#2e86c1", "#1b4f72"),
+    };
+
     let template = r#"<!DOCTYPE html>
 <html lang="ro">
 <head>
@@ -132,7 +466,7 @@ fn build_html_body(
   .wrap { max-width: 620px; margin: 0 auto; background: #fff; border-radius: 6px;
           overflow: hidden; box-shadow: 0 2px 10px rgba(0,0,0,0.12); }
   /* HEADER */
-  .hdr { background: linear-gradient(135deg, #c0392b 0%, #96281b 100%);
+  .hdr { background: linear-gradient(135deg, __HDR_COLOR__ 0%, __HDR_COLOR_DARK__ 100%);
          color: #fff; padding: 24px 28px; }
   .hdr-label { font-size: 10px; text-transform: uppercase; letter-spacing: 2px;
                opacity: 0.7; margin-bottom: 10px; }
@@ -176,7 +510,7 @@ fn build_html_body(
 
   <div class="hdr">
     <div class="hdr-label">IDS-RS &mdash; Intrusion Detection System</div>
-    <h1>&#x1F534; ALERTA SCANARE RETEA</h1>
+    <h1>__EMOJI__ ALERTA SCANARE RETEA</h1>
     <span class="badge">__SCAN_TYPE__</span>
     <span class="badge">Severitate: __SEVERITY__</span>
   </div>
@@ -188,7 +522,7 @@ fn build_html_body(
       <tr><td>IP Destinatie</td><td>__DST_IP__ __DST_HOST__ __DST_LOC__</td></tr>
       <tr><td>Porturi scanate</td><td>__PORT_COUNT__</td></tr>
       <tr><td>Timestamp</td><td>__TIMESTAMP__</td></tr>
-    </table>
+__EXTRA_ROWS__    </table>
   </div>
 
   <div class="sec">
@@ -246,9 +580,23 @@ fn build_html_body(
         format!("[{}]", dst_location)
     };
 
+    let extra_rows_html: String = extra_rows
+        .iter()
+        .map(|(label, value)| {
+            let value_safe = value
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            format!("      <tr><td>{}</td><td>{}</td></tr>\n", label, value_safe)
+        })
+        .collect();
+
     template
         .replace("__SCAN_TYPE__", scan_type)
-        .replace("__SEVERITY__", severity)
+        .replace("__SEVERITY__", severity.label())
+        .replace("__EMOJI__", severity.emoji())
+        .replace("__HDR_COLOR__", hdr_color)
+        .replace("__HDR_COLOR_DARK__", hdr_color_dark)
         .replace("__SRC_IP__", src_ip)
         .replace("__SRC_HOST__", &src_host_display)
         .replace("__SRC_LOC__", &src_loc_display)
@@ -258,15 +606,16 @@ fn build_html_body(
         .replace("__PORT_COUNT__", &port_count.to_string())
         .replace("__TIMESTAMP__", timestamp)
         .replace("__PORTS__", ports)
+        .replace("__EXTRA_ROWS__", &extra_rows_html)
         .replace("__FOOTER__", &footer_safe)
 }
 
 /// Construieste transportul SMTP async din configurarea email.
 ///
-/// Functie privata separata — transportul este construit O SINGURA DATA
-/// la initializarea Alerter-ului si reutilizat la fiecare alerta,
-/// evitand reconectarea TLS/STARTTLS la fiecare email trimis.
-fn build_mailer(cfg: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+/// Transportul este construit O SINGURA DATA la initializarea Alerter-ului
+/// si reutilizat la fiecare alerta, evitand reconectarea TLS/STARTTLS la
+/// fiecare email trimis. `ids-rs check-config` il construieste de proba.
+pub fn build_mailer(cfg: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
     let smtp_timeout = Some(Duration::from_secs(30));
 
     let mailer = if cfg.smtp_tls {
@@ -294,6 +643,14 @@ fn build_mailer(cfg: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>>
     Ok(mailer)
 }
 
+/// Deschide istoricul de alerte (None daca `[alerting.history]` e dezactivat).
+fn open_history(cfg: &HistoryConfig) -> Result<Option<Arc<AlertHistory>>> {
+    if !cfg.enabled {
+        return Ok(None);
+    }
+    Ok(Some(Arc::new(AlertHistory::open(&cfg.path)?)))
+}
+
 /// Componenta de alertare - trimite notificari catre SIEM si email.
 ///
 /// NOTA RUST: Acest struct DETINE (owns) configurarea. Clonarea s-a facut
@@ -312,6 +669,11 @@ pub struct Alerter {
     hostnames: ArcSwap<HashMap<IpAddr, String>>,
     /// Mapping subnet CIDR → locatie (etaj, zona) pentru context fizic in alerte.
     subnets: ArcSwap<Vec<SubnetEntry>>,
+    /// Inventarul de active (proprietar, criticitate, rol) — partajat cu main loop-ul.
+    assets: ArcSwap<AssetInventory>,
+    /// Istoricul local al alertelor (None daca dezactivat). Arc: insert-ul
+    /// ruleaza pe un thread de blocking, care primeste propria referinta.
+    history: ArcSwap<Option<Arc<AlertHistory>>>,
 }
 
 impl Alerter {
@@ -332,15 +694,24 @@ impl Alerter {
         } else {
             None
         };
+        // Ca si transportul SMTP: o baza care nu se poate deschide opreste pornirea.
+        let history = open_history(&config.history)?;
         Ok(Self {
             config: ArcSwap::from_pointee(config),
             detection: ArcSwap::from_pointee(detection),
             mailer: ArcSwap::from_pointee(mailer),
             hostnames: ArcSwap::from_pointee(hostnames),
             subnets: ArcSwap::from_pointee(subnets),
+            assets: ArcSwap::from_pointee(AssetInventory::default()),
+            history: ArcSwap::from_pointee(history),
         })
     }
 
+    /// Inlocuieste inventarul de active (la pornire si la SIGHUP).
+    pub fn update_assets(&self, assets: Arc<AssetInventory>) {
+        self.assets.store(assets);
+    }
+
     /// Actualizeaza configurarea alerter-ului la runtime (hot reload SIGHUP #16).
     ///
     /// Rebuild-ul mailer-ului este necesar daca se schimba SMTP server/port/TLS/credentials.
@@ -352,6 +723,20 @@ impl Alerter {
         new_hostnames: HashMap<IpAddr, String>,
         new_subnets: Vec<SubnetEntry>,
     ) {
+        // Redeschidem istoricul doar daca s-a schimbat activarea sau fisierul.
+        // La eroare pastram baza veche.
+        let old_history = &self.config.load().history;
+        if old_history.enabled != new_alerting.history.enabled
+            || old_history.path != new_alerting.history.path
+        {
+            match open_history(&new_alerting.history) {
+                Ok(history) => self.history.store(Arc::new(history)),
+                Err(e) => display::log_error(&format!(
+                    "SIGHUP: istoric alerte neredeschis, pastrez baza veche: {:#}", e
+                )),
+            }
+        }
+
         // Rebuild mailer daca email e activat in noua configurare.
         let new_mailer = if new_alerting.email.enabled {
             match build_mailer(&new_alerting.email) {
@@ -390,19 +775,114 @@ impl Alerter {
     /// Pattern: "log and continue" vs "fail fast".
     ///
     pub async fn send_alert(&self, alert: &Alert) {
+        for (sink, result) in self.deliver(alert).await {
+            if let Err(e) = result {
+                let context = match sink {
+                    "istoric" => "Eroare salvare alerta in istoric",
+                    "SIEM" => "Eroare trimitere alerta SIEM",
+                    _ => "Eroare trimitere email",
+                };
+                display::log_error(&format!("{}: {:#}", context, e));
+            }
+        }
+    }
+
+    /// Trimite alerta prin destinatiile active (istoric → SIEM → email) si
+    /// intoarce rezultatul fiecareia — `ids-rs ctl test-alert` il raporteaza.
+    pub async fn deliver(&self, alert: &Alert) -> Vec<(&'static str, Result<()>)> {
+        let mut results = Vec::new();
+        if self.history.load().is_some() {
+            results.push(("istoric", self.record_history(alert).await));
+        }
+
         let cfg = self.config.load();
         if cfg.siem.enabled {
-            if let Err(e) = self.send_siem_alert(alert).await {
-                display::log_error(&format!("Eroare trimitere alerta SIEM: {:#}", e));
-            }
+            results.push(("SIEM", self.send_siem_alert(alert).await));
         }
 
         let mailer_guard = self.mailer.load();
         if let Some(ref mailer) = **mailer_guard {
-            if let Err(e) = self.send_email_alert(alert, mailer).await {
-                display::log_error(&format!("Eroare trimitere email: {:#}", e));
-            }
+            results.push(("email", self.send_email_alert(alert, mailer).await));
+        }
+        results
+    }
+
+    /// Salveaza alerta in istoricul local, cu hostname-urile, locatiile si
+    /// detaliile de la momentul alertei.
+    ///
+    /// NOTA RUST: SQLite este un API blocant — `spawn_blocking` muta insert-ul
+    /// pe thread-pool-ul dedicat operatiilor blocante, ca main loop-ul sa nu
+    /// astepte dupa disc. Primul `?` trateaza un panic in task, al doilea
+    /// eroarea insert-ului.
+    async fn record_history(&self, alert: &Alert) -> Result<()> {
+        let history = match **self.history.load() {
+            Some(ref history) => Arc::clone(history),
+            None => return Ok(()),
+        };
+
+        let hn = self.hostnames.load();
+        let sn = self.subnets.load();
+        let assets = self.assets.load();
+        let (_, _, summary) = describe(alert, &self.detection.load());
+
+        let mut details = Vec::new();
+        if !alert.unique_ports.is_empty() {
+            let ports = alert
+                .unique_ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            details.push(("Porturi".to_string(), ports));
         }
+        details.extend(
+            detail_rows(alert, &assets)
+                .into_iter()
+                .map(|(label, value)| (label.to_string(), value)),
+        );
+
+        let record = AlertRecord {
+            id: 0,
+            timestamp: alert.timestamp,
+            scan_type: alert.scan_type.key().to_string(),
+            severity: alert.severity.level,
+            source: alert.source_label(),
+            source_host: hostname(&hn, &assets, &alert.source_ip).map(str::to_string),
+            source_subnet: SubnetEntry::lookup(&sn, &alert.source_ip),
+            dest: alert.dest_ip.map(|ip| ip.to_string()),
+            dest_host: alert
+                .dest_ip
+                .as_ref()
+                .and_then(|ip| hostname(&hn, &assets, ip))
+                .map(str::to_string),
+            dest_subnet: alert.dest_ip.as_ref().and_then(|ip| SubnetEntry::lookup(&sn, ip)),
+            related: alert
+                .unique_dests
+                .iter()
+                .chain(&alert.unique_sources)
+                .map(|ip| ip.to_string())
+                .collect(),
+            summary,
+            details,
+        };
+
+        tokio::task::spawn_blocking(move || history.insert(&record))
+            .await
+            .context("Task-ul de salvare in istoric a esuat")??;
+        Ok(())
+    }
+
+    /// Sterge din istoric alertele mai vechi de `retention_days` (la cleanup).
+    /// Returneaza numarul de alerte sterse (0 daca istoricul e dezactivat).
+    pub async fn prune_history(&self) -> Result<usize> {
+        let history = match **self.history.load() {
+            Some(ref history) => Arc::clone(history),
+            None => return Ok(0),
+        };
+        let retention_days = self.config.load().history.retention_days;
+        tokio::task::spawn_blocking(move || history.prune(retention_days, chrono::Local::now()))
+            .await
+            .context("Task-ul de curatare a istoricului a esuat")?
     }
 
     /// Trimite o alerta catre SIEM prin UDP syslog.
@@ -427,74 +907,18 @@ impl Alerter {
         // Prioritate syslog: facility=4 (security) × 8 + severity=6 (info) = 38
         // Câmpuri CEF Extensions: rt, src, cnt, act, msg, cs1Label, cs1
 
-        // Tuple: (SignatureID, EventName, DescriereMsg, SeveritateCEF)
-        //
-        // Severitatea CEF (campul 7 din header) indica urgenta in ArcSight:
-        //   7 = High    → Fast Scan (raspuns imediat necesar)
-        //   6 = Medium  → Slow Scan (investigare necesara)
-        //   5 = Low     → Accept Scan (poate fi trafic legitim; investigare)
-        //
-        // Comentariul anterior spunea severitate 5 pentru AcceptScan, dar
-        // codul folosea 7 hardcodat pentru toate tipurile — inconsistenta fixata.
-        let det = self.detection.load();
-        let (sig_id, event_name, scan_label, cef_severity) = match alert.scan_type {
-            ScanType::Fast => (
-                "1001",
-                "Fast Port Scan Detected",
-                format!(
-                    "Fast Scan detectat: {} porturi unice in {} secunde",
-                    alert.unique_ports.len(),
-                    det.fast_scan.time_window_secs,
-                ),
-                7u8,
-            ),
-            ScanType::Slow => (
-                "1002",
-                "Slow Port Scan Detected",
-                format!(
-                    "Slow Scan detectat: {} porturi unice in {} minute",
-                    alert.unique_ports.len(),
-                    det.slow_scan.time_window_mins,
-                ),
-                6u8,
-            ),
-            ScanType::AcceptScan => (
-                "1003",
-                "Accept Port Scan Detected",
-                format!(
-                    "Accept Scan detectat: {} porturi deschise accesate in {} secunde",
-                    alert.unique_ports.len(),
-                    det.accept_scan.time_window_secs,
-                ),
-                5u8,
-            ),
-            ScanType::LateralMovement => (
-                "1004",
-                "Lateral Movement Detected",
-                format!(
-                    "Lateral Movement detectat: {} destinatii unice in {} secunde",
-                    alert.unique_dests.len(),
-                    det.lateral_movement.time_window_secs,
-                ),
-                8u8,
-            ),
-            ScanType::DistributedScan => (
-                "1005",
-                "Distributed Port Scan Detected",
-                format!(
-                    "Distributed Scan detectat: {} surse unice → tinta {} in {} secunde",
-                    alert.unique_sources.len(),
-                    alert.dest_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "N/A".to_string()),
-                    det.distributed_scan.time_window_secs,
-                ),
-                7u8,
-            ),
-        };
+        // Severitatea CEF (campul 7 din header) vine din alerta: baza tipului
+        // plus factorii din `[detection.severity]` (vezi severity.rs).
+        let (sig_id, event_name, scan_label) = describe(alert, &self.detection.load());
+
+        let cef_severity = alert.severity.level;
+        let assets = self.assets.load();
+        let target_asset = assets.most_critical(alert.targets());
 
         // Pentru Lateral Movement, campul cs1 contine destinatiile unice (IP-uri),
         // nu porturi. Pentru celelalte tipuri, cs1 contine porturile scanate.
         let (cs1_label, cs1_value, cnt) = match alert.scan_type {
-            ScanType::LateralMovement => {
+            ScanType::LateralMovement | ScanType::PingSweep => {
                 let dest_list = alert
                     .unique_dests
                     .iter()
@@ -512,6 +936,62 @@ impl Alerter {
                     .join(",");
                 ("AttackingSources", src_list, alert.unique_sources.len())
             }
+            ScanType::KnownBadSource | ScanType::KnownBadDestination => {
+                let indicator = alert
+                    .threat_intel
+                    .as_ref()
+                    .map(|m| sanitize_cef(&m.indicator))
+                    .unwrap_or_default();
+                ("MatchedIndicator", indicator, 1)
+            }
+            ScanType::Anomaly => {
+                let (metric, observed) = alert
+                    .anomaly
+                    .as_ref()
+                    .map(|a| (a.metric.to_string(), a.observed.round() as usize))
+                    .unwrap_or_default();
+                ("AnomalyMetric", metric, observed)
+            }
+            ScanType::Escalation => {
+                let (chain, count) = alert
+                    .risk
+                    .as_ref()
+                    .map(|r| (r.format_chain("; "), r.chain.len()))
+                    .unwrap_or_default();
+                ("EscalationChain", chain, count)
+            }
+            ScanType::AttackChain => {
+                let (stages, count) = alert
+                    .chain
+                    .as_ref()
+                    .map(|c| (sanitize_cef(&c.format_steps("; ")), c.steps.len()))
+                    .unwrap_or_default();
+                ("ChainStages", stages, count)
+            }
+            ScanType::Beaconing => {
+                let (interval, count) = alert
+                    .beacon
+                    .as_ref()
+                    .map(|b| (format!("{:.1}s", b.mean_interval_secs), b.connections))
+                    .unwrap_or_default();
+                ("BeaconInterval", interval, count)
+            }
+            ScanType::PersistentScanner => {
+                let (types, count) = alert
+                    .offence
+                    .as_ref()
+                    .map(|o| (o.scan_types.join(","), o.nth))
+                    .unwrap_or_default();
+                ("OffenceTypes", types, count)
+            }
+            ScanType::BruteForce => {
+                let (port, count) = alert
+                    .flood
+                    .as_ref()
+                    .map(|f| (f.port.to_string(), f.count))
+                    .unwrap_or_default();
+                ("TargetPort", port, count)
+            }
             _ => {
                 let port_list = alert
                     .unique_ports
@@ -534,7 +1014,124 @@ impl Alerter {
         };
 
         // Mesajul campului msg: descriere + lista valori (porturi sau IP-uri).
-        let msg_text = format!("{} | {}: {}", sanitize_cef(&scan_label), cs1_label.to_lowercase(), cs1_msg);
+        let mut msg_text = format!("{} | {}: {}", sanitize_cef(&scan_label), cs1_label.to_lowercase(), cs1_msg);
+        if !alert.sensitive_ports.is_empty() {
+            let sensitive = alert
+                .sensitive_ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            msg_text.push_str(&format!(" | porturi sensibile: {}", sensitive));
+        }
+
+        // Campul proto — protocolul de transport, cand alerta are unul singur.
+        // Scanarile mixte (TCP + UDP) primesc defalcarea in msg.
+        let proto_field = match alert.protocols.as_slice() {
+            [(proto, _)] => format!(" proto={}", proto.as_str().to_uppercase()),
+            _ => String::new(),
+        };
+        if alert.protocols.len() > 1 {
+            msg_text.push_str(&format!(" | protocoale: {}", alert.protocol_summary(",")));
+        }
+        if !alert.severity.factors.is_empty() {
+            msg_text.push_str(&format!(" | severitate: {}", alert.severity.factors.join(", ")));
+        }
+
+        // Campul cn3 — a cata alerta a sursei in fereastra de recidiva; contextul
+        // complet (total pe orizont, prima alerta, tipuri) merge in msg.
+        let offence_field = match alert.offence {
+            Some(ref o) => {
+                if o.is_repeat() && !matches!(alert.scan_type, ScanType::PersistentScanner) {
+                    msg_text.push_str(&format!(" | recidiva: {}", o.summary()));
+                }
+                format!(" cn3Label=OffenceCount cn3={}", o.nth)
+            }
+            None => String::new(),
+        };
+
+        // Campul flexNumber2 — durata scanarii in desfasurare (update / rezumat).
+        let ongoing_field = match alert.ongoing {
+            Some(ref o) => format!(" flexNumber2Label=ScanDurationSecs flexNumber2={}", o.duration.as_secs()),
+            None => String::new(),
+        };
+
+        // Campul flexString1 — unealta / tehnica probabila (amprenta scanarii).
+        // Dovada completa merge in msg, eticheta scurta in campul dedicat.
+        let tool_field = match alert.fingerprint {
+            Some(ref fp) => {
+                msg_text.push_str(&format!(" | unealta: {} ({})", fp.tool, sanitize_cef(&fp.evidence)));
+                format!(" flexString1Label=ScanTool flexString1={}", sanitize_cef(fp.tool))
+            }
+            None => String::new(),
+        };
+
+        // Campul cn1 — lungimea prefixului pentru o sursa IPv6 agregata; `src`
+        // poarta adresa de retea, prefixul complet apare si in msg.
+        let prefix_field = match alert.source_prefix {
+            Some(prefix) => {
+                msg_text.push_str(&format!(" | sursa agregata: {}", alert.source_label()));
+                format!(" cn1Label=SourcePrefixLength cn1={}", prefix)
+            }
+            None => String::new(),
+        };
+
+        // Campul flexString2 — regulile de firewall lovite de sursa (context pentru
+        // analist: ce regula a prins scanarea), trunchiat la 512 caractere.
+        let rules_field = if alert.rules.is_empty() {
+            String::new()
+        } else {
+            let rules = sanitize_cef(&alert.rules.join(","));
+            let rules = if rules.len() <= 512 {
+                rules
+            } else {
+                let mut end = 509;
+                while !rules.is_char_boundary(end) {
+                    end -= 1;
+                }
+                format!("{}...", &rules[..end])
+            };
+            format!(" flexString2Label=FirewallRules flexString2={}", rules)
+        };
+
+        // Campurile *Translated* — adresele/porturile de dupa NAT; src/dst raman
+        // identitatea pe care a cheiat detectorul, perechea completa merge in msg.
+        let nat_field = match alert.nat {
+            Some(ref nat) => {
+                msg_text.push_str(&format!(" | NAT: {}", nat.summary()));
+                let mut field = String::new();
+                if let Some(ip) = nat.xlate_src {
+                    field.push_str(&format!(" sourceTranslatedAddress={}", ip));
+                }
+                if let Some(port) = nat.xlate_sport {
+                    field.push_str(&format!(" sourceTranslatedPort={}", port));
+                }
+                if let Some(ip) = nat.xlate_dst {
+                    field.push_str(&format!(" destinationTranslatedAddress={}", ip));
+                }
+                if let Some(port) = nat.xlate_dport {
+                    field.push_str(&format!(" destinationTranslatedPort={}", port));
+                }
+                field
+            }
+            None => String::new(),
+        };
+
+        // Campul flexNumber1 — criticitatea activului tinta (1 = low … 4 = critical);
+        // proprietarul si rolul sursei / tintei merg in msg.
+        if let Some(asset) = assets.lookup(&alert.source_ip) {
+            msg_text.push_str(&format!(" | activ sursa: {}", sanitize_cef(&asset.summary())));
+        }
+        let asset_field = match target_asset {
+            Some(asset) => {
+                msg_text.push_str(&format!(" | activ tinta: {}", sanitize_cef(&asset.summary())));
+                format!(
+                    " flexNumber1Label=TargetCriticality flexNumber1={}",
+                    asset.criticality as u8 + 1
+                )
+            }
+            None => String::new(),
+        };
 
         // Sanitizare anti-injection pentru event_name (camp header CEF, separator '|').
         let event_name_safe = sanitize_cef(event_name);
@@ -547,17 +1144,15 @@ impl Alerter {
         };
 
         // Campurile shost/dhost (Source/Destination Hostname in ArcSight).
-        // Prezente doar daca hostname-ul este configurat in [network.hostnames].
+        // Prezente doar daca hostname-ul este configurat in [network.hostnames]
+        // sau in inventarul de active.
         let hn = self.hostnames.load();
-        let shost_field = match hn.get(&alert.source_ip) {
+        let shost_field = match hostname(&hn, &assets, &alert.source_ip) {
             Some(name) => format!(" shost={}", sanitize_cef(name)),
             None => String::new(),
         };
-        let dhost_field = match alert.dest_ip {
-            Some(ref ip) => match hn.get(ip) {
-                Some(name) => format!(" dhost={}", sanitize_cef(name)),
-                None => String::new(),
-            },
+        let dhost_field = match alert.dest_ip.as_ref().and_then(|ip| hostname(&hn, &assets, ip)) {
+            Some(name) => format!(" dhost={}", sanitize_cef(name)),
             None => String::new(),
         };
 
@@ -576,14 +1171,42 @@ impl Alerter {
             None => String::new(),
         };
 
+        // Campurile cs4/cs5 — feed-ul threat intel si increderea (doar KnownBad*).
+        let intel_field = match alert.threat_intel {
+            Some(ref m) => format!(
+                " cs4Label=ThreatFeed cs4={} cs5Label=FeedConfidence cs5={}",
+                sanitize_cef(&m.feed),
+                m.confidence
+            ),
+            None => String::new(),
+        };
+
+        // Campul cs6 — profilul de detectie aplicat (doar daca nu e cel global).
+        let profile_field = match alert.profile {
+            Some(ref name) => format!(" cs6Label=DetectionProfile cs6={}", sanitize_cef(name)),
+            None => String::new(),
+        };
+
+        // Campul cn2 — scorul de risc (doar Escalation; cn* este numeric intreg in CEF).
+        let risk_field = match alert.risk {
+            Some(ref r) => format!(" cn2Label=RiskScore cn2={}", r.score.round() as u64),
+            None => String::new(),
+        };
+
+        // Campul cfp1 — scorul ponderat al porturilor (doar cu port_weights activat).
+        let score_field = match alert.score {
+            Some(score) => format!(" cfp1Label=WeightedScore cfp1={:.2}", score),
+            None => String::new(),
+        };
+
         let syslog_ts = alert.timestamp.format("%b %e %H:%M:%S");
         let rt_ms = alert.timestamp.timestamp_millis();
 
         let message = format!(
             "<38>{syslog_ts} ids-rs CEF:0|IDS-RS|Network Scanner Detector|1.0\
              |{sig_id}|{event_name}|{sev}\
-             |rt={rt_ms} src={src}{shost}{src_loc}{dst}{dhost}{dst_loc} cnt={cnt} act=alert \
-             msg={msg} cs1Label={cs1label} cs1={cs1}",
+             |rt={rt_ms} src={src}{shost}{src_loc}{dst}{dhost}{dst_loc} cnt={cnt}{proto} act=alert \
+             msg={msg} cs1Label={cs1label} cs1={cs1}{intel}{profile}{score}{risk}{tool}{rules}{prefix}{nat}{asset}{offence}{ongoing}",
             sev = cef_severity,
             syslog_ts = syslog_ts,
             sig_id = sig_id,
@@ -596,9 +1219,21 @@ impl Alerter {
             dhost = dhost_field,
             dst_loc = dst_location_field,
             cnt = cnt,
+            proto = proto_field,
             msg = msg_text,
             cs1label = cs1_label,
             cs1 = cs1_value,
+            intel = intel_field,
+            profile = profile_field,
+            score = score_field,
+            risk = risk_field,
+            tool = tool_field,
+            rules = rules_field,
+            prefix = prefix_field,
+            nat = nat_field,
+            asset = asset_field,
+            offence = offence_field,
+            ongoing = ongoing_field,
         );
 
         // Cream un socket UDP efemer (port 0 = OS alege automat).
@@ -617,6 +1252,84 @@ impl Alerter {
         Ok(())
     }
 
+    /// Trimite rezumatul unei sesiuni de scanare inchise catre destinatiile
+    /// din `[alerting.sessions]`: SIEM (CEF) si fisierul JSON lines.
+    /// Ca la `send_alert`, o destinatie care esueaza nu le opreste pe celelalte.
+    pub async fn send_session(&self, session: &SessionSummary) {
+        let cfg = self.config.load();
+        if cfg.siem.enabled && cfg.sessions.siem {
+            if let Err(e) = self.send_siem_session(session).await {
+                display::log_error(&format!("Eroare trimitere sesiune SIEM: {:#}", e));
+            }
+        }
+        if !cfg.sessions.json_path.is_empty() {
+            if let Err(e) = append_session_json(&cfg.sessions.json_path, session).await {
+                display::log_error(&format!("Eroare scriere sesiune JSON: {:#}", e));
+            }
+        }
+    }
+
+    /// Evenimentul CEF "Scan Session Closed" (SigID 1015).
+    ///
+    /// `start` / `end` sunt campurile CEF standard pentru intervalul
+    /// evenimentului — SIEM-ul le afiseaza ca durata incidentului.
+    /// Sesiunea de sursa are `src=`, cea de tinta `dst=`.
+    async fn send_siem_session(&self, session: &SessionSummary) -> Result<()> {
+        let hn = self.hostnames.load();
+        let assets = self.assets.load();
+        let (addr_key, host_key, peer_label) = match session.kind {
+            SessionKind::Source => ("src", "shost", "UniqueDestinations"),
+            SessionKind::Target => ("dst", "dhost", "UniqueSources"),
+        };
+        let host_field = match hostname(&hn, &assets, &session.ip) {
+            Some(name) => format!(" {}={}", host_key, sanitize_cef(name)),
+            None => String::new(),
+        };
+        let ports = session
+            .ports
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let now = chrono::Local::now();
+
+        let message = format!(
+            "<38>{syslog_ts} ids-rs CEF:0|IDS-RS|Network Scanner Detector|1.0\
+             |1015|Scan Session Closed|{sev}\
+             |rt={rt_ms} start={start} end={end} {addr_key}={ip}{host} cnt={events} act=summary \
+             msg={msg} cs1Label=ProbedPorts cs1={ports} cn1Label={peer_label} cn1={peers} \
+             cn2Label=UniquePorts cn2={unique_ports} flexNumber2Label=ScanDurationSecs flexNumber2={duration}",
+            syslog_ts = now.format("%b %e %H:%M:%S"),
+            sev = session.severity,
+            rt_ms = now.timestamp_millis(),
+            start = session.first_seen.timestamp_millis(),
+            end = session.last_seen.timestamp_millis(),
+            addr_key = addr_key,
+            ip = session.ip,
+            host = host_field,
+            events = session.events,
+            msg = sanitize_cef(&format!("Sesiune de scanare ({}) inchisa: {}", session.kind.key(), session.summary())),
+            ports = ports,
+            peer_label = peer_label,
+            peers = session.peers,
+            unique_ports = session.unique_ports,
+            duration = session.duration.as_secs(),
+        );
+
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .context("Nu pot crea socket UDP pentru SIEM")?;
+        let cfg = self.config.load();
+        let dest = format!("{}:{}", cfg.siem.host, cfg.siem.port);
+        socket
+            .send_to(message.as_bytes(), &dest)
+            .await
+            .with_context(|| format!("Nu pot trimite catre SIEM {}", dest))?;
+
+        display::log_alert_sent(&dest, "Scan Session Closed");
+        Ok(())
+    }
+
     /// Trimite o notificare email catre toti destinatarii configurati.
     ///
     /// NOTA RUST - CLOSURES si OWNERSHIP:
@@ -643,97 +1356,161 @@ impl Alerter {
         let (subject, item_count, list_display) = match alert.scan_type {
             ScanType::DistributedScan => {
                 let count = alert.unique_sources.len();
-                let list = alert
-                    .unique_sources
-                    .iter()
-                    .take(30)
-                    .map(|ip| ip.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                let list = if count > 30 {
-                    format!("{} + {} more", list, count - 30)
-                } else {
-                    list
-                };
+                let list = email_list(&alert.unique_sources);
                 let target = alert.dest_ip
                     .map(|ip| ip.to_string())
                     .unwrap_or_else(|| "N/A".to_string());
                 let subj = format!(
-                    "\u{1F534} [{}][SCANARE COORDONATA] IDS-RS {} surse → {}",
+                    "[{}][SCANARE COORDONATA] IDS-RS {} surse → {}",
                     alert.scan_type, count, target
                 );
                 (subj, count, list)
             }
-            ScanType::LateralMovement => {
+            ScanType::LateralMovement | ScanType::PingSweep => {
                 let count = alert.unique_dests.len();
-                let list = alert
-                    .unique_dests
-                    .iter()
-                    .take(30)
-                    .map(|ip| ip.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                let list = if count > 30 {
-                    format!("{} + {} more", list, count - 30)
-                } else {
-                    list
+                let list = email_list(&alert.unique_dests);
+                let tag = match alert.scan_type {
+                    ScanType::PingSweep => "PING SWEEP",
+                    _ => "MISCARE LATERALA",
                 };
                 let subj = format!(
-                    "\u{1F534} [{}][MISCARE LATERALA] IDS-RS {} {} destinatii",
-                    alert.scan_type, alert.source_ip, count
+                    "[{}][{}] IDS-RS {} {} destinatii",
+                    alert.scan_type, tag, alert.source_label(), count
                 );
                 (subj, count, list)
             }
+            ScanType::KnownBadSource | ScanType::KnownBadDestination => {
+                let indicator = alert
+                    .threat_intel
+                    .as_ref()
+                    .map(|m| m.indicator.clone())
+                    .unwrap_or_default();
+                let peer = match alert.scan_type {
+                    ScanType::KnownBadSource => alert.source_ip.to_string(),
+                    _ => alert.dest_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "N/A".to_string()),
+                };
+                let subj = format!(
+                    "[{}][THREAT INTEL] IDS-RS {} ({})",
+                    alert.scan_type, peer, indicator
+                );
+                (subj, 1, indicator)
+            }
+            ScanType::AttackChain => {
+                let (rule, stages, count) = alert
+                    .chain
+                    .as_ref()
+                    .map(|c| (c.rule.clone(), c.format_steps(" → "), c.steps.len()))
+                    .unwrap_or_default();
+                let subj = format!(
+                    "[{}][LANT ATAC] IDS-RS {} ({})",
+                    alert.scan_type, alert.source_label(), rule
+                );
+                (subj, count, stages)
+            }
+            ScanType::BruteForce => {
+                let (port, count, accepted) = alert
+                    .flood
+                    .as_ref()
+                    .map(|f| (f.port, f.count, f.accepted))
+                    .unwrap_or_default();
+                let target = alert
+                    .dest_ip
+                    .map(|ip| ip.to_string())
+                    .unwrap_or_else(|| "N/A".to_string());
+                let subj = format!(
+                    "[{}][BRUTE FORCE] IDS-RS {} → {}:{} ({} {})",
+                    alert.scan_type,
+                    alert.source_label(),
+                    target,
+                    port,
+                    count,
+                    if accepted { "acceptate" } else { "blocate" }
+                );
+                (subj, count, format!("{}:{}", target, port))
+            }
+            ScanType::Beaconing => {
+                let count = alert.beacon.as_ref().map(|b| b.connections).unwrap_or(0);
+                let target = alert
+                    .dest_ip
+                    .map(|ip| ip.to_string())
+                    .unwrap_or_else(|| "N/A".to_string());
+                let port = alert.unique_ports.first().copied().unwrap_or(0);
+                let subj = format!(
+                    "[{}][BEACONING C2] IDS-RS {} → {}:{}",
+                    alert.scan_type, alert.source_label(), target, port
+                );
+                (subj, count, format!("{}:{}", target, port))
+            }
+            ScanType::PersistentScanner => {
+                let (count, window, types) = alert
+                    .offence
+                    .as_ref()
+                    .map(|o| (o.nth, o.window_hours, o.scan_types.join(", ")))
+                    .unwrap_or_default();
+                let subj = format!(
+                    "[{}][SCANNER PERSISTENT] IDS-RS {} {} alerte in {}h",
+                    alert.scan_type, alert.source_label(), count, window
+                );
+                (subj, count, types)
+            }
+            ScanType::Escalation => {
+                let (tier, score, chain, count) = alert
+                    .risk
+                    .as_ref()
+                    .map(|r| (r.tier, r.score, r.format_chain(" → "), r.chain.len()))
+                    .unwrap_or_default();
+                let subj = format!(
+                    "[{}][ESCALADARE] IDS-RS {} nivel {} (scor {:.1})",
+                    alert.scan_type, alert.source_label(), tier, score
+                );
+                (subj, count, chain)
+            }
+            ScanType::Anomaly => {
+                let (key, metric) = alert
+                    .anomaly
+                    .as_ref()
+                    .map(|a| (a.baseline_key.clone(), a.metric))
+                    .unwrap_or_else(|| (alert.source_ip.to_string(), "N/A"));
+                let subj = format!(
+                    "[{}][ANOMALIE] IDS-RS {} ({})",
+                    alert.scan_type, key, metric
+                );
+                (subj, alert.unique_ports.len(), email_list(&alert.unique_ports))
+            }
             _ => {
                 let count = alert.unique_ports.len();
-                let list = if count <= 30 {
-                    alert
-                        .unique_ports
-                        .iter()
-                        .map(|p| p.to_string())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                } else {
-                    let first_30: String = alert.unique_ports[..30]
-                        .iter()
-                        .map(|p| p.to_string())
-                        .collect::<Vec<_>>()
-                        .join(", ");
-                    format!("{} + {} more", first_30, count - 30)
-                };
+                let list = email_list(&alert.unique_ports);
                 let subj = format!(
-                    "\u{1F534} [{}][SCANARE RETEA] IDS-RS {} {} porturi",
-                    alert.scan_type, alert.source_ip, count
+                    "[{}][SCANARE RETEA] IDS-RS {} {} porturi",
+                    alert.scan_type, alert.source_label(), count
                 );
                 (subj, count, list)
             }
         };
 
-        // Severitate afisata in email — paralela cu severitatea CEF din send_siem_alert.
-        // Fast=7=RIDICATA, Slow=6=MEDIE, AcceptScan=5=MEDIE-MICA, LateralMovement=8=CRITICA.
-        let severity = match alert.scan_type {
-            ScanType::Fast => "RIDICATA",
-            ScanType::Slow => "MEDIE",
-            ScanType::AcceptScan => "MEDIE-MICA",
-            ScanType::LateralMovement => "CRITICA",
-            ScanType::DistributedScan => "RIDICATA",
+        // Severitate afisata in email — aceeasi ca severitatea CEF din send_siem_alert:
+        // >=8 CRITICA, 7 RIDICATA, 6 MEDIE, <=5 MEDIE-MICA. Emoji-ul din subiect
+        // si culoarea header-ului urmeaza nivelul.
+        let subject = match alert.ongoing {
+            Some(ref o) => format!("{} [{}]{}", alert.severity.emoji(), o.label(), subject),
+            None => format!("{} {}", alert.severity.emoji(), subject),
         };
 
+        let assets = self.assets.load();
+
         let dest_ip_display = match alert.dest_ip {
             Some(ip) => ip.to_string(),
             None => "N/A".to_string(),
         };
 
-        // Hostname-uri din mapping-ul static.
+        // Hostname-uri din mapping-ul static (sau din inventarul de active).
         let hn = self.hostnames.load();
-        let src_hostname = hn
-            .get(&alert.source_ip)
-            .map(|s| s.as_str())
+        let src_hostname = hostname(&hn, &assets, &alert.source_ip).unwrap_or("");
+        let dst_hostname = alert
+            .dest_ip
+            .as_ref()
+            .and_then(|ip| hostname(&hn, &assets, ip))
             .unwrap_or("");
-        let dst_hostname = match alert.dest_ip {
-            Some(ref ip) => hn.get(ip).map(|s| s.as_str()).unwrap_or(""),
-            None => "",
-        };
 
         // Locatii subnet din mapping-ul static.
         let sn = self.subnets.load();
@@ -745,10 +1522,13 @@ impl Alerter {
 
         let timestamp = alert.timestamp.format("%Y-%m-%d %H:%M:%S");
 
+        // Randuri specifice tipului de alerta in tabelul de detalii.
+        let extra_rows = detail_rows(alert, &assets);
+
         let html_body = build_html_body(
             &alert.scan_type.to_string(),
-            severity,
-            &alert.source_ip.to_string(),
+            &alert.severity,
+            &alert.source_label(),
             src_hostname,
             &src_location,
             &dest_ip_display,
@@ -757,6 +1537,7 @@ impl Alerter {
             item_count,
             &timestamp.to_string(),
             &list_display,
+            &extra_rows,
             &cfg.email_footer,
         );
 
@@ -792,12 +1573,12 @@ impl Alerter {
 }
 
 // =============================================================================
-// Teste unitare — sanitize_cef()
+// Teste unitare — sanitize_cef(), email_list()
 // =============================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::sanitize_cef;
+    use super::{email_list, sanitize_cef};
 
     #[test]
     fn test_sanitize_newline() {
@@ -847,4 +1628,14 @@ mod tests {
         let output = sanitize_cef(input);
         assert_eq!(output, "a\\\\\\|b");
     }
+
+    #[test]
+    fn test_email_list_truncation() {
+        // Aceeasi lista pentru toate tipurile (porturi, IP-uri), cu marcaj la trunchiere.
+        let ports: Vec<u16> = (1..=32).collect();
+        let list = email_list(&ports);
+        assert!(list.starts_with("1, 2, 3") && list.ends_with("30 + 2 more"));
+        assert_eq!(email_list(&ports[..3]), "1, 2, 3");
+        assert_eq!(email_list::<u16>(&[]), "");
+    }
 }
@@ -0,0 +1,440 @@
+// =============================================================================
+// api.rs - API REST read-only (`[api]`)
+// =============================================================================
+//
+// Un server HTTP local care expune starea detectorului ca JSON — pentru
+// scripturi, dashboard-uri externe sau un simplu `curl`:
+//
+//   GET /sources          sursele urmarite, porturi unice per fereastra
+//   GET /sources/{ip}     starea completa a unui IP (ca `ids-rs ctl state`)
+//   GET /targets          tintele lovite din surse multiple (Distributed Scan)
+//   GET /alerts/recent    ultimele alerte (`?limit=N`, implicit 50)
+//   GET /config           config-ul efectiv, fara secrete
+//   GET /stats            contoare, uptime, IP-uri urmarite, potriviri
+//                         per parser (`parser = "auto"`)
+//
+// API-ul este strict read-only: nu exista rute care modifica starea — pentru
+// asta exista socket-ul de control (control.rs), protejat de permisiunile
+// filesystem-ului. Cu `token` setat, fiecare cerere trebuie sa trimita
+// `Authorization: Bearer <token>`, altfel primeste 401.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. AXUM — ROUTER SI EXTRACTORI
+//    `Router::new().route("/sources/{ip}", get(handler))` asociaza o cale cu
+//    un handler async. Argumentele handler-ului sunt "extractori": `State<T>`
+//    (starea partajata), `Path<String>` (segmentul `{ip}`), `Query<T>`
+//    (parametrii `?limit=`). Axum ii construieste din cerere inainte de apel.
+//
+// 2. MIDDLEWARE (`from_fn_with_state`)
+//    O functie async care primeste cererea si `Next` — restul lantului. Poate
+//    raspunde direct (401) sau poate continua cu `next.run(request)`.
+//    Autentificarea se scrie o singura data, nu in fiecare handler.
+//
+// 3. `impl IntoResponse`
+//    Orice tip care stie sa devina raspuns HTTP: `Json<Value>`, un tuplu
+//    `(StatusCode, Json<Value>)` etc. Handler-ele intorc tipuri diferite
+//    fara un enum de raspuns scris manual.
+//
+// =============================================================================
+
+use crate::alerter;
+use crate::config::{ApiConfig, AppConfig};
+use crate::detector::{Alert, Detector, HitSnapshot, PortWindow, TrackedSource};
+use crate::parser::LogParser;
+use crate::stats::LiveStats;
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+
+/// Alertele intoarse implicit de `GET /alerts/recent`.
+const DEFAULT_RECENT_ALERTS: usize = 50;
+
+/// Ce primeste serverul API de la main.
+#[derive(Clone)]
+pub struct ApiContext {
+    pub detector: Arc<Detector>,
+    pub live: Arc<LiveStats>,
+    /// Config-ul curent — actualizat de main loop la fiecare reload reusit.
+    pub config: Arc<ArcSwap<AppConfig>>,
+    /// Parser-ul activ — inlocuit de main loop cand reload-ul il schimba.
+    pub parser: Arc<ArcSwap<Box<dyn LogParser>>>,
+    pub started: Instant,
+    pub started_at: DateTime<Local>,
+}
+
+/// Starea routerului: contextul plus token-ul fixat la pornire (schimbarea
+/// lui cere restart, ca adresa de ascultare — vezi reload-ul din main.rs).
+#[derive(Clone)]
+struct ApiState {
+    ctx: ApiContext,
+    token: Arc<str>,
+}
+
+/// Porneste serverul HTTP pe `listen_address:listen_port`.
+///
+/// Bind-ul se face aici, sincron cu pornirea: un port ocupat opreste IDS-ul
+/// cu eroare, ca la socket-ul UDP. Cererile sunt servite intr-un task separat.
+pub async fn start(cfg: &ApiConfig, ctx: ApiContext) -> Result<()> {
+    let bind_addr = format!("{}:{}", cfg.listen_address, cfg.listen_port);
+    let listener = TcpListener::bind(&bind_addr)
+        .await
+        .with_context(|| format!("Nu pot asculta pe {} ([api])", bind_addr))?;
+
+    let app = router(ApiState {
+        ctx,
+        token: Arc::from(cfg.token.as_str()),
+    });
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            crate::display::log_warning(&format!("API REST oprit: {:#}", e));
+        }
+    });
+    Ok(())
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/sources", get(sources))
+        .route("/sources/{ip}", get(source))
+        .route("/targets", get(targets))
+        .route("/alerts/recent", get(recent_alerts))
+        .route("/config", get(config))
+        .route("/stats", get(stats))
+        .fallback(|| async { error(StatusCode::NOT_FOUND, "ruta necunoscuta") })
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state)
+}
+
+/// Raspunsul de eroare: `{"error": "..."}` cu status-ul dat.
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if authorized(header, &state.token) {
+        next.run(request).await
+    } else {
+        let mut response = error(StatusCode::UNAUTHORIZED, "token lipsa sau invalid");
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+        response
+    }
+}
+
+/// Token gol = fara autentificare (permis doar pe loopback, vezi validate()).
+fn authorized(header: Option<&str>, token: &str) -> bool {
+    if token.is_empty() {
+        return true;
+    }
+    header
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided.trim().as_bytes(), token.as_bytes()))
+}
+
+/// Comparatie in timp constant (fata de lungimea token-ului) — un `==`
+/// obisnuit se opreste la primul octet diferit si scurge prefixul corect
+/// prin timpul de raspuns.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn sources(State(state): State<ApiState>) -> Json<Value> {
+    let sources: Vec<Value> = state.ctx.detector.tracked_sources().iter().map(source_json).collect();
+    Json(json!({ "count": sources.len(), "sources": sources }))
+}
+
+async fn source(State(state): State<ApiState>, Path(raw): Path<String>) -> Response {
+    let Ok(ip) = raw.parse::<IpAddr>() else {
+        return error(StatusCode::BAD_REQUEST, &format!("IP invalid: {}", raw));
+    };
+    let detector = &state.ctx.detector;
+    let ip_state = detector.ip_state(ip);
+    if ip_state.is_empty() {
+        return error(StatusCode::NOT_FOUND, &format!("{} nu este urmarit", ip));
+    }
+    let windows: Vec<Value> = detector
+        .tracked_source(ip)
+        .map(|s| s.windows.iter().map(window_json).collect())
+        .unwrap_or_default();
+    let cooldowns: Vec<Value> = ip_state
+        .cooldowns
+        .iter()
+        .map(|c| {
+            json!({
+                "detector": c.detector,
+                "key": c.key.to_string(),
+                "remaining_secs": c.remaining.as_secs(),
+                "mode": c.mode.as_str(),
+            })
+        })
+        .collect();
+    Json(json!({
+        "ip": ip.to_string(),
+        "whitelisted": ip_state.whitelisted,
+        "last_seen_secs": ip_state.last_seen.map(|d| d.as_secs()),
+        "windows": windows,
+        "drops": ip_state.drops.iter().map(hit_json).collect::<Vec<_>>(),
+        "accepts": ip_state.accepts.iter().map(hit_json).collect::<Vec<_>>(),
+        "lateral_dests": ips(&ip_state.lateral_dests),
+        "icmp_dests": ips(&ip_state.icmp_dests),
+        "scanned_by": ips(&ip_state.scanned_by),
+        "cooldowns": cooldowns,
+        "sessions": ip_state.sessions,
+    }))
+    .into_response()
+}
+
+async fn targets(State(state): State<ApiState>) -> Json<Value> {
+    let targets: Vec<Value> = state
+        .ctx
+        .detector
+        .distributed_targets()
+        .iter()
+        .map(|t| {
+            json!({
+                "ip": t.ip.to_string(),
+                "sources": ips(&t.sources),
+                "unique_sources": t.sources.len(),
+                "ports": t.ports,
+                "events": t.events,
+                "accepts": t.accepts,
+                "last_seen_secs": t.last_seen.as_secs(),
+                "cooling_down": t.cooling_down,
+            })
+        })
+        .collect();
+    Json(json!({ "count": targets.len(), "targets": targets }))
+}
+
+#[derive(Deserialize)]
+struct RecentParams {
+    limit: Option<usize>,
+}
+
+async fn recent_alerts(State(state): State<ApiState>, Query(params): Query<RecentParams>) -> Json<Value> {
+    let limit = params.limit.unwrap_or(DEFAULT_RECENT_ALERTS);
+    let config = state.ctx.config.load();
+    let alerts: Vec<Value> = state
+        .ctx
+        .live
+        .recent_alerts()
+        .iter()
+        .take(limit)
+        .map(|alert| alert_json(alert, &config))
+        .collect();
+    Json(json!({ "count": alerts.len(), "alerts": alerts }))
+}
+
+async fn config(State(state): State<ApiState>) -> Response {
+    match serde_json::to_value(state.ctx.config.load().redacted()) {
+        Ok(value) => Json(value).into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+async fn stats(State(state): State<ApiState>) -> Json<Value> {
+    let ctx = &state.ctx;
+    let counters = ctx.live.counters();
+    let parser = ctx.parser.load();
+    // Liniile recunoscute de fiecare parser; gol pentru un parser fix.
+    let parser_matches: serde_json::Map<String, Value> = parser
+        .match_counts()
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), json!(count)))
+        .collect();
+    Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "parser": parser.name(),
+        "parser_matches": parser_matches,
+        "started_at": ctx.started_at.to_rfc3339(),
+        "uptime_secs": ctx.started.elapsed().as_secs(),
+        "events": counters.events(),
+        "drops": counters.drops,
+        "accepts": counters.accepts,
+        "other": counters.other,
+        "parse_failures": counters.parse_failures,
+        "rate_limited": counters.rate_limited,
+        "alerts": counters.alerts,
+        "tracked_ips": ctx.detector.tracked_ips(),
+        "active_cooldowns": ctx.detector.active_cooldowns().len(),
+        "temp_whitelist": ctx.detector.temp_whitelist().len(),
+    }))
+}
+
+fn ips(list: &[IpAddr]) -> Vec<String> {
+    list.iter().map(|ip| ip.to_string()).collect()
+}
+
+fn source_json(s: &TrackedSource) -> Value {
+    json!({
+        "ip": s.ip.to_string(),
+        "profile": s.profile,
+        "last_seen_secs": s.last_seen.as_secs(),
+        "drops": s.drops,
+        "accepts": s.accepts,
+        "windows": s.windows.iter().map(window_json).collect::<Vec<_>>(),
+    })
+}
+
+fn window_json(w: &PortWindow) -> Value {
+    json!({
+        "detector": w.detector,
+        "window_secs": w.window.as_secs(),
+        "unique_ports": w.unique_ports,
+        "threshold": w.threshold,
+    })
+}
+
+fn hit_json(h: &HitSnapshot) -> Value {
+    json!({
+        "port": h.port,
+        "proto": h.proto.as_str(),
+        "dest_ip": h.dest_ip.map(|ip| ip.to_string()),
+        "age_secs": h.age.as_secs(),
+    })
+}
+
+/// O alerta ca JSON — SigID-ul, numele si descrierea sunt cele din CEF.
+fn alert_json(alert: &Alert, config: &AppConfig) -> Value {
+    let (sig_id, name, message) = alerter::describe(alert, &config.detection);
+    let source = match alert.source_prefix {
+        Some(prefix) => format!("{}/{}", alert.source_ip, prefix),
+        None => alert.source_ip.to_string(),
+    };
+    json!({
+        "timestamp": alert.timestamp.to_rfc3339(),
+        "type": alert.scan_type.key(),
+        "sig_id": sig_id,
+        "name": name,
+        "message": message,
+        "source_ip": source,
+        "dest_ip": alert.dest_ip.map(|ip| ip.to_string()),
+        "severity": alert.severity.level,
+        "severity_factors": alert.severity.factors,
+        "unique_ports": alert.unique_ports,
+        "unique_dests": ips(&alert.unique_dests),
+        "unique_sources": ips(&alert.unique_sources),
+        "threshold": alert.threshold,
+        "profile": alert.profile,
+        "rules": alert.rules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    #[test]
+    fn test_authorization_and_alert_json() {
+        // Fara token: orice cerere trece (API-ul este atunci doar pe loopback).
+        assert!(authorized(None, ""));
+        assert!(authorized(Some("Bearer orice"), ""));
+
+        // Cu token: doar `Bearer <token>` exact.
+        assert!(authorized(Some("Bearer s3cret"), "s3cret"));
+        assert!(!authorized(None, "s3cret"));
+        assert!(!authorized(Some("s3cret"), "s3cret"));
+        assert!(!authorized(Some("Bearer s3cre"), "s3cret"));
+        assert!(!authorized(Some("Bearer s3cretX"), "s3cret"));
+        assert!(!authorized(Some("Basic s3cret"), "s3cret"));
+
+        let config: AppConfig = toml::from_str(include_str!("../config.toml")).unwrap();
+        let src: IpAddr = "192.168.11.7".parse().unwrap();
+        let dst: IpAddr = "10.0.0.5".parse().unwrap();
+        let alert = Alert {
+            unique_ports: vec![22, 80, 443],
+            threshold: Some(3),
+            ..Alert::new(ScanType::Fast, src, Some(dst))
+        };
+        let value = alert_json(&alert, &config);
+        assert_eq!(value["type"], "fast_scan");
+        assert_eq!(value["sig_id"], "1001");
+        assert_eq!(value["source_ip"], "192.168.11.7");
+        assert_eq!(value["dest_ip"], "10.0.0.5");
+        assert_eq!(value["unique_ports"], json!([22, 80, 443]));
+        assert_eq!(value["threshold"], 3);
+    }
+
+    /// Porneste router-ul pe un port liber de pe loopback; intoarce adresa.
+    async fn serve(config: AppConfig) -> std::net::SocketAddr {
+        let ctx = ApiContext {
+            detector: Arc::new(Detector::new(config.detection.clone())),
+            live: Arc::new(LiveStats::new()),
+            parser: Arc::new(ArcSwap::from_pointee(crate::parser::create_parser("gaia").unwrap())),
+            config: Arc::new(ArcSwap::from_pointee(config.clone())),
+            started: Instant::now(),
+            started_at: Local::now(),
+        };
+        let app = router(ApiState { ctx, token: Arc::from(config.api.token.as_str()) });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    /// Un GET HTTP/1.1 minimal: (status, corpul raspunsului).
+    async fn get(addr: std::net::SocketAddr, path: &str, token: Option<&str>) -> (u16, String) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let auth = token.map(|t| format!("Authorization: Bearer {}\r\n", t)).unwrap_or_default();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n{}Connection: close\r\n\r\n", path, auth);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").map(|(_, b)| b.to_string()).unwrap_or_default();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn test_token_required_and_config_redacted() {
+        let mut config: AppConfig = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.api.token = "tok-9f2c".to_string();
+        config.alerting.email.password = "parola-smtp-secreta".to_string();
+        let addr = serve(config).await;
+
+        // Fara token si cu token gresit: 401 pe orice ruta.
+        for path in ["/stats", "/config", "/sources/10.0.0.1"] {
+            let (status, body) = get(addr, path, None).await;
+            assert_eq!(status, 401, "{} fara token", path);
+            assert!(body.contains("token lipsa sau invalid"));
+            assert_eq!(get(addr, path, Some("tok-gresit")).await.0, 401, "{} cu token gresit", path);
+        }
+
+        let (status, body) = get(addr, "/stats", Some("tok-9f2c")).await;
+        assert_eq!(status, 200);
+        let stats: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(stats["tracked_ips"], 0);
+
+        // `/config` nu contine niciun secret — nici parola SMTP, nici token-ul.
+        let (status, body) = get(addr, "/config", Some("tok-9f2c")).await;
+        assert_eq!(status, 200);
+        assert!(!body.contains("parola-smtp-secreta"));
+        assert!(!body.contains("tok-9f2c"));
+        let config: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(config["alerting"]["email"]["password"], "***");
+        assert_eq!(config["api"]["token"], "***");
+
+        assert_eq!(get(addr, "/sources/not-an-ip", Some("tok-9f2c")).await.0, 400);
+        assert_eq!(get(addr, "/sources/10.0.0.1", Some("tok-9f2c")).await.0, 404);
+    }
+}
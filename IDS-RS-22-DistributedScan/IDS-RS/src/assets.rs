@@ -0,0 +1,356 @@
+// =============================================================================
+// assets.rs - Inventarul de active (hostname, proprietar, criticitate, rol)
+// =============================================================================
+//
+// `[network.hostnames]` si `[network.subnets]` dau doar un nume si o locatie.
+// Analistul are nevoie si de "al cui este host-ul" si "cat de grav e daca e
+// atins". Inventarul este exportat din CMDB intr-un fisier local:
+//
+//   csv  → ip_or_cidr,hostname,owner,criticality,role — header-ul optional
+//          e ignorat, campurile goale sunt permise
+//   json → array de obiecte {"ip", "hostname", "owner", "criticality", "role"}
+//          sau {"assets": [...]}
+//
+// Criticitate: low / medium / high / critical (implicit medium).
+// Rol: server / workstation / printer / ot (sau orice alt text, ex: "camera").
+//
+// Inventarul imbogateste afisarea IP-urilor (CLI, email, CEF) si ridica
+// severitatea alertelor care vizeaza active critice (`[assets]` critical_boost).
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. #[derive(PartialOrd, Ord)] pe enum
+//    Ordinea variantelor din declaratie devine ordinea de comparatie:
+//    Low < Medium < High < Critical. `.max()` pe un iterator de Criticality
+//    alege direct activul cel mai critic, fara tabele de ranguri.
+//
+// 2. #[serde(untagged)]
+//    Radacina JSON poate fi un array sau un obiect cu cheia "assets".
+//    `untagged` incearca variantele in ordine si o alege pe prima care se
+//    deserializeaza — fara camp discriminator in fisier.
+//
+// =============================================================================
+
+use crate::config::{AssetsConfig, SubnetEntry};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+
+/// Criticitatea unui activ (ordonata: Low < Medium < High < Critical).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Criticality {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Criticality {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "low" => Some(Criticality::Low),
+            "medium" => Some(Criticality::Medium),
+            "high" => Some(Criticality::High),
+            "critical" => Some(Criticality::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Criticality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Criticality::Low => write!(f, "low"),
+            Criticality::Medium => write!(f, "medium"),
+            Criticality::High => write!(f, "high"),
+            Criticality::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// Un activ din inventar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub hostname: Option<String>,
+    /// Echipa proprietara (ex: "Infra Windows").
+    pub owner: Option<String>,
+    pub criticality: Criticality,
+    /// Rolul: server, workstation, printer, ot, ...
+    pub role: Option<String>,
+}
+
+impl Asset {
+    /// Forma compacta pentru CLI: "Infra · critical · server".
+    pub fn tag(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref owner) = self.owner {
+            parts.push(owner.clone());
+        }
+        parts.push(self.criticality.to_string());
+        if let Some(ref role) = self.role {
+            parts.push(role.clone());
+        }
+        parts.join(" · ")
+    }
+
+    /// Forma lunga pentru email si msg CEF:
+    /// "srv-dc01, echipa Infra, criticitate critical, rol server".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref hostname) = self.hostname {
+            parts.push(hostname.clone());
+        }
+        if let Some(ref owner) = self.owner {
+            parts.push(format!("echipa {}", owner));
+        }
+        parts.push(format!("criticitate {}", self.criticality));
+        if let Some(ref role) = self.role {
+            parts.push(format!("rol {}", role));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Intrare bruta din fisier (inainte de validarea IP/CIDR).
+#[derive(Debug, Deserialize)]
+struct RawAsset {
+    ip: String,
+    #[serde(default)]
+    hostname: Option<String>,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    criticality: Option<String>,
+    #[serde(default)]
+    role: Option<String>,
+}
+
+/// Radacina fisierului JSON: array direct sau `{"assets": [...]}`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonRoot {
+    List(Vec<RawAsset>),
+    Wrapped { assets: Vec<RawAsset> },
+}
+
+/// Inventarul indexat pentru lookup rapid (aceeasi structura ca threat intel).
+#[derive(Debug, Default)]
+pub struct AssetInventory {
+    /// IP-uri individuale — lookup O(1).
+    exact: HashMap<IpAddr, Asset>,
+    /// Subretele CIDR — parcurse linear, longest prefix match.
+    cidrs: Vec<(SubnetEntry, Asset)>,
+    /// Cresterile de severitate din `[assets]` (critical_boost, high_boost).
+    critical_boost: u8,
+    high_boost: u8,
+    /// Intrari incarcate / ignorate (IP invalid, criticitate necunoscuta).
+    pub loaded: usize,
+    pub skipped: usize,
+}
+
+impl AssetInventory {
+    /// Incarca inventarul din configurare.
+    ///
+    /// Daca inventarul este dezactivat, returneaza unul gol. Un fisier lipsa
+    /// sau un JSON invalid este eroare (fatal la pornire, warning la SIGHUP —
+    /// inventarul vechi ramane activ).
+    pub fn load(config: &AssetsConfig) -> Result<Self> {
+        let mut inventory = AssetInventory {
+            critical_boost: config.critical_boost,
+            high_boost: config.high_boost,
+            ..AssetInventory::default()
+        };
+        if !config.enabled {
+            return Ok(inventory);
+        }
+        let content = std::fs::read_to_string(&config.path)
+            .with_context(|| format!("Nu pot citi inventarul de active: {:?}", config.path))?;
+        inventory.add(&config.format, &content)?;
+        Ok(inventory)
+    }
+
+    /// Parseaza continutul fisierului si il adauga in index.
+    fn add(&mut self, format: &str, content: &str) -> Result<()> {
+        let (raw, mut skipped) = match format {
+            "json" => {
+                let root: JsonRoot = serde_json::from_str(content)
+                    .context("Inventarul de active nu este un JSON valid")?;
+                match root {
+                    JsonRoot::List(items) | JsonRoot::Wrapped { assets: items } => (items, 0),
+                }
+            }
+            _ => parse_csv(content),
+        };
+
+        for item in raw {
+            let Some(subnet) = SubnetEntry::parse_ip_or_cidr(item.ip.trim()) else {
+                skipped += 1;
+                continue;
+            };
+            let criticality = match item.criticality.as_deref().map(str::trim) {
+                None | Some("") => Criticality::Medium,
+                Some(value) => match Criticality::parse(value) {
+                    Some(c) => c,
+                    None => {
+                        skipped += 1;
+                        continue;
+                    }
+                },
+            };
+            let non_empty = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            let asset = Asset {
+                hostname: non_empty(item.hostname),
+                owner: non_empty(item.owner),
+                criticality,
+                role: non_empty(item.role).map(|r| r.to_lowercase()),
+            };
+            self.loaded += 1;
+
+            // IP individual (/32 sau /128) → HashMap; dublura: ultima intrare castiga.
+            let host_ip = item
+                .ip
+                .trim()
+                .split('/')
+                .next()
+                .and_then(|ip| ip.parse::<IpAddr>().ok())
+                .filter(|ip| subnet.prefix_len() == if ip.is_ipv4() { 32 } else { 128 });
+            match host_ip {
+                Some(ip) => {
+                    self.exact.insert(ip, asset);
+                }
+                None => self.cidrs.push((subnet, asset)),
+            }
+        }
+        self.skipped += skipped;
+        Ok(())
+    }
+
+    /// Activul unui IP: IP-ul exact are prioritate, apoi subnetul cu prefixul
+    /// cel mai lung.
+    pub fn lookup(&self, ip: &IpAddr) -> Option<&Asset> {
+        if let Some(asset) = self.exact.get(ip) {
+            return Some(asset);
+        }
+        self.cidrs
+            .iter()
+            .filter(|(subnet, _)| subnet.matches(ip))
+            .max_by_key(|(subnet, _)| subnet.prefix_len())
+            .map(|(_, asset)| asset)
+    }
+
+    /// Cel mai critic activ dintre tinte (None daca niciuna nu e inventariata).
+    pub fn most_critical<'a>(&self, targets: impl IntoIterator<Item = &'a IpAddr>) -> Option<&Asset> {
+        targets
+            .into_iter()
+            .filter_map(|ip| self.lookup(ip))
+            .max_by_key(|asset| asset.criticality)
+    }
+
+    /// Cresterea de severitate pentru o tinta cu criticitatea data.
+    pub fn severity_boost(&self, criticality: Criticality) -> u8 {
+        match criticality {
+            Criticality::Critical => self.critical_boost,
+            Criticality::High => self.high_boost,
+            _ => 0,
+        }
+    }
+}
+
+/// Format CSV: `ip_or_cidr,hostname,owner,criticality,role`.
+///
+/// Prima linie este considerata header daca primul camp nu este IP/CIDR valid.
+fn parse_csv(content: &str) -> (Vec<RawAsset>, usize) {
+    let mut raw = Vec::new();
+    let mut skipped = 0;
+    let mut first = true;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(',').map(|f| f.trim().trim_matches('"').to_string());
+        let ip = fields.next().unwrap_or_default();
+
+        if first {
+            first = false;
+            if SubnetEntry::parse_ip_or_cidr(&ip).is_none() {
+                continue; // header
+            }
+        }
+        if ip.is_empty() {
+            skipped += 1;
+            continue;
+        }
+
+        raw.push(RawAsset {
+            ip,
+            hostname: fields.next(),
+            owner: fields.next(),
+            criticality: fields.next(),
+            role: fields.next(),
+        });
+    }
+
+    (raw, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_inventory_header_and_defaults() {
+        let mut inventory = AssetInventory::default();
+        let content = "ip,hostname,owner,criticality,role\n\
+                       10.0.1.10,srv-dc01,Infra Windows,critical,server\n\
+                       10.0.50.0/24,,Productie,High,OT\n\
+                       10.0.2.7,ws-007,,,workstation\n\
+                       10.0.3.1,x,y,foarte,server\n\
+                       nu-e-ip,a,b,low,printer\n";
+        inventory.add("csv", content).unwrap();
+
+        assert_eq!(inventory.loaded, 3);
+        assert_eq!(inventory.skipped, 2);
+        let dc = inventory.lookup(&"10.0.1.10".parse().unwrap()).unwrap();
+        assert_eq!(dc.criticality, Criticality::Critical);
+        assert_eq!(dc.tag(), "Infra Windows · critical · server");
+        assert_eq!(dc.summary(), "srv-dc01, echipa Infra Windows, criticitate critical, rol server");
+        let plc = inventory.lookup(&"10.0.50.9".parse().unwrap()).unwrap();
+        assert_eq!((plc.hostname.as_deref(), plc.role.as_deref()), (None, Some("ot")));
+        // Criticitate lipsa → medium.
+        assert_eq!(inventory.lookup(&"10.0.2.7".parse().unwrap()).unwrap().criticality, Criticality::Medium);
+        assert!(inventory.lookup(&"10.9.9.9".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_json_inventory_and_longest_prefix() {
+        let mut inventory = AssetInventory::load(&AssetsConfig::default()).unwrap();
+        let content = r#"{"assets": [
+            {"ip": "10.0.0.0/8", "owner": "IT", "criticality": "low"},
+            {"ip": "10.0.5.0/24", "owner": "Tipografie", "role": "printer"},
+            {"ip": "2001:db8::10", "hostname": "web6", "criticality": "high", "role": "server"}
+        ]}"#;
+        inventory.add("json", content).unwrap();
+
+        assert_eq!(inventory.loaded, 3);
+        assert_eq!(inventory.lookup(&"10.0.5.3".parse().unwrap()).unwrap().owner.as_deref(), Some("Tipografie"));
+        assert_eq!(inventory.lookup(&"10.1.0.1".parse().unwrap()).unwrap().owner.as_deref(), Some("IT"));
+
+        let targets: Vec<IpAddr> = vec!["10.1.0.1".parse().unwrap(), "2001:db8::10".parse().unwrap()];
+        let top = inventory.most_critical(&targets).unwrap();
+        assert_eq!(top.hostname.as_deref(), Some("web6"));
+        // Boost-urile implicite: high +1, critical +2, restul 0.
+        assert_eq!(inventory.severity_boost(top.criticality), 1);
+        assert_eq!(inventory.severity_boost(Criticality::Critical), 2);
+        assert_eq!(inventory.severity_boost(Criticality::Low), 0);
+
+        // Array direct la radacina; JSON invalid → eroare.
+        let mut list = AssetInventory::default();
+        list.add("json", r#"[{"ip": "10.0.0.1"}]"#).unwrap();
+        assert_eq!(list.loaded, 1);
+        assert!(list.add("json", "{ nu e json").is_err());
+    }
+}
@@ -0,0 +1,509 @@
+// =============================================================================
+// baseline.rs - Baseline adaptiv per sursa / subnet (mod anomalie)
+// =============================================================================
+//
+// Pragurile fixe din [detection.fast_scan] etc. sunt aceleasi pentru toate
+// host-urile. Acest modul invata comportamentul NORMAL al fiecarei surse
+// (sau al fiecarui subnet etichetat) si semnaleaza abaterile:
+//
+//   1. Evenimentele sunt grupate in intervale de `bucket_secs` per cheie
+//   2. La inchiderea unui interval, metricile lui actualizeaza baseline-ul:
+//        - porturi unice, destinatii unice, proportia de drop-uri
+//   3. In timpul intervalului curent, fiecare eveniment compara metricile
+//      partiale cu baseline-ul: abatere > N deviatii standard → alerta
+//
+// Intervalele care au generat alerta NU sunt invatate — altfel un atacator
+// persistent ar deveni "normal" dupa cateva intervale.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. MEDIE SI VARIANTA EXPONENTIALA (EWMA / EWMVar)
+//    In loc sa pastram istoricul complet (memorie O(n) per sursa), pastram
+//    doar media si varianta, actualizate incremental:
+//        diff  = x - mean
+//        mean += alpha * diff
+//        var   = (1 - alpha) * (var + alpha * diff²)
+//    Memorie O(1) per metrica. Intervalele vechi conteaza exponential mai putin.
+//
+// 2. SERIALIZE / DESERIALIZE (serde)
+//    `#[derive(Serialize, Deserialize)]` genereaza codul de conversie
+//    struct ↔ JSON. `Instant` nu este serializabil (e relativ la boot-ul
+//    masinii), deci pentru persistenta folosim timestamp Unix (i64).
+//
+// =============================================================================
+
+use crate::config::AnomalyConfig;
+use crate::parser::LogEvent;
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Deviatia standard minima pentru proportia de drop-uri (valoare in [0, 1]).
+/// `min_stddev` din config este exprimat in porturi/destinatii, nu se aplica aici.
+const MIN_RATIO_STDDEV: f64 = 0.05;
+
+/// Numarul maxim de chei (surse / subnete) cu baseline sau interval deschis.
+/// Peste limita, cheile noi sunt ignorate pana cand `flush` sau `remove`
+/// elibereaza locuri (protectie memorie si dimensiune fisier de stare).
+const MAX_KEYS: usize = 100_000;
+
+/// Versiunea formatului fisierului de stare — crestem la schimbari incompatibile.
+const STATE_VERSION: u32 = 1;
+
+/// Media si varianta mobila exponentiala a unei metrici.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Ewma {
+    mean: f64,
+    var: f64,
+}
+
+impl Ewma {
+    fn update(&mut self, x: f64, alpha: f64, first: bool) {
+        if first {
+            self.mean = x;
+            self.var = 0.0;
+            return;
+        }
+        let diff = x - self.mean;
+        self.mean += alpha * diff;
+        self.var = (1.0 - alpha) * (self.var + alpha * diff * diff);
+    }
+
+    fn stddev(&self) -> f64 {
+        self.var.max(0.0).sqrt()
+    }
+}
+
+/// Baseline-ul invatat pentru o cheie (IP sursa sau subnet).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Baseline {
+    /// Numarul de intervale invatate (porturi/destinatii).
+    samples: u64,
+    /// Numarul de intervale cu suficiente evenimente pentru proportia de drop-uri.
+    ratio_samples: u64,
+    ports: Ewma,
+    dests: Ewma,
+    drop_ratio: Ewma,
+    /// Ultima actualizare (Unix timestamp, secunde).
+    updated_at: i64,
+}
+
+/// Intervalul de masurare curent pentru o cheie.
+struct Bucket {
+    started: Instant,
+    ports: HashSet<u16>,
+    dests: HashSet<IpAddr>,
+    drops: u64,
+    total: u64,
+    /// Intervalul a generat deja o alerta: nu mai alertam si nu il invatam.
+    alerted: bool,
+}
+
+impl Bucket {
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            ports: HashSet::new(),
+            dests: HashSet::new(),
+            drops: 0,
+            total: 0,
+            alerted: false,
+        }
+    }
+}
+
+/// Detaliile unei abateri de la baseline — transmise in alerta.
+#[derive(Debug, Clone)]
+pub struct AnomalyDetail {
+    /// Cheia baseline-ului: IP-ul sursa sau "subnet:<eticheta>".
+    pub baseline_key: String,
+    /// Metrica cu cea mai mare abatere: unique_ports, unique_dests, drop_ratio.
+    pub metric: &'static str,
+    pub observed: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    /// Abaterea exprimata in deviatii standard.
+    pub sigmas: f64,
+    /// Numarul de intervale din care a fost invatat baseline-ul.
+    pub samples: u64,
+}
+
+/// Rezultatul unei evaluari care a depasit baseline-ul.
+pub struct AnomalyHit {
+    pub detail: AnomalyDetail,
+    /// Porturile unice din intervalul curent (sortate).
+    pub ports: Vec<u16>,
+    /// Destinatiile unice din intervalul curent.
+    pub dests: Vec<IpAddr>,
+}
+
+/// Formatul fisierului de stare (JSON).
+#[derive(Serialize, Deserialize)]
+struct StateFile {
+    version: u32,
+    baselines: HashMap<String, Baseline>,
+}
+
+/// Depozitul de baseline-uri si intervalele curente, partajat prin `&self`.
+pub struct BaselineStore {
+    baselines: DashMap<String, Baseline>,
+    buckets: DashMap<String, Bucket>,
+    /// Limita pentru fiecare din cele doua map-uri (`MAX_KEYS`).
+    max_keys: usize,
+}
+
+impl BaselineStore {
+    pub fn new() -> Self {
+        Self::with_max_keys(MAX_KEYS)
+    }
+
+    fn with_max_keys(max_keys: usize) -> Self {
+        Self {
+            baselines: DashMap::new(),
+            buckets: DashMap::new(),
+            max_keys,
+        }
+    }
+
+    /// Sterge baseline-ul si intervalul curent al cheii — sursa evacuata
+    /// (LRU) sau `ids-rs ctl clear-state`.
+    pub fn remove(&self, key: &str) {
+        self.baselines.remove(key);
+        self.buckets.remove(key);
+    }
+
+    /// Cheia are baseline sau interval deschis (teste).
+    #[cfg(test)]
+    pub fn contains(&self, key: &str) -> bool {
+        self.baselines.contains_key(key) || self.buckets.contains_key(key)
+    }
+
+    /// Inregistreaza un eveniment si evalueaza intervalul curent fata de baseline.
+    ///
+    /// Returneaza `Some` cel mult o data per interval (prima abatere detectata).
+    pub fn observe(
+        &self,
+        key: &str,
+        event: &LogEvent,
+        now: Instant,
+        cfg: &AnomalyConfig,
+    ) -> Option<AnomalyHit> {
+        let bucket_len = Duration::from_secs(cfg.bucket_secs);
+        if !self.buckets.contains_key(key) && self.buckets.len() >= self.max_keys {
+            return None;
+        }
+
+        // NOTA RUST: `RefMut` pe `buckets` ramane activ pana la finalul functiei.
+        // `learn()` si `baselines.get()` opereaza pe ALT DashMap → fara deadlock.
+        let mut bucket = self
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket::new(now));
+
+        if now.saturating_duration_since(bucket.started) >= bucket_len {
+            let finished = std::mem::replace(&mut *bucket, Bucket::new(now));
+            if !finished.alerted {
+                self.learn(key, &finished, cfg);
+            }
+        }
+
+        bucket.ports.insert(event.dest_port);
+        if let Some(dest) = event.dest_ip {
+            bucket.dests.insert(dest);
+        }
+        bucket.total += 1;
+        if event.action == "drop" {
+            bucket.drops += 1;
+        }
+
+        if bucket.alerted {
+            return None;
+        }
+
+        let baseline = self.baselines.get(key)?;
+        if baseline.samples < cfg.min_samples {
+            return None;
+        }
+
+        let mut candidates = vec![
+            ("unique_ports", bucket.ports.len() as f64, baseline.ports, cfg.min_stddev),
+            ("unique_dests", bucket.dests.len() as f64, baseline.dests, cfg.min_stddev),
+        ];
+        if bucket.total >= cfg.min_events && baseline.ratio_samples >= cfg.min_samples {
+            let ratio = bucket.drops as f64 / bucket.total as f64;
+            candidates.push(("drop_ratio", ratio, baseline.drop_ratio, MIN_RATIO_STDDEV));
+        }
+
+        let detail = candidates
+            .into_iter()
+            .map(|(metric, observed, ewma, floor)| {
+                let stddev = ewma.stddev().max(floor);
+                AnomalyDetail {
+                    baseline_key: key.to_string(),
+                    metric,
+                    observed,
+                    mean: ewma.mean,
+                    stddev,
+                    sigmas: (observed - ewma.mean) / stddev,
+                    samples: baseline.samples,
+                }
+            })
+            .filter(|d| d.sigmas > cfg.stddev_threshold)
+            .max_by(|a, b| a.sigmas.total_cmp(&b.sigmas))?;
+
+        bucket.alerted = true;
+        let mut ports: Vec<u16> = bucket.ports.iter().copied().collect();
+        ports.sort_unstable();
+        Some(AnomalyHit {
+            detail,
+            ports,
+            dests: bucket.dests.iter().copied().collect(),
+        })
+    }
+
+    /// Actualizeaza baseline-ul cu metricile unui interval inchis.
+    fn learn(&self, key: &str, bucket: &Bucket, cfg: &AnomalyConfig) {
+        if bucket.total == 0 {
+            return;
+        }
+        if !self.baselines.contains_key(key) && self.baselines.len() >= self.max_keys {
+            return;
+        }
+        let alpha = cfg.learning_rate;
+        let mut baseline = self.baselines.entry(key.to_string()).or_default();
+        let first = baseline.samples == 0;
+        baseline.ports.update(bucket.ports.len() as f64, alpha, first);
+        baseline.dests.update(bucket.dests.len() as f64, alpha, first);
+        baseline.samples += 1;
+
+        if bucket.total >= cfg.min_events {
+            let ratio = bucket.drops as f64 / bucket.total as f64;
+            let first_ratio = baseline.ratio_samples == 0;
+            baseline.drop_ratio.update(ratio, alpha, first_ratio);
+            baseline.ratio_samples += 1;
+        }
+        baseline.updated_at = chrono::Utc::now().timestamp();
+    }
+
+    /// Inchide intervalele expirate (surse care nu au mai trimis evenimente)
+    /// si sterge baseline-urile neactualizate de `max_idle_days`.
+    ///
+    /// Apelat din `Detector::cleanup()`. Fara acest pas, ultimul interval al unei
+    /// surse care tace ar ramane in memorie si nu ar fi invatat niciodata.
+    pub fn flush(&self, now: Instant, cfg: &AnomalyConfig) {
+        let bucket_len = Duration::from_secs(cfg.bucket_secs);
+        let expired: Vec<String> = self
+            .buckets
+            .iter()
+            .filter(|e| now.saturating_duration_since(e.started) >= bucket_len)
+            .map(|e| e.key().clone())
+            .collect();
+        for key in expired {
+            if let Some((key, bucket)) = self.buckets.remove(&key) {
+                if !bucket.alerted {
+                    self.learn(&key, &bucket, cfg);
+                }
+            }
+        }
+
+        let cutoff = chrono::Utc::now().timestamp() - (cfg.max_idle_days * 86_400) as i64;
+        self.baselines.retain(|_, b| b.updated_at >= cutoff);
+    }
+
+    /// Incarca baseline-urile din fisierul de stare.
+    ///
+    /// Fisier inexistent = prima pornire, nu eroare. Returneaza numarul de
+    /// baseline-uri incarcate.
+    pub fn load(&self, path: &str) -> Result<usize> {
+        if path.is_empty() || !std::path::Path::new(path).exists() {
+            return Ok(0);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Nu pot citi fisierul de baseline {:?}", path))?;
+        let state: StateFile = serde_json::from_str(&content)
+            .with_context(|| format!("Fisierul de baseline {:?} este corupt", path))?;
+        if state.version != STATE_VERSION {
+            anyhow::bail!(
+                "Fisierul de baseline {:?} are versiunea {} (asteptat {})",
+                path, state.version, STATE_VERSION
+            );
+        }
+        // Un fisier scris de o versiune fara limita poate avea mai multe chei;
+        // le pastram pe cele actualizate cel mai recent.
+        let mut baselines: Vec<(String, Baseline)> = state.baselines.into_iter().collect();
+        baselines.sort_unstable_by_key(|(_, b)| std::cmp::Reverse(b.updated_at));
+        baselines.truncate(self.max_keys);
+        let count = baselines.len();
+        for (key, baseline) in baselines {
+            self.baselines.insert(key, baseline);
+        }
+        Ok(count)
+    }
+
+    /// Salveaza baseline-urile in fisierul de stare.
+    ///
+    /// Scriem intr-un fisier temporar si apoi `rename` — atomic pe acelasi
+    /// filesystem: o oprire in timpul scrierii nu lasa un JSON trunchiat.
+    pub fn save(&self, path: &str) -> Result<()> {
+        if path.is_empty() {
+            return Ok(());
+        }
+        let state = StateFile {
+            version: STATE_VERSION,
+            baselines: self
+                .baselines
+                .iter()
+                .map(|e| (e.key().clone(), e.value().clone()))
+                .collect(),
+        };
+        let json = serde_json::to_string(&state)?;
+        let tmp = format!("{}.tmp", path);
+        std::fs::write(&tmp, json)
+            .with_context(|| format!("Nu pot scrie fisierul de baseline {:?}", tmp))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Nu pot redenumi {:?} in {:?}", tmp, path))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg() -> AnomalyConfig {
+        AnomalyConfig {
+            enabled: true,
+            bucket_secs: 10,
+            learning_rate: 0.2,
+            min_samples: 3,
+            ..AnomalyConfig::default()
+        }
+    }
+
+    fn event(port: u16, action: &str) -> LogEvent {
+        LogEvent::new(
+            "10.0.1.5".parse().unwrap(),
+            Some("10.0.0.1".parse().unwrap()),
+            port,
+            "tcp".to_string(),
+            action.to_string(),
+            String::new(),
+        )
+    }
+
+    /// Invata `intervals` intervale, fiecare cu porturile 80 si 443.
+    fn train(store: &BaselineStore, cfg: &AnomalyConfig, start: Instant, intervals: u64) -> Instant {
+        let mut t = start;
+        for _ in 0..intervals {
+            store.observe("10.0.1.5", &event(80, "accept"), t, cfg);
+            store.observe("10.0.1.5", &event(443, "accept"), t, cfg);
+            t += Duration::from_secs(cfg.bucket_secs);
+        }
+        t
+    }
+
+    #[test]
+    fn test_no_alert_during_warmup() {
+        let store = BaselineStore::new();
+        let cfg = cfg();
+        let t = train(&store, &cfg, Instant::now(), 2);
+        // Doar 1 interval inchis (al doilea e inca deschis) < min_samples.
+        for port in 1000..1020 {
+            assert!(store.observe("10.0.1.5", &event(port, "drop"), t, &cfg).is_none());
+        }
+    }
+
+    #[test]
+    fn test_port_spike_alerts_once_per_bucket() {
+        let store = BaselineStore::new();
+        let cfg = cfg();
+        let t = train(&store, &cfg, Instant::now(), 5);
+
+        let mut hits = 0;
+        for port in 1000..1010 {
+            if let Some(hit) = store.observe("10.0.1.5", &event(port, "accept"), t, &cfg) {
+                assert_eq!(hit.detail.metric, "unique_ports");
+                assert!(hit.detail.sigmas > cfg.stddev_threshold);
+                hits += 1;
+            }
+        }
+        assert_eq!(hits, 1, "O singura alerta per interval");
+    }
+
+    #[test]
+    fn test_normal_behaviour_no_alert() {
+        let store = BaselineStore::new();
+        let cfg = cfg();
+        let t = train(&store, &cfg, Instant::now(), 5);
+        assert!(store.observe("10.0.1.5", &event(80, "accept"), t, &cfg).is_none());
+        assert!(store.observe("10.0.1.5", &event(443, "accept"), t, &cfg).is_none());
+    }
+
+    #[test]
+    fn test_alerted_bucket_not_learned() {
+        let store = BaselineStore::new();
+        let cfg = cfg();
+        let mut t = train(&store, &cfg, Instant::now(), 5);
+        let samples_before = store.baselines.get("10.0.1.5").unwrap().samples;
+
+        for port in 1000..1010 {
+            store.observe("10.0.1.5", &event(port, "accept"), t, &cfg);
+        }
+        t += Duration::from_secs(cfg.bucket_secs);
+        store.observe("10.0.1.5", &event(80, "accept"), t, &cfg);
+
+        let baseline = store.baselines.get("10.0.1.5").unwrap();
+        // Intervalul cu alerta nu a fost invatat; ultimul interval normal (deschis
+        // in train) a fost inchis de primul eveniment al intervalului cu alerta.
+        assert_eq!(baseline.samples, samples_before + 1);
+        assert!(baseline.ports.mean < 3.0);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let store = BaselineStore::new();
+        let cfg = cfg();
+        train(&store, &cfg, Instant::now(), 5);
+
+        let path = std::env::temp_dir().join(format!("ids-rs-baseline-{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        store.save(&path).unwrap();
+
+        let restored = BaselineStore::new();
+        assert_eq!(restored.load(&path).unwrap(), 1);
+        assert_eq!(
+            restored.baselines.get("10.0.1.5").unwrap().samples,
+            store.baselines.get("10.0.1.5").unwrap().samples
+        );
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_max_keys_and_remove() {
+        let store = BaselineStore::with_max_keys(2);
+        let cfg = cfg();
+        let t = Instant::now();
+        for key in ["10.0.1.1", "10.0.1.2", "10.0.1.3"] {
+            store.observe(key, &event(80, "accept"), t, &cfg);
+        }
+        assert_eq!(store.buckets.len(), 2, "cheia peste limita este ignorata");
+        assert!(!store.buckets.contains_key("10.0.1.3"));
+
+        store.flush(t + Duration::from_secs(cfg.bucket_secs), &cfg);
+        assert_eq!(store.baselines.len(), 2);
+
+        store.remove("10.0.1.1");
+        assert_eq!(store.baselines.len(), 1);
+        store.observe("10.0.1.3", &event(80, "accept"), t, &cfg);
+        assert!(store.buckets.contains_key("10.0.1.3"), "locul eliberat este refolosit");
+    }
+
+    #[test]
+    fn test_load_missing_file_is_not_error() {
+        let store = BaselineStore::new();
+        assert_eq!(store.load("/nonexistent/ids-rs-baseline.json").unwrap(), 0);
+    }
+}
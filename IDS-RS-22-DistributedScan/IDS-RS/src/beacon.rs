@@ -0,0 +1,260 @@
+// =============================================================================
+// beacon.rs - Detectie beaconing C2 (conexiuni periodice)
+// =============================================================================
+//
+// Celelalte detectoare masoara fan-out: cate porturi, cate destinatii, cate
+// surse. Un implant C2 nu face fan-out — contacteaza ACEEASI destinatie pe
+// ACELASI port, la intervale regulate (ex: la fiecare 60 s ± cateva secunde).
+// Fiecare conexiune in parte este acceptata de firewall si pare legitima;
+// regularitatea intervalelor este semnatura.
+//
+// Pentru fiecare tuplu (sursa, destinatie, port) din evenimentele accept:
+//   1. Hit-urile apropiate (< min_interval_secs) formeaza o singura sesiune
+//   2. Se retin momentele de inceput ale ultimelor MAX_SESSIONS sesiuni
+//   3. Din intervalele dintre sesiuni se calculeaza media si deviatia standard
+//   4. Coeficient de variatie (stddev / medie) ≤ max_jitter → beacon
+//
+// Un tuplu este raportat o singura data. Raportarea se rearmeaza doar dupa
+// ce tuplul tace mai mult de `max_interval_secs` (masurarea reporneste).
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. ZIP PE ACELASI ITERATOR DECALAT
+//    `VecDeque` nu are `.windows(2)` (memoria nu e contigua). Perechile de
+//    elemente consecutive se obtin cu `iter().zip(iter().skip(1))`.
+//
+// 2. COEFICIENT DE VARIATIE
+//    Deviatia standard singura depinde de scara: 5 s jitter inseamna mult la
+//    un beacon de 10 s si nimic la unul de o ora. Raportul stddev / medie este
+//    adimensional — acelasi prag functioneaza pentru orice interval.
+//
+// =============================================================================
+
+use crate::config::BeaconingConfig;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Numarul maxim de sesiuni retinute per tuplu.
+const MAX_SESSIONS: usize = 64;
+
+/// Numarul maxim de tupluri urmarite. Peste limita, tuplurile noi sunt
+/// ignorate pana la urmatorul cleanup (protectie memorie).
+const MAX_FLOWS: usize = 200_000;
+
+/// Detaliile unui beacon — atasate alertei Beaconing.
+#[derive(Debug, Clone)]
+pub struct BeaconDetail {
+    pub port: u16,
+    /// Numarul de sesiuni masurate.
+    pub connections: usize,
+    /// Intervalul mediu intre sesiuni (secunde).
+    pub mean_interval_secs: f64,
+    /// Coeficientul de variatie al intervalelor (0.05 = 5%).
+    pub jitter: f64,
+    /// Durata dintre prima si ultima sesiune (secunde).
+    pub duration_secs: u64,
+}
+
+/// Starea unui tuplu (sursa, destinatie, port).
+struct Flow {
+    /// Momentele de inceput ale sesiunilor, in ordine cronologica.
+    starts: VecDeque<Instant>,
+    /// Ultimul hit (inceputul sau continuarea unei sesiuni).
+    last_seen: Instant,
+    /// Tuplul a fost deja raportat ca beacon.
+    reported: bool,
+}
+
+/// Tuplurile urmarite pentru detectia beaconing.
+pub struct BeaconTracker {
+    flows: DashMap<(IpAddr, IpAddr, u16), Flow>,
+}
+
+impl BeaconTracker {
+    pub fn new() -> Self {
+        Self {
+            flows: DashMap::new(),
+        }
+    }
+
+    /// Inregistreaza o conexiune acceptata.
+    ///
+    /// Returneaza `Some` la prima masurare a tuplului care indeplineste toate
+    /// conditiile de beacon.
+    pub fn record(
+        &self,
+        src: IpAddr,
+        dst: IpAddr,
+        port: u16,
+        now: Instant,
+        cfg: &BeaconingConfig,
+    ) -> Option<BeaconDetail> {
+        let key = (src, dst, port);
+        if !self.flows.contains_key(&key) && self.flows.len() >= MAX_FLOWS {
+            return None;
+        }
+
+        let mut flow = self.flows.entry(key).or_insert_with(|| Flow {
+            starts: VecDeque::new(),
+            last_seen: now,
+            reported: false,
+        });
+
+        if !flow.starts.is_empty() {
+            let gap = now.saturating_duration_since(flow.last_seen);
+            if gap > Duration::from_secs(cfg.max_interval_secs) {
+                // Tuplul a tacut prea mult — masurarea reporneste.
+                flow.starts.clear();
+                flow.reported = false;
+            } else if gap < Duration::from_secs(cfg.min_interval_secs) {
+                // Continuarea sesiunii curente (alt log al aceleiasi conexiuni
+                // sau trafic continuu) — nu este o conexiune noua.
+                flow.last_seen = now;
+                return None;
+            }
+        }
+
+        flow.last_seen = now;
+        flow.starts.push_back(now);
+        if flow.starts.len() > MAX_SESSIONS {
+            flow.starts.pop_front();
+        }
+
+        if flow.reported || flow.starts.len() < cfg.min_connections {
+            return None;
+        }
+        let first = *flow.starts.front()?;
+        let duration = now.saturating_duration_since(first);
+        if duration < Duration::from_secs(cfg.min_duration_secs) {
+            return None;
+        }
+
+        let intervals: Vec<f64> = flow
+            .starts
+            .iter()
+            .zip(flow.starts.iter().skip(1))
+            .map(|(a, b)| b.saturating_duration_since(*a).as_secs_f64())
+            .collect();
+        let n = intervals.len() as f64;
+        let mean = intervals.iter().sum::<f64>() / n;
+        if mean <= 0.0 {
+            return None;
+        }
+        let variance = intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n;
+        let jitter = variance.sqrt() / mean;
+        if jitter > cfg.max_jitter {
+            return None;
+        }
+
+        flow.reported = true;
+        Some(BeaconDetail {
+            port,
+            connections: flow.starts.len(),
+            mean_interval_secs: mean,
+            jitter,
+            duration_secs: duration.as_secs(),
+        })
+    }
+
+    /// Sterge tuplurile tacute de mai mult de `max_interval_secs`.
+    pub fn cleanup(&self, now: Instant, cfg: &BeaconingConfig) {
+        let max_gap = Duration::from_secs(cfg.max_interval_secs);
+        self.flows
+            .retain(|_, f| now.saturating_duration_since(f.last_seen) <= max_gap);
+    }
+
+    /// Sterge toate tuplurile unei surse (LRU eviction in Detector).
+    pub fn remove_source(&self, ip: &IpAddr) {
+        self.flows.retain(|(src, _, _), _| src != ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg() -> BeaconingConfig {
+        BeaconingConfig {
+            enabled: true,
+            min_connections: 6,
+            min_duration_secs: 240,
+            max_jitter: 0.1,
+            ..BeaconingConfig::default()
+        }
+    }
+
+    fn ips() -> (IpAddr, IpAddr) {
+        ("10.0.0.9".parse().unwrap(), "203.0.113.50".parse().unwrap())
+    }
+
+    #[test]
+    fn test_regular_interval_reported_once() {
+        let tracker = BeaconTracker::new();
+        let (src, dst) = ips();
+        let t0 = Instant::now();
+
+        // 60 s ± 2 s: sesiunile 1..5 nu ajung la min_connections.
+        let offsets = [0, 61, 119, 180, 242, 300, 361];
+        let mut hits = Vec::new();
+        for secs in offsets {
+            hits.push(tracker.record(src, dst, 443, t0 + Duration::from_secs(secs), &cfg()));
+        }
+        assert!(hits[..5].iter().all(|h| h.is_none()));
+        let d = hits[5].as_ref().expect("a 6-a sesiune trebuie sa declanseze");
+        assert_eq!(d.connections, 6);
+        assert_eq!(d.duration_secs, 300);
+        assert!((d.mean_interval_secs - 60.0).abs() < 0.01);
+        assert!(d.jitter < 0.05);
+        // Deja raportat — a 7-a sesiune nu mai alerteaza.
+        assert!(hits[6].is_none());
+    }
+
+    #[test]
+    fn test_irregular_intervals_not_reported() {
+        let tracker = BeaconTracker::new();
+        let (src, dst) = ips();
+        let t0 = Instant::now();
+
+        // Trafic uman: intervale intre 10 s si 3 minute.
+        for secs in [0, 15, 200, 260, 430, 445, 600, 790] {
+            assert!(tracker
+                .record(src, dst, 443, t0 + Duration::from_secs(secs), &cfg())
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn test_close_hits_merge_into_one_session() {
+        let tracker = BeaconTracker::new();
+        let (src, dst) = ips();
+        let t0 = Instant::now();
+
+        // Trafic continuu la fiecare 2 s timp de 10 minute — o singura sesiune.
+        for secs in (0..600).step_by(2) {
+            assert!(tracker
+                .record(src, dst, 443, t0 + Duration::from_secs(secs), &cfg())
+                .is_none());
+        }
+        assert_eq!(tracker.flows.get(&(src, dst, 443)).unwrap().starts.len(), 1);
+    }
+
+    #[test]
+    fn test_long_silence_restarts_and_cleanup() {
+        let tracker = BeaconTracker::new();
+        let (src, dst) = ips();
+        let t0 = Instant::now();
+        for i in 0..4 {
+            tracker.record(src, dst, 443, t0 + Duration::from_secs(i * 60), &cfg());
+        }
+
+        // Pauza > max_interval_secs (3600) — masurarea reporneste de la zero.
+        let t1 = t0 + Duration::from_secs(180 + 4000);
+        tracker.record(src, dst, 443, t1, &cfg());
+        assert_eq!(tracker.flows.get(&(src, dst, 443)).unwrap().starts.len(), 1);
+
+        tracker.cleanup(t1 + Duration::from_secs(3601), &cfg());
+        assert!(tracker.flows.is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+// =============================================================================
+// check.rs - Verificarea config-ului inainte de deploy (`ids-rs check-config`)
+// =============================================================================
+//
+// `AppConfig::validate` ruleaza doar in procesul pornit (la startup sau la
+// reload). `ids-rs check-config` face aceleasi verificari offline, pe masina
+// operatorului sau in pipeline-ul de deploy:
+//
+//   1. parsarea TOML + validarea completa (toate erorile, numerotate)
+//   2. parser-ul ales (cu ordinea pentru "auto"), feed-urile threat intel, inventarul de active —
+//      aceleasi incarcari care sunt fatale la pornire
+//   3. transportul SMTP construit cu `build_mailer` (fara conexiune)
+//   4. avertismente pentru valori legale, dar riscante (`AppConfig::warnings`)
+//   5. optional, `--sample <fisier>`: rata de parsare a parser-ului ales pe
+//      un log real, cu primele linii nerecunoscute (si, pentru "auto", cate
+//      linii a recunoscut fiecare format)
+//
+// Exit code 0 = config utilizabil (avertismentele nu conteaza), 1 = erori.
+// Nimic nu este creat pe disc si nu se deschide niciun socket.
+//
+// NOTA RUST - `&dyn LogParser`:
+// `from_config` intoarce `Box<dyn LogParser>`; `sample_report` primeste
+// doar o referinta la trait object (`&*parser`), deci poate fi testat cu
+// orice parser, fara sa detina Box-ul.
+//
+// =============================================================================
+
+use crate::alerter;
+use crate::assets::AssetInventory;
+use crate::cli::CheckOptions;
+use crate::parser::{self, LogParser};
+use crate::threat_intel::ThreatIntel;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::collections::BTreeMap;
+
+/// Liniile nerecunoscute afisate din sample.
+const MAX_FAILED_SHOWN: usize = 5;
+
+/// Lungimea maxima afisata dintr-o linie nerecunoscuta.
+const MAX_LINE_SHOWN: usize = 160;
+
+/// Rezultatul parsarii unui fisier de proba.
+#[derive(Debug, Default)]
+struct SampleReport {
+    lines: usize,
+    parsed: usize,
+    /// Evenimente parsate per actiune (drop, accept, ...).
+    actions: BTreeMap<String, usize>,
+    /// Primele linii nerecunoscute: (numarul liniei, continutul).
+    failed: Vec<(usize, String)>,
+}
+
+impl SampleReport {
+    fn rate(&self) -> f64 {
+        if self.lines == 0 {
+            0.0
+        } else {
+            self.parsed as f64 * 100.0 / self.lines as f64
+        }
+    }
+}
+
+/// Parseaza fiecare linie nevida din `content` cu parser-ul dat.
+fn sample_report(parser: &dyn LogParser, content: &str) -> SampleReport {
+    let mut report = SampleReport::default();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        report.lines += 1;
+        match parser.parse(line) {
+            Some(event) => {
+                report.parsed += 1;
+                *report.actions.entry(event.action.to_lowercase()).or_insert(0) += 1;
+            }
+            None if report.failed.len() < MAX_FAILED_SHOWN => {
+                let shown: String = line.chars().take(MAX_LINE_SHOWN).collect();
+                report.failed.push((index + 1, shown));
+            }
+            None => {}
+        }
+    }
+    report
+}
+
+/// Un rand "OK" / "WARN" / "EROARE" al raportului.
+fn ok(msg: &str) {
+    println!("  {}     {}", "OK".green().bold(), msg);
+}
+
+fn warn(msg: &str) {
+    println!("  {}   {}", "WARN".yellow().bold(), msg);
+}
+
+fn fail(msg: &str) {
+    println!("  {} {}", "EROARE".red().bold(), msg);
+}
+
+/// `ids-rs check-config [<config>] [--sample <fisier>]` — optiunile din cli.rs,
+/// inclusiv suprascrierile (`--parser cef --sample fw.log` testeaza alt parser).
+pub fn run_check_config(opts: &CheckOptions) -> Result<()> {
+    let config_path = &opts.config_path;
+    println!("Config: {}", config_path.bold());
+
+    // Erorile de parsare / validare opresc verificarea: fara un AppConfig
+    // valid nu avem ce incarca mai departe. Mesajul contine toate erorile.
+    let config = opts.overrides.load(config_path)?;
+    ok("TOML parsat, validare trecuta");
+    let overrides = opts.overrides.describe();
+    if !overrides.is_empty() {
+        ok(&format!("Suprascrieri din linia de comanda: {}", overrides.join(" ")));
+    }
+
+    let mut errors = 0usize;
+    let parser = parser::from_config(&config.network)?;
+    ok(&format!("Parser {:?}: {}", config.network.parser, parser.name()));
+
+    if config.threat_intel.enabled {
+        match ThreatIntel::load(&config.threat_intel) {
+            Ok(intel) => crate::log_threat_intel(&intel, ok),
+            Err(e) => {
+                errors += 1;
+                fail(&format!("Threat intel: {:#}", e));
+            }
+        }
+    }
+    if config.assets.enabled {
+        match AssetInventory::load(&config.assets) {
+            Ok(assets) => crate::log_assets(&assets, ok),
+            Err(e) => {
+                errors += 1;
+                fail(&format!("Inventar active: {:#}", e));
+            }
+        }
+    }
+
+    let email = &config.alerting.email;
+    if email.enabled {
+        match alerter::build_mailer(email) {
+            Ok(_) => ok(&format!(
+                "SMTP: transport construit pentru {}:{}{} (fara conexiune de test)",
+                email.smtp_server,
+                email.smtp_port,
+                if email.smtp_tls { " cu TLS" } else { "" }
+            )),
+            Err(e) => {
+                errors += 1;
+                fail(&format!("SMTP: {:#}", e));
+            }
+        }
+    }
+
+    let warnings = config.warnings();
+    for w in &warnings {
+        warn(w);
+    }
+
+    if let Some(ref path) = opts.sample {
+        let bytes = std::fs::read(path).with_context(|| format!("Nu pot citi sample-ul: {:?}", path))?;
+        let report = sample_report(&*parser, &String::from_utf8_lossy(&bytes));
+        println!("\nSample: {}", path.bold());
+        let summary = format!(
+            "{} din {} linii parsate ({:.1}%)",
+            report.parsed,
+            report.lines,
+            report.rate()
+        );
+        if report.lines == 0 || report.parsed == 0 {
+            // Parser-ul gresit pentru formatul firewall-ului: in productie,
+            // fiecare pachet ar fi ignorat fara nicio alerta.
+            errors += 1;
+            fail(&format!("{} — parser-ul {:?} nu recunoaste log-urile", summary, config.network.parser));
+        } else if report.parsed < report.lines {
+            warn(&summary);
+        } else {
+            ok(&summary);
+        }
+        if !report.actions.is_empty() {
+            let actions: Vec<String> = report.actions.iter().map(|(a, n)| format!("{} {}", a, n)).collect();
+            println!("         actiuni: {}", actions.join(", "));
+        }
+        let matches = parser.match_counts();
+        if !matches.is_empty() {
+            let matches: Vec<String> = matches.iter().map(|(p, n)| format!("{} {}", p, n)).collect();
+            println!("         formate: {}", matches.join(", "));
+        }
+        if !report.failed.is_empty() {
+            println!("         primele linii nerecunoscute:");
+            for (line_no, line) in &report.failed {
+                println!("         {:>6}: {}", line_no, line.dimmed());
+            }
+        }
+    }
+
+    println!();
+    if errors > 0 {
+        bail!("{}: {} erori, {} avertismente", config_path, errors, warnings.len());
+    }
+    println!(
+        "{} {} avertismente",
+        "Config valid —".green().bold(),
+        warnings.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_report_and_warnings() {
+        let parser = parser::create_parser("gaia").unwrap();
+        let sample = "\
+Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop 192.168.11.7 >eth8 rule: 113; src: 192.168.11.7; dst: 10.0.0.5; proto: tcp; service: 22; s_port: 40000;
+
+Sep 3 15:12:21 192.168.99.1 Checkpoint: 3Sep2007 15:12:09 accept 192.168.11.7 >eth8 rule: 7; src: 192.168.11.7; dst: 10.0.0.5; proto: tcp; service: 443; s_port: 40001;
+CEF:0|Vendor|Product|1.0|100|format gresit|5|src=10.0.0.1
+";
+        let report = sample_report(&*parser, sample);
+        assert_eq!(report.lines, 3, "liniile goale nu se numara");
+        assert_eq!(report.parsed, 2);
+        assert_eq!(report.actions.get("drop"), Some(&1));
+        assert_eq!(report.actions.get("accept"), Some(&1));
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, 4, "numarul liniei din fisier, nu al liniei nevide");
+        assert!((report.rate() - 66.6).abs() < 0.1);
+
+        let mut config: crate::config::AppConfig = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.network.debug = false;
+        config.network.udp_rate_limit = 1000;
+        config.detection.whitelist = vec!["10.0.0.0/8".to_string()];
+        let baseline = config.warnings().len();
+        config.network.debug = true;
+        config.detection.whitelist.push("0.0.0.0/0".to_string());
+        let warnings = config.warnings();
+        assert_eq!(warnings.len(), baseline + 2);
+        assert!(warnings.iter().any(|w| w.contains("network.debug")));
+        assert!(warnings.iter().any(|w| w.contains("0.0.0.0/0")));
+        assert!(!warnings.iter().any(|w| w.contains("10.0.0.0/8")));
+    }
+}
@@ -0,0 +1,602 @@
+// =============================================================================
+// cli.rs - Linia de comanda: subcomenzi, suprascrieri, pidfile
+// =============================================================================
+//
+//   ids-rs [run] [<config>] [optiuni]      detectorul (implicit)
+//   ids-rs check-config [<config>]         validare offline (check.rs)
+//   ids-rs replay <log> [<config>]         log-uri din fisier prin detector
+//   ids-rs parse-test [<linie>...]         campurile extrase de parser
+//   ids-rs alerts ... / ids-rs ctl ...     istoricul si socket-ul de control
+//   ids-rs version
+//
+// Suprascrierile (`--listen`, `--port`, `--parser`, `--no-email`, `--no-siem`)
+// se aplica peste config.toml INAINTE de validare si din nou la fiecare
+// reload — altfel un SIGHUP ar reactiva in tacere email-ul oprit cu
+// `--no-email` pe durata unui test.
+//
+// Compatibilitate: `ids-rs config.toml` si `ids-rs --tui config.toml` (forma
+// de dinainte de subcomenzi) sunt interpretate ca `run`. Un prim argument
+// fara `.` sau `/` (`ids-rs replya`) este refuzat ca subcomanda necunoscuta,
+// nu citit ca fisier de config.
+//
+// NOTA RUST - `std::slice::Iter` CA CURSOR:
+// Optiunile cu valoare (`--port 5514`) consuma doua argumente. Parcurgem
+// lista cu un iterator explicit si apelam `iter.next()` pentru valoare —
+// acelasi cursor avanseaza si in bucla, deci valoarea nu este reinterpretata
+// ca argument.
+//
+// =============================================================================
+
+use crate::config::AppConfig;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Nivelurile acceptate de `--log-level` (filtrul tracing pentru `ids_rs`).
+const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+/// Textul de ajutor pentru `ids-rs help`.
+pub const USAGE: &str = "\
+Utilizare: ids-rs [comanda] [optiuni]
+
+Comenzi:
+  run [<config>]                 porneste detectorul (implicit; config.toml)
+  check-config [<config>]        valideaza config-ul si iese (exit 1 la erori)
+  replay <log> [<config>]        trece un fisier de log-uri prin detector ('-' = stdin)
+  parse-test [<linie>...]        afiseaza campurile extrase (fara linii: stdin)
+  alerts [list|show|count]       istoricul alertelor (ids-rs alerts --help)
+  ctl <comanda>                  socket-ul de control (ids-rs ctl --help)
+  version                        versiunea si parserele disponibile
+
+Optiuni comune (run, check-config, replay, parse-test):
+  --config <fisier>              config-ul (alternativa la argumentul pozitional)
+  --listen <adresa>              suprascrie network.listen_address
+  --port <port>                  suprascrie network.listen_port
+  --parser <nume>                suprascrie network.parser (gaia, cef, gaia_cef, auto)
+  --no-email                     dezactiveaza alertele email
+  --no-siem                      dezactiveaza trimiterea catre SIEM
+  --log-level <nivel>            error, warn, info, debug, trace (implicit RUST_LOG / warn)
+  --no-color                     fara culori ANSI (la fel ca NO_COLOR=1; util sub journald)
+
+run:
+  --tui                          dashboard interactiv in locul log-ului derulant
+  --foreground                   ruleaza in prim-plan (implicit; IDS-RS nu face fork,
+                                 acceptat pentru systemd Type=simple)
+  --pidfile <fisier>             scrie PID-ul; refuza pornirea daca procesul ruleaza deja
+
+check-config:
+  --sample <fisier>              rata de parsare a parser-ului ales pe un log de proba
+
+replay:
+  --send                         trimite alertele si catre SIEM / email / istoric";
+
+/// Suprascrierile config-ului din linia de comanda.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Overrides {
+    pub listen_address: Option<String>,
+    pub listen_port: Option<u16>,
+    pub parser: Option<String>,
+    pub no_email: bool,
+    pub no_siem: bool,
+}
+
+impl Overrides {
+    /// Citeste config-ul, aplica suprascrierile si abia apoi valideaza — un
+    /// `--parser` gresit este raportat ca orice eroare de configurare.
+    pub fn load(&self, path: &str) -> Result<AppConfig> {
+        let mut config = AppConfig::read(path)?;
+        self.apply(&mut config);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(ref address) = self.listen_address {
+            config.network.listen_address = address.clone();
+        }
+        if let Some(port) = self.listen_port {
+            config.network.listen_port = port;
+        }
+        if let Some(ref parser) = self.parser {
+            config.network.parser = parser.clone();
+        }
+        if self.no_email {
+            config.alerting.email.enabled = false;
+        }
+        if self.no_siem {
+            config.alerting.siem.enabled = false;
+        }
+    }
+
+    /// Suprascrierile active, pentru log-ul de pornire. Gol = niciuna.
+    pub fn describe(&self) -> Vec<String> {
+        let mut active = Vec::new();
+        if let Some(ref address) = self.listen_address {
+            active.push(format!("--listen {}", address));
+        }
+        if let Some(port) = self.listen_port {
+            active.push(format!("--port {}", port));
+        }
+        if let Some(ref parser) = self.parser {
+            active.push(format!("--parser {}", parser));
+        }
+        if self.no_email {
+            active.push("--no-email".to_string());
+        }
+        if self.no_siem {
+            active.push("--no-siem".to_string());
+        }
+        active
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub config_path: String,
+    pub overrides: Overrides,
+    pub tui: bool,
+    /// `--foreground`: fara efect — IDS-RS nu face niciodata fork; acceptat
+    /// pentru unitatile systemd `Type=simple` care il scriu explicit.
+    pub foreground: bool,
+    pub pidfile: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CheckOptions {
+    pub config_path: String,
+    pub overrides: Overrides,
+    pub sample: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReplayOptions {
+    pub config_path: String,
+    pub overrides: Overrides,
+    /// Fisierul cu log-uri; "-" = stdin.
+    pub log_path: String,
+    pub send: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseTestOptions {
+    pub config_path: String,
+    pub overrides: Overrides,
+    /// Liniile de testat; goala = citite din stdin.
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    CheckConfig(CheckOptions),
+    Replay(ReplayOptions),
+    ParseTest(ParseTestOptions),
+    /// Argumentele de dupa `alerts`, parsate de history.rs.
+    Alerts(Vec<String>),
+    /// Argumentele de dupa `ctl`, parsate de control.rs.
+    Ctl(Vec<String>),
+    Version,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    pub command: Command,
+    /// `--log-level`; None = RUST_LOG sau "warn".
+    pub log_level: Option<String>,
+    /// `--no-color`: fara coduri ANSI in consola si in log (ca `NO_COLOR=1`).
+    pub no_color: bool,
+}
+
+/// Optiunile acceptate de toate subcomenzile care citesc config-ul.
+#[derive(Default)]
+struct Common {
+    config_path: Option<String>,
+    overrides: Overrides,
+    log_level: Option<String>,
+    no_color: bool,
+    positional: Vec<String>,
+}
+
+impl Common {
+    /// Consuma `arg` (si valoarea lui) daca este o optiune comuna sau un
+    /// argument pozitional. `false` = optiune specifica subcomenzii.
+    fn take(&mut self, arg: &str, iter: &mut std::slice::Iter<String>) -> Result<bool> {
+        match arg {
+            "--config" => self.config_path = Some(value(iter, arg)?),
+            "--listen" => self.overrides.listen_address = Some(value(iter, arg)?),
+            "--port" => {
+                let raw = value(iter, arg)?;
+                let port = raw
+                    .parse::<u16>()
+                    .with_context(|| format!("--port: port invalid: {}", raw))?;
+                self.overrides.listen_port = Some(port);
+            }
+            "--parser" => self.overrides.parser = Some(value(iter, arg)?),
+            "--no-email" => self.overrides.no_email = true,
+            "--no-siem" => self.overrides.no_siem = true,
+            "--no-color" => self.no_color = true,
+            "--log-level" => {
+                let level = value(iter, arg)?.to_lowercase();
+                if !LOG_LEVELS.contains(&level.as_str()) {
+                    bail!("--log-level: nivel invalid {:?} (acceptate: {})", level, LOG_LEVELS.join(", "));
+                }
+                self.log_level = Some(level);
+            }
+            other if other.starts_with('-') && other != "-" => return Ok(false),
+            other => self.positional.push(other.to_string()),
+        }
+        Ok(true)
+    }
+
+    /// Calea config-ului: `--config` sau argumentul pozitional de pe pozitia
+    /// `index`, nu ambele.
+    fn config_path(&self, index: usize) -> Result<String> {
+        match (&self.config_path, self.positional.get(index)) {
+            (Some(_), Some(extra)) => bail!("Config dat de doua ori: --config si {:?}", extra),
+            (Some(path), None) => Ok(path.clone()),
+            (None, Some(path)) => Ok(path.clone()),
+            (None, None) => Ok("config.toml".to_string()),
+        }
+    }
+
+    /// Refuza argumentele pozitionale in plus fata de `max`.
+    fn limit_positional(&self, max: usize) -> Result<()> {
+        match self.positional.get(max) {
+            Some(extra) => bail!("Argument neasteptat: {:?}\n\n{}", extra, USAGE),
+            None => Ok(()),
+        }
+    }
+}
+
+fn value(iter: &mut std::slice::Iter<String>, flag: &str) -> Result<String> {
+    iter.next()
+        .cloned()
+        .with_context(|| format!("{} cere o valoare", flag))
+}
+
+fn unknown(arg: &str) -> anyhow::Error {
+    anyhow::anyhow!("Argument necunoscut: {}\n\n{}", arg, USAGE)
+}
+
+impl Cli {
+    /// Parseaza argumentele de dupa numele executabilului.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let (first, rest) = match args.split_first() {
+            Some((first, rest)) => (first.as_str(), rest),
+            None => ("run", args),
+        };
+        let simple = |command| Ok(Cli { command, log_level: None, no_color: false });
+        match first {
+            "run" => Self::parse_run(rest),
+            "check-config" | "--check-config" => Self::parse_check(rest),
+            "replay" => Self::parse_replay(rest),
+            "parse-test" => Self::parse_parse_test(rest),
+            "alerts" => simple(Command::Alerts(rest.to_vec())),
+            "ctl" => simple(Command::Ctl(rest.to_vec())),
+            "version" | "--version" | "-V" => simple(Command::Version),
+            "help" | "--help" | "-h" => simple(Command::Help),
+            // `ids-rs config.toml`, `ids-rs --tui config.toml`; un cuvant fara
+            // `.` sau `/` nu este o cale — este o comanda scrisa gresit.
+            other if !other.starts_with('-') && !other.contains(['.', '/']) => {
+                bail!("Comanda necunoscuta: {}\n\n{}", other, USAGE)
+            }
+            _ => Self::parse_run(args),
+        }
+    }
+
+    fn parse_run(args: &[String]) -> Result<Self> {
+        let mut common = Common::default();
+        let (mut tui, mut foreground, mut pidfile) = (false, false, None);
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if common.take(arg, &mut iter)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--tui" => tui = true,
+                "--foreground" => foreground = true,
+                "--pidfile" => pidfile = Some(PathBuf::from(value(&mut iter, arg)?)),
+                "-h" | "--help" => return Ok(Cli { command: Command::Help, log_level: None, no_color: false }),
+                other => return Err(unknown(other)),
+            }
+        }
+        common.limit_positional(1)?;
+        Ok(Cli {
+            command: Command::Run(RunOptions {
+                config_path: common.config_path(0)?,
+                overrides: common.overrides,
+                tui,
+                foreground,
+                pidfile,
+            }),
+            log_level: common.log_level,
+            no_color: common.no_color,
+        })
+    }
+
+    fn parse_check(args: &[String]) -> Result<Self> {
+        let mut common = Common::default();
+        let mut sample = None;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if common.take(arg, &mut iter)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--sample" => sample = Some(value(&mut iter, arg)?),
+                "-h" | "--help" => return Ok(Cli { command: Command::Help, log_level: None, no_color: false }),
+                other => return Err(unknown(other)),
+            }
+        }
+        common.limit_positional(1)?;
+        Ok(Cli {
+            command: Command::CheckConfig(CheckOptions {
+                config_path: common.config_path(0)?,
+                overrides: common.overrides,
+                sample,
+            }),
+            log_level: common.log_level,
+            no_color: common.no_color,
+        })
+    }
+
+    fn parse_replay(args: &[String]) -> Result<Self> {
+        let mut common = Common::default();
+        let mut send = false;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if common.take(arg, &mut iter)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--send" => send = true,
+                "-h" | "--help" => return Ok(Cli { command: Command::Help, log_level: None, no_color: false }),
+                other => return Err(unknown(other)),
+            }
+        }
+        common.limit_positional(2)?;
+        let log_path = common
+            .positional
+            .first()
+            .cloned()
+            .with_context(|| format!("replay cere fisierul cu log-uri\n\n{}", USAGE))?;
+        Ok(Cli {
+            command: Command::Replay(ReplayOptions {
+                config_path: common.config_path(1)?,
+                overrides: common.overrides,
+                log_path,
+                send,
+            }),
+            log_level: common.log_level,
+            no_color: common.no_color,
+        })
+    }
+
+    fn parse_parse_test(args: &[String]) -> Result<Self> {
+        let mut common = Common::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if common.take(arg, &mut iter)? {
+                continue;
+            }
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(Cli { command: Command::Help, log_level: None, no_color: false }),
+                other => return Err(unknown(other)),
+            }
+        }
+        // Pozitionalele sunt liniile de log, nu config-ul.
+        Ok(Cli {
+            command: Command::ParseTest(ParseTestOptions {
+                config_path: common.config_path.unwrap_or_else(|| "config.toml".to_string()),
+                overrides: common.overrides,
+                lines: common.positional,
+            }),
+            log_level: common.log_level,
+            no_color: common.no_color,
+        })
+    }
+}
+
+/// Fisierul cu PID-ul procesului (`--pidfile`), sters la oprire.
+///
+/// NOTA RUST - DROP CA GARDA:
+/// `impl Drop` ruleaza la iesirea din scope, pe orice drum — return normal,
+/// `?` sau oprire gratiosa — deci fisierul nu ramane in urma unei erori.
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Scrie PID-ul curent. Un pidfile existent al unui proces inca in viata
+    /// (`/proc/<pid>`) opreste pornirea; unul ramas de la un crash este suprascris.
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            if let Ok(pid) = content.trim().parse::<u32>() {
+                if pid != std::process::id() && Path::new(&format!("/proc/{}", pid)).exists() {
+                    bail!("IDS-RS ruleaza deja (PID {} in {:?})", pid, path);
+                }
+            }
+        }
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .with_context(|| format!("Nu pot scrie pidfile-ul {:?}", path))?;
+        Ok(PidFile { path: path.to_path_buf() })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli> {
+        Cli::parse(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    fn run(args: &[&str]) -> RunOptions {
+        match parse(args).unwrap().command {
+            Command::Run(opts) => opts,
+            other => panic!("asteptam run, am primit {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse() {
+        // Forma veche: fara subcomanda = run.
+        assert_eq!(run(&[]).config_path, "config.toml");
+        assert_eq!(run(&["/etc/ids.toml"]).config_path, "/etc/ids.toml");
+        let legacy = run(&["--tui", "/etc/ids.toml"]);
+        assert!(legacy.tui);
+        assert_eq!(legacy.config_path, "/etc/ids.toml");
+
+        let opts = run(&[
+            "run", "--config", "x.toml", "--port", "5514", "--listen", "127.0.0.1",
+            "--parser", "cef", "--no-email", "--no-siem", "--foreground", "--pidfile", "/run/ids.pid",
+        ]);
+        assert_eq!(opts.config_path, "x.toml");
+        assert!(opts.foreground && !opts.tui);
+        assert_eq!(opts.pidfile, Some(PathBuf::from("/run/ids.pid")));
+        assert_eq!(
+            opts.overrides,
+            Overrides {
+                listen_address: Some("127.0.0.1".to_string()),
+                listen_port: Some(5514),
+                parser: Some("cef".to_string()),
+                no_email: true,
+                no_siem: true,
+            }
+        );
+        assert_eq!(opts.overrides.describe().len(), 5);
+
+        // Suprascrierile ajung in config inaintea validarii.
+        let mut config: AppConfig = toml::from_str(include_str!("../config.toml")).unwrap();
+        opts.overrides.apply(&mut config);
+        assert_eq!(config.network.listen_port, 5514);
+        assert_eq!(config.network.parser, "cef");
+        assert!(!config.alerting.email.enabled && !config.alerting.siem.enabled);
+
+        let cli = parse(&["replay", "fw.log", "x.toml", "--send", "--log-level", "DEBUG"]).unwrap();
+        assert_eq!(cli.log_level.as_deref(), Some("debug"));
+        assert!(!cli.no_color);
+        assert_eq!(
+            cli.command,
+            Command::Replay(ReplayOptions {
+                config_path: "x.toml".to_string(),
+                overrides: Overrides::default(),
+                log_path: "fw.log".to_string(),
+                send: true,
+            })
+        );
+        match parse(&["parse-test", "--parser", "gaia", "linia 1", "linia 2"]).unwrap().command {
+            Command::ParseTest(opts) => {
+                assert_eq!(opts.lines, vec!["linia 1", "linia 2"]);
+                assert_eq!(opts.overrides.parser.as_deref(), Some("gaia"));
+            }
+            other => panic!("asteptam parse-test, am primit {:?}", other),
+        }
+        assert_eq!(parse(&["ctl", "state", "10.0.0.1"]).unwrap().command, Command::Ctl(vec![
+            "state".to_string(),
+            "10.0.0.1".to_string(),
+        ]));
+        assert_eq!(parse(&["--version"]).unwrap().command, Command::Version);
+
+        // Erori: valori lipsa / invalide, optiuni necunoscute, conflicte.
+        assert!(parse(&["run", "--port"]).is_err());
+        assert!(parse(&["run", "--port", "70000"]).is_err());
+        assert!(parse(&["run", "--log-level", "verbose"]).is_err());
+        assert!(parse(&["run", "--bogus"]).is_err());
+        assert!(parse(&["run", "a.toml", "--config", "b.toml"]).is_err());
+        assert!(parse(&["run", "a.toml", "b.toml"]).is_err());
+        assert!(parse(&["replay"]).is_err());
+        assert!(parse(&["check-config", "--send"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_foreground_and_no_color() {
+        // `--foreground` nu schimba nimic: procesul ruleaza oricum in prim-plan,
+        // deci nu intra in conflict nici cu `--tui` si nu opreste culorile.
+        let with_tui = parse(&["run", "--tui", "--foreground"]).unwrap();
+        assert!(!with_tui.no_color);
+        match with_tui.command {
+            Command::Run(opts) => assert!(opts.tui && opts.foreground),
+            other => panic!("asteptam run, am primit {:?}", other),
+        }
+
+        // `--no-color` este o optiune comuna, separata de `--foreground`.
+        for args in [
+            &["run", "--foreground", "--no-color"][..],
+            &["--no-color", "config.toml"],
+            &["check-config", "--no-color"],
+            &["replay", "fw.log", "--no-color"],
+            &["parse-test", "--no-color", "linia 1"],
+        ] {
+            assert!(parse(args).unwrap().no_color, "{:?}", args);
+        }
+        assert!(!parse(&["run", "--foreground"]).unwrap().no_color);
+        assert!(USAGE.contains("--no-color"));
+    }
+
+    #[test]
+    fn test_cli_unknown_commands_and_flags() {
+        // Subcomenzi scrise gresit: eroare cu USAGE, nu config "replya".
+        for typo in ["replya", "chek-config", "status", "Run"] {
+            let err = parse(&[typo]).unwrap_err().to_string();
+            assert!(err.contains("Comanda necunoscuta"), "{}: {}", typo, err);
+            assert!(err.contains("Utilizare:"));
+        }
+        // Caile de config raman acceptate in forma veche.
+        assert_eq!(run(&["ids.toml"]).config_path, "ids.toml");
+        assert_eq!(run(&["./ids"]).config_path, "./ids");
+
+        // Optiuni necunoscute sau specifice altei subcomenzi.
+        for args in [
+            &["--bogus"][..],
+            &["run", "--send"],
+            &["check-config", "--tui"],
+            &["replay", "fw.log", "--sample", "x.log"],
+            &["replay", "fw.log", "--foreground"],
+            &["parse-test", "--send"],
+            &["parse-test", "--pidfile", "/run/ids.pid"],
+        ] {
+            let err = parse(args).unwrap_err().to_string();
+            assert!(err.contains("Argument necunoscut"), "{:?}: {}", args, err);
+        }
+    }
+
+    #[test]
+    fn test_overrides_applied_over_config_file() {
+        let path = std::env::temp_dir().join(format!("ids-rs-cli-{}.toml", std::process::id()));
+        let text = include_str!("../config.toml")
+            .replacen("listen_port = 5555", "listen_port = 6000", 1)
+            .replacen("parser = \"gaia_cef\"", "parser = \"gaia\"", 1);
+        std::fs::write(&path, &text).unwrap();
+        let path_str = path.to_str().unwrap();
+        let file_config: AppConfig = toml::from_str(&text).unwrap();
+
+        // Fara suprascrieri: valorile din fisier.
+        let config = Overrides::default().load(path_str).unwrap();
+        assert_eq!(config.network.listen_port, 6000);
+        assert_eq!(config.network.parser, "gaia");
+
+        // `--config <fisier> --port --parser --no-siem`: suprascrierile castiga,
+        // restul valorilor raman cele din fisier.
+        let opts = run(&["run", "--config", path_str, "--port", "5514", "--parser", "cef", "--no-siem"]);
+        let config = opts.overrides.load(&opts.config_path).unwrap();
+        assert_eq!(config.network.listen_port, 5514);
+        assert_eq!(config.network.parser, "cef");
+        assert!(!config.alerting.siem.enabled);
+        assert_eq!(config.network.listen_address, file_config.network.listen_address);
+        assert_eq!(config.alerting.email.enabled, file_config.alerting.email.enabled);
+
+        // Suprascrierea trece prin validare ca orice valoare din fisier.
+        let opts = run(&["run", "--config", path_str, "--parser", "syslog"]);
+        let err = format!("{:#}", opts.overrides.load(&opts.config_path).unwrap_err());
+        assert!(err.contains("syslog"), "{}", err);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
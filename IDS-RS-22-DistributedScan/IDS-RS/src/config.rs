@@ -35,7 +35,7 @@
 // =============================================================================
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::path::Path;
@@ -49,19 +49,45 @@ use std::path::Path;
 /// sub-structuri (ex: DetectionConfig) catre alte componente prin `.clone()`.
 /// In Rust, copierea explicita (Clone) este preferata fata de copierea
 /// implicita, pentru a face costul vizibil in cod.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AppConfig {
     pub network: NetworkConfig,
     pub detection: DetectionConfig,
     pub alerting: AlertingConfig,
     pub cleanup: CleanupConfig,
+
+    /// Liste IOC (indicatori de compromitere) incarcate din fisiere locale.
+    /// Retrocompatibil: daca sectiunea lipseste, threat intel este dezactivat.
+    #[serde(default)]
+    pub threat_intel: ThreatIntelConfig,
+
+    /// Inventarul de active (proprietar, criticitate, rol) incarcat din fisier.
+    /// Retrocompatibil: daca sectiunea lipseste, inventarul este dezactivat.
+    #[serde(default)]
+    pub assets: AssetsConfig,
+
+    /// Socket-ul Unix de control (`ids-rs ctl`).
+    /// Retrocompatibil: daca sectiunea lipseste, socket-ul nu este deschis.
+    #[serde(default)]
+    pub control: ControlConfig,
+
+    /// API-ul REST read-only (starea detectorului pentru portalul SOC).
+    /// Retrocompatibil: daca sectiunea lipseste, API-ul nu este pornit.
+    #[serde(default)]
+    pub api: ApiConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NetworkConfig {
     pub listen_address: String,
     pub listen_port: u16,
     pub parser: String,
+
+    /// Ordinea incercarilor pentru `parser = "auto"` (ignorata altfel).
+    /// Implicit: toate parserele concrete, `PARSER_NAMES`.
+    #[serde(default = "default_auto_parsers")]
+    pub auto_parsers: Vec<String>,
+
     #[serde(default)]
     pub debug: bool,
 
@@ -92,6 +118,10 @@ fn default_udp_burst_size() -> u64 {
     10_000
 }
 
+fn default_auto_parsers() -> Vec<String> {
+    crate::parser::PARSER_NAMES.iter().map(|n| n.to_string()).collect()
+}
+
 /// Configurare detectie - contine sub-structuri pentru fiecare tip de scan.
 ///
 /// NOTA RUST: Structurile imbricate (nested) se mapeaza pe sectiuni TOML
@@ -103,7 +133,7 @@ fn default_udp_burst_size() -> u64 {
 ///   - Daca lipseste din fisier, serde apeleaza functia specificata pentru valoare default
 ///   - Retrocompatibil: configuratii vechi fara campul nou continua sa functioneze
 ///   - Functiile de default trebuie sa returneze acelasi tip ca si campul
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DetectionConfig {
     pub alert_cooldown_secs: u64,
 
@@ -120,6 +150,14 @@ pub struct DetectionConfig {
     #[serde(default = "default_max_tracked_ips")]
     pub max_tracked_ips: usize,
 
+    /// Agregarea surselor IPv6: lungimea prefixului dupa care sunt urmarite
+    /// sursele IPv6 (64 = un /64 este o singura sursa). Un atacator IPv6 poate
+    /// roti adresa in interiorul prefixului la fiecare pachet — fara agregare
+    /// ar ocoli orice prag per sursa si ar goli LRU-ul max_tracked_ips.
+    /// Implicit: 128 (fara agregare, fiecare adresa separat).
+    #[serde(default = "default_ipv6_source_prefix")]
+    pub ipv6_source_prefix: u8,
+
     /// Lista de IP-uri si subrețele excluse din detecție.
     /// Accepta IP-uri individuale ("10.0.1.10") si CIDR ("10.0.2.0/24").
     /// IP-urile din whitelist nu genereaza alerte (trafic legitim cunoscut).
@@ -144,6 +182,350 @@ pub struct DetectionConfig {
     /// Retrocompatibil: daca lipseste din config.toml, se aplica valorile implicite.
     #[serde(default = "default_distributed_scan")]
     pub distributed_scan: DistributedScanConfig,
+
+    /// Detectie Brute Force / flood — rata de evenimente per (sursa,
+    /// destinatie, port). Retrocompatibil: dezactivat implicit.
+    #[serde(default)]
+    pub brute_force: BruteForceConfig,
+
+    /// Detectie Ping Sweep — o sursa trimite ICMP echo catre N destinatii.
+    /// Retrocompatibil: dezactivat implicit.
+    #[serde(default)]
+    pub ping_sweep: PingSweepConfig,
+
+    /// Identitatea NAT — adresele originale sau traduse, per detector.
+    /// Retrocompatibil: implicit "original" (src / dst din log) peste tot.
+    #[serde(default)]
+    pub nat: NatConfig,
+
+    /// Severitatea dinamica a alertelor (factori peste severitatea de baza).
+    /// Retrocompatibil: activa cu ponderile implicite.
+    #[serde(default)]
+    pub severity: SeverityConfig,
+
+    /// Istoricul de recidiva per sursa si alerta PersistentScanner.
+    /// Retrocompatibil: activ cu valorile implicite.
+    #[serde(default)]
+    pub repeat_offenders: RepeatOffendersConfig,
+
+    /// Cooldown per detector si modul de realertare (fixed / sliding / ongoing).
+    /// Retrocompatibil: "fixed" cu `alert_cooldown_secs` pentru toti detectorii.
+    #[serde(default)]
+    pub cooldown: CooldownConfig,
+
+    /// Sesiuni de scanare per sursa / tinta, rezumate la inchidere.
+    /// Retrocompatibil: dezactivat implicit.
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+
+    /// Detectie beaconing C2 — conexiuni periodice, cu jitter mic, catre
+    /// aceeasi destinatie si port. Retrocompatibil: dezactivat implicit.
+    #[serde(default)]
+    pub beaconing: BeaconingConfig,
+
+    /// Mod anomalie: baseline adaptiv per sursa (sau per subnet) in loc de
+    /// praguri fixe. Retrocompatibil: dezactivat daca sectiunea lipseste.
+    #[serde(default)]
+    pub anomaly: AnomalyConfig,
+
+    /// Profile de detectie per subnet — praguri diferite pentru VLAN-ul de
+    /// servere, etajele de utilizatori, Wi-Fi guest. Retrocompatibil: lista
+    /// goala = pragurile globale se aplica tuturor surselor.
+    #[serde(default)]
+    pub profiles: Vec<DetectionProfile>,
+
+    /// Ponderi per port pentru Fast/Slow/Accept Scan — porturile sensibile
+    /// (RDP, SMB, SSH) conteaza mai mult spre `port_threshold`.
+    /// Retrocompatibil: dezactivat = fiecare port unic conteaza 1.
+    #[serde(default)]
+    pub port_weights: PortWeightsConfig,
+
+    /// Scor de risc per sursa acumulat din toate tipurile de alerta, cu alerta
+    /// Escalation la depasirea nivelurilor. Retrocompatibil: dezactivat implicit.
+    #[serde(default)]
+    pub risk: RiskConfig,
+
+    /// Corelare lanturi de atac in mai multe etape (scan → accept → lateral).
+    /// Retrocompatibil: dezactivat implicit.
+    #[serde(default)]
+    pub correlation: CorrelationConfig,
+}
+
+/// Tipurile de etapa acceptate in `[[detection.correlation.rules]]`: cheile
+/// tipurilor de alerta plus evenimentul sintetic `probed_port_accepted`.
+pub const CHAIN_STAGE_KINDS: &[&str] = &[
+    "fast_scan",
+    "slow_scan",
+    "accept_scan",
+    "lateral_movement",
+    "distributed_scan",
+    "known_bad_source",
+    "known_bad_destination",
+    "anomaly",
+    "brute_force",
+    "beaconing",
+    "ping_sweep",
+    "probed_port_accepted",
+];
+
+/// Configurare corelare `[detection.correlation]`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CorrelationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub rules: Vec<ChainRuleConfig>,
+}
+
+/// Regula de lant `[[detection.correlation.rules]]` — secventa ordonata de
+/// etape care trebuie sa apara in `window_secs` de la prima etapa.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChainRuleConfig {
+    pub name: String,
+    #[serde(default = "default_chain_window")]
+    pub window_secs: u64,
+    pub stages: Vec<ChainStageConfig>,
+}
+
+fn default_chain_window() -> u64 { 3600 }
+
+/// O etapa a lantului.
+///
+/// `alert` = tipul etapei (vezi `CHAIN_STAGE_KINDS`).
+/// `on` = cum se leaga etapa de host-ul urmarit de lant ("subiectul"):
+///   - "source"  — sursa alertei este subiectul (la prima etapa: subiectul
+///     devine sursa alertei)
+///   - "target"  — destinatia alertei este subiectul (la prima etapa:
+///     subiectul devine tinta, ex. tinta unui Distributed Scan)
+///   - "touched" — sursa alertei este un host contactat anterior de subiect
+///     (pe conexiune acceptata); acel host devine noul subiect
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChainStageConfig {
+    pub alert: String,
+    #[serde(default = "default_chain_on")]
+    pub on: String,
+}
+
+fn default_chain_on() -> String { "source".to_string() }
+
+impl CorrelationConfig {
+    /// Cea mai lunga fereastra dintre reguli — cat timp trebuie pastrata
+    /// activitatea host-urilor (porturi sondate, destinatii contactate).
+    pub fn max_window_secs(&self) -> u64 {
+        self.rules.iter().map(|r| r.window_secs).max().unwrap_or(0)
+    }
+}
+
+/// Configurare scor de risc `[detection.risk]`.
+///
+/// Fiecare alerta adauga puncte la scorul sursei (`weights`). Scorul scade
+/// exponential cu timpul (`half_life_secs` = timpul in care scorul se
+/// injumatateste). Cand scorul depaseste un prag din `tiers`, se emite o
+/// alerta Escalation cu lantul alertelor care au contribuit.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RiskConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Timpul de injumatatire al scorului (secunde).
+    #[serde(default = "default_risk_half_life")]
+    pub half_life_secs: u64,
+
+    /// Pragurile nivelurilor de risc, crescatoare (ex: [25, 50, 100]).
+    #[serde(default = "default_risk_tiers")]
+    pub tiers: Vec<f64>,
+
+    /// Punctele adaugate de fiecare tip de alerta.
+    #[serde(default)]
+    pub weights: RiskWeights,
+}
+
+fn default_risk_half_life() -> u64 { 1800 }
+fn default_risk_tiers() -> Vec<f64> { vec![25.0, 50.0, 100.0] }
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            half_life_secs: default_risk_half_life(),
+            tiers: default_risk_tiers(),
+            weights: RiskWeights::default(),
+        }
+    }
+}
+
+/// Punctele de risc per tip de alerta `[detection.risk.weights]`.
+/// Campurile lipsa primesc valorile implicite (`#[serde(default)]` pe struct).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RiskWeights {
+    pub fast_scan: f64,
+    pub slow_scan: f64,
+    pub accept_scan: f64,
+    pub lateral_movement: f64,
+    pub distributed_scan: f64,
+    pub known_bad_source: f64,
+    pub known_bad_destination: f64,
+    pub anomaly: f64,
+    pub brute_force: f64,
+    pub beaconing: f64,
+    pub ping_sweep: f64,
+    pub attack_chain: f64,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            fast_scan: 10.0,
+            slow_scan: 10.0,
+            accept_scan: 15.0,
+            lateral_movement: 30.0,
+            distributed_scan: 5.0,
+            known_bad_source: 40.0,
+            known_bad_destination: 50.0,
+            anomaly: 10.0,
+            brute_force: 25.0,
+            beaconing: 20.0,
+            ping_sweep: 5.0,
+            attack_chain: 50.0,
+        }
+    }
+}
+
+/// Tabela de ponderi `[detection.port_weights]`.
+///
+/// Scorul unei scanari = suma ponderilor porturilor unice; se compara cu
+/// `port_threshold` in loc de numarul de porturi. Ponderea unui port:
+///   1. valoarea din `ports` (daca portul e listat explicit)
+///   2. altfel ponderea maxima a grupurilor care contin portul
+///   3. altfel `default_weight`
+///
+/// Porturile cu pondere > 1 sunt "sensibile" si sunt listate in alerte.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortWeightsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Ponderea porturilor nelistate.
+    #[serde(default = "default_port_weight")]
+    pub default_weight: f64,
+
+    /// Ponderi per port. Cheile TOML sunt string-uri: `"3389" = 3.0`.
+    #[serde(default)]
+    pub ports: HashMap<String, f64>,
+
+    /// Grupuri de porturi cu pondere comuna (ex: "database").
+    #[serde(default)]
+    pub groups: Vec<PortGroupConfig>,
+}
+
+fn default_port_weight() -> f64 { 1.0 }
+
+impl Default for PortWeightsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_weight: default_port_weight(),
+            ports: HashMap::new(),
+            groups: Vec::new(),
+        }
+    }
+}
+
+/// Grup de porturi `[[detection.port_weights.groups]]`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortGroupConfig {
+    pub name: String,
+    pub ports: Vec<u16>,
+    pub weight: f64,
+}
+
+/// Profil de detectie `[[detection.profiles]]` — suprascrie pragurile globale
+/// pentru sursele care se potrivesc.
+///
+/// `sources` accepta IP-uri, CIDR-uri sau etichete din [network.subnets]
+/// (eticheta = toate CIDR-urile cu acea eticheta). Daca o sursa se potriveste
+/// in mai multe profile, castiga potrivirea cu prefixul cel mai lung.
+///
+/// Sectiunile prezente inlocuiesc INTEGRAL sectiunea globala corespunzatoare
+/// (ex: `[detection.profiles.fast_scan]` trebuie sa contina ambele campuri).
+/// Sectiunile absente raman cele globale.
+///
+/// NOTA RUST: `Option<T>` cu serde — campul lipsa din TOML devine `None`
+/// automat, fara `#[serde(default)]`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DetectionProfile {
+    /// Numele profilului — afisat in alerte (CLI, SIEM, email).
+    pub name: String,
+    /// IP-uri, CIDR-uri sau etichete [network.subnets] acoperite de profil.
+    #[serde(default)]
+    pub sources: Vec<String>,
+    pub alert_cooldown_secs: Option<u64>,
+    pub fast_scan: Option<FastScanConfig>,
+    pub slow_scan: Option<SlowScanConfig>,
+    pub accept_scan: Option<AcceptScanConfig>,
+    pub lateral_movement: Option<LateralMovementConfig>,
+    pub distributed_scan: Option<DistributedScanConfig>,
+    pub brute_force: Option<BruteForceConfig>,
+    pub ping_sweep: Option<PingSweepConfig>,
+}
+
+impl DetectionConfig {
+    /// Configuratia efectiva pentru un profil: copia config-ului global cu
+    /// sectiunile suprascrise de profil. Lista de profile a copiei este goala.
+    pub fn with_profile(&self, profile: &DetectionProfile) -> DetectionConfig {
+        let mut cfg = DetectionConfig {
+            profiles: Vec::new(),
+            ..self.clone()
+        };
+        if let Some(secs) = profile.alert_cooldown_secs {
+            cfg.alert_cooldown_secs = secs;
+        }
+        if let Some(ref fs) = profile.fast_scan {
+            cfg.fast_scan = fs.clone();
+        }
+        if let Some(ref ss) = profile.slow_scan {
+            cfg.slow_scan = ss.clone();
+        }
+        if let Some(ref acc) = profile.accept_scan {
+            cfg.accept_scan = acc.clone();
+        }
+        if let Some(ref lm) = profile.lateral_movement {
+            cfg.lateral_movement = lm.clone();
+        }
+        if let Some(ref ds) = profile.distributed_scan {
+            cfg.distributed_scan = ds.clone();
+        }
+        if let Some(ref bf) = profile.brute_force {
+            cfg.brute_force = bf.clone();
+        }
+        if let Some(ref ps) = profile.ping_sweep {
+            cfg.ping_sweep = ps.clone();
+        }
+        cfg
+    }
+
+    /// Cel mai lung cooldown configurat (global sau in orice profil).
+    /// Cleanup-ul nu trebuie sa stearga cooldown-urile inca active ale unui profil.
+    /// Include si cooldown-ul propriu al Brute Force si suprascrierile per detector.
+    pub fn max_alert_cooldown_secs(&self) -> u64 {
+        let profile_cooldowns = self.profiles.iter().flat_map(|p| {
+            [
+                p.alert_cooldown_secs,
+                p.brute_force.as_ref().and_then(|bf| bf.cooldown_secs),
+            ]
+        });
+        let detector_cooldowns = self
+            .cooldown
+            .overrides()
+            .into_iter()
+            .map(|(_, o)| o.as_ref().and_then(|o| o.secs));
+        profile_cooldowns
+            .chain([self.brute_force.cooldown_secs])
+            .chain(detector_cooldowns)
+            .flatten()
+            .fold(self.alert_cooldown_secs, u64::max)
+    }
 }
 
 fn default_max_hits_per_ip() -> usize {
@@ -154,7 +536,11 @@ fn default_max_tracked_ips() -> usize {
     100_000
 }
 
-#[derive(Debug, Clone, Deserialize)]
+fn default_ipv6_source_prefix() -> u8 {
+    128
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct FastScanConfig {
     /// Numar de porturi unice peste care se declanseaza alerta.
     pub port_threshold: usize,
@@ -162,7 +548,7 @@ pub struct FastScanConfig {
     pub time_window_secs: u64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SlowScanConfig {
     pub port_threshold: usize,
     /// Fereastra de timp in minute (convertita in secunde la utilizare).
@@ -182,7 +568,7 @@ pub struct SlowScanConfig {
 ///
 /// Pragurile implicite sunt mai conservative decat Fast Scan deoarece
 /// traficul accepted este mai "normal" si am vrea sa evitam false positives.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AcceptScanConfig {
     /// Numarul de porturi ACCEPTATE unice care declanseaza alerta.
     pub port_threshold: usize,
@@ -214,7 +600,7 @@ fn default_accept_scan() -> AcceptScanConfig {
 ///
 /// Valori implicite: 5 destinatii in 60 secunde, dezactivat implicit
 /// pentru retrocompatibilitate (config-uri vechi nu au sectiunea).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LateralMovementConfig {
     /// Activare/dezactivare detectie. Implicit: false (retrocompatibil).
     #[serde(default)]
@@ -253,7 +639,7 @@ fn default_lateral_movement() -> LateralMovementConfig {
 ///
 /// Valori implicite: 5 surse unice in 60 secunde, dezactivat implicit
 /// pentru retrocompatibilitate (config-uri vechi nu au sectiunea).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DistributedScanConfig {
     /// Activare/dezactivare detectie. Implicit: false (retrocompatibil).
     #[serde(default)]
@@ -279,13 +665,566 @@ fn default_distributed_scan() -> DistributedScanConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Configurare detectie Brute Force / flood `[detection.brute_force]`.
+///
+/// Un atacator care insista pe UN singur port (brute force SSH, password
+/// spraying RDP) genereaza un singur port unic — Fast/Slow Scan nu se
+/// declanseaza niciodata. Aici numaram evenimentele per tuplu (sursa,
+/// destinatie, port) intr-o fereastra glisanta, separat pentru drop si accept:
+///   - drop   → flood pe un port filtrat
+///   - accept → incercari repetate de autentificare pe un serviciu deschis
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BruteForceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Numarul de evenimente drop per tuplu care declanseaza alerta.
+    #[serde(default = "default_bf_drop_threshold")]
+    pub drop_threshold: usize,
+
+    /// Numarul de evenimente accept per tuplu care declanseaza alerta.
+    #[serde(default = "default_bf_accept_threshold")]
+    pub accept_threshold: usize,
+
+    /// Fereastra glisanta in secunde.
+    #[serde(default = "default_bf_time_window")]
+    pub time_window_secs: u64,
+
+    /// Cooldown propriu per tuplu (secunde). Lipsa = `alert_cooldown_secs`.
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+}
+
+fn default_bf_drop_threshold() -> usize { 50 }
+fn default_bf_accept_threshold() -> usize { 20 }
+fn default_bf_time_window() -> u64 { 60 }
+
+impl Default for BruteForceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            drop_threshold: default_bf_drop_threshold(),
+            accept_threshold: default_bf_accept_threshold(),
+            time_window_secs: default_bf_time_window(),
+            cooldown_secs: None,
+        }
+    }
+}
+
+/// Configurare detectie Ping Sweep `[detection.ping_sweep]`.
+///
+/// Descoperirea host-urilor active precede de obicei scanarea de porturi:
+/// atacatorul trimite ICMP echo request catre un subnet intreg. ICMP nu are
+/// porturi, deci Fast/Slow Scan nu vad nimic; aici numaram destinatiile unice
+/// atinse prin ICMP de recunoastere (echo, timestamp, address mask) — drop
+/// sau accept, indiferent.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PingSweepConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Numarul de destinatii unice care declanseaza alerta.
+    #[serde(default = "default_ping_dest_threshold")]
+    pub unique_dest_threshold: usize,
+
+    /// Fereastra glisanta in secunde.
+    #[serde(default = "default_ping_time_window")]
+    pub time_window_secs: u64,
+}
+
+fn default_ping_dest_threshold() -> usize { 10 }
+fn default_ping_time_window() -> u64 { 60 }
+
+impl Default for PingSweepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unique_dest_threshold: default_ping_dest_threshold(),
+            time_window_secs: default_ping_time_window(),
+        }
+    }
+}
+
+/// Configurare severitate dinamica `[detection.severity]`.
+///
+/// Fiecare tip de alerta are o severitate de baza (Fast 7, Slow 6, Accept 5,
+/// Lateral 8, Distributed 7, ...). Factorii de mai jos o ridica, plafonat la 10:
+///   - depasirea pragului: +`excess_per_doubling` pentru fiecare dublare a
+///     pragului (x2, x4, ...), maxim `max_excess`
+///   - activul tinta din inventar: `[assets]` critical_boost / high_boost
+///   - zona sursei: `internal_source` daca sursa e intr-un `[network.subnets]`,
+///     altfel `external_source`
+///   - porturi sensibile atinse (`[detection.port_weights]`): +`sensitive_ports`
+///   - recidiva: +`repeat_per_offence` pentru fiecare alerta anterioara a
+///     sursei in fereastra `[detection.repeat_offenders]`, maxim `max_repeat`
+///     (0 = recidiva apare in alerta dar nu ridica severitatea)
+///
+/// Cu `enabled = false` se folosesc severitatile fixe de baza.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SeverityConfig {
+    pub enabled: bool,
+    pub excess_per_doubling: u8,
+    pub max_excess: u8,
+    pub internal_source: u8,
+    pub external_source: u8,
+    pub sensitive_ports: u8,
+    pub repeat_per_offence: u8,
+    pub max_repeat: u8,
+}
+
+impl Default for SeverityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            excess_per_doubling: 1,
+            max_excess: 2,
+            internal_source: 1,
+            external_source: 0,
+            sensitive_ports: 1,
+            repeat_per_offence: 1,
+            max_repeat: 2,
+        }
+    }
+}
+
+/// Configurare recidiva `[detection.repeat_offenders]`.
+///
+/// Istoricul alertelor per sursa pe `horizon_hours` ore. Fiecare alerta poarta
+/// "a N-a alerta in `window_hours`h"; la `persistent_threshold` alerte in
+/// fereastra se emite o alerta PersistentScanner (0 = niciodata), cel mult
+/// una per sursa la `persistent_cooldown_hours` ore.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RepeatOffendersConfig {
+    pub enabled: bool,
+    pub window_hours: u64,
+    pub horizon_hours: u64,
+    pub persistent_threshold: usize,
+    pub persistent_cooldown_hours: u64,
+}
+
+impl Default for RepeatOffendersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_hours: 24,
+            horizon_hours: 168,
+            persistent_threshold: 5,
+            persistent_cooldown_hours: 24,
+        }
+    }
+}
+
+/// Configurare cooldown `[detection.cooldown]`.
+///
+/// Decide ce se intampla cu re-detectiile unei surse dupa alerta:
+///   "fixed"   — suprimate pana expira cooldown-ul de la alerta (implicit)
+///   "sliding" — fiecare re-detectie suprimata reporneste cooldown-ul
+///   "ongoing" — update cu totalurile cumulate la expirarea cooldown-ului daca
+///               scanarea continua, rezumat "scan ended" dupa `quiet_secs` fara
+///               activitate (verificat la fiecare cleanup)
+/// `mode` este valoarea implicita; sectiunile per detector suprascriu modul
+/// si/sau durata (`secs`, altfel `alert_cooldown_secs`). Brute Force are
+/// `cooldown_secs` propriu, celelalte detectoare raman pe "fixed".
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CooldownConfig {
+    pub mode: String,
+    pub quiet_secs: u64,
+    pub fast_scan: Option<DetectorCooldown>,
+    pub slow_scan: Option<DetectorCooldown>,
+    pub accept_scan: Option<DetectorCooldown>,
+    pub lateral_movement: Option<DetectorCooldown>,
+    pub ping_sweep: Option<DetectorCooldown>,
+    pub distributed_scan: Option<DetectorCooldown>,
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        Self {
+            mode: "fixed".to_string(),
+            quiet_secs: 120,
+            fast_scan: None,
+            slow_scan: None,
+            accept_scan: None,
+            lateral_movement: None,
+            ping_sweep: None,
+            distributed_scan: None,
+        }
+    }
+}
+
+/// Suprascrierea cooldown-ului unui detector (`[detection.cooldown.fast_scan]`).
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DetectorCooldown {
+    #[serde(default)]
+    pub secs: Option<u64>,
+    #[serde(default)]
+    pub mode: Option<String>,
+}
+
+impl CooldownConfig {
+    /// Suprascrierile per detector, cu numele cheii din config.
+    pub fn overrides(&self) -> [(&'static str, &Option<DetectorCooldown>); 6] {
+        [
+            ("fast_scan", &self.fast_scan),
+            ("slow_scan", &self.slow_scan),
+            ("accept_scan", &self.accept_scan),
+            ("lateral_movement", &self.lateral_movement),
+            ("ping_sweep", &self.ping_sweep),
+            ("distributed_scan", &self.distributed_scan),
+        ]
+    }
+
+    /// Durata (secunde) si modul efective ale detectorului `detector`.
+    pub fn resolve(&self, detector: &str, default_secs: u64) -> (u64, &str) {
+        let own = self
+            .overrides()
+            .into_iter()
+            .find(|(name, _)| *name == detector)
+            .and_then(|(_, o)| o.as_ref());
+        (
+            own.and_then(|o| o.secs).unwrap_or(default_secs),
+            own.and_then(|o| o.mode.as_deref()).unwrap_or(&self.mode),
+        )
+    }
+}
+
+/// Configurare sesiuni de scanare `[detection.sessions]`.
+///
+/// Prima alerta de scanare a unei surse (sau Distributed Scan a unei tinte)
+/// deschide o sesiune; dupa `idle_timeout_secs` fara evenimente, rezumatul
+/// sesiunii (durata, totaluri, drop/accept, porturile in ordinea sondarii —
+/// primele `max_ports`) merge catre `[alerting.sessions]`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionsConfig {
+    pub enabled: bool,
+    pub idle_timeout_secs: u64,
+    pub max_ports: usize,
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_timeout_secs: 300,
+            max_ports: 1024,
+        }
+    }
+}
+
+/// Configurare identitate NAT `[detection.nat]`.
+///
+/// Checkpoint logheaza adresele originale (`src` / `dst`) si, cand gateway-ul
+/// traduce, pe cele dupa NAT (`xlatesrc` / `xlatedst` / porturile). Fiecare
+/// detector poate urmari oricare identitate:
+///   "original"   — src / dst din log (host-urile interne reale, implicit)
+///   "translated" — adresele si porturile traduse, cand exista (ex: serverul
+///                  intern din spatele unui port-forward public)
+/// `identity` este valoarea implicita; campurile per detector o suprascriu.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NatConfig {
+    #[serde(default = "default_nat_identity")]
+    pub identity: String,
+
+    /// Fast + Slow Scan (impart evidenta drop-urilor).
+    #[serde(default)]
+    pub port_scan: Option<String>,
+    #[serde(default)]
+    pub accept_scan: Option<String>,
+    #[serde(default)]
+    pub lateral_movement: Option<String>,
+    #[serde(default)]
+    pub ping_sweep: Option<String>,
+    #[serde(default)]
+    pub distributed_scan: Option<String>,
+    #[serde(default)]
+    pub brute_force: Option<String>,
+    #[serde(default)]
+    pub beaconing: Option<String>,
+    #[serde(default)]
+    pub anomaly: Option<String>,
+}
+
+fn default_nat_identity() -> String { "original".to_string() }
+
+impl Default for NatConfig {
+    fn default() -> Self {
+        Self {
+            identity: default_nat_identity(),
+            port_scan: None,
+            accept_scan: None,
+            lateral_movement: None,
+            ping_sweep: None,
+            distributed_scan: None,
+            brute_force: None,
+            beaconing: None,
+            anomaly: None,
+        }
+    }
+}
+
+impl NatConfig {
+    /// Suprascrierile per detector, cu numele cheii din config.
+    fn overrides(&self) -> [(&'static str, &Option<String>); 8] {
+        [
+            ("port_scan", &self.port_scan),
+            ("accept_scan", &self.accept_scan),
+            ("lateral_movement", &self.lateral_movement),
+            ("ping_sweep", &self.ping_sweep),
+            ("distributed_scan", &self.distributed_scan),
+            ("brute_force", &self.brute_force),
+            ("beaconing", &self.beaconing),
+            ("anomaly", &self.anomaly),
+        ]
+    }
+
+    /// Detectorul `detector` (cheie din `[detection.nat]`) urmareste adresele traduse.
+    pub fn translated(&self, detector: &str) -> bool {
+        let value = self
+            .overrides()
+            .into_iter()
+            .find(|(name, _)| *name == detector)
+            .and_then(|(_, value)| value.as_deref())
+            .unwrap_or(&self.identity);
+        value == "translated"
+    }
+
+    /// Cel putin un detector urmareste adresele traduse.
+    pub fn any_translated(&self) -> bool {
+        self.identity == "translated"
+            || self.overrides().iter().any(|(_, value)| value.as_deref() == Some("translated"))
+    }
+}
+
+/// Configurare detectie beaconing `[detection.beaconing]`.
+///
+/// Malware-ul C2 se conecteaza periodic la serverul de comanda (ex: la fiecare
+/// 60 s, cu jitter mic). Fiecare conexiune in parte arata legitim — firewall-ul
+/// o accepta — dar regularitatea intervalelor o tradeaza.
+///
+/// Se urmaresc tuplurile (sursa, destinatie, port) din evenimentele accept.
+/// Alerta se emite cand un tuplu are cel putin `min_connections` conexiuni
+/// intinse pe cel putin `min_duration_secs`, iar coeficientul de variatie al
+/// intervalelor (deviatie standard / medie) este cel mult `max_jitter`.
+///
+/// Hit-urile la mai putin de `min_interval_secs` de precedentul apartin
+/// aceleiasi sesiuni (mai multe linii de log per conexiune, trafic continuu);
+/// o pauza mai lunga de `max_interval_secs` reporneste masurarea tuplului.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BeaconingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Numarul minim de conexiuni (sesiuni) observate pe tuplu.
+    #[serde(default = "default_beacon_min_connections")]
+    pub min_connections: usize,
+
+    /// Durata minima intre prima si ultima conexiune (secunde).
+    #[serde(default = "default_beacon_min_duration")]
+    pub min_duration_secs: u64,
+
+    /// Jitter maxim acceptat: coeficientul de variatie al intervalelor
+    /// (0.1 = deviatia standard este cel mult 10% din intervalul mediu).
+    #[serde(default = "default_beacon_max_jitter")]
+    pub max_jitter: f64,
+
+    /// Hit-urile mai apropiate de atat fac parte din aceeasi sesiune.
+    #[serde(default = "default_beacon_min_interval")]
+    pub min_interval_secs: u64,
+
+    /// O pauza mai lunga reporneste masurarea tuplului.
+    #[serde(default = "default_beacon_max_interval")]
+    pub max_interval_secs: u64,
+
+    /// Destinatii ignorate (IP sau CIDR) — servere NTP, update, antivirus,
+    /// care sunt contactate periodic in mod legitim.
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+}
+
+fn default_beacon_min_connections() -> usize { 10 }
+fn default_beacon_min_duration() -> u64 { 600 }
+fn default_beacon_max_jitter() -> f64 { 0.1 }
+fn default_beacon_min_interval() -> u64 { 5 }
+fn default_beacon_max_interval() -> u64 { 3600 }
+
+impl Default for BeaconingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_connections: default_beacon_min_connections(),
+            min_duration_secs: default_beacon_min_duration(),
+            max_jitter: default_beacon_max_jitter(),
+            min_interval_secs: default_beacon_min_interval(),
+            max_interval_secs: default_beacon_max_interval(),
+            allowlist: Vec::new(),
+        }
+    }
+}
+
+/// Configurare detectie anomalii pe baza de baseline invatat.
+///
+/// Pragurile fixe (fast_scan.port_threshold etc.) sunt globale: un jump host
+/// atinge zilnic 20 de porturi, o statie de lucru care atinge 8 este deja
+/// suspecta. Modul anomalie invata pentru fiecare sursa (sau subnet din
+/// [network.subnets]) comportamentul normal pe intervale de `bucket_secs`:
+///   - numarul de porturi unice
+///   - numarul de destinatii unice
+///   - proportia de drop-uri din total evenimente
+///
+/// Alerta se emite cand intervalul curent depaseste media cu mai mult de
+/// `stddev_threshold` deviatii standard. Baseline-ul este salvat pe disc
+/// (`state_file`) si reincarcat la pornire.
+///
+/// NOTA RUST: `impl Default` manual (nu derive) — valorile implicite nu sunt
+/// zero. Aceleasi functii `default_*` sunt folosite si de serde per camp.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AnomalyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Cheia baseline-ului: "source" (per IP sursa) sau "subnet" (per eticheta
+    /// din [network.subnets]; IP-urile fara eticheta raman pe baseline propriu).
+    #[serde(default = "default_anomaly_group_by")]
+    pub group_by: String,
+
+    /// Durata unui interval de masurare (secunde).
+    #[serde(default = "default_anomaly_bucket_secs")]
+    pub bucket_secs: u64,
+
+    /// Ponderea unui interval nou in media mobila exponentiala (0 < alpha <= 1).
+    /// 0.05 ≈ ultimele ~20 intervale active conteaza cel mai mult.
+    #[serde(default = "default_anomaly_learning_rate")]
+    pub learning_rate: f64,
+
+    /// Numarul minim de intervale invatate inainte de a emite alerte (warm-up).
+    #[serde(default = "default_anomaly_min_samples")]
+    pub min_samples: u64,
+
+    /// Cate deviatii standard peste medie declanseaza alerta.
+    #[serde(default = "default_anomaly_stddev_threshold")]
+    pub stddev_threshold: f64,
+
+    /// Deviatia standard minima folosita pentru porturi/destinatii. Evita alertele
+    /// la o sursa perfect constanta (stddev 0) care atinge un singur port in plus.
+    #[serde(default = "default_anomaly_min_stddev")]
+    pub min_stddev: f64,
+
+    /// Numarul minim de evenimente intr-un interval pentru evaluarea proportiei
+    /// de drop-uri (sub acest numar proportia nu este relevanta statistic).
+    #[serde(default = "default_anomaly_min_events")]
+    pub min_events: u64,
+
+    /// Fisierul in care se persista baseline-ul (JSON). Gol = fara persistenta.
+    #[serde(default)]
+    pub state_file: String,
+
+    /// Baseline-urile neactualizate de atatea zile sunt sterse la cleanup.
+    #[serde(default = "default_anomaly_max_idle_days")]
+    pub max_idle_days: u64,
+}
+
+fn default_anomaly_group_by() -> String { "source".to_string() }
+fn default_anomaly_bucket_secs() -> u64 { 60 }
+fn default_anomaly_learning_rate() -> f64 { 0.05 }
+fn default_anomaly_min_samples() -> u64 { 30 }
+fn default_anomaly_stddev_threshold() -> f64 { 3.0 }
+fn default_anomaly_min_stddev() -> f64 { 1.0 }
+fn default_anomaly_min_events() -> u64 { 10 }
+fn default_anomaly_max_idle_days() -> u64 { 30 }
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            group_by: default_anomaly_group_by(),
+            bucket_secs: default_anomaly_bucket_secs(),
+            learning_rate: default_anomaly_learning_rate(),
+            min_samples: default_anomaly_min_samples(),
+            stddev_threshold: default_anomaly_stddev_threshold(),
+            min_stddev: default_anomaly_min_stddev(),
+            min_events: default_anomaly_min_events(),
+            state_file: String::new(),
+            max_idle_days: default_anomaly_max_idle_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AlertingConfig {
     pub siem: SiemConfig,
     pub email: EmailConfig,
+
+    /// Istoricul local al alertelor (SQLite), interogat cu `ids-rs alerts`.
+    /// Retrocompatibil: daca sectiunea lipseste, istoricul este dezactivat.
+    #[serde(default)]
+    pub history: HistoryConfig,
+
+    /// Destinatiile rezumatelor de sesiune (`[detection.sessions]`).
+    #[serde(default)]
+    pub sessions: SessionOutputConfig,
+}
+
+/// Configurare `[alerting.history]`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HistoryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fisierul SQLite (creat la pornire, impreuna cu directorul).
+    #[serde(default = "default_history_path")]
+    pub path: String,
+
+    /// Alertele mai vechi de N zile sunt sterse la cleanup. 0 = pastreaza tot.
+    #[serde(default = "default_history_retention_days")]
+    pub retention_days: u64,
+}
+
+fn default_history_path() -> String {
+    "/var/lib/ids-rs/alerts.db".to_string()
+}
+
+/// Retentia maxima acceptata pentru istoric (100 de ani) — valorile mai mari
+/// nu pot fi scazute dintr-o data calendaristica.
+const MAX_HISTORY_RETENTION_DAYS: u64 = 36_500;
+
+fn default_history_retention_days() -> u64 {
+    90
+}
+
+/// Configurare `[alerting.sessions]` — unde merg rezumatele sesiunilor inchise.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionOutputConfig {
+    /// Eveniment CEF "Scan Session Closed" catre SIEM (daca SIEM e activat).
+    pub siem: bool,
+    /// Fisier JSON lines (o inregistrare per sesiune). Gol = dezactivat.
+    pub json_path: String,
+}
+
+impl Default for SessionOutputConfig {
+    fn default() -> Self {
+        Self {
+            siem: true,
+            json_path: String::new(),
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: default_history_path(),
+            retention_days: default_history_retention_days(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SiemConfig {
     pub enabled: bool,
     pub host: String,
@@ -298,7 +1237,7 @@ pub struct SiemConfig {
 /// Fiecare String este owned (detinut) de Vec, care la randul lui
 /// este owned de EmailConfig. Cand EmailConfig este dropat, tot
 /// lantul de ownership este dealocat automat - zero memory leaks.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct EmailConfig {
     pub enabled: bool,
     pub smtp_server: String,
@@ -328,7 +1267,154 @@ fn default_email_footer() -> String {
         .to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Configurare threat intel — liste de IP-uri/subretele cunoscute ca malitioase.
+///
+/// Listele vin offline (ex: pe USB de la CERT-ul national) si sunt citite din
+/// fisiere locale la pornire si la fiecare SIGHUP. Orice eveniment de la sau
+/// catre o adresa listata genereaza imediat alerta KnownBadSource /
+/// KnownBadDestination, indiferent de pragurile de scanare.
+///
+/// NOTA RUST: `#[derive(Default)]` genereaza `enabled = false, feeds = vec![]`
+/// — exact ce vrem cand sectiunea lipseste din config.toml.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ThreatIntelConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Lista de feed-uri. In TOML: `[[threat_intel.feeds]]` (array of tables).
+    #[serde(default)]
+    pub feeds: Vec<ThreatFeedConfig>,
+}
+
+/// Un feed IOC individual (un fisier).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ThreatFeedConfig {
+    /// Numele feed-ului — transmis in CEF (cs4) si in email.
+    pub name: String,
+
+    /// Calea catre fisierul local.
+    pub path: String,
+
+    /// Formatul fisierului: "plain" (un IP/CIDR pe linie), "csv"
+    /// (indicator[,confidence[,descriere]]) sau "stix" (bundle STIX 2.x simplificat).
+    #[serde(default = "default_feed_format")]
+    pub format: String,
+
+    /// Increderea implicita (0-100) pentru indicatorii fara valoare proprie.
+    #[serde(default = "default_feed_confidence")]
+    pub confidence: u8,
+}
+
+/// Inventarul de active — sectiunea `[assets]`.
+///
+/// Fisierul (CSV sau JSON) asociaza IP-uri / subretele cu hostname, echipa
+/// proprietara, criticitate si rol. Este re-citit la fiecare SIGHUP; un activ
+/// critic vizat de o alerta ridica severitatea ei.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AssetsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Calea catre fisierul inventarului.
+    #[serde(default)]
+    pub path: String,
+
+    /// Formatul fisierului: "csv" (ip,hostname,owner,criticality,role) sau "json".
+    #[serde(default = "default_assets_format")]
+    pub format: String,
+
+    /// Cu cat creste severitatea CEF cand tinta este un activ `critical`.
+    #[serde(default = "default_critical_boost")]
+    pub critical_boost: u8,
+
+    /// Cu cat creste severitatea CEF cand tinta este un activ `high`.
+    #[serde(default = "default_high_boost")]
+    pub high_boost: u8,
+}
+
+fn default_assets_format() -> String {
+    "csv".to_string()
+}
+
+fn default_critical_boost() -> u8 {
+    2
+}
+
+fn default_high_boost() -> u8 {
+    1
+}
+
+impl Default for AssetsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: String::new(),
+            format: default_assets_format(),
+            critical_boost: default_critical_boost(),
+            high_boost: default_high_boost(),
+        }
+    }
+}
+
+/// Socket-ul Unix de control — interogarea si administrarea detectorului
+/// pornit (starea unui IP, cooldown-uri, whitelist temporar, reload, alerta
+/// de test) prin `ids-rs ctl`.
+///
+/// Socket-ul este creat cu permisiuni 0600: doar utilizatorul procesului
+/// (si root) il poate folosi — nu exista alta autentificare.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ControlConfig {
+    pub enabled: bool,
+
+    /// Calea socket-ului. Directorul parinte este creat la pornire.
+    pub socket_path: String,
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            socket_path: "/run/ids-rs/ids-rs.sock".to_string(),
+        }
+    }
+}
+
+/// API-ul REST read-only: sursele si tintele urmarite, alertele recente,
+/// config-ul efectiv (fara secrete) si contoarele — JSON peste HTTP.
+///
+/// Cu `token` setat, fiecare cerere trebuie sa trimita
+/// `Authorization: Bearer <token>`. Fara token API-ul poate asculta doar
+/// pe loopback (validat).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub listen_address: String,
+    pub listen_port: u16,
+    pub token: String,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: "127.0.0.1".to_string(),
+            listen_port: 8088,
+            token: String::new(),
+        }
+    }
+}
+
+fn default_feed_format() -> String {
+    "plain".to_string()
+}
+
+fn default_feed_confidence() -> u8 {
+    50
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CleanupConfig {
     pub interval_secs: u64,
     pub max_entry_age_secs: u64,
@@ -347,10 +1433,7 @@ impl AppConfig {
     ///
     /// 2. OPERATORUL ? (Question Mark / Try)
     ///    `something()?` este echivalent cu:
-    ///      match something() {
-    ///          Ok(val) => val,
-    ///          Err(e)  => return Err(e.into()),
-    ///      }
+    ///    `match something() { Ok(val) => val, Err(e) => return Err(e.into()) }`
     ///    Propaga erorile automat in sus pe call stack. Functioneaza doar
     ///    in functii care returneaza Result sau Option.
     ///
@@ -359,6 +1442,19 @@ impl AppConfig {
     ///    Utile pentru debugging - stii exact UNDE si DE CE a esuat operatia.
     ///
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let config = Self::read(path)?;
+
+        // Validare semantica post-deserializare.
+        // serde verifica doar tipurile; validate() verifica logica si valorile.
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Citeste si deserializeaza fisierul, fara validare — pentru apelantii
+    /// care modifica config-ul inainte de a-l valida (suprascrierile din
+    /// linia de comanda, vezi cli.rs).
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
         // `read_to_string` citeste intregul fisier intr-un String (owned).
         // Returneaza Result<String, io::Error>.
         let content = std::fs::read_to_string(path.as_ref())
@@ -367,14 +1463,21 @@ impl AppConfig {
         // `toml::from_str` deserializeaza continutul TOML in structura noastra.
         // Aceasta functioneaza datorita #[derive(Deserialize)] de pe AppConfig.
         // serde mapeaza automat cheile TOML pe campurile structurii.
-        let config: AppConfig = toml::from_str(&content)
-            .context("Eroare la parsarea fisierului TOML")?;
-
-        // Validare semantica post-deserializare.
-        // serde verifica doar tipurile; validate() verifica logica si valorile.
-        config.validate()?;
+        toml::from_str(&content).context("Eroare la parsarea fisierului TOML")
+    }
 
-        Ok(config)
+    /// Copia config-ului fara secrete (parola SMTP, token-ul API) — pentru
+    /// `GET /config`. Un secret setat devine "***", unul gol ramane gol.
+    pub fn redacted(&self) -> AppConfig {
+        let mask = |secret: &mut String| {
+            if !secret.is_empty() {
+                *secret = "***".to_string();
+            }
+        };
+        let mut config = self.clone();
+        mask(&mut config.alerting.email.password);
+        mask(&mut config.api.token);
+        config
     }
 
     /// Valideaza constrangerile semantice ale configuratiei.
@@ -385,7 +1488,7 @@ impl AppConfig {
     ///
     /// `anyhow::bail!` este echivalent cu `return Err(anyhow::anyhow!(...))`.
     /// Macro-ul bail! accepta acelasi format ca println!, cu {} interpolation.
-    fn validate(&self) -> Result<()> {
+    pub fn validate(&self) -> Result<()> {
         let mut errors: Vec<String> = Vec::new();
 
         // --- Network ---
@@ -399,14 +1502,33 @@ impl AppConfig {
         if self.network.listen_address.is_empty() {
             errors.push("network.listen_address nu poate fi gol".to_string());
         }
-        if !matches!(self.network.parser.as_str(), "gaia" | "cef" | "gaia_cef") {
+        if !matches!(self.network.parser.as_str(), "gaia" | "cef" | "gaia_cef" | "auto") {
             errors.push(format!(
-                "network.parser = {:?} este invalid. Valori acceptate: \"gaia\", \"cef\", \"gaia_cef\"",
+                "network.parser = {:?} este invalid. Valori acceptate: \"gaia\", \"cef\", \"gaia_cef\", \"auto\"",
                 self.network.parser
             ));
         }
+        // Lista pentru "auto": parsere concrete, fara duplicate.
+        if self.network.parser == "auto" {
+            if self.network.auto_parsers.is_empty() {
+                errors.push("network.auto_parsers nu poate fi gol cand parser = \"auto\"".to_string());
+            }
+            let mut seen: Vec<&str> = Vec::new();
+            for name in &self.network.auto_parsers {
+                if !crate::parser::PARSER_NAMES.contains(&name.as_str()) {
+                    errors.push(format!(
+                        "network.auto_parsers: {:?} este invalid. Valori acceptate: {}",
+                        name,
+                        crate::parser::PARSER_NAMES.join(", ")
+                    ));
+                } else if seen.contains(&name.as_str()) {
+                    errors.push(format!("network.auto_parsers: {:?} duplicat", name));
+                }
+                seen.push(name);
+            }
+        }
         // Validare hostnames: cheile trebuie sa fie IP-uri valide.
-        for (ip_str, _hostname) in &self.network.hostnames {
+        for ip_str in self.network.hostnames.keys() {
             if ip_str.parse::<std::net::IpAddr>().is_err() {
                 errors.push(format!(
                     "network.hostnames: cheia \"{}\" nu este un IP valid", ip_str
@@ -415,7 +1537,7 @@ impl AppConfig {
         }
 
         // Validare subnets: cheile trebuie sa fie CIDR valide.
-        for (cidr_str, _label) in &self.network.subnets {
+        for cidr_str in self.network.subnets.keys() {
             if SubnetEntry::parse(cidr_str).is_none() {
                 errors.push(format!(
                     "network.subnets: cheia \"{}\" nu este un CIDR valid (ex: \"10.10.1.0/24\")",
@@ -491,6 +1613,12 @@ impl AppConfig {
                     .to_string(),
             );
         }
+        if !(32..=128).contains(&self.detection.ipv6_source_prefix) {
+            errors.push(format!(
+                "detection.ipv6_source_prefix = {}: trebuie intre 32 si 128 (recomandat 64 sau 56)",
+                self.detection.ipv6_source_prefix
+            ));
+        }
         if self.detection.fast_scan.port_threshold == 0 {
             errors.push(
                 "detection.fast_scan.port_threshold = 0: orice pachet va declansa alerta Fast Scan"
@@ -560,6 +1688,416 @@ impl AppConfig {
             }
         }
 
+        // Validare Brute Force (doar daca e activat).
+        let bf = &self.detection.brute_force;
+        if bf.enabled {
+            if bf.drop_threshold == 0 || bf.accept_threshold == 0 {
+                errors.push(
+                    "detection.brute_force: drop_threshold si accept_threshold trebuie sa fie > 0"
+                        .to_string(),
+                );
+            }
+            if bf.time_window_secs == 0 {
+                errors.push(
+                    "detection.brute_force.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+            if bf.cooldown_secs == Some(0) {
+                errors.push("detection.brute_force.cooldown_secs = 0: fara cooldown".to_string());
+            }
+        }
+
+        // Validare Ping Sweep (doar daca e activat).
+        let ps = &self.detection.ping_sweep;
+        if ps.enabled {
+            if ps.unique_dest_threshold < 2 {
+                errors.push(format!(
+                    "detection.ping_sweep.unique_dest_threshold = {}: un sweep inseamna cel putin 2 destinatii",
+                    ps.unique_dest_threshold
+                ));
+            }
+            if ps.time_window_secs == 0 {
+                errors.push(
+                    "detection.ping_sweep.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+        }
+
+        // Validare Beaconing (doar daca e activat).
+        let bc = &self.detection.beaconing;
+        if bc.enabled {
+            if bc.min_connections < 3 {
+                errors.push(format!(
+                    "detection.beaconing.min_connections = {}: sunt necesare cel putin 3 conexiuni (2 intervale)",
+                    bc.min_connections
+                ));
+            }
+            if !(bc.max_jitter > 0.0 && bc.max_jitter <= 1.0) {
+                errors.push(format!(
+                    "detection.beaconing.max_jitter = {}: trebuie sa fie in intervalul (0, 1]",
+                    bc.max_jitter
+                ));
+            }
+            if bc.min_interval_secs == 0 {
+                errors.push(
+                    "detection.beaconing.min_interval_secs = 0: fiecare linie de log ar fi o conexiune separata"
+                        .to_string(),
+                );
+            }
+            if bc.max_interval_secs <= bc.min_interval_secs {
+                errors.push(format!(
+                    "detection.beaconing.max_interval_secs ({}) trebuie sa fie > min_interval_secs ({})",
+                    bc.max_interval_secs, bc.min_interval_secs
+                ));
+            }
+            for entry in &bc.allowlist {
+                if SubnetEntry::parse_ip_or_cidr(entry).is_none() {
+                    errors.push(format!(
+                        "detection.beaconing.allowlist: \"{}\" nu este IP sau CIDR valid", entry
+                    ));
+                }
+            }
+        }
+
+        // Validare identitate NAT.
+        let nat = &self.detection.nat;
+        let identities = std::iter::once(("identity", Some(&nat.identity)))
+            .chain(nat.overrides().into_iter().map(|(name, value)| (name, value.as_ref())));
+        for (name, value) in identities {
+            if let Some(value) = value {
+                if !matches!(value.as_str(), "original" | "translated") {
+                    errors.push(format!(
+                        "detection.nat.{} = {:?} este invalid. Valori acceptate: \"original\", \"translated\"",
+                        name, value
+                    ));
+                }
+            }
+        }
+
+        // Validare cooldown per detector.
+        let cd = &self.detection.cooldown;
+        let modes = std::iter::once(("mode".to_string(), Some(&cd.mode))).chain(
+            cd.overrides()
+                .into_iter()
+                .filter_map(|(name, o)| o.as_ref().map(|o| (format!("{}.mode", name), o.mode.as_ref()))),
+        );
+        for (name, value) in modes {
+            if let Some(value) = value {
+                if !matches!(value.as_str(), "fixed" | "sliding" | "ongoing") {
+                    errors.push(format!(
+                        "detection.cooldown.{} = {:?} este invalid. Valori acceptate: \"fixed\", \"sliding\", \"ongoing\"",
+                        name, value
+                    ));
+                }
+            }
+        }
+        for (name, o) in cd.overrides() {
+            if o.as_ref().and_then(|o| o.secs) == Some(0) {
+                errors.push(format!("detection.cooldown.{}.secs trebuie sa fie >= 1", name));
+            }
+        }
+        if cd.quiet_secs == 0 {
+            errors.push("detection.cooldown.quiet_secs trebuie sa fie >= 1".to_string());
+        }
+
+        // Validare sesiuni de scanare (doar daca sunt activate).
+        let ss = &self.detection.sessions;
+        if ss.enabled {
+            if ss.idle_timeout_secs == 0 {
+                errors.push("detection.sessions.idle_timeout_secs trebuie sa fie >= 1".to_string());
+            }
+            if ss.max_ports == 0 {
+                errors.push("detection.sessions.max_ports trebuie sa fie >= 1".to_string());
+            }
+        }
+
+        // Validare severitate dinamica.
+        let sev = &self.detection.severity;
+        if sev.enabled {
+            for (name, value) in [
+                ("excess_per_doubling", sev.excess_per_doubling),
+                ("max_excess", sev.max_excess),
+                ("internal_source", sev.internal_source),
+                ("external_source", sev.external_source),
+                ("sensitive_ports", sev.sensitive_ports),
+                ("repeat_per_offence", sev.repeat_per_offence),
+                ("max_repeat", sev.max_repeat),
+            ] {
+                if value > 5 {
+                    errors.push(format!("detection.severity.{} = {}: valoarea maxima este 5", name, value));
+                }
+            }
+        }
+
+        // Validare recidiva (doar daca e activata).
+        let ro = &self.detection.repeat_offenders;
+        if ro.enabled {
+            if ro.window_hours == 0 {
+                errors.push("detection.repeat_offenders.window_hours trebuie sa fie >= 1".to_string());
+            }
+            if ro.horizon_hours < ro.window_hours {
+                errors.push(format!(
+                    "detection.repeat_offenders.horizon_hours ({}) trebuie sa fie >= window_hours ({})",
+                    ro.horizon_hours, ro.window_hours
+                ));
+            }
+            if ro.persistent_threshold > 0 && ro.persistent_cooldown_hours == 0 {
+                errors.push(
+                    "detection.repeat_offenders.persistent_cooldown_hours trebuie sa fie >= 1".to_string(),
+                );
+            }
+        }
+
+        // Validare Anomaly (doar daca e activat).
+        let an = &self.detection.anomaly;
+        if an.enabled {
+            if !matches!(an.group_by.as_str(), "source" | "subnet") {
+                errors.push(format!(
+                    "detection.anomaly.group_by = {:?} este invalid. Valori acceptate: \"source\", \"subnet\"",
+                    an.group_by
+                ));
+            }
+            if an.bucket_secs == 0 {
+                errors.push(
+                    "detection.anomaly.bucket_secs = 0: intervalul de masurare nu poate fi zero"
+                        .to_string(),
+                );
+            }
+            if !(an.learning_rate > 0.0 && an.learning_rate <= 1.0) {
+                errors.push(format!(
+                    "detection.anomaly.learning_rate = {}: trebuie sa fie in intervalul (0, 1]",
+                    an.learning_rate
+                ));
+            }
+            if an.min_samples == 0 {
+                errors.push(
+                    "detection.anomaly.min_samples = 0: alertele ar porni inainte de orice invatare"
+                        .to_string(),
+                );
+            }
+            if an.stddev_threshold <= 0.0 {
+                errors.push(format!(
+                    "detection.anomaly.stddev_threshold = {}: trebuie sa fie pozitiv",
+                    an.stddev_threshold
+                ));
+            }
+            if an.min_stddev < 0.0 {
+                errors.push(format!(
+                    "detection.anomaly.min_stddev = {}: nu poate fi negativ",
+                    an.min_stddev
+                ));
+            }
+        }
+
+        // Validare ponderi porturi (doar daca e activat).
+        let pw = &self.detection.port_weights;
+        if pw.enabled {
+            if !(pw.default_weight.is_finite() && pw.default_weight >= 0.0) {
+                errors.push(format!(
+                    "detection.port_weights.default_weight = {}: trebuie sa fie ≥ 0",
+                    pw.default_weight
+                ));
+            }
+            for (port, weight) in &pw.ports {
+                if port.parse::<u16>().is_err() {
+                    errors.push(format!(
+                        "detection.port_weights.ports: cheia \"{}\" nu este un port valid (0-65535)",
+                        port
+                    ));
+                }
+                if !(weight.is_finite() && *weight >= 0.0) {
+                    errors.push(format!(
+                        "detection.port_weights.ports: ponderea portului {} = {}: trebuie sa fie ≥ 0",
+                        port, weight
+                    ));
+                }
+            }
+            for group in &pw.groups {
+                if group.name.trim().is_empty() {
+                    errors.push("detection.port_weights.groups: fiecare grup trebuie sa aiba un nume".to_string());
+                }
+                if group.ports.is_empty() {
+                    errors.push(format!(
+                        "detection.port_weights.groups[\"{}\"]: lista de porturi goala", group.name
+                    ));
+                }
+                if !(group.weight.is_finite() && group.weight >= 0.0) {
+                    errors.push(format!(
+                        "detection.port_weights.groups[\"{}\"].weight = {}: trebuie sa fie ≥ 0",
+                        group.name, group.weight
+                    ));
+                }
+            }
+        }
+
+        // Validare scor de risc (doar daca e activat).
+        let risk = &self.detection.risk;
+        if risk.enabled {
+            if risk.half_life_secs == 0 {
+                errors.push(
+                    "detection.risk.half_life_secs = 0: scorul ar disparea instantaneu".to_string(),
+                );
+            }
+            if risk.tiers.is_empty() {
+                errors.push("detection.risk.tiers: cel putin un prag de risc".to_string());
+            }
+            if risk.tiers.iter().any(|t| !(t.is_finite() && *t > 0.0)) {
+                errors.push(format!(
+                    "detection.risk.tiers = {:?}: pragurile trebuie sa fie pozitive",
+                    risk.tiers
+                ));
+            }
+            if risk.tiers.windows(2).any(|w| w[0] >= w[1]) {
+                errors.push(format!(
+                    "detection.risk.tiers = {:?}: pragurile trebuie sa fie strict crescatoare",
+                    risk.tiers
+                ));
+            }
+            let w = &risk.weights;
+            let weights = [
+                w.fast_scan, w.slow_scan, w.accept_scan, w.lateral_movement,
+                w.distributed_scan, w.known_bad_source, w.known_bad_destination, w.anomaly,
+                w.brute_force, w.beaconing, w.ping_sweep, w.attack_chain,
+            ];
+            if weights.iter().any(|x| !(x.is_finite() && *x >= 0.0)) {
+                errors.push("detection.risk.weights: punctele trebuie sa fie ≥ 0".to_string());
+            }
+        }
+
+        // Validare reguli de corelare (doar daca e activat).
+        let corr = &self.detection.correlation;
+        if corr.enabled {
+            if corr.rules.is_empty() {
+                errors.push("detection.correlation.rules: cel putin o regula de lant".to_string());
+            }
+            let mut rule_names: Vec<&str> = Vec::new();
+            for rule in &corr.rules {
+                let ctx = format!("detection.correlation.rules[\"{}\"]", rule.name);
+                if rule.name.trim().is_empty() {
+                    errors.push("detection.correlation.rules: fiecare regula trebuie sa aiba un nume".to_string());
+                } else if rule_names.contains(&rule.name.as_str()) {
+                    errors.push(format!("{}: nume de regula duplicat", ctx));
+                }
+                rule_names.push(&rule.name);
+                if rule.window_secs == 0 {
+                    errors.push(format!("{}.window_secs = 0: lantul nu se poate forma", ctx));
+                }
+                if rule.stages.len() < 2 {
+                    errors.push(format!("{}.stages: un lant are cel putin 2 etape", ctx));
+                }
+                for (i, stage) in rule.stages.iter().enumerate() {
+                    if !CHAIN_STAGE_KINDS.contains(&stage.alert.as_str()) {
+                        errors.push(format!(
+                            "{}.stages[{}].alert = {:?} este invalid. Valori acceptate: {}",
+                            ctx, i, stage.alert, CHAIN_STAGE_KINDS.join(", ")
+                        ));
+                    }
+                    if !matches!(stage.on.as_str(), "source" | "target" | "touched") {
+                        errors.push(format!(
+                            "{}.stages[{}].on = {:?} este invalid. Valori acceptate: \"source\", \"target\", \"touched\"",
+                            ctx, i, stage.on
+                        ));
+                    }
+                    if i == 0 && stage.on == "touched" {
+                        errors.push(format!(
+                            "{}.stages[0].on = \"touched\": prima etapa nu are inca un subiect", ctx
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Validare profile de detectie.
+        let mut profile_names: Vec<&str> = Vec::new();
+        let subnet_labels: Vec<&String> = self.network.subnets.values().collect();
+        for profile in &self.detection.profiles {
+            let ctx = format!("detection.profiles[\"{}\"]", profile.name);
+            if profile.name.trim().is_empty() {
+                errors.push("detection.profiles: fiecare profil trebuie sa aiba un nume".to_string());
+            } else if profile_names.contains(&profile.name.as_str()) {
+                errors.push(format!("{}: nume de profil duplicat", ctx));
+            }
+            profile_names.push(&profile.name);
+
+            if profile.sources.is_empty() {
+                errors.push(format!("{}.sources: lista goala, profilul nu s-ar aplica niciodata", ctx));
+            }
+            for src in &profile.sources {
+                if SubnetEntry::parse_ip_or_cidr(src).is_none()
+                    && !subnet_labels.contains(&src)
+                {
+                    errors.push(format!(
+                        "{}.sources: \"{}\" nu este IP, CIDR sau eticheta din [network.subnets]",
+                        ctx, src
+                    ));
+                }
+            }
+
+            if profile.alert_cooldown_secs == Some(0) {
+                errors.push(format!("{}.alert_cooldown_secs = 0: fara cooldown", ctx));
+            }
+            if let Some(ref fs) = profile.fast_scan {
+                if fs.port_threshold == 0 || fs.time_window_secs == 0 {
+                    errors.push(format!(
+                        "{}.fast_scan: port_threshold si time_window_secs trebuie sa fie > 0", ctx
+                    ));
+                }
+            }
+            if let Some(ref ss) = profile.slow_scan {
+                if ss.port_threshold == 0 || ss.time_window_mins == 0 {
+                    errors.push(format!(
+                        "{}.slow_scan: port_threshold si time_window_mins trebuie sa fie > 0", ctx
+                    ));
+                }
+            }
+            if let Some(ref acc) = profile.accept_scan {
+                if acc.port_threshold == 0 || acc.time_window_secs == 0 {
+                    errors.push(format!(
+                        "{}.accept_scan: port_threshold si time_window_secs trebuie sa fie > 0", ctx
+                    ));
+                }
+            }
+            if let Some(ref lm) = profile.lateral_movement {
+                if lm.enabled && (lm.unique_dest_threshold == 0 || lm.time_window_secs == 0) {
+                    errors.push(format!(
+                        "{}.lateral_movement: unique_dest_threshold si time_window_secs trebuie sa fie > 0",
+                        ctx
+                    ));
+                }
+            }
+            if let Some(ref ds) = profile.distributed_scan {
+                if ds.enabled && (ds.unique_sources_threshold == 0 || ds.time_window_secs == 0) {
+                    errors.push(format!(
+                        "{}.distributed_scan: unique_sources_threshold si time_window_secs trebuie sa fie > 0",
+                        ctx
+                    ));
+                }
+            }
+            if let Some(ref bf) = profile.brute_force {
+                if bf.enabled
+                    && (bf.drop_threshold == 0
+                        || bf.accept_threshold == 0
+                        || bf.time_window_secs == 0
+                        || bf.cooldown_secs == Some(0))
+                {
+                    errors.push(format!(
+                        "{}.brute_force: pragurile, time_window_secs si cooldown_secs trebuie sa fie > 0",
+                        ctx
+                    ));
+                }
+            }
+            if let Some(ref ps) = profile.ping_sweep {
+                if ps.enabled && (ps.unique_dest_threshold < 2 || ps.time_window_secs == 0) {
+                    errors.push(format!(
+                        "{}.ping_sweep: unique_dest_threshold trebuie sa fie ≥ 2 si time_window_secs > 0",
+                        ctx
+                    ));
+                }
+            }
+        }
+
         // Consistenta logica: fereastra Slow Scan trebuie sa fie mai mare decat Fast Scan.
         // Altfel cele doua detectii se suprapun si Slow Scan nu are sens.
         let fast_secs = self.detection.fast_scan.time_window_secs;
@@ -603,6 +2141,80 @@ impl AppConfig {
             ));
         }
 
+        // --- Threat intel ---
+
+        if self.threat_intel.enabled && self.threat_intel.feeds.is_empty() {
+            errors.push(
+                "threat_intel.enabled = true dar nu exista niciun [[threat_intel.feeds]]"
+                    .to_string(),
+            );
+        }
+        for (i, feed) in self.threat_intel.feeds.iter().enumerate() {
+            if feed.name.is_empty() {
+                errors.push(format!("threat_intel.feeds[{}].name nu poate fi gol", i));
+            }
+            if feed.path.is_empty() {
+                errors.push(format!("threat_intel.feeds[{}].path nu poate fi gol", i));
+            }
+            if !matches!(feed.format.as_str(), "plain" | "csv" | "stix") {
+                errors.push(format!(
+                    "threat_intel.feeds[{}].format = {:?} este invalid. Valori acceptate: \"plain\", \"csv\", \"stix\"",
+                    i, feed.format
+                ));
+            }
+            if feed.confidence > 100 {
+                errors.push(format!(
+                    "threat_intel.feeds[{}].confidence = {}: valoarea maxima este 100",
+                    i, feed.confidence
+                ));
+            }
+        }
+
+        // --- Inventar active ---
+
+        if self.assets.enabled && self.assets.path.is_empty() {
+            errors.push("assets.enabled = true dar assets.path este gol".to_string());
+        }
+        if !matches!(self.assets.format.as_str(), "csv" | "json") {
+            errors.push(format!(
+                "assets.format = {:?} este invalid. Valori acceptate: \"csv\", \"json\"",
+                self.assets.format
+            ));
+        }
+        for (name, boost) in [
+            ("critical_boost", self.assets.critical_boost),
+            ("high_boost", self.assets.high_boost),
+        ] {
+            if boost > 5 {
+                errors.push(format!("assets.{} = {}: valoarea maxima este 5", name, boost));
+            }
+        }
+
+        // --- Socket de control ---
+
+        if self.control.enabled && self.control.socket_path.is_empty() {
+            errors.push("control.enabled = true dar control.socket_path este gol".to_string());
+        }
+
+        // --- API REST ---
+
+        if self.api.enabled {
+            if self.api.listen_port == 0 {
+                errors.push("api.listen_port = 0 este invalid".to_string());
+            }
+            match self.api.listen_address.parse::<IpAddr>() {
+                Ok(ip) if !ip.is_loopback() && self.api.token.is_empty() => errors.push(format!(
+                    "api.listen_address = {:?} nu este loopback: seteaza api.token",
+                    self.api.listen_address
+                )),
+                Ok(_) => {}
+                Err(_) => errors.push(format!(
+                    "api.listen_address = {:?} nu este o adresa IP valida",
+                    self.api.listen_address
+                )),
+            }
+        }
+
         // --- Alerting: SIEM ---
 
         if self.alerting.siem.enabled {
@@ -639,6 +2251,18 @@ impl AppConfig {
             }
         }
 
+        // --- Alerting: Istoric ---
+
+        if self.alerting.history.enabled && self.alerting.history.path.is_empty() {
+            errors.push("alerting.history.enabled = true dar alerting.history.path este gol".to_string());
+        }
+        if self.alerting.history.retention_days > MAX_HISTORY_RETENTION_DAYS {
+            errors.push(format!(
+                "alerting.history.retention_days = {}: maxim {} (0 = pastreaza tot)",
+                self.alerting.history.retention_days, MAX_HISTORY_RETENTION_DAYS
+            ));
+        }
+
         // Raportam toate erorile dintr-o singura data.
         if errors.is_empty() {
             Ok(())
@@ -656,6 +2280,95 @@ impl AppConfig {
             );
         }
     }
+
+    /// Valori legale, dar riscante in productie — raportate de
+    /// `ids-rs check-config`, fara sa opreasca pornirea.
+    ///
+    /// Presupune un config deja validat (CIDR-urile din whitelist sunt corecte).
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = Vec::new();
+
+        if self.network.debug {
+            warnings.push(
+                "network.debug = true: fiecare pachet este afisat — volum mare de output in productie"
+                    .to_string(),
+            );
+        }
+        if self.network.udp_rate_limit == 0 {
+            warnings.push(
+                "network.udp_rate_limit = 0: fara rate limiting, un flood UDP pe portul de syslog \
+                 consuma CPU si memorie nelimitat"
+                    .to_string(),
+            );
+        }
+
+        // Un CIDR foarte larg in whitelist scoate din detectie o parte mare
+        // din retea (0.0.0.0/0 = totul).
+        for entry in &self.detection.whitelist {
+            if let Some((ip, prefix)) = entry.split_once('/') {
+                let prefix: u8 = prefix.parse().unwrap_or(u8::MAX);
+                let too_wide = if ip.contains(':') { prefix < 32 } else { prefix < 8 };
+                if too_wide {
+                    warnings.push(format!(
+                        "detection.whitelist: \"{}\" acopera o parte foarte mare din spatiul de adrese \
+                         — sursele din el nu mai sunt analizate deloc",
+                        entry
+                    ));
+                }
+            }
+        }
+
+        if self.detection.fast_scan.port_threshold < 5 {
+            warnings.push(format!(
+                "detection.fast_scan.port_threshold = {}: clientii legitimi ating des cateva porturi \
+                 in cateva secunde — risc mare de alerte false",
+                self.detection.fast_scan.port_threshold
+            ));
+        }
+        if self.detection.alert_cooldown_secs < 10 {
+            warnings.push(format!(
+                "detection.alert_cooldown_secs = {}: o scanare lunga genereaza o alerta la fiecare \
+                 cateva secunde",
+                self.detection.alert_cooldown_secs
+            ));
+        }
+
+        let email = &self.alerting.email;
+        if email.enabled && !email.smtp_tls && !email.username.is_empty() {
+            warnings.push(
+                "alerting.email: autentificare SMTP fara smtp_tls — credentialele circula in clar"
+                    .to_string(),
+            );
+        }
+        if !self.alerting.siem.enabled && !email.enabled && !self.alerting.history.enabled {
+            warnings.push(
+                "alerting: SIEM, email si istoric dezactivate — alertele apar doar in consola".to_string(),
+            );
+        }
+        if self.alerting.history.enabled && self.alerting.history.retention_days == 0 {
+            warnings.push(
+                "alerting.history.retention_days = 0: istoricul SQLite creste nelimitat".to_string(),
+            );
+        }
+
+        if self.api.enabled {
+            let loopback = self
+                .api
+                .listen_address
+                .parse::<std::net::IpAddr>()
+                .map(|ip| ip.is_loopback())
+                .unwrap_or(false);
+            if !loopback {
+                warnings.push(format!(
+                    "api.listen_address = {:?}: API-ul este HTTP simplu — token-ul si datele \
+                     circula necriptat in retea",
+                    self.api.listen_address
+                ));
+            }
+        }
+
+        warnings
+    }
 }
 
 // =============================================================================
@@ -712,6 +2425,27 @@ impl SubnetEntry {
         }
     }
 
+    /// Parseaza un IP individual sau un CIDR. IP-ul simplu devine /32 (IPv4)
+    /// sau /128 (IPv6). Folosit pentru indicatorii din feed-urile threat intel.
+    pub fn parse_ip_or_cidr(entry: &str) -> Option<Self> {
+        if entry.contains('/') {
+            return SubnetEntry::parse(entry);
+        }
+        let ip: IpAddr = entry.parse().ok()?;
+        let prefix = if ip.is_ipv4() { 32 } else { 128 };
+        SubnetEntry::parse(&format!("{}/{}", ip, prefix))
+    }
+
+    /// Lungimea prefixului (ex: 24 pentru /24).
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Eticheta subnetului din [network.subnets] (goala pentru CIDR-uri fara eticheta).
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
     /// Parseaza mapping-urile din config.toml intr-o lista de SubnetEntry cu label.
     pub fn parse_subnets(raw: &HashMap<String, String>) -> Vec<SubnetEntry> {
         raw.iter()
@@ -725,7 +2459,7 @@ impl SubnetEntry {
     }
 
     /// Verifica daca un IP apartine acestui subnet.
-    fn matches(&self, ip: &IpAddr) -> bool {
+    pub fn matches(&self, ip: &IpAddr) -> bool {
         match (&self.inner, ip) {
             (SubnetInner::V4 { network, mask }, IpAddr::V4(addr)) => {
                 (u32::from(*addr) & mask) == *network
@@ -0,0 +1,535 @@
+// =============================================================================
+// control.rs - Socket de control (`ids-rs ctl`)
+// =============================================================================
+//
+// Operatorul poate interoga si administra detectorul pornit fara restart si
+// fara SIGHUP "orb": un socket Unix (`[control].socket_path`) primeste cate o
+// comanda text pe conexiune si raspunde cu rezultatul.
+//
+//   state <ip>                        hit-uri, destinatii, cooldown-uri, sesiuni
+//   clear-cooldowns <ip>              urmatoarea detectie alerteaza imediat
+//   clear-state <ip>                  uita tot ce detectorul stie despre IP, inclusiv recidiva
+//   whitelist add <ip|cidr> [--ttl D] whitelist temporar (30m / 1h / 2d)
+//   whitelist remove <ip|cidr>
+//   whitelist list
+//   cleanup                           ruleaza acum ciclul de cleanup
+//   reload                            reincarca config.toml, cu raport
+//   test-alert                        alerta sintetica prin toate destinatiile
+//
+// Protocolul: clientul trimite o linie, serverul raspunde cu "OK" sau "ERR"
+// pe prima linie, apoi corpul raspunsului, si inchide conexiunea.
+//
+// `cleanup` si `reload` nu se executa aici: sunt trimise (cu un canal de
+// raspuns) task-ului de cleanup, respectiv main loop-ului — singurele locuri
+// care detin starea necesara (config-ul curent, rate limiter-ul).
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. CANALE TOKIO (mpsc + oneshot)
+//    `mpsc::Sender<Reply>` trimite cererea catre task-ul care o executa;
+//    `Reply` este un `oneshot::Sender` — un canal de unica folosinta prin care
+//    executantul intoarce raportul. Cererea "poarta" cu ea adresa raspunsului.
+//
+// 2. UNIX DOMAIN SOCKETS
+//    Un socket local identificat printr-o cale in filesystem. Accesul este
+//    controlat prin permisiunile fisierului (0600) — fara porturi deschise
+//    in retea si fara autentificare separata. `bind` creeaza fisierul cu
+//    umask-ul procesului, deci socket-ul este legat intr-un director 0700
+//    si mutat la calea finala abia dupa `chmod` (vezi `bind_private`).
+//
+// =============================================================================
+
+use crate::alerter::Alerter;
+use crate::assets::AssetInventory;
+use crate::config::SubnetEntry;
+use crate::cooldown::format_span;
+use crate::detector::{Alert, Detector, HitSnapshot, IpState, ScanType};
+use crate::display;
+use crate::stats::LiveStats;
+use anyhow::{bail, Context, Result};
+use arc_swap::ArcSwap;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+
+/// Canalul prin care executantul unei cereri (cleanup, reload) intoarce raportul.
+pub type Reply = oneshot::Sender<Result<String>>;
+
+/// Lungimea maxima a unei comenzi — protectie contra unui client care
+/// trimite date fara newline.
+const MAX_REQUEST_LEN: u64 = 4096;
+
+/// Hit-urile afisate per tip de actiune in `state`.
+const MAX_STATE_HITS: usize = 50;
+
+/// Cat asteapta clientul raspunsul (reload si test-alert pot astepta SMTP).
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Textul de ajutor pentru `ids-rs ctl`.
+pub const CTL_USAGE: &str = "\
+Utilizare: ids-rs ctl <comanda> [optiuni]
+
+  state <ip>                        hit-uri, destinatii, cooldown-uri si sesiuni
+  clear-cooldowns <ip>              sterge cooldown-urile in care apare IP-ul
+  clear-state <ip>                  uita tot ce detectorul stie despre IP, inclusiv recidiva
+  whitelist add <ip|cidr> [--ttl D] whitelist temporar; D = 30m / 1h / 2d
+  whitelist remove <ip|cidr>        sterge o intrare din whitelist-ul temporar
+  whitelist list                    intrarile temporare si timpul ramas
+  cleanup                           ruleaza acum ciclul de cleanup
+  reload                            reincarca config.toml, cu raport de validare
+  test-alert                        alerta de test prin CLI, istoric, SIEM, email
+
+Optiuni:
+  --config <fisier> config-ul cu [control].socket_path (implicit config.toml)
+  --socket <cale>   calea socket-ului, fara a citi config-ul";
+
+/// O comanda primita pe socket.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    State(IpAddr),
+    ClearCooldowns(IpAddr),
+    ClearState(IpAddr),
+    WhitelistAdd { entry: String, ttl: Option<Duration> },
+    WhitelistRemove(String),
+    WhitelistList,
+    Cleanup,
+    Reload,
+    TestAlert,
+    Help,
+}
+
+impl Command {
+    /// Parseaza linia trimisa de client (cuvinte separate prin spatii).
+    pub fn parse(line: &str) -> Result<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let ip = |word: Option<&&str>| -> Result<IpAddr> {
+            let word = word.context("lipseste IP-ul")?;
+            word.parse().with_context(|| format!("'{}' nu este un IP valid", word))
+        };
+        let command = match words.as_slice() {
+            ["state", rest @ ..] if rest.len() <= 1 => Command::State(ip(rest.first())?),
+            ["clear-cooldowns", rest @ ..] if rest.len() <= 1 => {
+                Command::ClearCooldowns(ip(rest.first())?)
+            }
+            ["clear-state", rest @ ..] if rest.len() <= 1 => Command::ClearState(ip(rest.first())?),
+            ["whitelist", "add", entry] => Command::WhitelistAdd { entry: entry.to_string(), ttl: None },
+            ["whitelist", "add", entry, "--ttl", ttl] => Command::WhitelistAdd {
+                entry: entry.to_string(),
+                ttl: Some(parse_ttl(ttl)?),
+            },
+            ["whitelist", "remove", entry] => Command::WhitelistRemove(entry.to_string()),
+            ["whitelist", "list"] => Command::WhitelistList,
+            ["cleanup"] => Command::Cleanup,
+            ["reload"] => Command::Reload,
+            ["test-alert"] => Command::TestAlert,
+            ["help"] | [] => Command::Help,
+            _ => bail!("Comanda necunoscuta: '{}'", line.trim()),
+        };
+        Ok(command)
+    }
+}
+
+/// Durata unui whitelist temporar: "90s", "30m", "1h", "2d".
+fn parse_ttl(input: &str) -> Result<Duration> {
+    let secs_per_unit = match input.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 3600,
+        Some('d') => 86_400,
+        _ => bail!("TTL invalid '{}': foloseste 90s / 30m / 1h / 2d", input),
+    };
+    match input[..input.len() - 1].parse::<u64>() {
+        Ok(0) => bail!("TTL invalid '{}': trebuie sa fie mai mare decat 0", input),
+        Ok(n) => match n.checked_mul(secs_per_unit) {
+            Some(secs) => Ok(Duration::from_secs(secs)),
+            None => bail!("TTL prea mare '{}'", input),
+        },
+        Err(_) => bail!("TTL invalid '{}': foloseste 90s / 30m / 1h / 2d", input),
+    }
+}
+
+/// Tot ce are nevoie serverul: componentele partajate cu main loop-ul si
+/// canalele catre executantii cererilor `cleanup` si `reload`.
+pub struct ControlContext {
+    pub detector: Arc<Detector>,
+    pub alerter: Arc<Alerter>,
+    pub hostnames: Arc<ArcSwap<HashMap<IpAddr, String>>>,
+    pub subnets: Arc<ArcSwap<Vec<SubnetEntry>>>,
+    pub assets: Arc<ArcSwap<AssetInventory>>,
+    pub live: Arc<LiveStats>,
+    pub cleanup: mpsc::Sender<Reply>,
+    pub reload: mpsc::Sender<Reply>,
+}
+
+/// Creeaza socket-ul la `path` fara ca el sa fie vreodata accesibil altor
+/// utilizatori: `bind` foloseste umask-ul procesului, deci socket-ul este
+/// creat intr-un director temporar 0700, primeste 0600 si abia apoi este
+/// mutat (`rename`, atomic) la calea finala.
+fn bind_private(path: &Path) -> Result<UnixListener> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().context("Calea socket-ului nu are nume de fisier")?;
+    let staging = dir.join(format!(".{}.{}.tmp", name.to_string_lossy(), std::process::id()));
+    // Ramas de la un crash cu acelasi PID (ex: PID 1 intr-un container).
+    if std::fs::symlink_metadata(&staging).is_ok() {
+        std::fs::remove_dir_all(&staging)
+            .with_context(|| format!("Nu pot sterge directorul temporar vechi: {:?}", staging))?;
+    }
+    // `create` (nu `create_all`) esueaza daca directorul a aparut intre timp —
+    // nu legam niciodata socket-ul intr-un director care nu este al nostru.
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Nu pot crea directorul temporar: {:?}", staging))?;
+
+    let staged = staging.join(name);
+    let result = UnixListener::bind(&staged)
+        .with_context(|| format!("Nu pot deschide socket-ul de control: {:?}", path))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Nu pot seta permisiunile socket-ului: {:?}", path))?;
+            std::fs::rename(&staged, path)
+                .with_context(|| format!("Nu pot muta socket-ul la {:?}", path))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// Creeaza socket-ul (inlocuind un socket ramas de la o rulare anterioara)
+/// cu permisiuni 0600 si porneste task-ul care accepta conexiuni.
+pub fn start(socket_path: &str, ctx: ControlContext) -> Result<()> {
+    let path = Path::new(socket_path);
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Nu pot crea directorul socket-ului: {:?}", dir))?;
+    }
+    if path.exists() {
+        std::fs::remove_file(path)
+            .with_context(|| format!("Nu pot sterge socket-ul vechi: {:?}", path))?;
+    }
+    let listener = bind_private(path)?;
+
+    let ctx = Arc::new(ctx);
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    // O conexiune lenta (reload, SMTP) nu blocheaza celelalte.
+                    tokio::spawn(handle(stream, Arc::clone(&ctx)));
+                }
+                Err(e) => display::log_warning(&format!("Socket de control: {}", e)),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Citeste comanda, o executa si scrie raspunsul "OK" / "ERR" + corp.
+async fn handle(stream: UnixStream, ctx: Arc<ControlContext>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_LEN));
+    let response = match reader.read_line(&mut line).await {
+        Ok(_) => match Command::parse(&line) {
+            Ok(command) => execute(command, &ctx).await,
+            Err(e) => Err(anyhow::anyhow!("{:#}\n\n{}", e, CTL_USAGE)),
+        },
+        Err(e) => Err(e.into()),
+    };
+    let text = match response {
+        Ok(body) => format!("OK\n{}\n", body.trim_end()),
+        Err(e) => format!("ERR\n{:#}\n", e),
+    };
+    let _ = writer.write_all(text.as_bytes()).await;
+    let _ = writer.shutdown().await;
+}
+
+async fn execute(command: Command, ctx: &ControlContext) -> Result<String> {
+    let detector = &ctx.detector;
+    match command {
+        Command::State(ip) => Ok(format_state(&detector.ip_state(ip), &ctx.hostnames.load())),
+        Command::ClearCooldowns(ip) => {
+            let cleared = detector.clear_cooldowns(ip);
+            display::log_info(&format!("ctl: {} cooldown-uri sterse pentru {}", cleared, ip));
+            Ok(format!("{} cooldown-uri sterse pentru {}", cleared, ip))
+        }
+        Command::ClearState(ip) => {
+            if !detector.clear_state(ip) {
+                return Ok(format!("{} nu era urmarit — nimic de sters", ip));
+            }
+            display::log_info(&format!("ctl: starea lui {} a fost stearsa", ip));
+            Ok(format!("Starea lui {} a fost stearsa", ip))
+        }
+        Command::WhitelistAdd { entry, ttl } => {
+            detector.add_temp_whitelist(&entry, ttl)?;
+            let until = match ttl {
+                Some(ttl) => format!("pentru {}", format_span(ttl)),
+                None => "pana la restart".to_string(),
+            };
+            display::log_info(&format!("ctl: {} adaugat in whitelist-ul temporar {}", entry, until));
+            Ok(format!("{} adaugat in whitelist-ul temporar {}", entry, until))
+        }
+        Command::WhitelistRemove(entry) => {
+            if !detector.remove_temp_whitelist(&entry) {
+                bail!("{} nu este in whitelist-ul temporar", entry);
+            }
+            display::log_info(&format!("ctl: {} sters din whitelist-ul temporar", entry));
+            Ok(format!("{} sters din whitelist-ul temporar", entry))
+        }
+        Command::WhitelistList => {
+            let entries = detector.temp_whitelist();
+            if entries.is_empty() {
+                return Ok("Whitelist-ul temporar este gol".to_string());
+            }
+            Ok(entries
+                .iter()
+                .map(|(entry, left)| match left {
+                    Some(left) => format!("{:<40} expira in {}", entry, format_span(*left)),
+                    None => format!("{:<40} pana la restart", entry),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        Command::Cleanup => request(&ctx.cleanup, "cleanup").await,
+        Command::Reload => request(&ctx.reload, "reload").await,
+        Command::TestAlert => send_test_alert(ctx).await,
+        Command::Help => Ok(CTL_USAGE.to_string()),
+    }
+}
+
+/// Trimite cererea executantului si asteapta raportul lui.
+async fn request(executor: &mpsc::Sender<Reply>, name: &str) -> Result<String> {
+    let (reply, report) = oneshot::channel();
+    executor
+        .send(reply)
+        .await
+        .map_err(|_| anyhow::anyhow!("{}: executantul nu mai ruleaza", name))?;
+    report
+        .await
+        .map_err(|_| anyhow::anyhow!("{}: cererea a fost abandonata", name))?
+}
+
+/// Alerta sintetica (TEST-NET, RFC 5737) pe drumul unei alerte reale:
+/// CLI, dashboard, apoi fiecare destinatie activa, cu rezultatul ei.
+async fn send_test_alert(ctx: &ControlContext) -> Result<String> {
+    let source: IpAddr = "192.0.2.1".parse()?;
+    let dest: IpAddr = "198.51.100.1".parse()?;
+    let mut alert = Alert::new(ScanType::TestAlert, source, Some(dest));
+    alert.severity.level = crate::severity::base_level(&alert);
+
+    display::log_alert(&alert, &ctx.hostnames.load(), &ctx.subnets.load(), &ctx.assets.load());
+    ctx.live.record_alert(&alert);
+
+    let mut lines = vec!["Alerta de test: CLI — afisata".to_string()];
+    let mut failed = 0;
+    for (sink, result) in ctx.alerter.deliver(&alert).await {
+        match result {
+            Ok(()) => lines.push(format!("Alerta de test: {} — trimisa", sink)),
+            Err(e) => {
+                failed += 1;
+                display::log_error(&format!("Alerta de test: {}: {:#}", sink, e));
+                lines.push(format!("Alerta de test: {} — EROARE: {:#}", sink, e));
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{}", lines.join("\n"));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Raportul `state <ip>` — text simplu, fara culori (ajunge intr-un pipe).
+fn format_state(state: &IpState, hostnames: &HashMap<IpAddr, String>) -> String {
+    let mut out = Vec::new();
+    match hostnames.get(&state.ip) {
+        Some(name) => out.push(format!("IP {} ({})", state.ip, name)),
+        None => out.push(format!("IP {}", state.ip)),
+    }
+    out.push(format!("Whitelist:        {}", if state.whitelisted { "da" } else { "nu" }));
+    out.push(format!(
+        "Ultimul eveniment: {}",
+        state
+            .last_seen
+            .map(|age| format!("acum {}", format_span(age)))
+            .unwrap_or_else(|| "- (nu este urmarit ca sursa)".to_string())
+    ));
+
+    for (label, hits) in [("drop", &state.drops), ("accept", &state.accepts)] {
+        out.push(String::new());
+        out.push(format!("Hit-uri {} ({}):", label, hits.len()));
+        out.extend(hits.iter().take(MAX_STATE_HITS).map(format_hit));
+        if hits.len() > MAX_STATE_HITS {
+            out.push(format!("  ... (+{} mai vechi)", hits.len() - MAX_STATE_HITS));
+        }
+    }
+
+    out.push(String::new());
+    for (label, ips) in [
+        ("Destinatii (Lateral Movement)", &state.lateral_dests),
+        ("Destinatii ICMP (Ping Sweep)", &state.icmp_dests),
+        ("Surse care l-au lovit ca tinta", &state.scanned_by),
+    ] {
+        out.push(format!("{}: {}", label, join_ips(ips)));
+    }
+
+    out.push(String::new());
+    if state.cooldowns.is_empty() {
+        out.push("Cooldown-uri: -".to_string());
+    } else {
+        out.push("Cooldown-uri:".to_string());
+        out.extend(state.cooldowns.iter().map(|c| {
+            format!("  {:<18} {:>8} ramase ({})", c.detector, format_span(c.remaining), c.mode.as_str())
+        }));
+    }
+    out.push(format!(
+        "Sesiuni deschise: {}",
+        if state.sessions.is_empty() { "-".to_string() } else { state.sessions.join(", ") }
+    ));
+    out.join("\n")
+}
+
+fn format_hit(hit: &HitSnapshot) -> String {
+    let dest = hit.dest_ip.map(|ip| format!(" → {}", ip)).unwrap_or_default();
+    format!("  {:>5}/{:<5}{:<24} acum {}", hit.port, hit.proto.as_str(), dest, format_span(hit.age))
+}
+
+fn join_ips(ips: &[IpAddr]) -> String {
+    if ips.is_empty() {
+        return "-".to_string();
+    }
+    ips.iter().map(|ip| ip.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// `ids-rs ctl <comanda>` — trimite comanda la socket-ul procesului pornit
+/// si afiseaza raspunsul. "ERR" devine eroare (exit code 1).
+pub async fn run_ctl_command(args: &[String]) -> Result<()> {
+    let mut socket_path: Option<String> = None;
+    let mut config_path = "config.toml".to_string();
+    let mut words: Vec<&str> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", CTL_USAGE);
+                return Ok(());
+            }
+            "--socket" => socket_path = Some(iter.next().context("--socket necesita o valoare")?.clone()),
+            "--config" => config_path = iter.next().context("--config necesita o valoare")?.clone(),
+            word => words.push(word),
+        }
+    }
+    if words.is_empty() {
+        println!("{}", CTL_USAGE);
+        return Ok(());
+    }
+    // Validam local — o greseala de tastare nu mai ajunge la server.
+    Command::parse(&words.join(" "))?;
+
+    let socket_path = match socket_path {
+        Some(path) => path,
+        None => crate::config::AppConfig::load(&config_path)?.control.socket_path,
+    };
+    let exchange = async {
+        let mut stream = UnixStream::connect(&socket_path).await.with_context(|| {
+            format!("Nu ma pot conecta la {} — ids-rs ruleaza cu [control] enabled = true?", socket_path)
+        })?;
+        stream.write_all(format!("{}\n", words.join(" ")).as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        anyhow::Ok(response)
+    };
+    let response = tokio::time::timeout(CLIENT_TIMEOUT, exchange)
+        .await
+        .context("Niciun raspuns de la ids-rs in 60 de secunde")??;
+
+    let (status, body) = response.split_once('\n').unwrap_or((response.as_str(), ""));
+    match status {
+        "OK" => {
+            print!("{}", body);
+            Ok(())
+        }
+        "ERR" => bail!("{}", body.trim_end()),
+        _ => bail!("Raspuns neasteptat de la socket: {:?}", response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let ip: IpAddr = "10.0.0.5".parse().unwrap();
+        assert_eq!(Command::parse("state 10.0.0.5\n").unwrap(), Command::State(ip));
+        assert_eq!(Command::parse("clear-cooldowns 10.0.0.5").unwrap(), Command::ClearCooldowns(ip));
+        assert_eq!(Command::parse("clear-state 10.0.0.5").unwrap(), Command::ClearState(ip));
+        assert_eq!(
+            Command::parse("whitelist add 10.0.2.0/24 --ttl 30m").unwrap(),
+            Command::WhitelistAdd {
+                entry: "10.0.2.0/24".to_string(),
+                ttl: Some(Duration::from_secs(1800)),
+            }
+        );
+        assert_eq!(
+            Command::parse("whitelist add 10.0.0.5").unwrap(),
+            Command::WhitelistAdd { entry: "10.0.0.5".to_string(), ttl: None }
+        );
+        assert_eq!(Command::parse("whitelist list").unwrap(), Command::WhitelistList);
+        assert_eq!(Command::parse("reload").unwrap(), Command::Reload);
+        assert_eq!(Command::parse("").unwrap(), Command::Help);
+
+        assert!(Command::parse("state").is_err());
+        assert!(Command::parse("state not-an-ip").is_err());
+        assert!(Command::parse("whitelist add 10.0.0.5 --ttl 0m").is_err());
+        assert!(Command::parse("whitelist add 10.0.0.5 --ttl soon").is_err());
+        assert!(Command::parse("reboot").is_err());
+    }
+
+    #[test]
+    fn test_parse_ttl_limits() {
+        assert_eq!(parse_ttl("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_ttl("2d").unwrap(), Duration::from_secs(172_800));
+
+        // Zero si unitatile lipsa sunt refuzate.
+        assert!(parse_ttl("0s").is_err());
+        assert!(parse_ttl("0d").is_err());
+        assert!(parse_ttl("15").is_err());
+        assert!(parse_ttl("d").is_err());
+        assert!(parse_ttl("").is_err());
+
+        // Inmultirea cu unitatea depaseste u64: eroare, nu panica.
+        assert!(parse_ttl("99999999999999999d").is_err());
+        assert!(parse_ttl(&format!("{}h", u64::MAX / 3600 + 1)).is_err());
+        assert!(parse_ttl(&format!("{}s", u64::MAX)).is_ok());
+        assert!(Command::parse("whitelist add 1.2.3.4 --ttl 99999999999999999d").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_socket_bound_private_then_moved() {
+        let dir = std::env::temp_dir().join(format!("ids-rs-ctl-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path = dir.join("ids-rs.sock");
+        // Un director temporar ramas de la un crash cu acelasi PID.
+        let stale = dir.join(format!(".ids-rs.sock.{}.tmp", std::process::id()));
+        std::fs::create_dir_all(&stale).unwrap();
+
+        let listener = bind_private(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // Directorul temporar nu ramane in urma; socket-ul mutat accepta conexiuni.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let client = tokio::spawn(UnixStream::connect(path.clone()));
+        listener.accept().await.unwrap();
+        client.await.unwrap().unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
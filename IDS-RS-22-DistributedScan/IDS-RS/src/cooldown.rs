@@ -0,0 +1,373 @@
+// =============================================================================
+// cooldown.rs - Moduri de cooldown si scanarile "in desfasurare"
+// =============================================================================
+//
+// Dupa o alerta, re-detectiile aceleiasi surse sunt suprimate `alert_cooldown_secs`
+// secunde. O scanare care continua o ora raporta astfel doar primele 15 porturi,
+// iar restul disparea in cooldown. `[detection.cooldown]` alege, per detector,
+// ce se intampla cu re-detectiile:
+//
+//   fixed   — suprimate pana expira cooldown-ul de la alerta (comportamentul clasic)
+//   sliding — fiecare re-detectie suprimata reporneste cooldown-ul: o scanare
+//             continua produce o singura alerta, realertarea vine doar dupa o
+//             pauza de cel putin un cooldown
+//   ongoing — sursa are o sesiune deschisa la prima alerta; la expirarea
+//             cooldown-ului, daca scanarea continua, se emite un update cu
+//             porturile/destinatiile cumulate si durata, iar dupa `quiet_secs`
+//             fara activitate un rezumat "scan ended"
+//
+// Update-urile si rezumatul pastreaza tipul alertei (acelasi SigID in SIEM);
+// `Alert::ongoing` le deosebeste de prima alerta.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. `FnOnce() -> Alert` — constructie amanata
+//    Alerta (cu amprentarea uneltei, porturile sensibile...) se construieste
+//    doar daca politica decide sa o emita. Closure-ul primit de `observe` este
+//    apelat cel mult o data — exact contractul lui `FnOnce`.
+//
+// 2. `DashMap::retain` cu efect lateral
+//    Sesiunile tacute sunt scoase din map si transformate in alerte in aceeasi
+//    trecere: closure-ul lui `retain` este `FnMut`, deci poate scrie intr-un
+//    `Vec` capturat prin referinta mutabila.
+//
+// =============================================================================
+
+use crate::config::DetectionConfig;
+use crate::detector::Alert;
+use chrono::{DateTime, Local};
+use dashmap::DashMap;
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Modul de cooldown al unui detector (`[detection.cooldown].mode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CooldownMode {
+    Fixed,
+    Sliding,
+    Ongoing,
+}
+
+impl CooldownMode {
+    /// Valorile sunt validate in config.rs; orice altceva inseamna "fixed".
+    fn parse(value: &str) -> Self {
+        match value {
+            "sliding" => CooldownMode::Sliding,
+            "ongoing" => CooldownMode::Ongoing,
+            _ => CooldownMode::Fixed,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CooldownMode::Fixed => "fixed",
+            CooldownMode::Sliding => "sliding",
+            CooldownMode::Ongoing => "ongoing",
+        }
+    }
+}
+
+/// Politica efectiva a unui detector: durata si modul, din suprascrierea
+/// detectorului sau din valorile implicite.
+#[derive(Debug, Clone, Copy)]
+pub struct CooldownPolicy {
+    /// Cheia detectorului din `[detection.cooldown]` (ex: "fast_scan").
+    pub detector: &'static str,
+    pub secs: Duration,
+    pub mode: CooldownMode,
+}
+
+impl CooldownPolicy {
+    pub fn resolve(cfg: &DetectionConfig, detector: &'static str) -> Self {
+        let (secs, mode) = cfg.cooldown.resolve(detector, cfg.alert_cooldown_secs);
+        Self {
+            detector,
+            secs: Duration::from_secs(secs),
+            mode: CooldownMode::parse(mode),
+        }
+    }
+}
+
+/// Ce a vazut detectorul la evenimentul curent, pentru cheia `key`.
+#[derive(Debug, Default)]
+pub struct Observation<'a> {
+    /// Cheia cooldown-ului: sursa (sau tinta, la Distributed Scan).
+    pub key: Option<IpAddr>,
+    /// Pragul detectorului este atins.
+    pub triggered: bool,
+    pub ports: &'a [u16],
+    pub dests: &'a [IpAddr],
+    pub sources: &'a [IpAddr],
+}
+
+/// Faza unei alerte emise de o sesiune ongoing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OngoingPhase {
+    /// Scanarea continua dupa expirarea cooldown-ului.
+    Update,
+    /// Sursa a tacut `quiet_secs` — rezumatul final.
+    Ended,
+}
+
+/// Contextul unui update / rezumat atasat alertei.
+#[derive(Debug, Clone)]
+pub struct OngoingDetail {
+    pub phase: OngoingPhase,
+    /// Update: numarul update-ului (1 = primul). Ended: cate update-uri au fost.
+    pub updates: u32,
+    pub started: DateTime<Local>,
+    pub duration: Duration,
+    /// Totalurile cumulate de la prima alerta.
+    pub ports: usize,
+    pub dests: usize,
+    pub sources: usize,
+}
+
+impl OngoingDetail {
+    /// "ONGOING #2" / "SCAN ENDED"
+    pub fn label(&self) -> String {
+        match self.phase {
+            OngoingPhase::Update => format!("ONGOING #{}", self.updates),
+            OngoingPhase::Ended => "SCAN ENDED".to_string(),
+        }
+    }
+
+    /// "in desfasurare de 12m 05s (din 08:12:40): 140 porturi"
+    pub fn summary(&self) -> String {
+        let mut totals = Vec::new();
+        if self.ports > 0 {
+            totals.push(format!("{} porturi", self.ports));
+        }
+        if self.dests > 0 {
+            totals.push(format!("{} destinatii", self.dests));
+        }
+        if self.sources > 0 {
+            totals.push(format!("{} surse", self.sources));
+        }
+        let span = format_span(self.duration);
+        let started = self.started.format("%H:%M:%S");
+        let head = match self.phase {
+            OngoingPhase::Update => format!("in desfasurare de {} (din {})", span, started),
+            OngoingPhase::Ended => format!(
+                "incheiata dupa {} (din {}, {} update-uri)",
+                span, started, self.updates
+            ),
+        };
+        if totals.is_empty() {
+            head
+        } else {
+            format!("{}: {}", head, totals.join(", "))
+        }
+    }
+}
+
+/// "45s", "12m 05s", "2h 03m"
+pub fn format_span(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+struct Session {
+    /// Prima alerta a sesiunii — baza update-urilor fara activitate noua si a rezumatului.
+    first: Alert,
+    started: Instant,
+    last_alert: Instant,
+    last_activity: Instant,
+    updates: u32,
+    ports: BTreeSet<u16>,
+    dests: BTreeSet<IpAddr>,
+    sources: BTreeSet<IpAddr>,
+}
+
+impl Session {
+    fn merge(&mut self, obs: &Observation<'_>, now: Instant) {
+        self.ports.extend(obs.ports.iter().copied());
+        self.dests.extend(obs.dests.iter().copied());
+        self.sources.extend(obs.sources.iter().copied());
+        self.last_activity = now;
+    }
+
+    fn detail(&self, phase: OngoingPhase, until: Instant) -> OngoingDetail {
+        OngoingDetail {
+            phase,
+            updates: self.updates,
+            started: self.first.timestamp,
+            duration: until.saturating_duration_since(self.started),
+            ports: self.ports.len(),
+            dests: self.dests.len(),
+            sources: self.sources.len(),
+        }
+    }
+
+    /// Alerta cu totalurile cumulate in locul celor din fereastra.
+    fn apply(&self, mut alert: Alert, phase: OngoingPhase, until: Instant) -> Alert {
+        if !self.ports.is_empty() {
+            alert.unique_ports = self.ports.iter().copied().collect();
+        }
+        if !self.dests.is_empty() {
+            alert.unique_dests = self.dests.iter().copied().collect();
+        }
+        if !self.sources.is_empty() {
+            alert.unique_sources = self.sources.iter().copied().collect();
+        }
+        alert.ongoing = Some(self.detail(phase, until));
+        alert
+    }
+}
+
+/// Sesiunile deschise ale detectoarelor in modul "ongoing".
+pub struct OngoingTracker {
+    sessions: DashMap<(&'static str, IpAddr), Session>,
+}
+
+impl OngoingTracker {
+    pub fn new() -> Self {
+        Self { sessions: DashMap::new() }
+    }
+
+    /// Prima alerta deschide sesiunea; activitatea ulterioara se cumuleaza, iar
+    /// o re-detectie dupa expirarea cooldown-ului produce un update.
+    /// `build` este apelat doar cand se emite o alerta.
+    pub fn observe(
+        &self,
+        policy: &CooldownPolicy,
+        obs: Observation<'_>,
+        now: Instant,
+        build: impl FnOnce() -> Alert,
+    ) -> Option<Alert> {
+        let key = (policy.detector, obs.key?);
+        if let Some(mut session) = self.sessions.get_mut(&key) {
+            session.merge(&obs, now);
+            if !obs.triggered || now.saturating_duration_since(session.last_alert) < policy.secs {
+                return None;
+            }
+            session.last_alert = now;
+            session.updates += 1;
+            return Some(session.apply(build(), OngoingPhase::Update, now));
+        }
+        if !obs.triggered {
+            return None;
+        }
+
+        let alert = build();
+        let mut session = Session {
+            first: alert.clone(),
+            started: now,
+            last_alert: now,
+            last_activity: now,
+            updates: 0,
+            ports: BTreeSet::new(),
+            dests: BTreeSet::new(),
+            sources: BTreeSet::new(),
+        };
+        session.merge(&obs, now);
+        self.sessions.insert(key, session);
+        Some(alert)
+    }
+
+    /// Sesiunile deschise: (detector, cheie, timpul de la ultima alerta).
+    pub fn active(&self, now: Instant) -> Vec<(&'static str, IpAddr, Duration)> {
+        self.sessions
+            .iter()
+            .map(|e| {
+                let (detector, key) = *e.key();
+                (detector, key, now.saturating_duration_since(e.value().last_alert))
+            })
+            .collect()
+    }
+
+    /// Renunta la sesiunile cheii `ip` (fara rezumat). Intoarce cate erau.
+    pub fn remove(&self, ip: &IpAddr) -> usize {
+        let before = self.sessions.len();
+        self.sessions.retain(|(_, key), _| key != ip);
+        before - self.sessions.len()
+    }
+
+    /// Inchide sesiunile fara activitate de `quiet` si intoarce rezumatele lor.
+    /// Durata unei sesiuni se masoara pana la ultima activitate, nu pana la sweep.
+    pub fn finish(&self, now: Instant, quiet: Duration) -> Vec<Alert> {
+        let mut ended = Vec::new();
+        self.sessions.retain(|_, session| {
+            if now.saturating_duration_since(session.last_activity) < quiet {
+                return true;
+            }
+            let summary = Alert {
+                timestamp: Local::now(),
+                ..session.first.clone()
+            };
+            ended.push(session.apply(summary, OngoingPhase::Ended, session.last_activity));
+            false
+        });
+        ended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    fn policy() -> CooldownPolicy {
+        CooldownPolicy {
+            detector: "fast_scan",
+            secs: Duration::from_secs(60),
+            mode: CooldownMode::Ongoing,
+        }
+    }
+
+    fn observe(tracker: &OngoingTracker, ports: &[u16], triggered: bool, at: Instant) -> Option<Alert> {
+        let src: IpAddr = "10.0.5.20".parse().unwrap();
+        let obs = Observation { key: Some(src), triggered, ports, ..Observation::default() };
+        tracker.observe(&policy(), obs, at, || Alert {
+            unique_ports: ports.to_vec(),
+            ..Alert::new(ScanType::Fast, src, None)
+        })
+    }
+
+    #[test]
+    fn test_ongoing_updates_and_summary() {
+        let tracker = OngoingTracker::new();
+        let start = Instant::now();
+
+        let first = observe(&tracker, &[20, 21, 22], true, start).unwrap();
+        assert!(first.ongoing.is_none());
+
+        // In cooldown: activitatea se cumuleaza, fara alerta.
+        assert!(observe(&tracker, &[23, 24], true, start + Duration::from_secs(30)).is_none());
+        assert!(observe(&tracker, &[25], false, start + Duration::from_secs(50)).is_none());
+
+        // Cooldown expirat, scanarea continua: update cu totalurile cumulate.
+        let update = observe(&tracker, &[26, 27, 28], true, start + Duration::from_secs(90)).unwrap();
+        let detail = update.ongoing.as_ref().unwrap();
+        assert_eq!((detail.phase, detail.updates, detail.ports), (OngoingPhase::Update, 1, 9));
+        assert_eq!(detail.duration, Duration::from_secs(90));
+        assert_eq!(update.unique_ports, (20..=28).collect::<Vec<u16>>());
+        assert!(detail.summary().starts_with("in desfasurare de 1m 30s"));
+
+        // Sursa tace: la 2 min dupa ultima activitate sesiunea se inchide.
+        let quiet = Duration::from_secs(120);
+        assert!(tracker.finish(start + Duration::from_secs(150), quiet).is_empty());
+        let ended = tracker.finish(start + Duration::from_secs(300), quiet);
+        assert_eq!(ended.len(), 1);
+        let detail = ended[0].ongoing.as_ref().unwrap();
+        assert_eq!((detail.phase, detail.updates, detail.ports), (OngoingPhase::Ended, 1, 9));
+        assert_eq!(detail.duration, Duration::from_secs(90));
+        assert!(tracker.sessions.is_empty());
+
+        // O noua re-detectie deschide o sesiune noua (alerta obisnuita).
+        let again = observe(&tracker, &[80], true, start + Duration::from_secs(400)).unwrap();
+        assert!(again.ongoing.is_none());
+    }
+
+    #[test]
+    fn test_format_span() {
+        assert_eq!(format_span(Duration::from_secs(45)), "45s");
+        assert_eq!(format_span(Duration::from_secs(725)), "12m 05s");
+        assert_eq!(format_span(Duration::from_secs(7380)), "2h 03m");
+    }
+}
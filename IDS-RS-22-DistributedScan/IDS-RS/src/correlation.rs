@@ -0,0 +1,435 @@
+// =============================================================================
+// correlation.rs - Corelare lanturi de atac in mai multe etape
+// =============================================================================
+//
+// Detectoarele vad cate un pattern izolat. Un atac real trece prin etape:
+//
+//   recunoastere (Fast Scan) → acces (accept pe porturile sondate)
+//     → propagare (Lateral Movement de pe masina atinsa)
+//
+// sau, din perspectiva tintei:
+//
+//   tinta unui Distributed Scan → devine ea insasi sursa de Lateral Movement
+//
+// Regulile `[[detection.correlation.rules]]` descriu astfel de secvente
+// ordonate. Motorul urmareste pentru fiecare regula lanturile partiale, legate
+// de un host "subiect", si emite o alerta AttackChain cand ultima etapa apare
+// in fereastra regulii.
+//
+// Pe langa alerte, motorul observa si evenimentele brute:
+//   - porturile sondate (drop) de fiecare sursa → etapa `probed_port_accepted`
+//     cand aceeasi sursa primeste accept pe un port sondat anterior
+//   - destinatiile contactate cu succes (accept) → pivot `on = "touched"`
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. CHEI COMPUSE IN DashMap
+//    Lanturile partiale sunt indexate dupa `(String, IpAddr)` = (regula, subiect).
+//    Tuplurile implementeaza automat Hash + Eq daca elementele lor o fac.
+//
+// 2. COLECTARE INAINTE DE MODIFICARE
+//    Nu modificam un DashMap in timp ce il iteram (acelasi shard = deadlock).
+//    Colectam intai cheile candidate intr-un Vec, apoi facem remove/insert.
+//
+// =============================================================================
+
+use crate::config::{ChainRuleConfig, CorrelationConfig};
+use crate::detector::Alert;
+use crate::parser::LogEvent;
+use chrono::{DateTime, Local};
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Numarul maxim de porturi sondate / destinatii retinute per host.
+const MAX_ACTIVITY: usize = 1024;
+
+/// O etapa potrivita a unui lant.
+#[derive(Debug, Clone)]
+pub struct ChainStep {
+    /// Cheia etapei (ex: "fast_scan", "probed_port_accepted").
+    pub kind: String,
+    /// Descrierea afisata (ex: "Fast Scan", "Probed Port Accepted").
+    pub label: String,
+    pub source: IpAddr,
+    pub dest: Option<IpAddr>,
+    /// Portul — doar pentru `probed_port_accepted`.
+    pub port: Option<u16>,
+    pub timestamp: DateTime<Local>,
+}
+
+/// Un lant complet — atasat alertei AttackChain.
+#[derive(Debug, Clone)]
+pub struct ChainMatch {
+    /// Numele regulii potrivite.
+    pub rule: String,
+    /// Host-ul urmarit la ultima etapa (dupa eventualele pivotari).
+    pub subject: IpAddr,
+    pub steps: Vec<ChainStep>,
+}
+
+impl ChainMatch {
+    /// Etapele formatate: `HH:MM:SS Fast Scan 10.0.0.5→10.0.1.2` separate prin `sep`.
+    pub fn format_steps(&self, sep: &str) -> String {
+        self.steps
+            .iter()
+            .map(|s| {
+                let target = match (s.dest, s.port) {
+                    (Some(d), Some(p)) => format!("→{}:{}", d, p),
+                    (Some(d), None) => format!("→{}", d),
+                    _ => String::new(),
+                };
+                format!("{} {} {}{}", s.timestamp.format("%H:%M:%S"), s.label, s.source, target)
+            })
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+}
+
+/// Activitatea recenta a unui host (ca sursa).
+#[derive(Default)]
+struct HostActivity {
+    /// Porturi pe care host-ul a primit drop (sondate).
+    probed: HashMap<u16, Instant>,
+    /// Destinatii contactate cu succes (accept).
+    touched: HashMap<IpAddr, Instant>,
+}
+
+/// Insereaza cu limita de dimensiune: la depasire se elimina intrarea cea mai veche.
+fn insert_capped<K: Eq + std::hash::Hash + Copy>(map: &mut HashMap<K, Instant>, key: K, now: Instant) {
+    map.insert(key, now);
+    if map.len() > MAX_ACTIVITY {
+        if let Some(oldest) = map.iter().min_by_key(|(_, t)| **t).map(|(k, _)| *k) {
+            map.remove(&oldest);
+        }
+    }
+}
+
+/// Lant partial: etapele `0..next` sunt deja potrivite.
+struct Partial {
+    next: usize,
+    started: Instant,
+    steps: Vec<ChainStep>,
+}
+
+/// Motorul de corelare. Partajat prin `Detector` (interior mutability).
+pub struct CorrelationEngine {
+    activity: DashMap<IpAddr, HostActivity>,
+    /// Cheie: (nume regula, subiect).
+    partials: DashMap<(String, IpAddr), Partial>,
+}
+
+impl CorrelationEngine {
+    pub fn new() -> Self {
+        Self {
+            activity: DashMap::new(),
+            partials: DashMap::new(),
+        }
+    }
+
+    /// Proceseaza un eveniment si alertele generate de el.
+    ///
+    /// Returneaza lanturile complete (de obicei niciunul).
+    pub fn observe(
+        &self,
+        event: &LogEvent,
+        alerts: &[Alert],
+        now: Instant,
+        cfg: &CorrelationConfig,
+    ) -> Vec<ChainMatch> {
+        let max_window = Duration::from_secs(cfg.max_window_secs());
+        let mut steps: Vec<ChainStep> = alerts
+            .iter()
+            .map(|a| ChainStep {
+                kind: a.scan_type.key().to_string(),
+                label: a.scan_type.to_string(),
+                source: a.source_ip,
+                dest: a.dest_ip,
+                port: None,
+                timestamp: a.timestamp,
+            })
+            .collect();
+
+        // Activitatea host-ului: accept pe un port sondat anterior → etapa sintetica.
+        // ICMP nu are porturi — nu sondeaza si nu atinge servicii.
+        if !event.is_icmp() {
+            let mut act = self.activity.entry(event.source_ip).or_default();
+            if event.action == "drop" {
+                insert_capped(&mut act.probed, event.dest_port, now);
+            } else if event.action == "accept" {
+                let probed_at = act.probed.get(&event.dest_port).copied();
+                if probed_at.is_some_and(|t| now.saturating_duration_since(t) <= max_window) {
+                    steps.push(ChainStep {
+                        kind: "probed_port_accepted".to_string(),
+                        label: "Probed Port Accepted".to_string(),
+                        source: event.source_ip,
+                        dest: event.dest_ip,
+                        port: Some(event.dest_port),
+                        timestamp: Local::now(),
+                    });
+                }
+                if let Some(dest) = event.dest_ip {
+                    insert_capped(&mut act.touched, dest, now);
+                }
+            }
+        }
+
+        let mut matches = Vec::new();
+        for rule in &cfg.rules {
+            for step in &steps {
+                if let Some(m) = self.advance(rule, step, now) {
+                    matches.push(m);
+                }
+            }
+        }
+        matches
+    }
+
+    /// Incearca sa avanseze lanturile partiale ale regulii cu etapa `step`,
+    /// apoi sa porneasca un lant nou. Returneaza lantul daca s-a completat.
+    fn advance(&self, rule: &ChainRuleConfig, step: &ChainStep, now: Instant) -> Option<ChainMatch> {
+        let window = Duration::from_secs(rule.window_secs);
+
+        // Lanturile partiale ale regulii care asteapta exact acest tip de etapa.
+        let candidates: Vec<IpAddr> = self
+            .partials
+            .iter()
+            .filter(|e| e.key().0 == rule.name)
+            .filter(|e| {
+                let p = e.value();
+                now.saturating_duration_since(p.started) <= window
+                    && rule.stages.get(p.next).is_some_and(|s| s.alert == step.kind)
+            })
+            .map(|e| e.key().1)
+            .collect();
+
+        for subject in candidates {
+            let Some(next) = self.partials.get(&(rule.name.clone(), subject)).map(|p| p.next) else {
+                continue;
+            };
+            let stage = &rule.stages[next];
+            let new_subject = match stage.on.as_str() {
+                "source" if step.source == subject => subject,
+                "target" if step.dest == Some(subject) => subject,
+                "touched" if step.source != subject && self.touched(subject, step.source, now, window) => {
+                    step.source
+                }
+                _ => continue,
+            };
+
+            let Some((_, mut partial)) = self.partials.remove(&(rule.name.clone(), subject)) else {
+                continue;
+            };
+            partial.steps.push(step.clone());
+            partial.next += 1;
+
+            if partial.next == rule.stages.len() {
+                return Some(ChainMatch {
+                    rule: rule.name.clone(),
+                    subject: new_subject,
+                    steps: partial.steps,
+                });
+            }
+
+            // Pivot: lantul continua sub noul subiect. Daca exista deja un lant
+            // mai avansat pentru el, il pastram pe acela.
+            let key = (rule.name.clone(), new_subject);
+            let keep_existing = self.partials.get(&key).is_some_and(|p| p.next >= partial.next);
+            if !keep_existing {
+                self.partials.insert(key, partial);
+            }
+            return None;
+        }
+
+        // Pornim un lant nou daca etapa se potriveste cu prima etapa a regulii.
+        let first = rule.stages.first()?;
+        if first.alert != step.kind {
+            return None;
+        }
+        let subject = match first.on.as_str() {
+            "target" => step.dest?,
+            _ => step.source,
+        };
+        let key = (rule.name.clone(), subject);
+        let expired = self
+            .partials
+            .get(&key)
+            .is_some_and(|p| now.saturating_duration_since(p.started) > window);
+        if expired || !self.partials.contains_key(&key) {
+            self.partials.insert(
+                key,
+                Partial {
+                    next: 1,
+                    started: now,
+                    steps: vec![step.clone()],
+                },
+            );
+        }
+        None
+    }
+
+    /// `subject` a contactat cu succes `host` in ultima fereastra?
+    fn touched(&self, subject: IpAddr, host: IpAddr, now: Instant, window: Duration) -> bool {
+        self.activity
+            .get(&subject)
+            .and_then(|a| a.touched.get(&host).copied())
+            .is_some_and(|t| now.saturating_duration_since(t) <= window)
+    }
+
+    /// Elimina activitatea si lanturile partiale expirate.
+    pub fn cleanup(&self, now: Instant, cfg: &CorrelationConfig) {
+        let max_window = Duration::from_secs(cfg.max_window_secs());
+        for mut entry in self.activity.iter_mut() {
+            let act = entry.value_mut();
+            act.probed.retain(|_, t| now.saturating_duration_since(*t) <= max_window);
+            act.touched.retain(|_, t| now.saturating_duration_since(*t) <= max_window);
+        }
+        self.activity
+            .retain(|_, a| !a.probed.is_empty() || !a.touched.is_empty());
+
+        let windows: HashMap<&str, Duration> = cfg
+            .rules
+            .iter()
+            .map(|r| (r.name.as_str(), Duration::from_secs(r.window_secs)))
+            .collect();
+        // Regulile sterse la SIGHUP nu mai au fereastra → lanturile lor dispar.
+        self.partials.retain(|(rule, _), p| {
+            windows
+                .get(rule.as_str())
+                .is_some_and(|w| now.saturating_duration_since(p.started) <= *w)
+        });
+    }
+
+    /// Sterge activitatea unui host (LRU eviction in Detector).
+    pub fn remove(&self, ip: &IpAddr) {
+        self.activity.remove(ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChainStageConfig;
+    use crate::detector::ScanType;
+
+    fn stage(alert: &str, on: &str) -> ChainStageConfig {
+        ChainStageConfig {
+            alert: alert.to_string(),
+            on: on.to_string(),
+        }
+    }
+
+    fn cfg() -> CorrelationConfig {
+        CorrelationConfig {
+            enabled: true,
+            rules: vec![
+                ChainRuleConfig {
+                    name: "scan-access-pivot".to_string(),
+                    window_secs: 600,
+                    stages: vec![
+                        stage("fast_scan", "source"),
+                        stage("probed_port_accepted", "source"),
+                        stage("lateral_movement", "touched"),
+                    ],
+                },
+                ChainRuleConfig {
+                    name: "target-compromised".to_string(),
+                    window_secs: 600,
+                    stages: vec![
+                        stage("distributed_scan", "target"),
+                        stage("lateral_movement", "source"),
+                    ],
+                },
+            ],
+        }
+    }
+
+    fn event(src: &str, dst: &str, port: u16, action: &str) -> LogEvent {
+        LogEvent::new(
+            src.parse().unwrap(),
+            Some(dst.parse().unwrap()),
+            port,
+            "tcp".to_string(),
+            action.to_string(),
+            String::new(),
+        )
+    }
+
+    fn alert(scan_type: ScanType, src: &str, dst: &str) -> Alert {
+        Alert::new(scan_type, src.parse().unwrap(), Some(dst.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_scan_access_pivot_chain() {
+        let engine = CorrelationEngine::new();
+        let now = Instant::now();
+        let cfg = cfg();
+
+        // Etapa 1: Fast Scan de la atacator (drop pe 22).
+        let ev = event("203.0.113.5", "10.0.1.20", 22, "drop");
+        assert!(engine.observe(&ev, &[alert(ScanType::Fast, "203.0.113.5", "10.0.1.20")], now, &cfg).is_empty());
+
+        // Etapa 2: accept pe portul sondat 22 → 10.0.1.20 devine "touched".
+        let ev = event("203.0.113.5", "10.0.1.20", 22, "accept");
+        assert!(engine.observe(&ev, &[], now, &cfg).is_empty());
+
+        // Etapa 3: Lateral Movement chiar de pe 10.0.1.20.
+        let ev = event("10.0.1.20", "10.0.1.30", 445, "accept");
+        let lm = alert(ScanType::LateralMovement, "10.0.1.20", "10.0.1.30");
+        let matches = engine.observe(&ev, &[lm], now, &cfg);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, "scan-access-pivot");
+        assert_eq!(matches[0].subject, "10.0.1.20".parse::<IpAddr>().unwrap());
+        assert_eq!(matches[0].steps.len(), 3);
+        assert_eq!(matches[0].steps[1].port, Some(22));
+    }
+
+    #[test]
+    fn test_lateral_from_untouched_host_does_not_match() {
+        let engine = CorrelationEngine::new();
+        let now = Instant::now();
+        let cfg = cfg();
+
+        let ev = event("203.0.113.5", "10.0.1.20", 22, "drop");
+        engine.observe(&ev, &[alert(ScanType::Fast, "203.0.113.5", "10.0.1.20")], now, &cfg);
+        engine.observe(&event("203.0.113.5", "10.0.1.20", 22, "accept"), &[], now, &cfg);
+
+        // Lateral Movement de pe alt host, necontactat de atacator.
+        let lm = alert(ScanType::LateralMovement, "10.0.1.99", "10.0.1.30");
+        let ev = event("10.0.1.99", "10.0.1.30", 445, "accept");
+        assert!(engine.observe(&ev, &[lm], now, &cfg).is_empty());
+    }
+
+    #[test]
+    fn test_distributed_target_becomes_source() {
+        let engine = CorrelationEngine::new();
+        let now = Instant::now();
+        let cfg = cfg();
+
+        let ds = alert(ScanType::DistributedScan, "198.51.100.1", "10.0.2.10");
+        engine.observe(&event("198.51.100.1", "10.0.2.10", 80, "drop"), &[ds], now, &cfg);
+
+        let lm = alert(ScanType::LateralMovement, "10.0.2.10", "10.0.2.11");
+        let matches = engine.observe(&event("10.0.2.10", "10.0.2.11", 22, "accept"), &[lm], now, &cfg);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, "target-compromised");
+    }
+
+    #[test]
+    fn test_chain_expires_after_window() {
+        let engine = CorrelationEngine::new();
+        let t0 = Instant::now();
+        let cfg = cfg();
+
+        let ds = alert(ScanType::DistributedScan, "198.51.100.1", "10.0.2.10");
+        engine.observe(&event("198.51.100.1", "10.0.2.10", 80, "drop"), &[ds], t0, &cfg);
+
+        let late = t0 + Duration::from_secs(601);
+        let lm = alert(ScanType::LateralMovement, "10.0.2.10", "10.0.2.11");
+        assert!(engine.observe(&event("10.0.2.10", "10.0.2.11", 22, "accept"), &[lm], late, &cfg).is_empty());
+
+        engine.cleanup(late, &cfg);
+        assert!(engine.partials.is_empty());
+    }
+}
@@ -9,6 +9,28 @@
 //   4. Detecteaza Accept Scan: >= N porturi ACCEPTATE unice in M secunde
 //   5. Gestioneaza cooldown-ul alertelor (anti-spam)
 //   6. Curata periodic datele vechi din memorie
+//   7. Alerta imediata pentru adrese din feed-urile threat intel (IOC)
+//   8. Mod anomalie: abateri fata de baseline-ul invatat per sursa/subnet
+//   9. Profile de detectie per subnet: praguri diferite per zona de retea
+//  10. Scor ponderat per port: porturile sensibile conteaza mai mult spre prag
+//  11. Scor de risc per sursa acumulat din toate alertele → alerta Escalation
+//  12. Corelare lanturi de atac in mai multe etape → alerta AttackChain
+//  13. Beaconing C2: conexiuni periodice cu jitter mic catre acelasi tuplu
+//  14. Brute Force / flood: rata de evenimente per (sursa, destinatie, port)
+//  15. Protocol: porturile TCP si UDP numarate separat; ICMP → Ping Sweep
+//  16. Amprenta uneltei de scanare (nmap, masscan, NULL/FIN/Xmas) pe alerte
+//  17. Regulile de firewall lovite per sursa, atasate alertelor + contoare
+//  18. Agregare IPv6: sursele urmarite per prefix (/64, /56) — rotatia adresei
+//      in interiorul prefixului nu ocoleste pragurile si LRU-ul
+//  19. Identitate NAT per detector: adresele originale sau traduse (xlatesrc)
+//  20. Recidiva per sursa pe orizont lung: "a N-a alerta in 24h" pe fiecare
+//      alerta, alerta PersistentScanner la N alerte in fereastra
+//  21. Moduri de cooldown per detector (fixed / sliding / ongoing): update-uri
+//      pentru scanarile care continua si rezumat "scan ended" la final
+//  22. Sesiuni de scanare per sursa / tinta: inceput, sfarsit, totaluri,
+//      split drop/accept, porturile in ordinea sondarii — rezumat la inchidere
+//  23. Administrare la runtime (`ids-rs ctl`): starea unui IP, stergerea
+//      cooldown-urilor / starii, whitelist temporar cu expirare
 //
 
 // CONCEPTE RUST EXPLICATE:
@@ -39,12 +61,25 @@
 //
 // =============================================================================
 
-use crate::config::DetectionConfig;
-use crate::parser::LogEvent;
+use crate::assets::AssetInventory;
+use crate::baseline::{AnomalyDetail, BaselineStore};
+use crate::beacon::{BeaconDetail, BeaconTracker};
+use crate::config::{DetectionConfig, DetectionProfile, PortWeightsConfig, SubnetEntry};
+use crate::cooldown::{CooldownMode, CooldownPolicy, Observation, OngoingDetail, OngoingTracker};
+use crate::correlation::{ChainMatch, CorrelationEngine};
+use crate::fingerprint::{self, Fingerprint, Sample};
+use crate::parser::{LogEvent, Protocol};
+use crate::risk::{RiskDetail, RiskTracker};
+use crate::rules::{RuleCounter, RuleTracker};
+use crate::offenders::{counts_as_offence, OffenceDetail, OffenderTracker};
+use crate::sessions::{self, Probe, SessionKind, SessionSummary, SessionTracker};
+use crate::severity::{self, Severity};
+use crate::threat_intel::{IocMatch, ThreatIntel};
 use arc_swap::ArcSwap;
 use chrono::{DateTime, Local};
 use dashmap::DashMap;
-use std::net::IpAddr;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv6Addr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -114,6 +149,151 @@ impl WhitelistEntry {
     }
 }
 
+/// Adresa de retea a prefixului /`prefix` pentru o sursa IPv6 — cheia sub
+/// care sursa este urmarita. None pentru IPv4 sau fara agregare (/128).
+fn aggregate_ipv6(ip: IpAddr, prefix: u8) -> Option<IpAddr> {
+    match ip {
+        IpAddr::V6(addr) if prefix < 128 => {
+            let mask = if prefix == 0 { 0u128 } else { !0u128 << (128 - prefix) };
+            Some(IpAddr::V6(Ipv6Addr::from(u128::from(addr) & mask)))
+        }
+        _ => None,
+    }
+}
+
+// =============================================================================
+// Profile de detectie — praguri per subnet
+// =============================================================================
+
+/// Profil de detectie rezolvat: CIDR-urile acoperite si config-ul efectiv.
+///
+/// Construit o singura data la reload (nu per eveniment): etichetele din
+/// [network.subnets] sunt traduse in CIDR-uri, iar `config` este deja
+/// config-ul global cu sectiunile profilului suprascrise.
+struct ResolvedProfile {
+    name: String,
+    matchers: Vec<SubnetEntry>,
+    config: DetectionConfig,
+}
+
+impl ResolvedProfile {
+    fn build(global: &DetectionConfig, subnets: &[SubnetEntry]) -> Vec<ResolvedProfile> {
+        global
+            .profiles
+            .iter()
+            .map(|profile| ResolvedProfile {
+                name: profile.name.clone(),
+                matchers: Self::matchers(profile, subnets),
+                config: global.with_profile(profile),
+            })
+            .collect()
+    }
+
+    /// Sursele profilului ca SubnetEntry: IP/CIDR direct, eticheta → toate
+    /// subnetele cu acea eticheta.
+    fn matchers(profile: &DetectionProfile, subnets: &[SubnetEntry]) -> Vec<SubnetEntry> {
+        let mut out = Vec::new();
+        for src in &profile.sources {
+            match SubnetEntry::parse_ip_or_cidr(src) {
+                Some(entry) => out.push(entry),
+                None => out.extend(subnets.iter().filter(|s| s.label() == src).cloned()),
+            }
+        }
+        out
+    }
+
+    /// Profilul aplicabil unui IP — potrivirea cu prefixul cel mai lung.
+    /// La egalitate castiga profilul declarat primul in config.toml.
+    fn lookup<'a>(profiles: &'a [ResolvedProfile], ip: &IpAddr) -> Option<&'a ResolvedProfile> {
+        let mut best: Option<(&ResolvedProfile, u8)> = None;
+        for profile in profiles {
+            let prefix = profile
+                .matchers
+                .iter()
+                .filter(|m| m.matches(ip))
+                .map(|m| m.prefix_len())
+                .max();
+            if let Some(prefix) = prefix {
+                if best.is_none_or(|(_, p)| prefix > p) {
+                    best = Some((profile, prefix));
+                }
+            }
+        }
+        best.map(|(profile, _)| profile)
+    }
+}
+
+// =============================================================================
+// Ponderi porturi — scor ponderat pentru Fast/Slow/Accept Scan
+// =============================================================================
+
+/// Tabela de ponderi rezolvata din `[detection.port_weights]`.
+///
+/// Construita o singura data la reload: ponderile explicite si cele din grupuri
+/// sunt combinate intr-un singur HashMap, deci scorul costa un lookup per port.
+struct PortWeights {
+    enabled: bool,
+    default_weight: f64,
+    weights: HashMap<u16, f64>,
+}
+
+impl PortWeights {
+    fn from_config(cfg: &PortWeightsConfig) -> Self {
+        let mut weights: HashMap<u16, f64> = HashMap::new();
+        // Grupurile primele: un port in mai multe grupuri primeste ponderea maxima.
+        for group in &cfg.groups {
+            for port in &group.ports {
+                let w = weights.entry(*port).or_insert(group.weight);
+                *w = w.max(group.weight);
+            }
+        }
+        // Ponderile explicite per port au prioritate fata de grupuri.
+        for (port, weight) in &cfg.ports {
+            if let Ok(port) = port.parse::<u16>() {
+                weights.insert(port, *weight);
+            }
+        }
+        Self {
+            enabled: cfg.enabled,
+            default_weight: cfg.default_weight,
+            weights,
+        }
+    }
+
+    fn weight(&self, port: u16) -> f64 {
+        self.weights.get(&port).copied().unwrap_or(self.default_weight)
+    }
+
+    /// Scorul comparat cu `port_threshold`: suma ponderilor sau, daca ponderile
+    /// sunt dezactivate, numarul de porturi unice (comportamentul clasic).
+    fn score(&self, ports: &[u16]) -> f64 {
+        if self.enabled {
+            ports.iter().map(|p| self.weight(*p)).sum()
+        } else {
+            ports.len() as f64
+        }
+    }
+
+    /// Porturile sensibile (pondere > 1) din lista — goala daca e dezactivat.
+    fn sensitive(&self, ports: &[u16]) -> Vec<u16> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        ports.iter().copied().filter(|p| self.weight(*p) > 1.0).collect()
+    }
+}
+
+/// Parseaza `beaconing.allowlist` (IP sau CIDR); intrarile invalide sunt
+/// respinse de validarea config-ului, aici sunt doar ignorate.
+fn parse_beacon_allowlist(config: &DetectionConfig) -> Vec<SubnetEntry> {
+    config
+        .beaconing
+        .allowlist
+        .iter()
+        .filter_map(|entry| SubnetEntry::parse_ip_or_cidr(entry))
+        .collect()
+}
+
 // =============================================================================
 // Structuri de date
 // =============================================================================
@@ -179,6 +359,109 @@ pub enum ScanType {
     ///
     /// SignatureID SIEM: 1005. Severitate: 7 (High) — atac coordonat.
     DistributedScan,
+
+    /// Sursa evenimentului apare intr-un feed threat intel (IOC).
+    ///
+    /// Se declanseaza la PRIMUL eveniment de la adresa listata, indiferent de
+    /// praguri — adresa este deja cunoscuta ca malitioasa de CERT.
+    ///
+    /// SignatureID SIEM: 1006. Severitate: 8 (Critical).
+    KnownBadSource,
+
+    /// Destinatia evenimentului apare intr-un feed threat intel — un host
+    /// intern contacteaza o adresa malitioasa (C2, exfiltrare).
+    ///
+    /// SignatureID SIEM: 1007. Severitate: 9 (Critical) — trafic outbound catre
+    /// un IOC indica de obicei un host deja compromis.
+    KnownBadDestination,
+
+    /// Abatere de la baseline-ul invatat al sursei (sau subnetului) — porturi
+    /// unice, destinatii unice sau proportia de drop-uri peste medie + N sigma.
+    ///
+    /// Complementar pragurilor fixe: un jump host cu 20 porturi/interval nu
+    /// alerteaza, o statie de lucru care sare de la 2 la 8 porturi — da.
+    ///
+    /// SignatureID SIEM: 1008. Severitate: 6 (Medium).
+    Anomaly,
+
+    /// Scorul de risc al sursei, acumulat din alertele de orice tip, a depasit
+    /// un nou nivel din `[detection.risk].tiers`.
+    ///
+    /// Leaga alertele independente (Fast → Accept → Lateral) intr-un singur
+    /// incident: mesajul contine lantul alertelor care au contribuit.
+    ///
+    /// SignatureID SIEM: 1009. Severitate: 8/9/10 dupa nivelul atins.
+    Escalation,
+
+    /// O regula `[[detection.correlation.rules]]` s-a potrivit: secventa
+    /// ordonata de etape (ex: Fast Scan → accept pe porturi sondate → Lateral
+    /// Movement de pe o destinatie atinsa) a aparut in fereastra regulii.
+    ///
+    /// SignatureID SIEM: 1010. Severitate: 10 (Critical) — compromitere probabila.
+    AttackChain,
+
+    /// Conexiuni periodice cu jitter mic de la aceeasi sursa catre aceeasi
+    /// destinatie si port — tiparul unui implant C2 care "suna acasa".
+    ///
+    /// Singurul detector bazat pe regularitate in timp, nu pe fan-out.
+    ///
+    /// SignatureID SIEM: 1011. Severitate: 7 (High).
+    Beaconing,
+
+    /// Rata mare de evenimente pe ACELASI tuplu (sursa, destinatie, port) —
+    /// brute force SSH, password spraying RDP, flood pe un port filtrat.
+    ///
+    /// Complementar Fast/Slow Scan: un singur port unic nu atinge niciodata
+    /// `port_threshold`, oricat de des ar fi lovit.
+    ///
+    /// SignatureID SIEM: 1012. Severitate: 7 (drop) / 8 (accept — serviciul
+    /// raspunde, incercarile ajung la autentificare).
+    BruteForce,
+
+    /// O sursa trimite ICMP de recunoastere (echo, timestamp, address mask)
+    /// catre N destinatii unice — descoperirea host-urilor active, etapa
+    /// care precede de obicei scanarea de porturi.
+    ///
+    /// SignatureID SIEM: 1013. Severitate: 5 (Medium).
+    PingSweep,
+
+    /// O sursa a atins `persistent_threshold` alerte in fereastra de recidiva
+    /// (`[detection.repeat_offenders]`) — scaneaza in reprize, sub cooldown,
+    /// si fiecare alerta luata separat pare identica cu cea dinainte.
+    ///
+    /// SignatureID SIEM: 1014. Severitate: 7 (High).
+    PersistentScanner,
+
+    /// Alerta sintetica trimisa la cerere (`ids-rs ctl test-alert`) prin
+    /// toate destinatiile — verifica lantul CLI → istoric → SIEM → email
+    /// fara a genera trafic de scanare. Adresele sunt din TEST-NET (RFC 5737).
+    ///
+    /// SignatureID SIEM: 1016. Severitate: 1 (Low).
+    TestAlert,
+}
+
+impl ScanType {
+    /// Cheia stabila a tipului (snake_case) — folosita in config
+    /// (`[[detection.correlation.rules]]`, `[detection.risk.weights]`).
+    pub fn key(&self) -> &'static str {
+        match self {
+            ScanType::Fast => "fast_scan",
+            ScanType::Slow => "slow_scan",
+            ScanType::AcceptScan => "accept_scan",
+            ScanType::LateralMovement => "lateral_movement",
+            ScanType::DistributedScan => "distributed_scan",
+            ScanType::KnownBadSource => "known_bad_source",
+            ScanType::KnownBadDestination => "known_bad_destination",
+            ScanType::Anomaly => "anomaly",
+            ScanType::Escalation => "escalation",
+            ScanType::AttackChain => "attack_chain",
+            ScanType::Beaconing => "beaconing",
+            ScanType::BruteForce => "brute_force",
+            ScanType::PingSweep => "ping_sweep",
+            ScanType::PersistentScanner => "persistent_scanner",
+            ScanType::TestAlert => "test_alert",
+        }
+    }
 }
 
 /// Implementarea trait-ului Display pentru ScanType.
@@ -197,6 +480,16 @@ impl std::fmt::Display for ScanType {
             ScanType::AcceptScan => write!(f, "Accept Scan"),
             ScanType::LateralMovement => write!(f, "Lateral Movement"),
             ScanType::DistributedScan => write!(f, "Distributed Scan"),
+            ScanType::KnownBadSource => write!(f, "Known Bad Source"),
+            ScanType::KnownBadDestination => write!(f, "Known Bad Destination"),
+            ScanType::Anomaly => write!(f, "Behavioral Anomaly"),
+            ScanType::Escalation => write!(f, "Risk Escalation"),
+            ScanType::AttackChain => write!(f, "Attack Chain"),
+            ScanType::Beaconing => write!(f, "Beaconing"),
+            ScanType::BruteForce => write!(f, "Brute Force"),
+            ScanType::PingSweep => write!(f, "Ping Sweep"),
+            ScanType::PersistentScanner => write!(f, "Persistent Scanner"),
+            ScanType::TestAlert => write!(f, "Test Alert"),
         }
     }
 }
@@ -210,21 +503,212 @@ impl std::fmt::Display for ScanType {
 pub struct Alert {
     pub scan_type: ScanType,
     pub source_ip: IpAddr,
+    /// Lungimea prefixului cand `source_ip` este o sursa IPv6 agregata
+    /// (adresa de retea a prefixului, nu adresa unui host).
+    pub source_prefix: Option<u8>,
     /// IP-ul tinta al scanarii — din campul `dst` al log-ului care a
     /// declansat alerta. Option<> deoarece unele log-uri nu au dst valid.
     pub dest_ip: Option<IpAddr>,
     /// Porturi unice detectate — populat pentru Fast/Slow/AcceptScan.
     /// Gol pentru LateralMovement (acolo relevant este unique_dests).
+    /// Acelasi numar poate aparea de doua ori (80/tcp si 80/udp).
     pub unique_ports: Vec<u16>,
-    /// Destinatii unice contactate — populat doar pentru LateralMovement.
+    /// Numarul de porturi unice per protocol (ex: tcp=12, udp=3) — populat
+    /// pentru Fast/Slow/AcceptScan; `[(icmp, N)]` pentru PingSweep.
+    pub protocols: Vec<(Protocol, usize)>,
+    /// Destinatii unice contactate — populat pentru LateralMovement si PingSweep.
     /// Gol pentru celelalte tipuri de scan.
     pub unique_dests: Vec<IpAddr>,
     /// Surse unice care au scanat aceeasi tinta — populat doar pentru DistributedScan.
     /// Gol pentru celelalte tipuri de scan.
     pub unique_sources: Vec<IpAddr>,
+    /// Indicatorul IOC potrivit — populat doar pentru KnownBadSource/Destination.
+    pub threat_intel: Option<IocMatch>,
+    /// Abaterea de la baseline — populat doar pentru Anomaly.
+    pub anomaly: Option<AnomalyDetail>,
+    /// Lantul de atac potrivit — populat doar pentru AttackChain.
+    pub chain: Option<ChainMatch>,
+    /// Intervalul si jitter-ul masurat — populat doar pentru Beaconing.
+    pub beacon: Option<BeaconDetail>,
+    /// Rata masurata pe tuplu — populat doar pentru BruteForce.
+    pub flood: Option<FloodDetail>,
+    /// Scorul de risc si lantul de alerte — populat doar pentru Escalation.
+    pub risk: Option<RiskDetail>,
+    /// Scorul ponderat al porturilor — populat pentru Fast/Slow/AcceptScan doar
+    /// cand `[detection.port_weights]` este activat.
+    pub score: Option<f64>,
+    /// Porturile sensibile (pondere > 1) atinse — subset din unique_ports.
+    pub sensitive_ports: Vec<u16>,
+    /// Unealta / tehnica probabila — Fast/Slow/AcceptScan, cand log-urile
+    /// contin port sursa sau flag-uri TCP suficiente (vezi fingerprint.rs).
+    pub fingerprint: Option<Fingerprint>,
+    /// Regulile de firewall lovite de sursa, cele mai frecvente primele
+    /// (goala daca log-urile nu contin regula).
+    pub rules: Vec<String>,
+    /// Adresele originale si traduse ale evenimentului, cand gateway-ul a
+    /// facut NAT — `source_ip` / `dest_ip` sunt identitatea detectorului.
+    pub nat: Option<NatDetail>,
+    /// Pragul depasit (porturi, destinatii, surse sau conexiuni), pentru
+    /// detectorii cu prag — factorul "prag depasit" al severitatii.
+    pub threshold: Option<usize>,
+    /// Recidiva sursei: a cata alerta in fereastra, totalul pe orizont,
+    /// tipurile (None cu `[detection.repeat_offenders]` dezactivat).
+    pub offence: Option<OffenceDetail>,
+    /// Update sau rezumat al unei scanari in desfasurare (cooldown "ongoing");
+    /// None pentru prima alerta si in modurile fixed / sliding.
+    pub ongoing: Option<OngoingDetail>,
+    /// Severitatea dinamica (1-10) si factorii aplicati.
+    pub severity: Severity,
+    /// Profilul de detectie aplicat (None = praguri globale).
+    /// Pentru DistributedScan este profilul tintei, nu al sursei.
+    pub profile: Option<String>,
     pub timestamp: DateTime<Local>,
 }
 
+impl Alert {
+    /// Alerta cu listele goale si timestamp-ul curent.
+    ///
+    /// NOTA RUST - STRUCT UPDATE SYNTAX:
+    /// `Alert { unique_ports: ports, ..Alert::new(...) }` completeaza campurile
+    /// nespecificate din valoarea de dupa `..`. Campurile noi adaugate in Alert
+    /// nu mai trebuie propagate in fiecare loc unde se construieste o alerta.
+    pub fn new(scan_type: ScanType, source_ip: IpAddr, dest_ip: Option<IpAddr>) -> Self {
+        Self {
+            scan_type,
+            source_ip,
+            source_prefix: None,
+            dest_ip,
+            unique_ports: Vec::new(),
+            protocols: Vec::new(),
+            unique_dests: Vec::new(),
+            unique_sources: Vec::new(),
+            threat_intel: None,
+            anomaly: None,
+            chain: None,
+            beacon: None,
+            flood: None,
+            risk: None,
+            score: None,
+            sensitive_ports: Vec::new(),
+            fingerprint: None,
+            rules: Vec::new(),
+            nat: None,
+            threshold: None,
+            offence: None,
+            ongoing: None,
+            severity: Severity::default(),
+            profile: None,
+            timestamp: Local::now(),
+        }
+    }
+
+    /// Valoarea comparata cu `threshold`: scorul ponderat sau numarul de
+    /// porturi / destinatii / surse unice, respectiv conexiunile Brute Force.
+    pub fn observed(&self) -> Option<f64> {
+        match self.scan_type {
+            ScanType::Fast | ScanType::Slow | ScanType::AcceptScan => {
+                Some(self.score.unwrap_or(self.unique_ports.len() as f64))
+            }
+            ScanType::LateralMovement | ScanType::PingSweep => Some(self.unique_dests.len() as f64),
+            ScanType::DistributedScan => Some(self.unique_sources.len() as f64),
+            ScanType::BruteForce => self.flood.as_ref().map(|f| f.count as f64),
+            ScanType::PersistentScanner => self.offence.as_ref().map(|o| o.nth as f64),
+            _ => None,
+        }
+    }
+
+    /// Tintele alertei: destinatia si destinatiile unice (Lateral Movement,
+    /// Ping Sweep) — pentru lookup-ul in inventarul de active.
+    pub fn targets(&self) -> impl Iterator<Item = &IpAddr> {
+        self.dest_ip.iter().chain(self.unique_dests.iter())
+    }
+
+    /// Sursa afisata: "2001:db8:1:2::/64" pentru o sursa IPv6 agregata, altfel IP-ul.
+    pub fn source_label(&self) -> String {
+        match self.source_prefix {
+            Some(prefix) => format!("{}/{}", self.source_ip, prefix),
+            None => self.source_ip.to_string(),
+        }
+    }
+
+    /// Defalcarea pe protocol, ex: "tcp=12,udp=3" (goala daca nu e populata).
+    pub fn protocol_summary(&self, sep: &str) -> String {
+        self.protocols
+            .iter()
+            .map(|(proto, n)| format!("{}={}", proto, n))
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+}
+
+/// Adresele originale si traduse ale evenimentului care a declansat alerta.
+#[derive(Debug, Clone)]
+pub struct NatDetail {
+    pub src: IpAddr,
+    pub dst: Option<IpAddr>,
+    pub dest_port: u16,
+    pub xlate_src: Option<IpAddr>,
+    pub xlate_dst: Option<IpAddr>,
+    pub xlate_sport: Option<u16>,
+    pub xlate_dport: Option<u16>,
+}
+
+impl NatDetail {
+    /// Detaliile NAT ale evenimentului; `src` este adresa reala (neagregata).
+    fn from_event(src: IpAddr, event: &LogEvent) -> Option<Self> {
+        event.has_nat().then_some(NatDetail {
+            src,
+            dst: event.dest_ip,
+            dest_port: event.dest_port,
+            xlate_src: event.xlate_src,
+            xlate_dst: event.xlate_dst,
+            xlate_sport: event.xlate_sport,
+            xlate_dport: event.xlate_dport,
+        })
+    }
+
+    /// Forma lizibila: "src 10.1.1.5 → 198.51.100.7:40001; dst 203.0.113.10:8443 → 10.0.0.20:443".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.xlate_src.is_some() || self.xlate_sport.is_some() {
+            let translated = self.xlate_src.unwrap_or(self.src);
+            let port = self.xlate_sport.map(|p| format!(":{}", p)).unwrap_or_default();
+            parts.push(format!("src {} → {}{}", self.src, translated, port));
+        }
+        if self.xlate_dst.is_some() || self.xlate_dport.is_some() {
+            let original = self.dst.map(|ip| ip.to_string()).unwrap_or_else(|| "N/A".to_string());
+            let translated = self
+                .xlate_dst
+                .or(self.dst)
+                .map(|ip| ip.to_string())
+                .unwrap_or_else(|| "N/A".to_string());
+            let port = self.xlate_dport.unwrap_or(self.dest_port);
+            parts.push(format!("dst {}:{} → {}:{}", original, self.dest_port, translated, port));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Detaliile unei alerte BruteForce.
+#[derive(Debug, Clone)]
+pub struct FloodDetail {
+    pub port: u16,
+    /// Evenimentele tuplului in fereastra (doar actiunea care a declansat).
+    pub count: usize,
+    /// true = accept-uri, false = drop-uri.
+    pub accepted: bool,
+    pub window_secs: u64,
+}
+
+/// Cheia Brute Force: (sursa, destinatie, port).
+type FloodKey = (IpAddr, IpAddr, u16);
+
+/// Un eveniment pe un tuplu Brute Force.
+struct FloodHit {
+    accepted: bool,
+    seen_at: Instant,
+}
+
 /// Inregistrarea unei conexiuni catre o destinatie (Lateral Movement #22).
 ///
 /// Tine minte CATRE CE IP s-a conectat sursa si cand.
@@ -232,6 +716,8 @@ pub struct Alert {
 /// DestHit tine minte destinatia accesata.
 struct DestHit {
     dest_ip: IpAddr,
+    /// Actiunea firewall-ului — pentru split-ul drop/accept al sesiunii.
+    accepted: bool,
     seen_at: Instant,
 }
 
@@ -243,6 +729,7 @@ struct DestHit {
 struct DistributedHit {
     source_ip: IpAddr,
     port: u16,
+    accepted: bool,
     seen_at: Instant,
 }
 
@@ -255,9 +742,42 @@ struct DistributedHit {
 /// dar este eficient si sigur pentru masuratori in-process.
 struct PortHit {
     port: u16,
+    /// Protocolul hit-ului: 80/tcp si 80/udp sunt porturi unice diferite.
+    proto: Protocol,
+    /// Portul sursa si flag-urile TCP — doar pentru amprentarea uneltei.
+    source_port: Option<u16>,
+    flags: Option<u8>,
+    /// Destinatia — pentru totalurile sesiunii de scanare.
+    dest_ip: Option<IpAddr>,
     seen_at: Instant,
 }
 
+/// Tipurile ICMP de recunoastere numarate de Ping Sweep: echo request (8),
+/// timestamp (13), address mask (17) si ICMPv6 echo request (128).
+/// Raspunsurile si erorile (unreachable, time exceeded) nu sunt sondari.
+const ICMP_PROBE_TYPES: &[u8] = &[8, 13, 17, 128];
+
+/// Evenimentul ICMP este o sondare. Fara tip in log (unele formate nu il
+/// exporta) il numaram — altfel Ping Sweep ar fi orb pe acele surse.
+fn is_icmp_probe(event: &LogEvent) -> bool {
+    event.icmp_type.is_none_or(|t| ICMP_PROBE_TYPES.contains(&t))
+}
+
+/// Imparte perechile (protocol, port) in lista de porturi si numarul de
+/// porturi per protocol, in ordinea fixa tcp, udp, icmp, other.
+fn split_protocols(pairs: &[(Protocol, u16)]) -> (Vec<u16>, Vec<(Protocol, usize)>) {
+    let ports = pairs.iter().map(|(_, port)| *port).collect();
+    let mut counts: Vec<(Protocol, usize)> = Vec::new();
+    for (proto, _) in pairs {
+        match counts.iter_mut().find(|(p, _)| p == proto) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((*proto, 1)),
+        }
+    }
+    counts.sort_unstable();
+    (ports, counts)
+}
+
 // =============================================================================
 // Detector - Motorul de detectie
 // =============================================================================
@@ -326,6 +846,13 @@ pub struct Detector {
     /// Cooldown alerte Lateral Movement per IP sursa.
     lateral_cooldowns: DashMap<IpAddr, Instant>,
 
+    /// Destinatiile sondate prin ICMP, per IP sursa (Ping Sweep).
+    /// Aceeasi structura ca lateral_hits, alimentata doar de ICMP.
+    icmp_hits: DashMap<IpAddr, Vec<DestHit>>,
+
+    /// Cooldown alerte Ping Sweep per IP sursa.
+    ping_sweep_cooldowns: DashMap<IpAddr, Instant>,
+
     /// Evidenta hit-urilor per destinatie (Distributed Scan #23).
     /// Perspectiva inversata: cheia este dest_ip, nu source_ip.
     /// Key: IP-ul destinatie | Value: lista de (source_ip, port, timestamp)
@@ -335,10 +862,82 @@ pub struct Detector {
     /// Indexat dupa dest_ip — cooldown-ul este al tintei, nu al atacatorului.
     distributed_cooldowns: DashMap<IpAddr, Instant>,
 
+    /// Evenimentele per tuplu (sursa, destinatie, port) — Brute Force.
+    /// Drop-urile si accept-urile stau in acelasi Vec, numarate separat.
+    flood_hits: DashMap<FloodKey, Vec<FloodHit>>,
+
+    /// Cooldown alerte Brute Force per (tuplu, accept?) — un flood blocat si
+    /// accept-urile ulterioare pe acelasi tuplu alerteaza independent.
+    flood_cooldowns: DashMap<(FloodKey, bool), Instant>,
+
+    /// Cooldown alerte KnownBadSource per IP sursa.
+    known_bad_src_cooldowns: DashMap<IpAddr, Instant>,
+
+    /// Cooldown alerte KnownBadDestination per pereche (sursa, destinatie).
+    /// Cheia compusa: acelasi host intern care contacteaza doua IOC-uri
+    /// diferite genereaza doua alerte; doua host-uri interne catre acelasi
+    /// IOC — la fel (fiecare host e potential compromis).
+    known_bad_dst_cooldowns: DashMap<(IpAddr, IpAddr), Instant>,
+
+    /// Indicatorii IOC incarcati din feed-urile threat intel.
+    /// ArcSwap separat de config: listele se reincarca din fisiere la SIGHUP,
+    /// iar un feed invalid pastreaza listele vechi fara a afecta restul config-ului.
+    threat_intel: ArcSwap<ThreatIntel>,
+
+    /// Baseline-urile invatate si intervalele de masurare curente (mod anomalie).
+    baselines: BaselineStore,
+
+    /// Cooldown alerte Anomaly per cheie de baseline (IP sau "subnet:<eticheta>").
+    anomaly_cooldowns: DashMap<String, Instant>,
+
+    /// Mapping-ul [network.subnets] — folosit pentru gruparea baseline-urilor
+    /// pe subnet (`anomaly.group_by = "subnet"`). Actualizat la SIGHUP.
+    subnets: ArcSwap<Vec<SubnetEntry>>,
+
+    /// Profilele de detectie rezolvate — reconstruite la schimbarea config-ului
+    /// sau a mapping-ului [network.subnets] (etichetele depind de el).
+    profiles: ArcSwap<Vec<ResolvedProfile>>,
+
+    /// Scorurile de risc per sursa (Escalation).
+    risk: RiskTracker,
+
+    /// Regulile de firewall lovite per sursa si contoarele per regula.
+    rules: RuleTracker,
+
+    /// Istoricul de alerte per sursa — recidiva si PersistentScanner.
+    offenders: OffenderTracker,
+
+    /// Sesiunile detectoarelor in cooldown "ongoing" (update-uri, "scan ended").
+    ongoing: OngoingTracker,
+
+    /// Sesiunile de scanare deschise (per sursa / tinta).
+    sessions: SessionTracker,
+
+    /// Inventarul de active — criticitatea tintei ridica severitatea.
+    assets: ArcSwap<AssetInventory>,
+
+    /// Lanturile de atac partiale si activitatea host-urilor (AttackChain).
+    correlation: CorrelationEngine,
+
+    /// Sesiunile per tuplu (sursa, destinatie, port) pentru Beaconing.
+    beacons: BeaconTracker,
+
+    /// Destinatiile excluse din Beaconing (NTP, update) — parsate din config,
+    /// reconstruite la SIGHUP.
+    beacon_allowlist: ArcSwap<Vec<SubnetEntry>>,
+
+    /// Tabela de ponderi porturi rezolvata — reconstruita la SIGHUP.
+    port_weights: ArcSwap<PortWeights>,
+
     /// IP-uri si subretele excluse din detectie (parsate din config la constructie).
     /// Wrapat in ArcSwap pentru hot reload atomic la SIGHUP (#16).
     whitelist: ArcSwap<Vec<WhitelistEntry>>,
 
+    /// Whitelist temporar adaugat prin `ids-rs ctl whitelist add`.
+    /// Key: intrarea ("10.0.0.5", "10.0.2.0/24") | Value: (intrare, expirare).
+    /// Nu supravietuieste unui restart si nu este atins de reload.
+    temp_whitelist: DashMap<String, (WhitelistEntry, Option<Instant>)>,
+
     /// Configurarea pragurilor de detectie.
     /// Wrapat in ArcSwap pentru hot reload atomic la SIGHUP (#16).
     /// `ArcSwap::load()` returneaza un `Guard` (pointer atomic, lock-free) —
@@ -358,6 +957,8 @@ impl Detector {
             .iter()
             .filter_map(|entry| WhitelistEntry::parse(entry))
             .collect();
+        let profiles = ResolvedProfile::build(&config, &[]);
+        let beacon_allowlist = parse_beacon_allowlist(&config);
 
         Self {
             port_hits: DashMap::new(),
@@ -367,10 +968,32 @@ impl Detector {
             accept_cooldowns: DashMap::new(),
             lateral_hits: DashMap::new(),
             lateral_cooldowns: DashMap::new(),
+            icmp_hits: DashMap::new(),
+            ping_sweep_cooldowns: DashMap::new(),
             distributed_hits: DashMap::new(),
             distributed_cooldowns: DashMap::new(),
+            flood_hits: DashMap::new(),
+            flood_cooldowns: DashMap::new(),
+            known_bad_src_cooldowns: DashMap::new(),
+            known_bad_dst_cooldowns: DashMap::new(),
+            threat_intel: ArcSwap::from_pointee(ThreatIntel::default()),
+            baselines: BaselineStore::new(),
+            anomaly_cooldowns: DashMap::new(),
+            subnets: ArcSwap::from_pointee(Vec::new()),
+            profiles: ArcSwap::from_pointee(profiles),
+            risk: RiskTracker::new(),
+            rules: RuleTracker::new(),
+            offenders: OffenderTracker::new(),
+            ongoing: OngoingTracker::new(),
+            sessions: SessionTracker::new(),
+            assets: ArcSwap::from_pointee(AssetInventory::default()),
+            correlation: CorrelationEngine::new(),
+            beacons: BeaconTracker::new(),
+            beacon_allowlist: ArcSwap::from_pointee(beacon_allowlist),
+            port_weights: ArcSwap::from_pointee(PortWeights::from_config(&config.port_weights)),
             last_seen: DashMap::new(),
             whitelist: ArcSwap::from_pointee(whitelist),
+            temp_whitelist: DashMap::new(),
             config: ArcSwap::from_pointee(config),
         }
     }
@@ -393,14 +1016,107 @@ impl Detector {
             .filter_map(|entry| WhitelistEntry::parse(entry))
             .collect();
 
+        let new_profiles = ResolvedProfile::build(&new_config, &self.subnets.load());
+
         // Swap atomic: noua configurare devine activa imediat.
+        self.profiles.store(Arc::new(new_profiles));
+        self.port_weights
+            .store(Arc::new(PortWeights::from_config(&new_config.port_weights)));
+        self.beacon_allowlist
+            .store(Arc::new(parse_beacon_allowlist(&new_config)));
         self.config.store(Arc::new(new_config));
         self.whitelist.store(Arc::new(new_whitelist));
     }
 
+    /// Inlocuieste atomic listele IOC (la pornire si la SIGHUP).
+    pub fn update_threat_intel(&self, intel: ThreatIntel) {
+        self.threat_intel.store(Arc::new(intel));
+    }
+
+    /// Inlocuieste atomic inventarul de active (la pornire si la SIGHUP).
+    pub fn update_assets(&self, assets: Arc<AssetInventory>) {
+        self.assets.store(assets);
+    }
+
+    /// Inlocuieste atomic mapping-ul subnet → eticheta (la pornire si la SIGHUP).
+    pub fn update_subnets(&self, subnets: Vec<SubnetEntry>) {
+        let profiles = ResolvedProfile::build(&self.config.load(), &subnets);
+        self.subnets.store(Arc::new(subnets));
+        self.profiles.store(Arc::new(profiles));
+    }
+
+    /// Incarca baseline-urile persistate (`anomaly.state_file`).
+    /// Returneaza numarul de baseline-uri incarcate.
+    pub fn load_baselines(&self) -> anyhow::Result<usize> {
+        self.baselines.load(&self.config.load().anomaly.state_file)
+    }
+
+    /// Salveaza baseline-urile in `anomaly.state_file` (daca este configurat).
+    pub fn save_baselines(&self) -> anyhow::Result<()> {
+        let cfg = self.config.load();
+        if !cfg.anomaly.enabled {
+            return Ok(());
+        }
+        self.baselines.save(&cfg.anomaly.state_file)
+    }
+
+    /// Cheia de baseline pentru un IP sursa: IP-ul insusi sau eticheta subnetului.
+    fn baseline_key(&self, ip: &IpAddr, cfg: &DetectionConfig) -> String {
+        if cfg.anomaly.group_by == "subnet" {
+            if let Some(label) = SubnetEntry::lookup(&self.subnets.load(), ip) {
+                return format!("subnet:{}", label);
+            }
+        }
+        ip.to_string()
+    }
+
     /// Verifica daca un IP este in whitelist (exclus din detectie).
     pub fn is_whitelisted(&self, ip: &IpAddr) -> bool {
-        self.whitelist.load().iter().any(|entry| entry.matches(ip))
+        if self.whitelist.load().iter().any(|entry| entry.matches(ip)) {
+            return true;
+        }
+        let now = Instant::now();
+        self.temp_whitelist
+            .iter()
+            .any(|e| e.value().1.is_none_or(|until| now < until) && e.value().0.matches(ip))
+    }
+
+    /// Adauga o intrare (IP sau CIDR) in whitelist-ul temporar, cu expirare
+    /// optionala. O intrare existenta este inlocuita (TTL-ul reinnoit).
+    pub fn add_temp_whitelist(&self, entry: &str, ttl: Option<Duration>) -> anyhow::Result<()> {
+        let entry = entry.trim();
+        let parsed = WhitelistEntry::parse(entry)
+            .ok_or_else(|| anyhow::anyhow!("'{}' nu este un IP sau un CIDR valid", entry))?;
+        // Un TTL urias (ex: u64::MAX secunde) nu se poate aduna la `Instant`.
+        let until = match ttl {
+            Some(t) => Some(
+                Instant::now()
+                    .checked_add(t)
+                    .ok_or_else(|| anyhow::anyhow!("TTL prea mare pentru '{}'", entry))?,
+            ),
+            None => None,
+        };
+        self.temp_whitelist.insert(entry.to_string(), (parsed, until));
+        Ok(())
+    }
+
+    /// Sterge o intrare din whitelist-ul temporar. false = nu exista.
+    pub fn remove_temp_whitelist(&self, entry: &str) -> bool {
+        self.temp_whitelist.remove(entry.trim()).is_some()
+    }
+
+    /// Intrarile whitelist-ului temporar inca valabile, cu timpul ramas
+    /// (None = pana la restart), sortate dupa intrare.
+    pub fn temp_whitelist(&self) -> Vec<(String, Option<Duration>)> {
+        let now = Instant::now();
+        let mut entries: Vec<(String, Option<Duration>)> = self
+            .temp_whitelist
+            .iter()
+            .filter(|e| e.value().1.is_none_or(|until| now < until))
+            .map(|e| (e.key().clone(), e.value().1.map(|until| until - now)))
+            .collect();
+        entries.sort();
+        entries
     }
 
     /// Proceseaza un eveniment de log si returneaza alertele detectate.
@@ -422,21 +1138,114 @@ impl Detector {
     ///
     pub fn process_event(&self, event: &LogEvent) -> Vec<Alert> {
         let now = Instant::now();
-        let ip = event.source_ip;
+        // Adresa reala a sursei — whitelist, profil si threat intel o folosesc
+        // pe ea; detectoarele folosesc cheia agregata (pasul 0c).
+        let src = event.source_ip;
 
         // Incarcam config-ul o singura data per eveniment (load atomic, lock-free).
         // `Guard` din ArcSwap tine o referinta la snapshot-ul curent al config-ului.
         // Daca un SIGHUP reload schimba config-ul in timpul procesarii, acest
         // eveniment continua cu config-ul vechi — urmatorul il va folosi pe cel nou.
-        let cfg = self.config.load();
+        let global = self.config.load();
+
+        // Profilul sursei (longest prefix) — daca exista, pragurile lui inlocuiesc
+        // pragurile globale pentru tot evenimentul. Whitelist-ul, limitele de
+        // memorie si modul anomalie raman globale (profilul le copiaza neschimbate).
+        let profiles = self.profiles.load();
+        let profile = ResolvedProfile::lookup(&profiles, &src);
+        let cfg: &DetectionConfig = profile.map(|p| &p.config).unwrap_or(&global);
+        let profile_name = profile.map(|p| p.name.clone());
+        let cooldown = Duration::from_secs(cfg.alert_cooldown_secs);
+        // Ponderile sunt globale (nu se suprascriu in profile).
+        let weights = self.port_weights.load();
 
         // --- 0. Whitelist check ---
         // IP-urile din whitelist sunt excluse complet din detectie.
         // Nu consuma memorie in DashMap, nu genereaza alerte.
-        if self.is_whitelisted(&ip) {
+        if self.is_whitelisted(&src) {
             return Vec::new();
         }
 
+        let mut alerts = Vec::new();
+
+        // --- 0b. Threat intel (IOC) ---
+        //
+        // Verificat INAINTE de orice prag: o adresa din feed-ul CERT este deja
+        // cunoscuta ca malitioasa, deci primul eveniment (drop sau accept)
+        // genereaza alerta. Cooldown-ul previne spam-ul la traficul continuu.
+        let intel = self.threat_intel.load();
+        if !intel.is_empty() {
+            if let Some(ioc) = intel.lookup(&src) {
+                if !self.in_cooldown(&self.known_bad_src_cooldowns, &src, cooldown) {
+                    self.known_bad_src_cooldowns.insert(src, now);
+                    alerts.push(Alert {
+                        threat_intel: Some(ioc),
+                        profile: profile_name.clone(),
+                        ..Alert::new(ScanType::KnownBadSource, src, event.dest_ip)
+                    });
+                }
+            }
+            if let Some(dest_ip) = event.dest_ip {
+                if let Some(ioc) = intel.lookup(&dest_ip) {
+                    let key = (src, dest_ip);
+                    if !self.in_cooldown(&self.known_bad_dst_cooldowns, &key, cooldown) {
+                        self.known_bad_dst_cooldowns.insert(key, now);
+                        alerts.push(Alert {
+                            threat_intel: Some(ioc),
+                            profile: profile_name.clone(),
+                            ..Alert::new(ScanType::KnownBadDestination, src, Some(dest_ip))
+                        });
+                    }
+                }
+            }
+        }
+
+        // --- 0c. Agregare IPv6 ---
+        //
+        // Sursele IPv6 sunt urmarite dupa prefix (`ipv6_source_prefix`): toate
+        // adresele din acelasi /64 impart hit-urile, cooldown-urile, scorul de
+        // risc si intrarea LRU. De aici incolo `event` poarta cheia agregata ca
+        // sursa, ca baseline-ul si corelarea sa vada aceeasi sursa ca detectoarele.
+        //
+        // NOTA RUST - SHADOWING + LEGARE AMANATA:
+        // `let keyed;` declara variabila fara valoare; este initializata doar pe
+        // ramura care cloneaza evenimentul. Referinta `&keyed` traieste cat
+        // `keyed` (pana la finalul functiei), deci poate inlocui `event`.
+        // IPv4 si IPv6 fara agregare nu platesc clona.
+        let source_prefix = global.ipv6_source_prefix;
+        let network = aggregate_ipv6(src, source_prefix);
+        let keyed;
+        let event = match network {
+            Some(network) => {
+                keyed = LogEvent { source_ip: network, ..event.clone() };
+                &keyed
+            }
+            None => event,
+        };
+        let ip = event.source_ip;
+        let aggregated = network.is_some();
+
+        // --- 0d. Identitate NAT ---
+        //
+        // Fiecare detector urmareste adresele originale sau pe cele traduse
+        // (`[detection.nat]`). Vederea tradusa se construieste doar daca log-ul
+        // are NAT si cel putin un detector o foloseste; sursa tradusa trece prin
+        // aceeasi agregare IPv6. `view` alege evenimentul vazut de un detector.
+        let nat_cfg = &global.nat;
+        let translated_event = if event.has_nat() && nat_cfg.any_translated() {
+            let mut translated = event.translated();
+            if let Some(network) = aggregate_ipv6(translated.source_ip, source_prefix) {
+                translated.source_ip = network;
+            }
+            Some(translated)
+        } else {
+            None
+        };
+        let view = |detector: &str| match translated_event {
+            Some(ref translated) if nat_cfg.translated(detector) => translated,
+            _ => event,
+        };
+
         // --- 1. Limitare globala IP-uri (anti-IP-spoofing flood) ---
         //
         // NOTA #4 - LRU EVICTION:
@@ -472,148 +1281,189 @@ impl Detector {
         // Problema: un IP care trimite doar "accept"-uri (fara "drop") nu aparea in
         // port_hits → nu era considerat "urmarit" → evictia nu se activa corect.
         // Acum: `last_seen` urmareste orice IP, indiferent de actiune.
-        let is_new_ip = !self.last_seen.contains_key(&ip);
-        if is_new_ip && self.last_seen.len() >= cfg.max_tracked_ips {
-            // Gasim IP-ul cu cel mai vechi last_seen (Least Recently Used).
-            let lru_ip: Option<IpAddr> = self
-                .last_seen
-                .iter()
-                .min_by_key(|e| *e.value())
-                .map(|e| *e.key());
-
-            if let Some(old_ip) = lru_ip {
-                // Eliminam IP-ul LRU din TOATE structurile.
-                self.port_hits.remove(&old_ip);
-                self.accept_hits.remove(&old_ip);
-                self.lateral_hits.remove(&old_ip);
-                self.last_seen.remove(&old_ip);
-                self.fast_cooldowns.remove(&old_ip);
-                self.slow_cooldowns.remove(&old_ip);
-                self.accept_cooldowns.remove(&old_ip);
-                self.lateral_cooldowns.remove(&old_ip);
-            }
+        //
+        // Cu identitate NAT "translated" pe unii detectori, adresa tradusa a
+        // sursei este urmarita separat (are propriile hit-uri) — deci si ea
+        // ocupa un loc in last_seen.
+        for key in std::iter::once(ip).chain(translated_event.as_ref().map(|t| t.source_ip)) {
+            self.track_source(key, now, cfg.max_tracked_ips);
         }
 
-        // Actualizam last_seen pentru IP-ul curent (nou sau existent).
-        self.last_seen.insert(ip, now);
-
-        // --- 2. Inregistram port hit-ul in map-ul corespunzator actiunii ---
-        //
-        // NOTA RUST - REFERINTE IMUTABILE la campuri diferite ale structurii:
-        //
-        // Selectam map-ul tinta pe baza actiunii evenimentului:
-        //   "drop"   → port_hits   (port BLOCAT de firewall → Fast/Slow Scan)
-        //   "accept" → accept_hits (port PERMIS de firewall → Accept Scan)
-        //
-        // `let hits_map: &DashMap<...>` stocheaza o referinta imutabila la unul
-        // din cele doua campuri. Chiar daca referinta este imutabila (&), DashMap
-        // permite modificari prin INTERIOR MUTABILITY (lock-uri interne per shard).
+        // --- 1b. Sesiuni de scanare deschise ---
         //
-        // Borrow checker-ul Rust stie ca `if-else` produce O SINGURA referinta,
-        // deci nu exista "doua borrows simultane". Compilatorul accepta acest cod
-        // si garanteaza la compile-time ca nu exista aliasing periculos.
-        //
-        // NOTA RUST - SCOP (SCOPE) EXPLICIT cu `{}`:
-        // Blocul `{}` garanteaza ca `RefMut` (write-lock-ul DashMap) este dropit
-        // (eliberat) inainte de urmatoarele operatii pe DashMap.
-        // Altfel: write-lock activ → urmatorul .get() pe acelasi shard → deadlock.
-        //
-        // NOTA #3 - LIMITARE MEMORIE PER IP:
-        // `.drain(..N)` sterge primele N elemente (cele mai vechi, oldest-first).
-        // Aplica aceeasi limita (max_hits_per_ip) la ambele map-uri.
-        //
-        let hits_map: &DashMap<IpAddr, Vec<PortHit>> = if event.action == "drop" {
-            &self.port_hits
-        } else {
-            // "accept" si orice alta actiune filtrata de parser → accept_hits.
-            &self.accept_hits
-        };
-        {
-            let mut hits = hits_map.entry(ip).or_default();
-            hits.push(PortHit {
-                port: event.dest_port,
-                seen_at: now,
-            });
-
-            // Cap la max_hits_per_ip: pastram doar cele mai recente intrari.
-            let max_hits = cfg.max_hits_per_ip;
-            if hits.len() > max_hits {
-                let overflow = hits.len() - max_hits;
-                hits.drain(..overflow);
+        // Evenimentul continua sesiunea sursei (adresa originala si cea
+        // tradusa) si sesiunea tintei, daca au fost deschise de o alerta
+        // anterioara. Sesiunile noi se deschid la pasul 14, din hit-uri.
+        let sessions_cfg = &global.sessions;
+        if sessions_cfg.enabled {
+            let accept = event.action == "accept";
+            let port = (!event.is_icmp()).then_some(event.dest_port);
+            let at_source = Probe { at: now, port, peer: event.dest_ip, accept };
+            for key in std::iter::once(ip).chain(translated_event.as_ref().map(|t| t.source_ip)) {
+                self.sessions.record(SessionKind::Source, key, &at_source, sessions_cfg);
+            }
+            if let Some(dest) = event.dest_ip {
+                let at_target = Probe { peer: Some(event.source_ip), ..at_source };
+                self.sessions.record(SessionKind::Target, dest, &at_target, sessions_cfg);
             }
         }
 
-        let mut alerts = Vec::new();
+        // Regula de firewall care a decis evenimentul (orice protocol/actiune).
+        if let Some(label) = event.rule_label() {
+            self.rules.record(ip, &label, event.action == "accept");
+        }
 
-        // --- 3. Verificam Fast Scan (pe port_hits — drop-uri) ---
-        //
-        // `unique_ports_in_window` acum primeste map-ul ca parametru explicit.
-        // Aceasta este o REFACTORIZARE necesara: inainte functia accesa `self.port_hits`
-        // direct (hardcodat). Acum poate lucra cu orice DashMap de tip corect,
-        // ceea ce ne permite sa o refolosim pentru Accept Scan (pasul 5) cu `accept_hits`.
-        let fast_window = Duration::from_secs(cfg.fast_scan.time_window_secs);
-        if let Some(ports) = self.unique_ports_in_window(&self.port_hits, ip, fast_window, now) {
-            if ports.len() >= cfg.fast_scan.port_threshold
-                && !self.in_cooldown(&self.fast_cooldowns, ip)
+        // ICMP nu are porturi (dest_port = 0): evenimentele ICMP ocolesc
+        // detectoarele bazate pe porturi (pasii 2-8) si alimenteaza doar
+        // threat intel (mai sus), Ping Sweep, corelarea si scorul de risc.
+        let is_icmp = event.is_icmp();
+
+        if !is_icmp {
+            // --- 2. Inregistram port hit-ul in map-ul corespunzator actiunii ---
+            //
+            // NOTA RUST - REFERINTE IMUTABILE la campuri diferite ale structurii:
+            //
+            // Selectam map-ul tinta pe baza actiunii evenimentului:
+            //   "drop"   → port_hits   (port BLOCAT de firewall → Fast/Slow Scan)
+            //   "accept" → accept_hits (port PERMIS de firewall → Accept Scan)
+            //
+            // `let hits_map: &DashMap<...>` stocheaza o referinta imutabila la unul
+            // din cele doua campuri. Chiar daca referinta este imutabila (&), DashMap
+            // permite modificari prin INTERIOR MUTABILITY (lock-uri interne per shard).
+            //
+            // Borrow checker-ul Rust stie ca `if-else` produce O SINGURA referinta,
+            // deci nu exista "doua borrows simultane". Compilatorul accepta acest cod
+            // si garanteaza la compile-time ca nu exista aliasing periculos.
+            //
+            // NOTA RUST - SCOP (SCOPE) EXPLICIT cu `{}`:
+            // Blocul `{}` garanteaza ca `RefMut` (write-lock-ul DashMap) este dropit
+            // (eliberat) inainte de urmatoarele operatii pe DashMap.
+            // Altfel: write-lock activ → urmatorul .get() pe acelasi shard → deadlock.
+            //
+            // NOTA #3 - LIMITARE MEMORIE PER IP:
+            // `.drain(..N)` sterge primele N elemente (cele mai vechi, oldest-first).
+            // Aplica aceeasi limita (max_hits_per_ip) la ambele map-uri.
+            //
+            // Fast/Slow Scan si Accept Scan pot avea identitati NAT diferite:
+            // hit-ul se inregistreaza sub identitatea detectorului care il foloseste.
+            let scan_event = view("port_scan");
+            let accept_event = view("accept_scan");
+            let (hits_map, hit_event): (&DashMap<IpAddr, Vec<PortHit>>, &LogEvent) =
+                if event.action == "drop" {
+                    (&self.port_hits, scan_event)
+                } else {
+                    // "accept" si orice alta actiune filtrata de parser → accept_hits.
+                    (&self.accept_hits, accept_event)
+                };
             {
-                self.fast_cooldowns.insert(ip, now);
-                alerts.push(Alert {
-                    scan_type: ScanType::Fast,
-                    source_ip: ip,
-                    dest_ip: event.dest_ip,
-                    unique_ports: ports,
-                    unique_dests: Vec::new(),
-                    unique_sources: Vec::new(),
-                    timestamp: Local::now(),
+                let mut hits = hits_map.entry(hit_event.source_ip).or_default();
+                hits.push(PortHit {
+                    port: hit_event.dest_port,
+                    proto: hit_event.proto(),
+                    source_port: hit_event.source_port,
+                    flags: hit_event.tcp_flags.as_deref().and_then(fingerprint::parse_tcp_flags),
+                    dest_ip: hit_event.dest_ip,
+                    seen_at: now,
                 });
+
+                // Cap la max_hits_per_ip: pastram doar cele mai recente intrari.
+                let max_hits = cfg.max_hits_per_ip;
+                if hits.len() > max_hits {
+                    let overflow = hits.len() - max_hits;
+                    hits.drain(..overflow);
+                }
             }
-        }
 
-        // --- 4. Verificam Slow Scan (pe port_hits — drop-uri) ---
-        let slow_window = Duration::from_secs(cfg.slow_scan.time_window_mins * 60);
-        if let Some(ports) = self.unique_ports_in_window(&self.port_hits, ip, slow_window, now) {
-            if ports.len() >= cfg.slow_scan.port_threshold
-                && !self.in_cooldown(&self.slow_cooldowns, ip)
-            {
-                self.slow_cooldowns.insert(ip, now);
-                alerts.push(Alert {
-                    scan_type: ScanType::Slow,
-                    source_ip: ip,
-                    dest_ip: event.dest_ip,
-                    unique_ports: ports,
-                    unique_dests: Vec::new(),
-                    unique_sources: Vec::new(),
-                    timestamp: Local::now(),
-                });
+            // --- 3. Verificam Fast Scan (pe port_hits — drop-uri) ---
+            //
+            // `unique_ports_in_window` acum primeste map-ul ca parametru explicit.
+            // Aceasta este o REFACTORIZARE necesara: inainte functia accesa `self.port_hits`
+            // direct (hardcodat). Acum poate lucra cu orice DashMap de tip corect,
+            // ceea ce ne permite sa o refolosim pentru Accept Scan (pasul 5) cu `accept_hits`.
+            //
+            // Pragul se compara cu scorul ponderat (`PortWeights::score`): cu ponderile
+            // dezactivate scorul este chiar numarul de porturi unice.
+            let ip = scan_event.source_ip;
+            let fast_window = Duration::from_secs(cfg.fast_scan.time_window_secs);
+            if let Some(pairs) = self.unique_ports_in_window(&self.port_hits, ip, fast_window, now) {
+                let (ports, protocols) = split_protocols(&pairs);
+                let score = weights.score(&ports);
+                let obs = Observation {
+                    key: Some(ip),
+                    triggered: score >= cfg.fast_scan.port_threshold as f64,
+                    ports: &ports,
+                    ..Observation::default()
+                };
+                let policy = CooldownPolicy::resolve(cfg, "fast_scan");
+                alerts.extend(self.gate(&self.fast_cooldowns, &policy, obs, now, || Alert {
+                    score: weights.enabled.then_some(score),
+                    sensitive_ports: weights.sensitive(&ports),
+                    unique_ports: ports.clone(),
+                    protocols,
+                    fingerprint: self.fingerprint_in_window(&self.port_hits, ip, fast_window, now),
+                    threshold: Some(cfg.fast_scan.port_threshold),
+                    profile: profile_name.clone(),
+                    ..Alert::new(ScanType::Fast, ip, scan_event.dest_ip)
+                }));
             }
-        }
 
-        // --- 5. Verificam Accept Scan (pe accept_hits — conexiuni permise) ---
-        //
-        // Logica este identica cu Fast Scan, dar:
-        //   - Sursa de date: accept_hits (nu port_hits)
-        //   - Praguri: din config.accept_scan (pot fi diferite de Fast Scan)
-        //   - Cooldown propriu: accept_cooldowns (independent de fast/slow)
-        //   - ScanType: AcceptScan → SignatureID 1003 in SIEM
-        //
-        // Separarea completa de Fast/Slow Scan inseamna ca un IP poate declansa
-        // simultan o alerta Fast Scan (din drop-uri) SI o alerta Accept Scan (din
-        // accept-uri) — si amandoua vor fi trimise la SIEM si email, independent.
-        let accept_window = Duration::from_secs(cfg.accept_scan.time_window_secs);
-        if let Some(ports) = self.unique_ports_in_window(&self.accept_hits, ip, accept_window, now) {
-            if ports.len() >= cfg.accept_scan.port_threshold
-                && !self.in_cooldown(&self.accept_cooldowns, ip)
-            {
-                self.accept_cooldowns.insert(ip, now);
-                alerts.push(Alert {
-                    scan_type: ScanType::AcceptScan,
-                    source_ip: ip,
-                    dest_ip: event.dest_ip,
-                    unique_ports: ports,
-                    unique_dests: Vec::new(),
-                    unique_sources: Vec::new(),
-                    timestamp: Local::now(),
-                });
+            // --- 4. Verificam Slow Scan (pe port_hits — drop-uri) ---
+            let slow_window = Duration::from_secs(cfg.slow_scan.time_window_mins * 60);
+            if let Some(pairs) = self.unique_ports_in_window(&self.port_hits, ip, slow_window, now) {
+                let (ports, protocols) = split_protocols(&pairs);
+                let score = weights.score(&ports);
+                let obs = Observation {
+                    key: Some(ip),
+                    triggered: score >= cfg.slow_scan.port_threshold as f64,
+                    ports: &ports,
+                    ..Observation::default()
+                };
+                let policy = CooldownPolicy::resolve(cfg, "slow_scan");
+                alerts.extend(self.gate(&self.slow_cooldowns, &policy, obs, now, || Alert {
+                    score: weights.enabled.then_some(score),
+                    sensitive_ports: weights.sensitive(&ports),
+                    unique_ports: ports.clone(),
+                    protocols,
+                    fingerprint: self.fingerprint_in_window(&self.port_hits, ip, slow_window, now),
+                    threshold: Some(cfg.slow_scan.port_threshold),
+                    profile: profile_name.clone(),
+                    ..Alert::new(ScanType::Slow, ip, scan_event.dest_ip)
+                }));
+            }
+
+            // --- 5. Verificam Accept Scan (pe accept_hits — conexiuni permise) ---
+            //
+            // Logica este identica cu Fast Scan, dar:
+            //   - Sursa de date: accept_hits (nu port_hits)
+            //   - Praguri: din config.accept_scan (pot fi diferite de Fast Scan)
+            //   - Cooldown propriu: accept_cooldowns (independent de fast/slow)
+            //   - ScanType: AcceptScan → SignatureID 1003 in SIEM
+            //
+            // Separarea completa de Fast/Slow Scan inseamna ca un IP poate declansa
+            // simultan o alerta Fast Scan (din drop-uri) SI o alerta Accept Scan (din
+            // accept-uri) — si amandoua vor fi trimise la SIEM si email, independent.
+            let ip = accept_event.source_ip;
+            let accept_window = Duration::from_secs(cfg.accept_scan.time_window_secs);
+            if let Some(pairs) = self.unique_ports_in_window(&self.accept_hits, ip, accept_window, now) {
+                let (ports, protocols) = split_protocols(&pairs);
+                let score = weights.score(&ports);
+                let obs = Observation {
+                    key: Some(ip),
+                    triggered: score >= cfg.accept_scan.port_threshold as f64,
+                    ports: &ports,
+                    ..Observation::default()
+                };
+                let policy = CooldownPolicy::resolve(cfg, "accept_scan");
+                alerts.extend(self.gate(&self.accept_cooldowns, &policy, obs, now, || Alert {
+                    score: weights.enabled.then_some(score),
+                    sensitive_ports: weights.sensitive(&ports),
+                    unique_ports: ports.clone(),
+                    protocols,
+                    fingerprint: self.fingerprint_in_window(&self.accept_hits, ip, accept_window, now),
+                    threshold: Some(cfg.accept_scan.port_threshold),
+                    profile: profile_name.clone(),
+                    ..Alert::new(ScanType::AcceptScan, ip, accept_event.dest_ip)
+                }));
             }
         }
 
@@ -632,12 +1482,14 @@ impl Detector {
         // de destinatii unice in fereastra de timp depaseste pragul, generam alerta.
         let lm_cfg = &cfg.lateral_movement;
         if lm_cfg.enabled {
+            let event = view("lateral_movement");
+            let ip = event.source_ip;
             if let Some(dest_ip) = event.dest_ip {
-                if event.action == "accept" {
+                if event.action == "accept" && !is_icmp {
                     // Inregistram destinatia in lateral_hits pentru IP-ul sursa.
                     {
                         let mut hits = self.lateral_hits.entry(ip).or_default();
-                        hits.push(DestHit { dest_ip, seen_at: now });
+                        hits.push(DestHit { dest_ip, accepted: true, seen_at: now });
                         // Cap memorie: refolosim max_hits_per_ip ca limita.
                         let max_hits = cfg.max_hits_per_ip;
                         if hits.len() > max_hits {
@@ -648,46 +1500,100 @@ impl Detector {
 
                     // Colectam destinatiile unice in fereastra de timp.
                     let lm_window = Duration::from_secs(lm_cfg.time_window_secs);
-                    if let Some(unique_dests) = self.unique_dests_in_window(ip, lm_window, now) {
-                        if unique_dests.len() >= lm_cfg.unique_dest_threshold
-                            && !self.in_cooldown(&self.lateral_cooldowns, ip)
-                        {
-                            self.lateral_cooldowns.insert(ip, now);
-                            alerts.push(Alert {
-                                scan_type: ScanType::LateralMovement,
-                                source_ip: ip,
-                                dest_ip: Some(dest_ip),
-                                unique_ports: Vec::new(),
-                                unique_dests,
-                                unique_sources: Vec::new(),
-                                timestamp: Local::now(),
-                            });
-                        }
+                    if let Some(unique_dests) = self.unique_dests_in_window(&self.lateral_hits, ip, lm_window, now) {
+                        let obs = Observation {
+                            key: Some(ip),
+                            triggered: unique_dests.len() >= lm_cfg.unique_dest_threshold,
+                            dests: &unique_dests,
+                            ..Observation::default()
+                        };
+                        let policy = CooldownPolicy::resolve(cfg, "lateral_movement");
+                        alerts.extend(self.gate(&self.lateral_cooldowns, &policy, obs, now, || Alert {
+                            unique_dests: unique_dests.clone(),
+                            threshold: Some(lm_cfg.unique_dest_threshold),
+                            profile: profile_name.clone(),
+                            ..Alert::new(ScanType::LateralMovement, ip, Some(dest_ip))
+                        }));
                     }
                 }
             }
         }
 
-        // --- 7. Verificam Distributed Scan (#23) ---
-        //
-        // Perspectiva inversata: indexam dupa dest_ip, numaram surse unice.
-        // Conditii:
-        //   a) Distributed Scan este activat in config
-        //   b) dest_ip este prezent in eveniment
+        // --- 6b. Verificam Ping Sweep ---
         //
-        // Inregistram hit-ul indiferent de actiune (drop sau accept) —
-        // un atac coordonat poate genera ambele tipuri de trafic.
+        // Analog cu Lateral Movement, pe evenimentele ICMP de recunoastere:
+        // destinatii unice per sursa in fereastra. Drop si accept conteaza la
+        // fel — un echo blocat tot dezvaluie intentia de descoperire.
+        let ps_cfg = &cfg.ping_sweep;
+        if ps_cfg.enabled && is_icmp && is_icmp_probe(event) {
+            let event = view("ping_sweep");
+            let ip = event.source_ip;
+            if let Some(dest_ip) = event.dest_ip {
+                {
+                    let mut hits = self.icmp_hits.entry(ip).or_default();
+                    hits.push(DestHit {
+                        dest_ip,
+                        accepted: event.action == "accept",
+                        seen_at: now,
+                    });
+                    let max_hits = cfg.max_hits_per_ip;
+                    if hits.len() > max_hits {
+                        let overflow = hits.len() - max_hits;
+                        hits.drain(..overflow);
+                    }
+                }
+
+                let ps_window = Duration::from_secs(ps_cfg.time_window_secs);
+                if let Some(unique_dests) = self.unique_dests_in_window(&self.icmp_hits, ip, ps_window, now) {
+                    let obs = Observation {
+                        key: Some(ip),
+                        triggered: unique_dests.len() >= ps_cfg.unique_dest_threshold,
+                        dests: &unique_dests,
+                        ..Observation::default()
+                    };
+                    let policy = CooldownPolicy::resolve(cfg, "ping_sweep");
+                    alerts.extend(self.gate(&self.ping_sweep_cooldowns, &policy, obs, now, || Alert {
+                        protocols: vec![(Protocol::Icmp, unique_dests.len())],
+                        unique_dests: unique_dests.clone(),
+                        threshold: Some(ps_cfg.unique_dest_threshold),
+                        profile: profile_name.clone(),
+                        ..Alert::new(ScanType::PingSweep, ip, Some(dest_ip))
+                    }));
+                }
+            }
+        }
+
+        // --- 7. Verificam Distributed Scan (#23) ---
+        //
+        // Perspectiva inversata: indexam dupa dest_ip, numaram surse unice.
+        // Conditii:
+        //   a) Distributed Scan este activat in config
+        //   b) dest_ip este prezent in eveniment
+        //
+        // Inregistram hit-ul indiferent de actiune (drop sau accept) —
+        // un atac coordonat poate genera ambele tipuri de trafic.
         //
         // Cooldown-ul este per dest_ip (tinta), nu per source_ip:
         // daca 10 surse scanează tinta X, o singura alerta este generata
         // pentru X, nu 10 alerte separate.
-        let ds_cfg = &cfg.distributed_scan;
-        if ds_cfg.enabled {
-            if let Some(dest_ip) = event.dest_ip {
+        //
+        // Profilul se rezolva dupa TINTA, nu dupa sursa: pragurile VLAN-ului de
+        // servere se aplica scanarilor asupra serverelor, oricare ar fi sursele.
+        let ds_event = view("distributed_scan");
+        if let Some(dest_ip) = ds_event.dest_ip.filter(|_| !is_icmp) {
+            let target_profile = ResolvedProfile::lookup(&profiles, &dest_ip);
+            let target_cfg: &DetectionConfig = target_profile.map(|p| &p.config).unwrap_or(&global);
+            let ds_cfg = &target_cfg.distributed_scan;
+            if ds_cfg.enabled {
                 // Inregistram hit-ul in distributed_hits pentru IP-ul destinatie.
                 {
                     let mut hits = self.distributed_hits.entry(dest_ip).or_default();
-                    hits.push(DistributedHit { source_ip: ip, port: event.dest_port, seen_at: now });
+                    hits.push(DistributedHit {
+                        source_ip: ds_event.source_ip,
+                        port: ds_event.dest_port,
+                        accepted: ds_event.action == "accept",
+                        seen_at: now,
+                    });
                     // Cap memorie: refolosim max_hits_per_ip ca limita.
                     let max_hits = cfg.max_hits_per_ip;
                     if hits.len() > max_hits {
@@ -699,28 +1605,341 @@ impl Detector {
                 // Colectam sursele unice si porturile in fereastra de timp.
                 let ds_window = Duration::from_secs(ds_cfg.time_window_secs);
                 if let Some((unique_srcs, targeted_ports)) = self.unique_sources_in_window(dest_ip, ds_window, now) {
-                    if unique_srcs.len() >= ds_cfg.unique_sources_threshold
-                        && !self.in_cooldown(&self.distributed_cooldowns, dest_ip)
+                    let obs = Observation {
+                        key: Some(dest_ip),
+                        triggered: unique_srcs.len() >= ds_cfg.unique_sources_threshold,
+                        ports: &targeted_ports,
+                        sources: &unique_srcs,
+                        ..Observation::default()
+                    };
+                    // Cooldown-ul tintei vine din profilul ei, ca pragurile.
+                    let policy = CooldownPolicy::resolve(target_cfg, "distributed_scan");
+                    alerts.extend(self.gate(&self.distributed_cooldowns, &policy, obs, now, || Alert {
+                        unique_ports: targeted_ports.clone(),
+                        unique_sources: unique_srcs.clone(),
+                        threshold: Some(ds_cfg.unique_sources_threshold),
+                        profile: target_profile.map(|p| p.name.clone()),
+                        ..Alert::new(ScanType::DistributedScan, ds_event.source_ip, Some(dest_ip))
+                    }));
+                }
+            }
+        }
+
+        // --- 7b. Brute Force / flood per tuplu ---
+        //
+        // Un port lovit repetat nu creste numarul de porturi unice — Fast/Slow
+        // Scan nu il vad. Numaram evenimentele tuplului (sursa, destinatie, port)
+        // in fereastra, separat pentru drop si accept, cu praguri si cooldown-uri
+        // separate: un flood blocat urmat de accept-uri repetate pe acelasi
+        // serviciu (atacul a trecut de firewall) produce a doua alerta.
+        //
+        // Limite de memorie ca la port_hits: max_hits_per_ip evenimente per tuplu,
+        // max_tracked_ips tupluri (tuplurile noi peste limita sunt ignorate
+        // pana la urmatorul cleanup).
+        let bf_cfg = &cfg.brute_force;
+        if bf_cfg.enabled && !is_icmp {
+            let event = view("brute_force");
+            let ip = event.source_ip;
+            if let Some(dest_ip) = event.dest_ip {
+                let key: FloodKey = (ip, dest_ip, event.dest_port);
+                let accepted = event.action != "drop";
+                if self.flood_hits.contains_key(&key) || self.flood_hits.len() < cfg.max_tracked_ips {
+                    let bf_window = Duration::from_secs(bf_cfg.time_window_secs);
+                    let count = {
+                        let mut hits = self.flood_hits.entry(key).or_default();
+                        hits.push(FloodHit { accepted, seen_at: now });
+                        let max_hits = cfg.max_hits_per_ip;
+                        if hits.len() > max_hits {
+                            let overflow = hits.len() - max_hits;
+                            hits.drain(..overflow);
+                        }
+                        hits.iter()
+                            .filter(|h| h.accepted == accepted)
+                            .filter(|h| now.saturating_duration_since(h.seen_at) <= bf_window)
+                            .count()
+                    };
+
+                    let threshold = if accepted { bf_cfg.accept_threshold } else { bf_cfg.drop_threshold };
+                    let bf_cooldown = bf_cfg.cooldown_secs.map(Duration::from_secs).unwrap_or(cooldown);
+                    let cd_key = (key, accepted);
+                    if count >= threshold && !self.in_cooldown(&self.flood_cooldowns, &cd_key, bf_cooldown) {
+                        self.flood_cooldowns.insert(cd_key, now);
+                        alerts.push(Alert {
+                            unique_ports: vec![event.dest_port],
+                            flood: Some(FloodDetail {
+                                port: event.dest_port,
+                                count,
+                                accepted,
+                                window_secs: bf_cfg.time_window_secs,
+                            }),
+                            threshold: Some(threshold),
+                            profile: profile_name.clone(),
+                            ..Alert::new(ScanType::BruteForce, ip, Some(dest_ip))
+                        });
+                    }
+                }
+            }
+        }
+
+        // --- 7c. Beaconing C2 ---
+        //
+        // Doar conexiunile acceptate cu destinatie cunoscuta. Tuplul include
+        // portul: acelasi host contactat pe 443 si pe 8080 = doua masuratori.
+        // Raportarea este o data per tuplu (vezi beacon.rs), deci nu folosim
+        // cooldown-ul obisnuit — un beacon continuu ar realerta la nesfarsit.
+        if cfg.beaconing.enabled && event.action == "accept" && !is_icmp {
+            let event = view("beaconing");
+            let ip = event.source_ip;
+            if let Some(dest_ip) = event.dest_ip {
+                let allowed = self.beacon_allowlist.load().iter().any(|e| e.matches(&dest_ip));
+                if !allowed {
+                    if let Some(detail) =
+                        self.beacons.record(ip, dest_ip, event.dest_port, now, &cfg.beaconing)
                     {
-                        self.distributed_cooldowns.insert(dest_ip, now);
                         alerts.push(Alert {
-                            scan_type: ScanType::DistributedScan,
-                            source_ip: ip,
-                            dest_ip: Some(dest_ip),
-                            unique_ports: targeted_ports,
-                            unique_dests: Vec::new(),
-                            unique_sources: unique_srcs,
-                            timestamp: Local::now(),
+                            unique_ports: vec![detail.port],
+                            beacon: Some(detail),
+                            profile: profile_name.clone(),
+                            ..Alert::new(ScanType::Beaconing, ip, Some(dest_ip))
                         });
                     }
                 }
             }
         }
 
+        // --- 8. Verificam abaterea de la baseline (mod anomalie) ---
+        //
+        // Evenimentul alimenteaza intervalul curent al cheii (sursa sau subnet);
+        // `observe` returneaza Some doar la prima abatere din interval.
+        if cfg.anomaly.enabled && !is_icmp {
+            let event = view("anomaly");
+            let ip = event.source_ip;
+            let key = self.baseline_key(&ip, cfg);
+            if let Some(hit) = self.baselines.observe(&key, event, now, &cfg.anomaly) {
+                if !self.in_cooldown(&self.anomaly_cooldowns, &key, cooldown) {
+                    self.anomaly_cooldowns.insert(key, now);
+                    alerts.push(Alert {
+                        unique_ports: hit.ports,
+                        unique_dests: hit.dests,
+                        anomaly: Some(hit.detail),
+                        profile: profile_name.clone(),
+                        ..Alert::new(ScanType::Anomaly, ip, event.dest_ip)
+                    });
+                }
+            }
+        }
+
+        // --- 9. Corelare lanturi de atac ---
+        //
+        // Motorul vede evenimentul si alertele lui; un lant complet produce o
+        // alerta AttackChain pe subiectul lantului — dupa un pivot "touched"
+        // acesta este host-ul compromis, nu sursa evenimentului curent.
+        if cfg.correlation.enabled {
+            for chain in self.correlation.observe(event, &alerts, now, &cfg.correlation) {
+                let subject = chain.subject;
+                alerts.push(Alert {
+                    chain: Some(chain),
+                    profile: ResolvedProfile::lookup(&profiles, &subject).map(|p| p.name.clone()),
+                    ..Alert::new(ScanType::AttackChain, subject, event.dest_ip)
+                });
+            }
+        }
+
+        // --- 10. Scor de risc si escaladare ---
+        //
+        // Fiecare alerta adauga puncte la scorul sursei ei (de obicei `ip`;
+        // AttackChain poate avea alt subiect). La depasirea unui nivel nou se
+        // adauga o alerta Escalation cu lantul complet. Daca un singur eveniment
+        // urca aceeasi sursa doua niveluri, se raporteaza doar cel mai inalt.
+        if cfg.risk.enabled {
+            let mut escalations: Vec<(IpAddr, RiskDetail)> = Vec::new();
+            // Update-urile ongoing continua o alerta deja punctata.
+            for alert in alerts.iter().filter(|a| a.ongoing.is_none()) {
+                if let Some(detail) = self.risk.record(alert, now, &cfg.risk) {
+                    escalations.retain(|(src, _)| *src != alert.source_ip);
+                    escalations.push((alert.source_ip, detail));
+                }
+            }
+            for (src, detail) in escalations {
+                alerts.push(Alert {
+                    risk: Some(detail),
+                    profile: ResolvedProfile::lookup(&profiles, &src).map(|p| p.name.clone()),
+                    ..Alert::new(ScanType::Escalation, src, event.dest_ip)
+                });
+            }
+        }
+
+        // --- 11. Context: regulile de firewall lovite de sursa alertei ---
+        //
+        // Alertele pe cheia agregata (nu cele threat intel, care raporteaza
+        // adresa exacta) primesc si prefixul IPv6. Cu NAT, toate alertele
+        // evenimentului poarta ambele seturi de adrese.
+        let nat = NatDetail::from_event(src, event);
+        for alert in &mut alerts {
+            alert.nat = nat.clone();
+            alert.rules = self.rules.rules_for(&alert.source_ip);
+            self.rules.count_alert(&alert.rules);
+            let exact = matches!(alert.scan_type, ScanType::KnownBadSource | ScanType::KnownBadDestination);
+            if aggregated && alert.source_ip == ip && !exact {
+                alert.source_prefix = Some(source_prefix);
+            }
+        }
+
+        // --- 12. Recidiva ---
+        //
+        // Contextul se calculeaza pentru toate alertele evenimentului inainte de
+        // a le inregistra: un Fast Scan si un Accept Scan simultane nu se numara
+        // unul pe altul. O sursa care atinge pragul primeste in plus o alerta
+        // PersistentScanner (cu cooldown propriu, vezi offenders.rs).
+        let repeat = &global.repeat_offenders;
+        if repeat.enabled {
+            // Update-urile ongoing continua o alerta deja numarata — fara context.
+            for alert in alerts.iter_mut().filter(|a| a.ongoing.is_none()) {
+                alert.offence =
+                    Some(self.offenders.context(&alert.source_ip, &alert.scan_type, now, repeat));
+            }
+            let mut offenders: Vec<IpAddr> = Vec::new();
+            for alert in alerts.iter().filter(|a| a.ongoing.is_none() && counts_as_offence(&a.scan_type)) {
+                self.offenders.record(alert.source_ip, &alert.scan_type, now);
+                if !offenders.contains(&alert.source_ip) {
+                    offenders.push(alert.source_ip);
+                }
+            }
+            for src in offenders {
+                if let Some(detail) = self.offenders.persistent(&src, now, repeat) {
+                    let source = alerts.iter().find(|a| a.source_ip == src).cloned();
+                    alerts.push(Alert {
+                        threshold: Some(repeat.persistent_threshold),
+                        offence: Some(detail),
+                        profile: ResolvedProfile::lookup(&profiles, &src).map(|p| p.name.clone()),
+                        source_prefix: source.as_ref().and_then(|a| a.source_prefix),
+                        nat: nat.clone(),
+                        rules: source.map(|a| a.rules).unwrap_or_default(),
+                        ..Alert::new(ScanType::PersistentScanner, src, event.dest_ip)
+                    });
+                }
+            }
+        }
+
+        // --- 13. Severitatea dinamica ---
+        let assets = self.assets.load();
+        let subnets = self.subnets.load();
+        for alert in &mut alerts {
+            alert.severity = severity::assess(alert, &global.severity, &assets, &subnets);
+        }
+
+        // --- 14. Sesiuni de scanare ---
+        //
+        // Prima alerta de scanare a cheii deschide sesiunea din hit-urile din
+        // memorie (inceputul real al scanarii); urmatoarele doar se adauga.
+        // Update-urile ongoing continua o alerta deja numarata.
+        if sessions_cfg.enabled {
+            for alert in alerts.iter().filter(|a| a.ongoing.is_none()) {
+                let Some((kind, key)) = sessions::opens_session(alert) else {
+                    continue;
+                };
+                if !self.sessions.is_open(kind, key) {
+                    self.sessions.open(kind, key, self.session_seed(kind, key), now, sessions_cfg);
+                }
+                self.sessions.note_alert(kind, key, alert);
+            }
+        }
+
         alerts
     }
 
-    /// Returneaza lista porturilor unice accesate de un IP in fereastra de timp.
+    /// Hit-urile din memorie ale cheii, ca sondari pentru o sesiune noua.
+    ///
+    /// Sursa: drop-uri (port_hits), accept-uri (accept_hits) si ICMP
+    /// (icmp_hits). lateral_hits nu se adauga — sunt aceleasi accept-uri.
+    /// Tinta: distributed_hits, cu sursa ca peer.
+    fn session_seed(&self, kind: SessionKind, key: IpAddr) -> Vec<Probe> {
+        let mut seed = Vec::new();
+        match kind {
+            SessionKind::Source => {
+                for (map, accept) in [(&self.port_hits, false), (&self.accept_hits, true)] {
+                    if let Some(hits) = map.get(&key) {
+                        seed.extend(hits.iter().map(|h| Probe {
+                            at: h.seen_at,
+                            port: Some(h.port),
+                            peer: h.dest_ip,
+                            accept,
+                        }));
+                    }
+                }
+                if let Some(hits) = self.icmp_hits.get(&key) {
+                    seed.extend(hits.iter().map(|h| Probe {
+                        at: h.seen_at,
+                        port: None,
+                        peer: Some(h.dest_ip),
+                        accept: h.accepted,
+                    }));
+                }
+            }
+            SessionKind::Target => {
+                if let Some(hits) = self.distributed_hits.get(&key) {
+                    seed.extend(hits.iter().map(|h| Probe {
+                        at: h.seen_at,
+                        port: Some(h.port),
+                        peer: Some(h.source_ip),
+                        accept: h.accepted,
+                    }));
+                }
+            }
+        }
+        seed
+    }
+
+    /// Inregistreaza activitatea sursei `ip` in `last_seen`; la atingerea
+    /// limitei `max_tracked_ips`, evacueaza intai sursa LRU din toate structurile.
+    fn track_source(&self, ip: IpAddr, now: Instant, max_tracked_ips: usize) {
+        let is_new_ip = !self.last_seen.contains_key(&ip);
+        if is_new_ip && self.last_seen.len() >= max_tracked_ips {
+            // Gasim IP-ul cu cel mai vechi last_seen (Least Recently Used).
+            let lru_ip: Option<IpAddr> = self
+                .last_seen
+                .iter()
+                .min_by_key(|e| *e.value())
+                .map(|e| *e.key());
+
+            if let Some(old_ip) = lru_ip {
+                self.forget_source(old_ip);
+            }
+        }
+
+        // Actualizam last_seen pentru IP-ul curent (nou sau existent).
+        self.last_seen.insert(ip, now);
+    }
+
+    /// Elimina sursa `ip` din TOATE structurile indexate dupa sursa —
+    /// evacuarea LRU si `ids-rs ctl clear-state`.
+    fn forget_source(&self, ip: IpAddr) {
+        self.port_hits.remove(&ip);
+        self.accept_hits.remove(&ip);
+        self.lateral_hits.remove(&ip);
+        self.icmp_hits.remove(&ip);
+        self.last_seen.remove(&ip);
+        self.fast_cooldowns.remove(&ip);
+        self.slow_cooldowns.remove(&ip);
+        self.accept_cooldowns.remove(&ip);
+        self.lateral_cooldowns.remove(&ip);
+        self.ping_sweep_cooldowns.remove(&ip);
+        self.known_bad_src_cooldowns.remove(&ip);
+        self.risk.remove(&ip);
+        self.rules.remove(&ip);
+        self.correlation.remove(&ip);
+        self.beacons.remove_source(&ip);
+        // Baseline-ul per sursa (cheia subnet este comuna, ramane).
+        let baseline_key = ip.to_string();
+        self.baselines.remove(&baseline_key);
+        self.anomaly_cooldowns.remove(&baseline_key);
+        self.offenders.remove(&ip);
+        self.sessions.remove_source(&ip);
+        self.flood_hits.retain(|(src, _, _), _| *src != ip);
+        self.flood_cooldowns.retain(|((src, _, _), _), _| *src != ip);
+    }
+
+    /// Returneaza perechile (protocol, port) unice accesate de un IP in fereastra de timp.
     ///
     /// NOTA RUST - REFACTORIZARE (#10): Aceasta functie primeste `hits_map` ca parametru.
     ///
@@ -748,21 +1967,22 @@ impl Detector {
         ip: IpAddr,
         window: Duration,
         now: Instant,
-    ) -> Option<Vec<u16>> {
+    ) -> Option<Vec<(Protocol, u16)>> {
         let entry = hits_map.get(&ip)?;
         let hits = entry.value();
 
-        let mut unique_ports: Vec<u16> = hits
+        let mut unique_ports: Vec<(Protocol, u16)> = hits
             .iter()
             // `now.duration_since(h.seen_at)` poate panica daca h.seen_at > now
             // (imposibil cu Instant monotonic, dar saturating_duration_since e mai safe).
             .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
-            .map(|h| h.port)
+            .map(|h| (h.proto, h.port))
             .collect();
 
         // Deduplicam: sort + dedup elimina duplicatele consecutive.
-        // Rezultat: lista de porturi unice, sortata.
-        unique_ports.sort_unstable();
+        // Rezultat: perechi (protocol, port) unice, sortate dupa port —
+        // 80/tcp si 80/udp raman doua intrari distincte.
+        unique_ports.sort_unstable_by_key(|&(proto, port)| (port, proto));
         unique_ports.dedup();
 
         if unique_ports.is_empty() {
@@ -772,20 +1992,40 @@ impl Detector {
         }
     }
 
+    /// Amprenta uneltei de scanare din hit-urile lui `ip` in fereastra `window`
+    /// (port sursa + flag-uri TCP, in ordinea sosirii). Apelat doar la alerta.
+    fn fingerprint_in_window(
+        &self,
+        hits_map: &DashMap<IpAddr, Vec<PortHit>>,
+        ip: IpAddr,
+        window: Duration,
+        now: Instant,
+    ) -> Option<Fingerprint> {
+        let hits = hits_map.get(&ip)?;
+        let samples: Vec<Sample> = hits
+            .iter()
+            .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
+            .map(|h| Sample { source_port: h.source_port, flags: h.flags })
+            .collect();
+        fingerprint::classify(&samples)
+    }
+
     /// Returneaza destinatiile unice contactate de `ip` in fereastra `window`.
     ///
     /// Analog cu `unique_ports_in_window`, dar opereaza pe `lateral_hits`
-    /// si colecteaza IP-uri destinatie unice in loc de porturi unice.
+    /// (Lateral Movement) sau `icmp_hits` (Ping Sweep) si colecteaza IP-uri
+    /// destinatie unice in loc de porturi unice.
     ///
     /// Returneaza `None` daca nu exista date pentru IP sau lista e goala.
     /// Returneaza `Some(Vec<IpAddr>)` cu destinatiile unice din fereastra.
     fn unique_dests_in_window(
         &self,
+        hits_map: &DashMap<IpAddr, Vec<DestHit>>,
         ip: IpAddr,
         window: Duration,
         now: Instant,
     ) -> Option<Vec<IpAddr>> {
-        let hits = self.lateral_hits.get(&ip)?;
+        let hits = hits_map.get(&ip)?;
         let mut seen: std::collections::HashSet<IpAddr> = std::collections::HashSet::new();
         for hit in hits.iter() {
             if now.duration_since(hit.seen_at) <= window {
@@ -831,15 +2071,65 @@ impl Detector {
         }
     }
 
-    /// Verifica daca un IP este in perioada de cooldown pentru un tip de alerta.
+    /// Aplica politica de cooldown a detectorului (vezi cooldown.rs) si intoarce
+    /// alerta de emis — prima alerta sau un update "ongoing" — ori None daca
+    /// re-detectia este suprimata. `build` se apeleaza doar cand alerta se emite.
+    ///
+    /// NOTA RUST: rezultatul este `Option<Alert>`, iar `Vec::extend` accepta
+    /// orice `IntoIterator` — un Option este un iterator cu 0 sau 1 elemente,
+    /// deci `alerts.extend(self.gate(...))` inlocuieste un `if let Some`.
+    fn gate(
+        &self,
+        cooldowns: &DashMap<IpAddr, Instant>,
+        policy: &CooldownPolicy,
+        obs: Observation<'_>,
+        now: Instant,
+        build: impl FnOnce() -> Alert,
+    ) -> Option<Alert> {
+        let key = obs.key?;
+        match policy.mode {
+            CooldownMode::Fixed => {
+                if !obs.triggered || self.in_cooldown(cooldowns, &key, policy.secs) {
+                    return None;
+                }
+                cooldowns.insert(key, now);
+                Some(build())
+            }
+            // Re-detectia suprimata reporneste cooldown-ul: realertarea cere o
+            // pauza de cel putin un cooldown intreg.
+            CooldownMode::Sliding => {
+                if !obs.triggered {
+                    return None;
+                }
+                let suppressed = self.in_cooldown(cooldowns, &key, policy.secs);
+                cooldowns.insert(key, now);
+                (!suppressed).then(build)
+            }
+            CooldownMode::Ongoing => self.ongoing.observe(policy, obs, now, build),
+        }
+    }
+
+    /// Verifica daca o cheie (IP sau pereche de IP-uri) este in perioada de
+    /// cooldown pentru un tip de alerta.
     ///
     /// NOTA RUST - REFERINTE la DashMap:
     /// `cooldowns: &DashMap<...>` - imprumut imutabil al DashMap-ului.
     /// DashMap permite `.get()` prin &self (interior mutability cu read-lock).
-    fn in_cooldown(&self, cooldowns: &DashMap<IpAddr, Instant>, ip: IpAddr) -> bool {
-        if let Some(last_alert) = cooldowns.get(&ip) {
+    ///
+    /// NOTA RUST - GENERICS: `K: Eq + Hash` accepta orice tip de cheie
+    /// (IpAddr, (IpAddr, IpAddr)) — compilatorul genereaza cate o versiune
+    /// specializata per tip concret (monomorphization), fara cost la runtime.
+    ///
+    /// `cooldown` vine din config-ul efectiv (global sau al profilului sursei).
+    fn in_cooldown<K: Eq + std::hash::Hash>(
+        &self,
+        cooldowns: &DashMap<K, Instant>,
+        key: &K,
+        cooldown: Duration,
+    ) -> bool {
+        if let Some(last_alert) = cooldowns.get(key) {
             // `elapsed()` = cat timp a trecut de la momentul stocat.
-            last_alert.elapsed() < Duration::from_secs(self.config.load().alert_cooldown_secs)
+            last_alert.elapsed() < cooldown
         } else {
             false
         }
@@ -907,6 +2197,20 @@ impl Detector {
             self.lateral_hits.remove(ip);
         }
 
+        // --- Curatam icmp_hits (Ping Sweep) ---
+        let mut icmp_empty: Vec<IpAddr> = Vec::new();
+        for mut entry in self.icmp_hits.iter_mut() {
+            entry.value_mut().retain(|hit| {
+                now.saturating_duration_since(hit.seen_at) <= max_age
+            });
+            if entry.value().is_empty() {
+                icmp_empty.push(*entry.key());
+            }
+        }
+        for ip in &icmp_empty {
+            self.icmp_hits.remove(ip);
+        }
+
         // --- Curatam distributed_hits (Distributed Scan #23) ---
         // Indexat dupa dest_ip, nu source_ip — cleanup separat de celelalte.
         let mut dist_empty: Vec<IpAddr> = Vec::new();
@@ -922,6 +2226,20 @@ impl Detector {
             self.distributed_hits.remove(ip);
         }
 
+        // --- Curatam flood_hits (Brute Force) ---
+        let mut flood_empty: Vec<FloodKey> = Vec::new();
+        for mut entry in self.flood_hits.iter_mut() {
+            entry.value_mut().retain(|hit| {
+                now.saturating_duration_since(hit.seen_at) <= max_age
+            });
+            if entry.value().is_empty() {
+                flood_empty.push(*entry.key());
+            }
+        }
+        for key in &flood_empty {
+            self.flood_hits.remove(key);
+        }
+
         // --- Sincronizam last_seen ---
         //
         // Eliminam din last_seen IP-urile care nu mai au date in NICIUN map.
@@ -938,10 +2256,16 @@ impl Detector {
             self.port_hits.contains_key(ip)
                 || self.accept_hits.contains_key(ip)
                 || self.lateral_hits.contains_key(ip)
+                || self.icmp_hits.contains_key(ip)
         });
 
-        // --- Curatam cooldown-urile expirate (toate patru tipuri) ---
-        let cooldown_dur = Duration::from_secs(self.config.load().alert_cooldown_secs);
+        // Regulile per sursa traiesc cat sursa este urmarita.
+        self.rules.retain_sources(|ip| self.last_seen.contains_key(ip));
+
+        // --- Curatam cooldown-urile expirate (toate tipurile) ---
+        // Cel mai lung cooldown (global sau din profile) — un profil cu cooldown
+        // mai mare nu isi pierde cooldown-urile active la cleanup.
+        let cooldown_dur = Duration::from_secs(self.config.load().max_alert_cooldown_secs());
         self.fast_cooldowns
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
         self.slow_cooldowns
@@ -950,8 +2274,74 @@ impl Detector {
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
         self.lateral_cooldowns
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.ping_sweep_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
         self.distributed_cooldowns
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.flood_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.known_bad_src_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.known_bad_dst_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.anomaly_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+
+        // --- Whitelist temporar expirat ---
+        self.temp_whitelist
+            .retain(|_, (_, until)| until.is_none_or(|until| now < until));
+
+        // --- Baseline: inchidem intervalele expirate, stergem baseline-urile vechi ---
+        let cfg = self.config.load();
+        if cfg.anomaly.enabled {
+            self.baselines.flush(now, &cfg.anomaly);
+        }
+
+        // --- Corelare: activitate si lanturi partiale expirate ---
+        if cfg.correlation.enabled {
+            self.correlation.cleanup(now, &cfg.correlation);
+        }
+
+        // --- Beaconing: tupluri tacute ---
+        if cfg.beaconing.enabled {
+            self.beacons.cleanup(now, &cfg.beaconing);
+        }
+
+        // --- Scoruri de risc stinse ---
+        if cfg.risk.enabled {
+            self.risk.cleanup(now, &cfg.risk);
+        }
+
+        // --- Istoricul de recidiva: alerte iesite din orizont ---
+        if cfg.repeat_offenders.enabled {
+            self.offenders.cleanup(now, &cfg.repeat_offenders);
+        }
+    }
+
+    /// Rezumatele "scan ended" ale sesiunilor ongoing fara activitate de
+    /// `cooldown.quiet_secs` secunde. Apelata din task-ul de cleanup, deci
+    /// rezumatul vine cu cel mult un interval de cleanup dupa `quiet_secs`.
+    pub fn finished_scans(&self) -> Vec<Alert> {
+        let global = self.config.load();
+        let quiet = Duration::from_secs(global.cooldown.quiet_secs);
+        let mut alerts = self.ongoing.finish(Instant::now(), quiet);
+        let assets = self.assets.load();
+        let subnets = self.subnets.load();
+        for alert in &mut alerts {
+            alert.rules = self.rules.rules_for(&alert.source_ip);
+            alert.severity = severity::assess(alert, &global.severity, &assets, &subnets);
+        }
+        alerts
+    }
+
+    /// Inchide sesiunile de scanare inactive de `idle_timeout_secs` si intoarce
+    /// rezumatele lor. Apelat periodic din task-ul de cleanup.
+    ///
+    /// Fara verificarea `enabled`: sesiunile deschise inainte de un reload
+    /// care dezactiveaza functia se inchid normal, nu raman in memorie.
+    pub fn closed_sessions(&self) -> Vec<SessionSummary> {
+        let global = self.config.load();
+        self.sessions.close_idle(Instant::now(), &global.sessions)
     }
 
     /// Returneaza numarul total de IP-uri urmarite in memorie (drop + accept).
@@ -962,106 +2352,494 @@ impl Detector {
     pub fn tracked_ips(&self) -> usize {
         self.last_seen.len()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::{
-        AcceptScanConfig, DetectionConfig, DistributedScanConfig, FastScanConfig,
-        LateralMovementConfig, SlowScanConfig,
-    };
-
-    /// Creeaza o configuratie de test cu praguri mici pentru teste rapide.
-    fn test_config() -> DetectionConfig {
-        DetectionConfig {
-            alert_cooldown_secs: 5,
-            max_hits_per_ip: 1_000,
-            max_tracked_ips: 10_000,
-            whitelist: Vec::new(),
-            fast_scan: FastScanConfig {
-                port_threshold: 3,
-                time_window_secs: 10,
-            },
-            slow_scan: SlowScanConfig {
-                port_threshold: 50,
-                time_window_mins: 1,
-            },
-            // Accept Scan cu acelasi prag ca Fast Scan pentru teste simetrice.
-            accept_scan: AcceptScanConfig {
-                port_threshold: 3,
-                time_window_secs: 10,
-            },
-            // Lateral Movement dezactivat implicit in test_config.
-            // Testele specifice folosesc lateral_config().
-            lateral_movement: LateralMovementConfig {
-                enabled: false,
-                unique_dest_threshold: 3,
-                time_window_secs: 10,
-            },
-            // Distributed Scan dezactivat implicit in test_config.
-            // Testele specifice folosesc distributed_config().
-            distributed_scan: DistributedScanConfig {
-                enabled: false,
-                unique_sources_threshold: 3,
-                time_window_secs: 10,
-            },
-        }
+    /// Contoarele per regula de firewall (de la pornire), cele mai lovite
+    /// primele — pentru revizuirea politicii.
+    pub fn rule_stats(&self) -> Vec<(String, RuleCounter)> {
+        self.rules.stats()
     }
 
-    /// Creeaza o configuratie cu Lateral Movement activat (prag 3 destinatii in 10s).
-    fn lateral_config() -> DetectionConfig {
-        DetectionConfig {
-            alert_cooldown_secs: 5,
-            max_hits_per_ip: 1_000,
-            max_tracked_ips: 10_000,
-            whitelist: Vec::new(),
-            fast_scan: FastScanConfig {
-                port_threshold: 100,
-                time_window_secs: 10,
-            },
-            slow_scan: SlowScanConfig {
-                port_threshold: 200,
-                time_window_mins: 1,
-            },
-            accept_scan: AcceptScanConfig {
-                port_threshold: 100,
-                time_window_secs: 10,
-            },
-            lateral_movement: LateralMovementConfig {
-                enabled: true,
-                unique_dest_threshold: 3,
-                time_window_secs: 10,
-            },
-            distributed_scan: DistributedScanConfig {
-                enabled: false,
-                unique_sources_threshold: 3,
-                time_window_secs: 10,
-            },
+    /// Sursele cu cele mai multe porturi unice in hit-urile din memorie
+    /// (drop + accept) — panoul "Top surse" din `--tui`.
+    pub fn top_sources(&self, limit: usize) -> Vec<SourceActivity> {
+        let mut sources: HashMap<IpAddr, (HashSet<u16>, usize, usize)> = HashMap::new();
+        for (map, accept) in [(&self.port_hits, false), (&self.accept_hits, true)] {
+            for entry in map.iter() {
+                let (ports, drops, accepts) = sources.entry(*entry.key()).or_default();
+                ports.extend(entry.value().iter().map(|h| h.port));
+                if accept {
+                    *accepts += entry.value().len();
+                } else {
+                    *drops += entry.value().len();
+                }
+            }
         }
+        let mut top: Vec<SourceActivity> = sources
+            .into_iter()
+            .map(|(ip, (ports, drops, accepts))| SourceActivity {
+                ip,
+                unique_ports: ports.len(),
+                drops,
+                accepts,
+            })
+            .collect();
+        top.sort_by(|a, b| {
+            (b.unique_ports, b.drops + b.accepts, a.ip).cmp(&(a.unique_ports, a.drops + a.accepts, b.ip))
+        });
+        top.truncate(limit);
+        top
     }
 
-    fn make_event(ip: &str, port: u16) -> LogEvent {
-        LogEvent {
-            source_ip: ip.parse().unwrap(),
-            dest_ip: Some("10.0.0.1".parse().unwrap()),
-            dest_port: port,
-            protocol: "tcp".to_string(),
-            action: "drop".to_string(),
-            raw_log: String::new(),
+    /// Destinatiile cu cele mai multe evenimente in hit-urile din memorie,
+    /// cu numarul de surse unice — panoul "Top tinte" din `--tui`.
+    pub fn top_targets(&self, limit: usize) -> Vec<TargetActivity> {
+        let mut targets: HashMap<IpAddr, (HashSet<IpAddr>, usize)> = HashMap::new();
+        for map in [&self.port_hits, &self.accept_hits] {
+            for entry in map.iter() {
+                for dest in entry.value().iter().filter_map(|h| h.dest_ip) {
+                    let (sources, events) = targets.entry(dest).or_default();
+                    sources.insert(*entry.key());
+                    *events += 1;
+                }
+            }
         }
+        let mut top: Vec<TargetActivity> = targets
+            .into_iter()
+            .map(|(ip, (sources, events))| TargetActivity { ip, unique_sources: sources.len(), events })
+            .collect();
+        top.sort_by(|a, b| (b.events, b.unique_sources, a.ip).cmp(&(a.events, a.unique_sources, b.ip)));
+        top.truncate(limit);
+        top
+    }
+
+    /// Cooldown-urile active ale detectorilor cu moduri de cooldown, cele mai
+    /// lungi primele. Durata vine din config-ul global (profilele pot diferi).
+    /// In modul "ongoing" timpul ramas este pana la urmatorul update posibil.
+    pub fn active_cooldowns(&self) -> Vec<ActiveCooldown> {
+        let cfg = self.config.load();
+        let now = Instant::now();
+        let mut active = Vec::new();
+        for (detector, map) in [
+            ("fast_scan", &self.fast_cooldowns),
+            ("slow_scan", &self.slow_cooldowns),
+            ("accept_scan", &self.accept_cooldowns),
+            ("lateral_movement", &self.lateral_cooldowns),
+            ("ping_sweep", &self.ping_sweep_cooldowns),
+            ("distributed_scan", &self.distributed_cooldowns),
+        ] {
+            let policy = CooldownPolicy::resolve(&cfg, detector);
+            for entry in map.iter() {
+                let remaining = policy.secs.saturating_sub(now.saturating_duration_since(*entry.value()));
+                if !remaining.is_zero() {
+                    active.push(ActiveCooldown { detector, key: *entry.key(), remaining, mode: policy.mode });
+                }
+            }
+        }
+        for (detector, key, since_alert) in self.ongoing.active(now) {
+            let policy = CooldownPolicy::resolve(&cfg, detector);
+            active.push(ActiveCooldown {
+                detector,
+                key,
+                remaining: policy.secs.saturating_sub(since_alert),
+                mode: CooldownMode::Ongoing,
+            });
+        }
+        active.sort_by_key(|c| std::cmp::Reverse(c.remaining));
+        active
+    }
+
+    /// Tot ce tine detectorul despre `ip` — `ids-rs ctl state <ip>`.
+    /// Hit-urile sunt cele mai recente primele.
+    pub fn ip_state(&self, ip: IpAddr) -> IpState {
+        let now = Instant::now();
+        let hits = |map: &DashMap<IpAddr, Vec<PortHit>>| -> Vec<HitSnapshot> {
+            let mut hits: Vec<HitSnapshot> = map
+                .get(&ip)
+                .map(|entry| {
+                    entry
+                        .value()
+                        .iter()
+                        .map(|h| HitSnapshot {
+                            port: h.port,
+                            proto: h.proto,
+                            dest_ip: h.dest_ip,
+                            age: now.saturating_duration_since(h.seen_at),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            hits.sort_by_key(|h| h.age);
+            hits
+        };
+        let dests = |map: &DashMap<IpAddr, Vec<DestHit>>| -> Vec<IpAddr> {
+            let mut dests: Vec<IpAddr> = map
+                .get(&ip)
+                .map(|entry| entry.value().iter().map(|h| h.dest_ip).collect())
+                .unwrap_or_default();
+            dests.sort_unstable();
+            dests.dedup();
+            dests
+        };
+        let mut scanned_by: Vec<IpAddr> = self
+            .distributed_hits
+            .get(&ip)
+            .map(|entry| entry.value().iter().map(|h| h.source_ip).collect())
+            .unwrap_or_default();
+        scanned_by.sort_unstable();
+        scanned_by.dedup();
+
+        IpState {
+            ip,
+            whitelisted: self.is_whitelisted(&ip),
+            last_seen: self.last_seen.get(&ip).map(|t| now.saturating_duration_since(*t)),
+            drops: hits(&self.port_hits),
+            accepts: hits(&self.accept_hits),
+            lateral_dests: dests(&self.lateral_hits),
+            icmp_dests: dests(&self.icmp_hits),
+            scanned_by,
+            cooldowns: self.active_cooldowns().into_iter().filter(|c| c.key == ip).collect(),
+            sessions: self.sessions.open_for(ip).iter().map(|k| k.key()).collect(),
+        }
+    }
+
+    /// Sterge toate cooldown-urile in care apare `ip` (sursa sau tinta),
+    /// inclusiv sesiunile ongoing — urmatoarea detectie alerteaza imediat.
+    /// Intoarce numarul de cooldown-uri sterse.
+    pub fn clear_cooldowns(&self, ip: IpAddr) -> usize {
+        let mut cleared = 0;
+        for map in [
+            &self.fast_cooldowns,
+            &self.slow_cooldowns,
+            &self.accept_cooldowns,
+            &self.lateral_cooldowns,
+            &self.ping_sweep_cooldowns,
+            &self.distributed_cooldowns,
+            &self.known_bad_src_cooldowns,
+        ] {
+            cleared += usize::from(map.remove(&ip).is_some());
+        }
+        let before = self.known_bad_dst_cooldowns.len() + self.flood_cooldowns.len();
+        self.known_bad_dst_cooldowns
+            .retain(|(src, dst), _| *src != ip && *dst != ip);
+        self.flood_cooldowns
+            .retain(|((src, dst, _), _), _| *src != ip && *dst != ip);
+        cleared += before - self.known_bad_dst_cooldowns.len() - self.flood_cooldowns.len();
+        cleared + self.ongoing.remove(&ip)
+    }
+
+    /// Uita tot ce detectorul stie despre `ip` — hit-uri ca sursa si ca tinta,
+    /// cooldown-uri, scor de risc, lanturi, sesiuni deschise (fara rezumat) si
+    /// istoricul de recidiva — urmatoarea alerta a IP-ului este din nou prima.
+    /// Intoarce false daca IP-ul nu era urmarit deloc.
+    pub fn clear_state(&self, ip: IpAddr) -> bool {
+        let tracked = !self.ip_state(ip).is_empty();
+
+        self.forget_source(ip);
+        self.distributed_hits.remove(&ip);
+        for mut entry in self.distributed_hits.iter_mut() {
+            entry.value_mut().retain(|h| h.source_ip != ip);
+        }
+        self.distributed_hits.retain(|_, hits| !hits.is_empty());
+        self.clear_cooldowns(ip);
+        self.sessions.remove(ip);
+        tracked
+    }
+
+    /// Sursele urmarite cu porturile unice din fereastra fiecarui detector de
+    /// porturi (ferestrele profilului sursei, daca are unul) — `GET /sources`.
+    /// Sortate dupa cea mai recenta activitate.
+    pub fn tracked_sources(&self) -> Vec<TrackedSource> {
+        let now = Instant::now();
+        let seen: Vec<(IpAddr, Instant)> = self.last_seen.iter().map(|e| (*e.key(), *e.value())).collect();
+        let mut sources: Vec<TrackedSource> = seen
+            .into_iter()
+            .map(|(ip, seen_at)| self.tracked_source_at(ip, seen_at, now))
+            .collect();
+        sources.sort_by_key(|s| (s.last_seen, s.ip));
+        sources
+    }
+
+    /// Ca `tracked_sources`, pentru o singura sursa. None = sursa neurmarita.
+    pub fn tracked_source(&self, ip: IpAddr) -> Option<TrackedSource> {
+        let seen_at = *self.last_seen.get(&ip)?;
+        Some(self.tracked_source_at(ip, seen_at, Instant::now()))
+    }
+
+    fn tracked_source_at(&self, ip: IpAddr, seen_at: Instant, now: Instant) -> TrackedSource {
+        let global = self.config.load();
+        let profiles = self.profiles.load();
+        let profile = ResolvedProfile::lookup(&profiles, &ip);
+        let cfg: &DetectionConfig = profile.map(|p| &p.config).unwrap_or(&global);
+        let count = |map: &DashMap<IpAddr, Vec<PortHit>>| map.get(&ip).map(|hits| hits.len()).unwrap_or(0);
+        let windows = [
+            ("fast_scan", &self.port_hits, cfg.fast_scan.time_window_secs, cfg.fast_scan.port_threshold),
+            ("slow_scan", &self.port_hits, cfg.slow_scan.time_window_mins * 60, cfg.slow_scan.port_threshold),
+            ("accept_scan", &self.accept_hits, cfg.accept_scan.time_window_secs, cfg.accept_scan.port_threshold),
+        ]
+        .into_iter()
+        .map(|(detector, map, secs, threshold)| {
+            let window = Duration::from_secs(secs);
+            let unique_ports = self
+                .unique_ports_in_window(map, ip, window, now)
+                .map(|pairs| pairs.len())
+                .unwrap_or(0);
+            PortWindow { detector, window, unique_ports, threshold }
+        })
+        .collect();
+        TrackedSource {
+            ip,
+            profile: profile.map(|p| p.name.clone()),
+            last_seen: now.saturating_duration_since(seen_at),
+            drops: count(&self.port_hits),
+            accepts: count(&self.accept_hits),
+            windows,
+        }
+    }
+
+    /// Tintele din perspectiva Distributed Scan (`distributed_hits`), cele cu
+    /// cele mai multe surse unice primele — `GET /targets`.
+    pub fn distributed_targets(&self) -> Vec<DistributedTarget> {
+        let now = Instant::now();
+        let mut targets: Vec<DistributedTarget> = self
+            .distributed_hits
+            .iter()
+            .map(|entry| {
+                let hits = entry.value();
+                let mut sources: Vec<IpAddr> = hits.iter().map(|h| h.source_ip).collect();
+                sources.sort_unstable();
+                sources.dedup();
+                let mut ports: Vec<u16> = hits.iter().map(|h| h.port).collect();
+                ports.sort_unstable();
+                ports.dedup();
+                let newest = hits.iter().map(|h| h.seen_at).max().unwrap_or(now);
+                DistributedTarget {
+                    ip: *entry.key(),
+                    sources,
+                    ports,
+                    events: hits.len(),
+                    accepts: hits.iter().filter(|h| h.accepted).count(),
+                    last_seen: now.saturating_duration_since(newest),
+                    cooling_down: self.distributed_cooldowns.contains_key(entry.key()),
+                }
+            })
+            .collect();
+        targets.sort_by(|a, b| (b.sources.len(), b.events, a.ip).cmp(&(a.sources.len(), a.events, b.ip)));
+        targets
+    }
+}
+
+/// Activitatea unei surse in memoria detectorului (`Detector::top_sources`).
+#[derive(Debug, Clone)]
+pub struct SourceActivity {
+    pub ip: IpAddr,
+    pub unique_ports: usize,
+    pub drops: usize,
+    pub accepts: usize,
+}
+
+/// Activitatea asupra unei destinatii (`Detector::top_targets`).
+#[derive(Debug, Clone)]
+pub struct TargetActivity {
+    pub ip: IpAddr,
+    pub unique_sources: usize,
+    pub events: usize,
+}
+
+/// Starea unui IP in memoria detectorului (`Detector::ip_state`).
+#[derive(Debug, Clone)]
+pub struct IpState {
+    pub ip: IpAddr,
+    pub whitelisted: bool,
+    /// Timpul de la ultimul eveniment ca sursa. None = sursa neurmarita.
+    pub last_seen: Option<Duration>,
+    pub drops: Vec<HitSnapshot>,
+    pub accepts: Vec<HitSnapshot>,
+    /// Destinatiile unice (Lateral Movement, Ping Sweep).
+    pub lateral_dests: Vec<IpAddr>,
+    pub icmp_dests: Vec<IpAddr>,
+    /// Sursele care au lovit IP-ul ca tinta (Distributed Scan).
+    pub scanned_by: Vec<IpAddr>,
+    pub cooldowns: Vec<ActiveCooldown>,
+    /// Sesiunile de scanare deschise ("source" / "target").
+    pub sessions: Vec<&'static str>,
+}
+
+impl IpState {
+    /// Detectorul nu stie nimic despre IP (nici ca sursa, nici ca tinta).
+    pub fn is_empty(&self) -> bool {
+        self.last_seen.is_none()
+            && self.scanned_by.is_empty()
+            && self.cooldowns.is_empty()
+            && self.sessions.is_empty()
+    }
+}
+
+/// Un hit din memorie, cu varsta lui (`IpState`).
+#[derive(Debug, Clone)]
+pub struct HitSnapshot {
+    pub port: u16,
+    pub proto: Protocol,
+    pub dest_ip: Option<IpAddr>,
+    pub age: Duration,
+}
+
+/// O sursa urmarita (`Detector::tracked_sources`).
+#[derive(Debug, Clone)]
+pub struct TrackedSource {
+    pub ip: IpAddr,
+    /// Profilul de detectie al sursei, daca are unul.
+    pub profile: Option<String>,
+    pub last_seen: Duration,
+    pub drops: usize,
+    pub accepts: usize,
+    /// Fast Scan, Slow Scan, Accept Scan — in aceasta ordine.
+    pub windows: Vec<PortWindow>,
+}
+
+/// Porturile unice ale unei surse in fereastra unui detector, langa prag.
+#[derive(Debug, Clone)]
+pub struct PortWindow {
+    pub detector: &'static str,
+    pub window: Duration,
+    pub unique_ports: usize,
+    pub threshold: usize,
+}
+
+/// O tinta lovita din surse multiple (`Detector::distributed_targets`).
+#[derive(Debug, Clone)]
+pub struct DistributedTarget {
+    pub ip: IpAddr,
+    pub sources: Vec<IpAddr>,
+    pub ports: Vec<u16>,
+    pub events: usize,
+    pub accepts: usize,
+    pub last_seen: Duration,
+    /// Tinta are un cooldown Distributed Scan activ (a generat deja alerta).
+    pub cooling_down: bool,
+}
+
+/// Un cooldown activ (`Detector::active_cooldowns`).
+#[derive(Debug, Clone)]
+pub struct ActiveCooldown {
+    pub detector: &'static str,
+    /// Sursa (sau tinta, la Distributed Scan).
+    pub key: IpAddr,
+    pub remaining: Duration,
+    pub mode: CooldownMode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        AcceptScanConfig, AnomalyConfig, BeaconingConfig, BruteForceConfig, ChainRuleConfig,
+        ChainStageConfig, CooldownConfig, DetectorCooldown,
+        CorrelationConfig, DetectionConfig, DistributedScanConfig, FastScanConfig,
+        LateralMovementConfig, NatConfig, PingSweepConfig, PortGroupConfig,
+        RepeatOffendersConfig, RiskConfig, SessionsConfig, SeverityConfig, SlowScanConfig,
+    };
+
+    /// Creeaza o configuratie de test cu praguri mici pentru teste rapide.
+    fn test_config() -> DetectionConfig {
+        DetectionConfig {
+            alert_cooldown_secs: 5,
+            max_hits_per_ip: 1_000,
+            max_tracked_ips: 10_000,
+            ipv6_source_prefix: 128,
+            whitelist: Vec::new(),
+            fast_scan: FastScanConfig {
+                port_threshold: 3,
+                time_window_secs: 10,
+            },
+            slow_scan: SlowScanConfig {
+                port_threshold: 50,
+                time_window_mins: 1,
+            },
+            // Accept Scan cu acelasi prag ca Fast Scan pentru teste simetrice.
+            accept_scan: AcceptScanConfig {
+                port_threshold: 3,
+                time_window_secs: 10,
+            },
+            // Lateral Movement dezactivat implicit in test_config.
+            // Testele specifice folosesc lateral_config().
+            lateral_movement: LateralMovementConfig {
+                enabled: false,
+                unique_dest_threshold: 3,
+                time_window_secs: 10,
+            },
+            // Distributed Scan dezactivat implicit in test_config.
+            // Testele specifice folosesc distributed_config().
+            distributed_scan: DistributedScanConfig {
+                enabled: false,
+                unique_sources_threshold: 3,
+                time_window_secs: 10,
+            },
+            anomaly: AnomalyConfig::default(),
+            profiles: Vec::new(),
+            port_weights: PortWeightsConfig::default(),
+            risk: RiskConfig::default(),
+            correlation: CorrelationConfig::default(),
+            beaconing: BeaconingConfig::default(),
+            brute_force: BruteForceConfig::default(),
+            ping_sweep: PingSweepConfig::default(),
+            nat: NatConfig::default(),
+            severity: SeverityConfig::default(),
+            repeat_offenders: RepeatOffendersConfig::default(),
+            cooldown: CooldownConfig::default(),
+            sessions: SessionsConfig::default(),
+        }
+    }
+
+    /// Creeaza o configuratie cu Lateral Movement activat (prag 3 destinatii in 10s).
+    fn lateral_config() -> DetectionConfig {
+        DetectionConfig {
+            fast_scan: FastScanConfig {
+                port_threshold: 100,
+                time_window_secs: 10,
+            },
+            slow_scan: SlowScanConfig {
+                port_threshold: 200,
+                time_window_mins: 1,
+            },
+            accept_scan: AcceptScanConfig {
+                port_threshold: 100,
+                time_window_secs: 10,
+            },
+            lateral_movement: LateralMovementConfig {
+                enabled: true,
+                unique_dest_threshold: 3,
+                time_window_secs: 10,
+            },
+            distributed_scan: DistributedScanConfig {
+                enabled: false,
+                unique_sources_threshold: 3,
+                time_window_secs: 10,
+            },
+            ..test_config()
+        }
+    }
+
+    fn make_event(ip: &str, port: u16) -> LogEvent {
+        LogEvent::new(
+            ip.parse().unwrap(),
+            Some("10.0.0.1".parse().unwrap()),
+            port,
+            "tcp".to_string(),
+            "drop".to_string(),
+            String::new(),
+        )
     }
 
     /// Creeaza un eveniment accept cu IP destinatie explicit (pentru Lateral Movement).
     fn make_lateral_event(src_ip: &str, dest_ip: &str, port: u16) -> LogEvent {
-        LogEvent {
-            source_ip: src_ip.parse().unwrap(),
-            dest_ip: Some(dest_ip.parse().unwrap()),
-            dest_port: port,
-            protocol: "tcp".to_string(),
-            action: "accept".to_string(),
-            raw_log: String::new(),
-        }
+        LogEvent::new(
+            src_ip.parse().unwrap(),
+            Some(dest_ip.parse().unwrap()),
+            port,
+            "tcp".to_string(),
+            "accept".to_string(),
+            String::new(),
+        )
     }
 
     #[test]
@@ -1168,15 +2946,15 @@ mod tests {
 
     /// Construieste un eveniment de tip "accept" (port deschis, permis de firewall).
     fn make_accept_event(ip: &str, port: u16) -> LogEvent {
-        LogEvent {
-            source_ip: ip.parse().unwrap(),
-            dest_ip: Some("10.0.0.1".parse().unwrap()),
-            dest_port: port,
-            protocol: "tcp".to_string(),
-            // Diferenta fata de make_event: actiunea este "accept" nu "drop".
-            action: "accept".to_string(),
-            raw_log: String::new(),
-        }
+        // Diferenta fata de make_event: actiunea este "accept" nu "drop".
+        LogEvent::new(
+            ip.parse().unwrap(),
+            Some("10.0.0.1".parse().unwrap()),
+            port,
+            "tcp".to_string(),
+            "accept".to_string(),
+            String::new(),
+        )
     }
 
     #[test]
@@ -1304,10 +3082,6 @@ mod tests {
     /// din test (milisecunde) sunt in cadrul ferestrei.
     fn slow_test_config() -> DetectionConfig {
         DetectionConfig {
-            alert_cooldown_secs: 5,
-            max_hits_per_ip: 1_000,
-            max_tracked_ips: 10_000,
-            whitelist: Vec::new(),
             fast_scan: FastScanConfig {
                 port_threshold: 1_000, // prag mare — nu se declanseaza in teste slow
                 time_window_secs: 10,
@@ -1330,6 +3104,7 @@ mod tests {
                 unique_sources_threshold: 3,
                 time_window_secs: 10,
             },
+            ..test_config()
         }
     }
 
@@ -1586,10 +3361,6 @@ mod tests {
     /// Fast/Slow/Accept au praguri ridicate pentru a nu se declansa in teste.
     fn distributed_config() -> DetectionConfig {
         DetectionConfig {
-            alert_cooldown_secs: 5,
-            max_hits_per_ip: 1_000,
-            max_tracked_ips: 10_000,
-            whitelist: Vec::new(),
             fast_scan: FastScanConfig {
                 port_threshold: 100,
                 time_window_secs: 10,
@@ -1612,19 +3383,20 @@ mod tests {
                 unique_sources_threshold: 3,
                 time_window_secs: 10,
             },
+            ..test_config()
         }
     }
 
     /// Creeaza un eveniment drop cu sursa si destinatie explicite (pentru Distributed Scan).
     fn make_distributed_event(src_ip: &str, dest_ip: &str, port: u16) -> LogEvent {
-        LogEvent {
-            source_ip: src_ip.parse().unwrap(),
-            dest_ip: Some(dest_ip.parse().unwrap()),
-            dest_port: port,
-            protocol: "tcp".to_string(),
-            action: "drop".to_string(),
-            raw_log: String::new(),
-        }
+        LogEvent::new(
+            src_ip.parse().unwrap(),
+            Some(dest_ip.parse().unwrap()),
+            port,
+            "tcp".to_string(),
+            "drop".to_string(),
+            String::new(),
+        )
     }
 
     #[test]
@@ -1737,14 +3509,14 @@ mod tests {
         // Sursa 1: drop
         detector.process_event(&make_distributed_event("10.0.1.1", "10.0.0.100", 80));
         // Sursa 2: accept
-        detector.process_event(&LogEvent {
-            source_ip: "10.0.1.2".parse().unwrap(),
-            dest_ip: Some("10.0.0.100".parse().unwrap()),
-            dest_port: 80,
-            protocol: "tcp".to_string(),
-            action: "accept".to_string(),
-            raw_log: String::new(),
-        });
+        detector.process_event(&LogEvent::new(
+            "10.0.1.2".parse().unwrap(),
+            Some("10.0.0.100".parse().unwrap()),
+            80,
+            "tcp".to_string(),
+            "accept".to_string(),
+            String::new(),
+        ));
         // Sursa 3: drop → ar trebui sa declanseze alerta
         let alerts = detector.process_event(&make_distributed_event("10.0.1.3", "10.0.0.100", 80));
 
@@ -1784,14 +3556,14 @@ mod tests {
 
         for dest in &["10.0.0.10", "10.0.0.11", "10.0.0.12"] {
             // drop in loc de accept
-            let alerts = detector.process_event(&LogEvent {
-                source_ip: "10.0.1.5".parse().unwrap(),
-                dest_ip: Some(dest.parse().unwrap()),
-                dest_port: 445,
-                protocol: "tcp".to_string(),
-                action: "drop".to_string(),
-                raw_log: String::new(),
-            });
+            let alerts = detector.process_event(&LogEvent::new(
+                "10.0.1.5".parse().unwrap(),
+                Some(dest.parse().unwrap()),
+                445,
+                "tcp".to_string(),
+                "drop".to_string(),
+                String::new(),
+            ));
             let lateral: Vec<_> = alerts
                 .iter()
                 .filter(|a| matches!(a.scan_type, ScanType::LateralMovement))
@@ -1802,4 +3574,820 @@ mod tests {
             );
         }
     }
+
+    // =========================================================================
+    // Teste Threat Intel (KnownBadSource / KnownBadDestination)
+    // =========================================================================
+
+    /// Incarca un feed plain dintr-un fisier temporar (ThreatIntel::load citeste de pe disc).
+    fn intel_from_plain(name: &str, content: &str) -> ThreatIntel {
+        use crate::config::{ThreatFeedConfig, ThreatIntelConfig};
+        let path = std::env::temp_dir().join(format!("ids-rs-test-{}-{}.txt", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        let intel = ThreatIntel::load(&ThreatIntelConfig {
+            enabled: true,
+            feeds: vec![ThreatFeedConfig {
+                name: name.to_string(),
+                path: path.to_string_lossy().to_string(),
+                format: "plain".to_string(),
+                confidence: 75,
+            }],
+        })
+        .unwrap();
+        std::fs::remove_file(&path).ok();
+        intel
+    }
+
+    #[test]
+    fn test_known_bad_source_first_event() {
+        // Un singur eveniment (sub orice prag de scan) → alerta imediata.
+        let detector = Detector::new(test_config());
+        detector.update_threat_intel(intel_from_plain("src", "203.0.113.0/24\n"));
+
+        let alerts = detector.process_event(&make_event("203.0.113.9", 22));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::KnownBadSource));
+        let ioc = alerts[0].threat_intel.as_ref().unwrap();
+        assert_eq!(ioc.feed, "src");
+        assert_eq!(ioc.confidence, 75);
+        assert_eq!(ioc.indicator, "203.0.113.0/24");
+
+        // Al doilea eveniment: in cooldown.
+        let alerts = detector.process_event(&make_event("203.0.113.9", 23));
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_known_bad_destination_per_pair_cooldown() {
+        let detector = Detector::new(test_config());
+        detector.update_threat_intel(intel_from_plain("dst", "198.51.100.7\n"));
+
+        let a1 = detector.process_event(&make_lateral_event("10.0.1.5", "198.51.100.7", 443));
+        assert_eq!(a1.len(), 1);
+        assert!(matches!(a1[0].scan_type, ScanType::KnownBadDestination));
+        assert_eq!(a1[0].dest_ip, Some("198.51.100.7".parse().unwrap()));
+
+        // Acelasi host, aceeasi destinatie → cooldown.
+        assert!(detector.process_event(&make_lateral_event("10.0.1.5", "198.51.100.7", 443)).is_empty());
+        // Alt host intern catre acelasi IOC → alerta separata.
+        assert_eq!(detector.process_event(&make_lateral_event("10.0.1.6", "198.51.100.7", 443)).len(), 1);
+    }
+
+    #[test]
+    fn test_known_bad_whitelist_wins() {
+        let detector = Detector::new(DetectionConfig {
+            whitelist: vec!["203.0.113.9".to_string()],
+            ..test_config()
+        });
+        detector.update_threat_intel(intel_from_plain("wl", "203.0.113.9\n"));
+
+        assert!(detector.process_event(&make_event("203.0.113.9", 22)).is_empty());
+    }
+
+    /// Profil care suprascrie doar pragul Fast Scan.
+    fn fast_profile(name: &str, sources: &[&str], port_threshold: usize) -> DetectionProfile {
+        DetectionProfile {
+            name: name.to_string(),
+            sources: sources.iter().map(|s| s.to_string()).collect(),
+            alert_cooldown_secs: None,
+            fast_scan: Some(FastScanConfig { port_threshold, time_window_secs: 10 }),
+            slow_scan: None,
+            accept_scan: None,
+            lateral_movement: None,
+            distributed_scan: None,
+            brute_force: None,
+            ping_sweep: None,
+        }
+    }
+
+    #[test]
+    fn test_profile_overrides_threshold_for_matching_source() {
+        let detector = Detector::new(DetectionConfig {
+            profiles: vec![fast_profile("jump-hosts", &["10.0.5.0/24"], 10)],
+            ..test_config()
+        });
+
+        // Sursa din profil: 3 porturi nu mai ajung (prag 10).
+        for port in 1..=3 {
+            assert!(detector.process_event(&make_event("10.0.5.7", port)).is_empty());
+        }
+        // Sursa din afara profilului: pragul global (3) se aplica.
+        let mut alerts = Vec::new();
+        for port in 1..=3 {
+            alerts = detector.process_event(&make_event("10.0.9.7", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].profile.is_none());
+
+        let mut alerts = Vec::new();
+        for port in 4..=10 {
+            alerts = detector.process_event(&make_event("10.0.5.7", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].profile.as_deref(), Some("jump-hosts"));
+    }
+
+    #[test]
+    fn test_profile_longest_prefix_wins() {
+        let detector = Detector::new(DetectionConfig {
+            profiles: vec![
+                fast_profile("servere-host", &["10.0.0.0/8", "10.1.1.1"], 2),
+                fast_profile("vlan", &["10.1.0.0/16"], 20),
+            ],
+            ..test_config()
+        });
+
+        // 10.1.1.1 este /32 in primul profil — mai specific decat /16.
+        detector.process_event(&make_event("10.1.1.1", 1));
+        let alerts = detector.process_event(&make_event("10.1.1.1", 2));
+        assert_eq!(alerts[0].profile.as_deref(), Some("servere-host"));
+
+        // 10.1.1.2 → /16 din "vlan" bate /8 din primul profil.
+        for port in 1..=5 {
+            assert!(detector.process_event(&make_event("10.1.1.2", port)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_profile_matches_subnet_label() {
+        let detector = Detector::new(DetectionConfig {
+            profiles: vec![fast_profile("guest", &["Wi-Fi Guest"], 2)],
+            ..test_config()
+        });
+        let raw: std::collections::HashMap<String, String> =
+            [("192.168.50.0/24".to_string(), "Wi-Fi Guest".to_string())].into();
+        detector.update_subnets(SubnetEntry::parse_subnets(&raw));
+
+        detector.process_event(&make_event("192.168.50.20", 1));
+        let alerts = detector.process_event(&make_event("192.168.50.20", 2));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].profile.as_deref(), Some("guest"));
+    }
+
+    /// Ponderi: SSH/RDP = 3, HTTP = 0.5, grupul "database" = 2.
+    fn weighted_config() -> DetectionConfig {
+        DetectionConfig {
+            fast_scan: FastScanConfig { port_threshold: 6, time_window_secs: 10 },
+            port_weights: PortWeightsConfig {
+                enabled: true,
+                default_weight: 1.0,
+                ports: [("22".to_string(), 3.0), ("3389".to_string(), 3.0), ("80".to_string(), 0.5)].into(),
+                groups: vec![PortGroupConfig {
+                    name: "database".to_string(),
+                    ports: vec![1433, 3306, 5432],
+                    weight: 2.0,
+                }],
+            },
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn test_weighted_sensitive_ports_alert_sooner() {
+        let detector = Detector::new(weighted_config());
+
+        // 22 + 3389 = 6 ≥ prag 6 — doar doua porturi unice.
+        assert!(detector.process_event(&make_event("10.0.0.50", 22)).is_empty());
+        let alerts = detector.process_event(&make_event("10.0.0.50", 3389));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert_eq!(alerts[0].score, Some(6.0));
+        assert_eq!(alerts[0].sensitive_ports, vec![22, 3389]);
+    }
+
+    #[test]
+    fn test_weighted_low_ports_need_more() {
+        let detector = Detector::new(weighted_config());
+
+        // 80 (0.5) + 8080, 8081, 8082 (1 fiecare) = 3.5 < 6 — fara alerta,
+        // desi 4 porturi unice ar fi depasit pragul clasic.
+        for port in [80, 8080, 8081, 8082] {
+            assert!(detector.process_event(&make_event("10.0.0.51", port)).is_empty());
+        }
+        // + grupul database: 3306 (2) + 5432 (2) = 7.5 ≥ 6.
+        detector.process_event(&make_event("10.0.0.51", 3306));
+        let alerts = detector.process_event(&make_event("10.0.0.51", 5432));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].sensitive_ports, vec![3306, 5432]);
+    }
+
+    #[test]
+    fn test_risk_escalation_chains_detectors() {
+        let detector = Detector::new(DetectionConfig {
+            risk: RiskConfig {
+                enabled: true,
+                tiers: vec![20.0],
+                ..RiskConfig::default()
+            },
+            ..test_config()
+        });
+
+        // Fast Scan (10 puncte) — sub prag.
+        for port in 1..=3 {
+            let alerts = detector.process_event(&make_event("10.0.0.60", port));
+            assert!(alerts.iter().all(|a| !matches!(a.scan_type, ScanType::Escalation)));
+        }
+        // Accept Scan (15 puncte) → 25 ≥ 20 → Escalation in acelasi rezultat.
+        let mut alerts = Vec::new();
+        for port in 1..=3 {
+            alerts = detector.process_event(&make_accept_event("10.0.0.60", port));
+        }
+        assert_eq!(alerts.len(), 2);
+        assert!(matches!(alerts[0].scan_type, ScanType::AcceptScan));
+        assert!(matches!(alerts[1].scan_type, ScanType::Escalation));
+        let risk = alerts[1].risk.as_ref().unwrap();
+        assert_eq!(risk.tier, 1);
+        assert_eq!(risk.chain.len(), 2);
+        assert!(matches!(risk.chain[0].scan_type, ScanType::Fast));
+    }
+
+    #[test]
+    fn test_attack_chain_distributed_target_turns_lateral() {
+        let detector = Detector::new(DetectionConfig {
+            correlation: CorrelationConfig {
+                enabled: true,
+                rules: vec![ChainRuleConfig {
+                    name: "target-compromised".to_string(),
+                    window_secs: 600,
+                    stages: vec![
+                        ChainStageConfig { alert: "distributed_scan".to_string(), on: "target".to_string() },
+                        ChainStageConfig { alert: "lateral_movement".to_string(), on: "source".to_string() },
+                    ],
+                }],
+            },
+            lateral_movement: LateralMovementConfig {
+                enabled: true,
+                unique_dest_threshold: 3,
+                time_window_secs: 10,
+            },
+            ..distributed_config()
+        });
+
+        // 3 surse diferite scaneaza 10.0.2.10 → Distributed Scan (etapa 1).
+        for src in ["198.51.100.1", "198.51.100.2", "198.51.100.3"] {
+            detector.process_event(&make_lateral_event(src, "10.0.2.10", 80));
+        }
+        // 10.0.2.10 contacteaza 3 destinatii → Lateral Movement (etapa 2) → AttackChain.
+        let mut alerts = Vec::new();
+        for dst in ["10.0.2.11", "10.0.2.12", "10.0.2.13"] {
+            alerts = detector.process_event(&make_lateral_event("10.0.2.10", dst, 445));
+        }
+        let chain = alerts
+            .iter()
+            .find(|a| matches!(a.scan_type, ScanType::AttackChain))
+            .expect("lipseste alerta AttackChain");
+        assert_eq!(chain.source_ip, "10.0.2.10".parse::<IpAddr>().unwrap());
+        let m = chain.chain.as_ref().unwrap();
+        assert_eq!(m.rule, "target-compromised");
+        assert_eq!(m.steps.len(), 2);
+    }
+
+    /// Brute Force activat: 10 drop-uri sau 5 accept-uri pe acelasi tuplu in 10s.
+    fn brute_force_config() -> DetectionConfig {
+        DetectionConfig {
+            brute_force: BruteForceConfig {
+                enabled: true,
+                drop_threshold: 10,
+                accept_threshold: 5,
+                time_window_secs: 10,
+                cooldown_secs: None,
+            },
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn test_brute_force_drop_flood_single_port() {
+        let detector = Detector::new(brute_force_config());
+
+        // 9 drop-uri pe portul 22 — sub prag; un singur port unic, fara Fast Scan.
+        for _ in 0..9 {
+            assert!(detector.process_event(&make_event("10.0.0.70", 22)).is_empty());
+        }
+        let alerts = detector.process_event(&make_event("10.0.0.70", 22));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::BruteForce));
+        let flood = alerts[0].flood.as_ref().unwrap();
+        assert_eq!(flood.count, 10);
+        assert!(!flood.accepted);
+        assert_eq!(alerts[0].unique_ports, vec![22]);
+
+        // Cooldown activ — drop-urile urmatoare nu mai alerteaza.
+        assert!(detector.process_event(&make_event("10.0.0.70", 22)).is_empty());
+    }
+
+    #[test]
+    fn test_brute_force_accept_counted_separately() {
+        let detector = Detector::new(brute_force_config());
+
+        // Drop-urile nu se aduna la pragul de accept si invers.
+        for _ in 0..9 {
+            detector.process_event(&make_event("10.0.0.71", 3389));
+        }
+        let mut alerts = Vec::new();
+        for _ in 0..5 {
+            alerts = detector.process_event(&make_accept_event("10.0.0.71", 3389));
+        }
+        assert_eq!(alerts.len(), 1);
+        let flood = alerts[0].flood.as_ref().unwrap();
+        assert!(flood.accepted);
+        assert_eq!(flood.count, 5);
+
+        // Alt port pe aceeasi destinatie = alt tuplu, fara alerta.
+        assert!(detector.process_event(&make_accept_event("10.0.0.71", 22)).is_empty());
+    }
+
+    fn make_proto_event(ip: &str, dest: &str, port: u16, protocol: &str, icmp_type: Option<u8>) -> LogEvent {
+        LogEvent {
+            icmp_type,
+            icmp_code: icmp_type.map(|_| 0),
+            ..LogEvent::new(
+                ip.parse().unwrap(),
+                Some(dest.parse().unwrap()),
+                port,
+                protocol.to_string(),
+                "drop".to_string(),
+                String::new(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_tcp_and_udp_ports_counted_separately() {
+        let detector = Detector::new(test_config());
+
+        // 53/tcp + 53/udp = doua porturi unice; al treilea declanseaza Fast Scan.
+        assert!(detector.process_event(&make_proto_event("10.0.0.80", "10.0.0.1", 53, "tcp", None)).is_empty());
+        assert!(detector.process_event(&make_proto_event("10.0.0.80", "10.0.0.1", 53, "udp", None)).is_empty());
+        let alerts = detector.process_event(&make_proto_event("10.0.0.80", "10.0.0.1", 161, "udp", None));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert_eq!(alerts[0].unique_ports, vec![53, 53, 161]);
+        assert_eq!(alerts[0].protocols, vec![(Protocol::Tcp, 1), (Protocol::Udp, 2)]);
+        assert_eq!(alerts[0].protocol_summary(","), "tcp=1,udp=2");
+    }
+
+    fn ping_sweep_config() -> DetectionConfig {
+        DetectionConfig {
+            ping_sweep: PingSweepConfig {
+                enabled: true,
+                unique_dest_threshold: 3,
+                time_window_secs: 10,
+            },
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn test_ping_sweep_detected() {
+        let detector = Detector::new(ping_sweep_config());
+
+        for host in ["10.0.5.1", "10.0.5.2"] {
+            assert!(detector.process_event(&make_proto_event("10.0.0.90", host, 0, "icmp", Some(8))).is_empty());
+        }
+        // Acelasi host din nou — nu creste numarul de destinatii unice.
+        assert!(detector.process_event(&make_proto_event("10.0.0.90", "10.0.5.2", 0, "icmp", Some(8))).is_empty());
+
+        let alerts = detector.process_event(&make_proto_event("10.0.0.90", "10.0.5.3", 0, "icmp", None));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::PingSweep));
+        assert_eq!(alerts[0].unique_dests.len(), 3);
+        assert_eq!(alerts[0].protocols, vec![(Protocol::Icmp, 3)]);
+
+        // ICMP nu alimenteaza detectoarele de porturi (port 0 nu e port scanat).
+        assert!(!detector.port_hits.contains_key(&"10.0.0.90".parse::<IpAddr>().unwrap()));
+    }
+
+    #[test]
+    fn test_ping_sweep_ignores_non_probe_icmp() {
+        let detector = Detector::new(ping_sweep_config());
+
+        // Echo reply (0) si destination unreachable (3) nu sunt sondari.
+        for (i, icmp_type) in [0u8, 3, 0, 3].iter().enumerate() {
+            let dest = format!("10.0.6.{}", i + 1);
+            let event = make_proto_event("10.0.0.91", &dest, 0, "icmp", Some(*icmp_type));
+            assert!(detector.process_event(&event).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_fast_scan_fingerprinted_by_fixed_source_port() {
+        let config = DetectionConfig {
+            fast_scan: FastScanConfig { port_threshold: 6, time_window_secs: 10 },
+            ..test_config()
+        };
+        let detector = Detector::new(config);
+
+        // nmap -sS: acelasi port sursa pe toate porturile sondate.
+        let mut alerts = Vec::new();
+        for port in 20..26 {
+            let event = LogEvent {
+                source_port: Some(40000),
+                tcp_flags: Some("SYN".to_string()),
+                ..make_proto_event("10.0.0.95", "10.0.0.1", port, "tcp", None)
+            };
+            alerts = detector.process_event(&event);
+        }
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        let fp = alerts[0].fingerprint.as_ref().expect("amprenta lipsa");
+        assert_eq!(fp.tool, "nmap SYN scan");
+        assert!(fp.evidence.contains("40000"));
+    }
+
+    #[test]
+    fn test_no_fingerprint_without_source_port() {
+        let detector = Detector::new(test_config());
+        let mut alerts = Vec::new();
+        for port in 20..23 {
+            alerts = detector.process_event(&make_event("10.0.0.96", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].fingerprint.is_none());
+    }
+
+    #[test]
+    fn test_alert_lists_rules_hit_by_source() {
+        let detector = Detector::new(test_config());
+        let rule_event = |port: u16, rule: &str, name: &str| LogEvent {
+            rule: Some(rule.to_string()),
+            rule_name: Some(name.to_string()),
+            ..make_event("10.0.0.97", port)
+        };
+
+        detector.process_event(&rule_event(20, "9", "Cleanup rule"));
+        detector.process_event(&rule_event(21, "113", "Stealth"));
+        let alerts = detector.process_event(&rule_event(22, "9", "Cleanup rule"));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rules, vec!["9 (Cleanup rule)", "113 (Stealth)"]);
+
+        let stats = detector.rule_stats();
+        assert_eq!(stats[0].0, "9 (Cleanup rule)");
+        assert_eq!((stats[0].1.drops, stats[0].1.alerts), (2, 1));
+    }
+
+    /// Scanare de la adrese IPv6 diferite din acelasi /64 (rotatie per pachet).
+    fn rotating_ipv6_scan(detector: &Detector, ports: std::ops::Range<u16>) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for port in ports {
+            let src = format!("2001:db8:1:2:{:x}::{:x}", port, port * 7);
+            alerts.extend(detector.process_event(&make_event(&src, port)));
+        }
+        alerts
+    }
+
+    #[test]
+    fn test_rotating_ipv6_evades_without_aggregation() {
+        let detector = Detector::new(test_config());
+        assert!(rotating_ipv6_scan(&detector, 20..30).is_empty());
+        assert_eq!(detector.tracked_ips(), 10);
+    }
+
+    #[test]
+    fn test_rotating_ipv6_aggregated_by_prefix() {
+        let detector = Detector::new(DetectionConfig { ipv6_source_prefix: 64, ..test_config() });
+        let alerts = rotating_ipv6_scan(&detector, 20..30);
+
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert_eq!(alerts[0].source_ip, "2001:db8:1:2::".parse::<IpAddr>().unwrap());
+        assert_eq!(alerts[0].source_prefix, Some(64));
+        assert_eq!(alerts[0].source_label(), "2001:db8:1:2::/64");
+        // O singura intrare LRU pentru tot prefixul.
+        assert_eq!(detector.tracked_ips(), 1);
+
+        // Alt /64 ramane sursa separata; IPv4 nu este agregat.
+        detector.process_event(&make_event("2001:db8:1:3::1", 80));
+        detector.process_event(&make_event("10.0.0.1", 80));
+        assert_eq!(detector.tracked_ips(), 3);
+    }
+
+    #[test]
+    fn test_ipv6_aggregation_by_56_and_whitelist_exact() {
+        let detector = Detector::new(DetectionConfig {
+            ipv6_source_prefix: 56,
+            whitelist: vec!["2001:db8:1:2::9".to_string()],
+            ..test_config()
+        });
+
+        // /56: 2001:db8:1:2:: si 2001:db8:1:3:: sunt aceeasi sursa.
+        detector.process_event(&make_event("2001:db8:1:2::1", 20));
+        detector.process_event(&make_event("2001:db8:1:3::1", 21));
+        // Whitelist-ul se aplica adresei exacte, nu prefixului.
+        assert!(detector.process_event(&make_event("2001:db8:1:2::9", 22)).is_empty());
+        let alerts = detector.process_event(&make_event("2001:db8:1:ff::1", 23));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].source_label(), "2001:db8:1::/56");
+    }
+
+    #[test]
+    fn test_severity_assessed_with_repeat_offence() {
+        let detector = Detector::new(test_config());
+        let mut alerts = Vec::new();
+        for port in 20..23 {
+            alerts = detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, Some(3));
+        assert_eq!(alerts[0].severity.level, 7);
+        assert!(alerts[0].severity.factors.is_empty());
+
+        // A doua alerta a aceleiasi surse (Accept Scan, baza 5) urca cu recidiva.
+        for port in 80..83 {
+            alerts = detector.process_event(&make_accept_event("203.0.113.9", port));
+        }
+        assert!(matches!(alerts[0].scan_type, ScanType::AcceptScan));
+        assert_eq!(alerts[0].severity.level, 6);
+        assert_eq!(alerts[0].severity.factors, vec!["a 2-a alerta in 24h (+1)"]);
+    }
+
+    #[test]
+    fn test_persistent_scanner_after_threshold() {
+        let detector = Detector::new(DetectionConfig {
+            repeat_offenders: RepeatOffendersConfig {
+                persistent_threshold: 2,
+                ..RepeatOffendersConfig::default()
+            },
+            ..test_config()
+        });
+        let mut alerts = Vec::new();
+        for port in 20..23 {
+            alerts = detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].offence.as_ref().unwrap().nth, 1);
+
+        // A doua alerta atinge pragul: alerta proprie plus PersistentScanner.
+        for port in 80..83 {
+            alerts = detector.process_event(&make_accept_event("203.0.113.9", port));
+        }
+        assert_eq!(alerts.len(), 2);
+        assert!(matches!(alerts[0].scan_type, ScanType::AcceptScan));
+        assert!(alerts[0].offence.as_ref().unwrap().is_repeat());
+        let persistent = &alerts[1];
+        assert!(matches!(persistent.scan_type, ScanType::PersistentScanner));
+        let offence = persistent.offence.as_ref().unwrap();
+        assert_eq!((offence.nth, offence.total), (2, 2));
+        assert_eq!(offence.scan_types, vec!["Fast Scan", "Accept Scan"]);
+        assert_eq!(persistent.severity.level, 7);
+
+        // Cooldown-ul notificarii: a treia alerta nu mai produce PersistentScanner.
+        for port in 1000..1050 {
+            alerts = detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert!(alerts.iter().all(|a| !matches!(a.scan_type, ScanType::PersistentScanner)));
+
+        // `ctl clear-state` sterge si istoricul: sursa reincepe de la prima alerta.
+        detector.clear_state("203.0.113.9".parse().unwrap());
+        for port in 20..23 {
+            alerts = detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].offence.as_ref().unwrap().nth, 1);
+    }
+
+    #[test]
+    fn test_cooldown_policy_per_detector() {
+        let detector = Detector::new(DetectionConfig {
+            cooldown: CooldownConfig {
+                fast_scan: Some(DetectorCooldown {
+                    secs: Some(30),
+                    mode: Some("ongoing".to_string()),
+                }),
+                ..CooldownConfig::default()
+            },
+            ..test_config()
+        });
+        let cfg = detector.config.load();
+        let fast = CooldownPolicy::resolve(&cfg, "fast_scan");
+        assert_eq!((fast.secs, fast.mode), (Duration::from_secs(30), CooldownMode::Ongoing));
+        let accept = CooldownPolicy::resolve(&cfg, "accept_scan");
+        assert_eq!((accept.secs, accept.mode), (Duration::from_secs(5), CooldownMode::Fixed));
+        assert_eq!(cfg.max_alert_cooldown_secs(), 30);
+
+        // Prima alerta a sesiunii este o alerta obisnuita.
+        let mut alerts = Vec::new();
+        for port in 20..23 {
+            alerts = detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].ongoing.is_none());
+
+        // In cooldown scanarea continua fara alerte; sesiunea ramane deschisa.
+        for port in 23..40 {
+            assert!(detector.process_event(&make_event("203.0.113.9", port)).is_empty());
+        }
+        assert!(detector.finished_scans().is_empty());
+    }
+
+    #[test]
+    fn test_scan_session_seeded_and_closed() {
+        let cfg = DetectionConfig {
+            sessions: SessionsConfig { enabled: true, idle_timeout_secs: 60, max_ports: 100 },
+            ..test_config()
+        };
+        let detector = Detector::new(cfg.clone());
+        let src: IpAddr = "203.0.113.9".parse().unwrap();
+
+        // Fara alerta nu exista sesiune.
+        detector.process_event(&make_event("203.0.113.9", 22));
+        assert!(!detector.sessions.is_open(SessionKind::Source, src));
+
+        // Alerta Fast Scan deschide sesiunea din hit-urile deja vazute.
+        let mut alerts = Vec::new();
+        for port in [80, 443] {
+            alerts = detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert!(detector.sessions.is_open(SessionKind::Source, src));
+
+        // Evenimentele urmatoare continua sesiunea (inclusiv accept-urile).
+        detector.process_event(&make_event("203.0.113.9", 22));
+        detector.process_event(&make_event("203.0.113.9", 3389));
+        detector.process_event(&make_lateral_event("203.0.113.9", "10.0.0.2", 8080));
+
+        let later = Instant::now() + Duration::from_secs(120);
+        let closed = detector.sessions.close_idle(later, &cfg.sessions);
+        assert_eq!(closed.len(), 1);
+        let s = &closed[0];
+        assert_eq!((s.kind, s.ip), (SessionKind::Source, src));
+        assert_eq!((s.events, s.drops, s.accepts), (6, 5, 1));
+        assert_eq!(s.ports, vec![22, 80, 443, 3389, 8080]);
+        assert_eq!((s.peers, s.alerts), (2, 1));
+        assert_eq!(s.scan_types, vec!["Fast Scan"]);
+        assert!(!detector.sessions.is_open(SessionKind::Source, src));
+
+        // O sursa evacuata (LRU) isi pierde si sesiunea deschisa, fara rezumat.
+        detector.clear_cooldowns(src);
+        for port in [1000, 1001, 1002] {
+            detector.process_event(&make_event("203.0.113.9", port));
+        }
+        assert!(detector.sessions.is_open(SessionKind::Source, src));
+        detector.forget_source(src);
+        assert!(!detector.sessions.is_open(SessionKind::Source, src));
+        assert!(detector.sessions.close_idle(later, &cfg.sessions).is_empty());
+    }
+
+    #[test]
+    fn test_dashboard_snapshots() {
+        let detector = Detector::new(test_config());
+        for port in 20..24 {
+            detector.process_event(&make_event("203.0.113.9", port));
+        }
+        detector.process_event(&make_event("198.51.100.4", 22));
+        detector.process_event(&make_lateral_event("198.51.100.4", "10.0.0.2", 443));
+
+        let sources = detector.top_sources(10);
+        assert_eq!(sources[0].ip.to_string(), "203.0.113.9");
+        assert_eq!((sources[0].unique_ports, sources[0].drops, sources[0].accepts), (4, 4, 0));
+        assert_eq!((sources[1].unique_ports, sources[1].drops, sources[1].accepts), (2, 1, 1));
+        assert_eq!(detector.top_sources(1).len(), 1);
+
+        let targets = detector.top_targets(10);
+        assert_eq!(targets[0].ip.to_string(), "10.0.0.1");
+        assert_eq!((targets[0].unique_sources, targets[0].events), (2, 5));
+
+        // Alerta Fast Scan a lui 203.0.113.9 tine un cooldown activ.
+        let cooldowns = detector.active_cooldowns();
+        assert_eq!(cooldowns.len(), 1);
+        assert_eq!((cooldowns[0].detector, cooldowns[0].mode), ("fast_scan", CooldownMode::Fixed));
+        assert!(cooldowns[0].remaining <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_forget_source_drops_baseline() {
+        let mut config = test_config();
+        config.anomaly.enabled = true;
+        let detector = Detector::new(config);
+        detector.process_event(&make_event("203.0.113.9", 22));
+        detector.process_event(&make_event("203.0.113.10", 22));
+        assert!(detector.baselines.contains("203.0.113.9"));
+
+        // Evacuarea LRU si `ctl clear-state` sterg si baseline-ul sursei.
+        detector.clear_state("203.0.113.9".parse().unwrap());
+        assert!(!detector.baselines.contains("203.0.113.9"));
+        assert!(detector.baselines.contains("203.0.113.10"));
+    }
+
+    #[test]
+    fn test_control_state_clear_and_temp_whitelist() {
+        let detector = Detector::new(test_config());
+        let ip: IpAddr = "203.0.113.9".parse().unwrap();
+        for port in 20..24 {
+            detector.process_event(&make_event("203.0.113.9", port));
+        }
+
+        let state = detector.ip_state(ip);
+        assert_eq!(state.drops.len(), 4);
+        assert_eq!(state.cooldowns.len(), 1);
+        assert!(state.last_seen.is_some() && !state.whitelisted);
+
+        // Fara cooldown, urmatorul port realerteaza imediat.
+        assert_eq!(detector.clear_cooldowns(ip), 1);
+        assert!(!detector.process_event(&make_event("203.0.113.9", 24)).is_empty());
+
+        assert!(detector.clear_state(ip));
+        let state = detector.ip_state(ip);
+        assert!(state.drops.is_empty() && state.cooldowns.is_empty() && state.last_seen.is_none());
+        assert_eq!(detector.tracked_ips(), 0);
+        assert!(!detector.clear_state(ip));
+
+        // Whitelist temporar: sursa ignorata cat timp intrarea e valabila.
+        assert!(detector.add_temp_whitelist("203.0.113.0/24", None).is_ok());
+        assert!(detector.add_temp_whitelist("not-an-ip", None).is_err());
+        assert!(detector.add_temp_whitelist("198.51.100.1", Some(Duration::from_secs(u64::MAX))).is_err());
+        assert!(!detector.remove_temp_whitelist("198.51.100.1"), "intrarea refuzata nu este adaugata");
+        assert!(detector.is_whitelisted(&ip));
+        for port in 30..40 {
+            assert!(detector.process_event(&make_event("203.0.113.9", port)).is_empty());
+        }
+        assert!(detector.remove_temp_whitelist("203.0.113.0/24"));
+        assert!(!detector.is_whitelisted(&ip));
+
+        detector.add_temp_whitelist("203.0.113.9", Some(Duration::ZERO)).unwrap();
+        assert!(!detector.is_whitelisted(&ip));
+        detector.cleanup(Duration::from_secs(600));
+        assert!(detector.temp_whitelist().is_empty());
+    }
+
+    /// Eveniment de la o statie interna vazut prin NAT-ul gateway-ului.
+    fn nat_event(src: &str, port: u16) -> LogEvent {
+        LogEvent {
+            xlate_src: Some("198.51.100.7".parse().unwrap()),
+            xlate_sport: Some(40_000 + port),
+            ..make_event(src, port)
+        }
+    }
+
+    #[test]
+    fn test_nat_original_identity_keeps_hosts_separate() {
+        let detector = Detector::new(distributed_config());
+        let mut alerts = Vec::new();
+        for host in 1..=3 {
+            alerts.extend(detector.process_event(&nat_event(&format!("192.168.1.{}", host), 445)));
+        }
+
+        // Trei statii interne distincte, desi pe fir apar ca o singura adresa.
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::DistributedScan));
+        assert_eq!(alerts[0].unique_sources.len(), 3);
+        let nat = alerts[0].nat.as_ref().unwrap();
+        assert_eq!(nat.src, "192.168.1.3".parse::<IpAddr>().unwrap());
+        assert_eq!(nat.summary(), "src 192.168.1.3 → 198.51.100.7:40445");
+    }
+
+    #[test]
+    fn test_nat_translated_identity_per_detector() {
+        let detector = Detector::new(DetectionConfig {
+            nat: NatConfig {
+                port_scan: Some("translated".to_string()),
+                ..NatConfig::default()
+            },
+            ..test_config()
+        });
+
+        // Port Scan cheiat pe adresa tradusa: trei statii, cate un port fiecare,
+        // sunt o singura sursa publica care loveste trei porturi.
+        let mut alerts = Vec::new();
+        for (host, port) in [(1, 20), (2, 21), (3, 22)] {
+            alerts.extend(detector.process_event(&nat_event(&format!("192.168.1.{}", host), port)));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert_eq!(alerts[0].source_ip, "198.51.100.7".parse::<IpAddr>().unwrap());
+        assert_eq!(alerts[0].nat.as_ref().unwrap().xlate_src, alerts[0].source_ip.into());
+
+        // Fara campuri NAT in log, identitatea tradusa nu are efect.
+        let plain = Detector::new(DetectionConfig {
+            nat: NatConfig {
+                identity: "translated".to_string(),
+                ..NatConfig::default()
+            },
+            ..test_config()
+        });
+        for port in 20..23 {
+            alerts = plain.process_event(&make_event("10.0.0.98", port));
+        }
+        assert_eq!(alerts[0].source_ip, "10.0.0.98".parse::<IpAddr>().unwrap());
+        assert!(alerts[0].nat.is_none());
+    }
+
+    #[test]
+    fn test_ipv6_subnet_lookup() {
+        let raw: std::collections::HashMap<String, String> = [
+            ("2001:db8::/32".to_string(), "Internet".to_string()),
+            ("fd00:10::/64".to_string(), "Lab IPv6".to_string()),
+            ("10.0.0.0/8".to_string(), "LAN".to_string()),
+        ]
+        .into();
+        let subnets = SubnetEntry::parse_subnets(&raw);
+
+        let lookup = |ip: &str| SubnetEntry::lookup(&subnets, &ip.parse().unwrap());
+        assert_eq!(lookup("fd00:10::abcd").as_deref(), Some("Lab IPv6"));
+        assert_eq!(lookup("2001:db8:ffff::1").as_deref(), Some("Internet"));
+        assert_eq!(lookup("fd00:11::1"), None);
+        // IPv4 mapat este normalizat de parseri inainte de lookup.
+        let mapped = crate::parser::parse_ip("::ffff:10.1.2.3").unwrap();
+        assert_eq!(SubnetEntry::lookup(&subnets, &mapped).as_deref(), Some("LAN"));
+    }
 }
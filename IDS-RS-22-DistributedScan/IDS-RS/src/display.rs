@@ -30,17 +30,69 @@
 //
 // =============================================================================
 
+use crate::assets::AssetInventory;
 use crate::config::{AppConfig, SubnetEntry};
 use crate::detector::{Alert, ScanType};
+use crate::history::{AlertRecord, HistoryCounts};
 use crate::parser::LogEvent;
+use crate::rules::RuleCounter;
+use crate::sessions::{SessionKind, SessionSummary};
 use chrono::Local;
 use colored::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 /// Latimea separatorului orizontal (in caractere).
 const SEPARATOR_WIDTH: usize = 120;
 
+// ---------------------------------------------------------------------------
+// Modul dashboard (`--tui`)
+//
+// Cat timp dashboard-ul ocupa terminalul, orice linie afisata i-ar strica
+// ecranul. `set_muted(true)` opreste afisarea; mesajele INFO / WARN / ERR /
+// LOAD se pastreaza pentru panoul "Mesaje" (`recent_notices`).
+//
+// NOTA RUST: `static` cu `AtomicBool` si `Mutex` — stare globala sigura
+// intre thread-uri, fara `unsafe`. `Mutex::new` si `VecDeque::new` sunt
+// `const fn`, deci pot initializa direct un `static`.
+// ---------------------------------------------------------------------------
+
+static MUTED: AtomicBool = AtomicBool::new(false);
+static NOTICES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Mesajele pastrate pentru panoul "Mesaje".
+const MAX_NOTICES: usize = 50;
+
+/// Opreste (sau reporneste) afisarea in terminal.
+pub fn set_muted(muted: bool) {
+    MUTED.store(muted, Ordering::Relaxed);
+}
+
+fn muted() -> bool {
+    MUTED.load(Ordering::Relaxed)
+}
+
+/// Pastreaza mesajul pentru dashboard daca afisarea e oprita.
+/// Intoarce `true` daca apelantul nu trebuie sa mai afiseze nimic.
+fn divert(level: &str, message: &str) -> bool {
+    if !muted() {
+        return false;
+    }
+    let mut notices = NOTICES.lock().unwrap_or_else(|e| e.into_inner());
+    if notices.len() == MAX_NOTICES {
+        notices.pop_front();
+    }
+    notices.push_back(format!("{} {} {}", Local::now().format("%H:%M:%S"), level, message));
+    true
+}
+
+/// Mesajele pastrate cat timp afisarea a fost oprita, cele mai noi ultimele.
+pub fn recent_notices() -> Vec<String> {
+    NOTICES.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
+
 // ---------------------------------------------------------------------------
 // Banner-ul de pornire al aplicatiei
 //
@@ -155,6 +207,136 @@ pub fn print_banner(config: &AppConfig) {
         );
     }
 
+    // Mod anomalie — baseline adaptiv per sursa/subnet.
+    if config.detection.anomaly.enabled {
+        let an_line = format!(
+            "  Anomaly: baseline per {} | interval {}s | alerta > {} sigma",
+            config.detection.anomaly.group_by,
+            config.detection.anomaly.bucket_secs,
+            config.detection.anomaly.stddev_threshold
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", an_line, width = inner_width).cyan()
+        );
+    }
+
+    // Threat intel — afisam numarul de feed-uri IOC daca este activat.
+    if config.threat_intel.enabled {
+        let ti_line = format!(
+            "  Threat intel: {} feed-uri IOC (alerta la primul eveniment)",
+            config.threat_intel.feeds.len()
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", ti_line, width = inner_width).cyan()
+        );
+    }
+
+    // Inventar active — formatul si cresterea de severitate pentru tinte critice.
+    if config.assets.enabled {
+        let as_line = format!(
+            "  Inventar active: {} (critical +{}, high +{} severitate)",
+            config.assets.format, config.assets.critical_boost, config.assets.high_boost
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", as_line, width = inner_width).cyan()
+        );
+    }
+
+    // Brute Force / flood per tuplu.
+    if config.detection.brute_force.enabled {
+        let bf = &config.detection.brute_force;
+        let bf_line = format!(
+            "  Brute Force: >={} drop / >={} accept per port/{}s",
+            bf.drop_threshold, bf.accept_threshold, bf.time_window_secs
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", bf_line, width = inner_width).cyan()
+        );
+    }
+
+    // Ping Sweep (ICMP).
+    if config.detection.ping_sweep.enabled {
+        let ps = &config.detection.ping_sweep;
+        let ps_line = format!(
+            "  Ping Sweep: >={} destinatii ICMP/{}s",
+            ps.unique_dest_threshold, ps.time_window_secs
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", ps_line, width = inner_width).cyan()
+        );
+    }
+
+    // Beaconing C2.
+    if config.detection.beaconing.enabled {
+        let bc = &config.detection.beaconing;
+        let bc_line = format!(
+            "  Beaconing: >={} conexiuni/{}s, jitter <={:.0}%",
+            bc.min_connections,
+            bc.min_duration_secs,
+            bc.max_jitter * 100.0
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", bc_line, width = inner_width).cyan()
+        );
+    }
+
+    // Corelare lanturi de atac.
+    if config.detection.correlation.enabled {
+        let cr_line = format!(
+            "  Corelare: {} reguli de lant de atac",
+            config.detection.correlation.rules.len()
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", cr_line, width = inner_width).cyan()
+        );
+    }
+
+    // Scor de risc — alerta Escalation la depasirea nivelurilor.
+    if config.detection.risk.enabled {
+        let tiers: Vec<String> = config.detection.risk.tiers.iter().map(|t| t.to_string()).collect();
+        let rk_line = format!(
+            "  Risc: niveluri {} | injumatatire {}s",
+            tiers.join("/"),
+            config.detection.risk.half_life_secs
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", rk_line, width = inner_width).cyan()
+        );
+    }
+
+    // Ponderi porturi — scor ponderat in loc de numar de porturi.
+    if config.detection.port_weights.enabled {
+        let pw = &config.detection.port_weights;
+        let pw_line = format!(
+            "  Ponderi porturi: {} porturi, {} grupuri (implicit {})",
+            pw.ports.len(),
+            pw.groups.len(),
+            pw.default_weight
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", pw_line, width = inner_width).cyan()
+        );
+    }
+
+    // Profile de detectie per subnet.
+    if !config.detection.profiles.is_empty() {
+        let names: Vec<&str> = config.detection.profiles.iter().map(|p| p.name.as_str()).collect();
+        let pr_line = format!("  Profile detectie: {}", names.join(", "));
+        println!(
+            "{}",
+            format!("║{:<width$}║", pr_line, width = inner_width).cyan()
+        );
+    }
+
     println!("{}", format!("╚{}╝", border).bold().cyan());
     println!();
 }
@@ -178,6 +360,9 @@ pub fn print_separator() {
 
 /// Mesaj informational - badge verde, pentru operatii normale.
 pub fn log_info(message: &str) {
+    if divert("INFO", message) {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} {}",
@@ -189,6 +374,9 @@ pub fn log_info(message: &str) {
 
 /// Avertisment - badge galben, pentru situatii care merita atentie.
 pub fn log_warning(message: &str) {
+    if divert("WARN", message) {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} {}",
@@ -200,6 +388,9 @@ pub fn log_warning(message: &str) {
 
 /// Eroare - badge rosu, pentru esecuri non-fatale.
 pub fn log_error(message: &str) {
+    if divert("ERR", message) {
+        return;
+    }
     let ts = timestamp();
     eprintln!(
         "{} {} {}",
@@ -210,24 +401,31 @@ pub fn log_error(message: &str) {
 }
 
 // ---------------------------------------------------------------------------
-/// Functiile de alerta - cel mai inalt nivel de vizibilitate
-///
-/// Alertele sunt cele mai importante mesaje - trebuie sa fie
-/// imediat vizibile in stream-ul de log. Folosim:
-///   - ROSU cu fundal pentru Fast Scan (urgenta ridicata)
-///   - GALBEN cu fundal pentru Slow Scan (urgenta medie)
-///   - MAGENTA cu fundal pentru Accept Scan (urgenta medie-mica)
-///   - Separatoare colorate si simboluri ▶▶▶ pentru vizibilitate maxima
-///   - Lista de porturi (trunchiate la 25 pentru lizibilitate)
-///
-/// NOTA RUST - PATTERN MATCHING cu `match`:
-/// Match pe enum este exhaustiv - daca adaugi o noua varianta
-/// la ScanType, compilatorul te obliga sa o tratezi AICI.
-/// Nu poti "uita" un caz - eroare la compilare, nu la runtime.
+// Functiile de alerta - cel mai inalt nivel de vizibilitate
+//
+// Alertele sunt cele mai importante mesaje - trebuie sa fie
+// imediat vizibile in stream-ul de log. Folosim:
+//   - culoarea severitatii (rosu aprins 9-10, rosu 8, magenta 7,
+//     galben 6, cyan <=5) — aceeasi severitate ca in CEF si email
+//   - Separatoare colorate si simboluri ▶▶▶ pentru vizibilitate maxima
+//   - Lista de porturi (trunchiate la 25 pentru lizibilitate)
+//
+// NOTA RUST - PATTERN MATCHING cu `match`:
+// Match pe enum este exhaustiv - daca adaugi o noua varianta
+// la ScanType, compilatorul te obliga sa o tratezi AICI.
+// Nu poti "uita" un caz - eroare la compilare, nu la runtime.
 // ---------------------------------------------------------------------------
 
 /// Afiseaza o alerta de securitate cu formatare vizual distincta.
-pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[SubnetEntry]) {
+pub fn log_alert(
+    alert: &Alert,
+    hostnames: &HashMap<IpAddr, String>,
+    subnets: &[SubnetEntry],
+    assets: &AssetInventory,
+) {
+    if muted() {
+        return;
+    }
     let ts = alert
         .timestamp
         .format("[%Y-%m-%d %H:%M:%S]")
@@ -252,60 +450,69 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
 
     let arrows = "▶▶▶";
 
-    let src_display = format_ip(&alert.source_ip, hostnames, subnets);
+    // Culoarea urmeaza severitatea alertei (aceeasi ca in CEF si email),
+    // nu tipul ei — eticheta [FAST SCAN] etc. ramane distincta.
+    let (color, badge_text) = severity_colors(alert.severity.level);
+
+    let mut src_display = format_ip(&alert.source_ip, hostnames, subnets, assets);
+    if alert.source_prefix.is_some() {
+        // Sursa IPv6 agregata: prefixul in locul adresei de retea.
+        src_display = src_display.replacen(&alert.source_ip.to_string(), &alert.source_label(), 1);
+    }
 
     match alert.scan_type {
         ScanType::Fast => {
             println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).red());
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!(
                 "{} {} {} [FAST SCAN] {} | {} porturi unice detectate!",
                 ts.bold().white(),
-                arrows.red().bold(),
-                " ALERT ".on_red().white().bold(),
-                format!("[IP: {}]", src_display).red().bold(),
-                alert.unique_ports.len().to_string().red().bold()
+                arrows.color(color).bold(),
+                " ALERT ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                alert.unique_ports.len().to_string().color(color).bold()
             );
             println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).red());
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!();
         }
         ScanType::Slow => {
             println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).yellow());
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!(
                 "{} {} {} [SLOW SCAN] {} | {} porturi unice detectate!",
                 ts.bold().white(),
-                arrows.yellow().bold(),
-                " ALERT ".on_yellow().black().bold(),
-                format!("[IP: {}]", src_display).yellow().bold(),
-                alert.unique_ports.len().to_string().yellow().bold()
+                arrows.color(color).bold(),
+                " ALERT ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                alert.unique_ports.len().to_string().color(color).bold()
             );
             println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).yellow());
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!();
         }
-        // Accept Scan: magenta — distinct vizual fata de rosu (Fast) si galben (Slow).
-        // Magenta semnalizeaza o amenintare de nivel mediu: traficul este "legitim"
-        // din perspectiva firewall-ului, dar pattern-ul este suspect.
+        // Accept Scan: traficul este "legitim" din perspectiva firewall-ului,
+        // dar pattern-ul este suspect.
         ScanType::AcceptScan => {
             println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).magenta());
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!(
                 "{} {} {} [ACCEPT SCAN] {} | {} porturi deschise accesate!",
                 ts.bold().white(),
-                arrows.magenta().bold(),
-                " ALERT ".on_magenta().white().bold(),
-                format!("[IP: {}]", src_display).magenta().bold(),
-                alert.unique_ports.len().to_string().magenta().bold()
+                arrows.color(color).bold(),
+                " ALERT ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                alert.unique_ports.len().to_string().color(color).bold()
             );
             println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).magenta());
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!();
         }
-        // Lateral Movement: portocaliu (bright_red aproximeaza orange in terminale ANSI).
-        // Cel mai inalt nivel de urgenta vizuala — indica host compromis care se
-        // misca lateral in retea. Afisam destinatii unice in loc de porturi.
+        // Lateral Movement: host compromis care se misca lateral in retea.
+        // Afisam destinatii unice in loc de porturi.
         ScanType::LateralMovement => {
             let dest_list: String = alert
                 .unique_dests
@@ -320,20 +527,21 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
                 String::new()
             };
             println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_red());
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!(
                 "{} {} {} [LATERAL MOVEMENT] {} | {} destinatii unice!",
                 ts.bold().white(),
-                arrows.bright_red().bold(),
-                " ALERT ".on_bright_red().white().bold(),
-                format!("[IP: {}]", src_display).bright_red().bold(),
-                alert.unique_dests.len().to_string().bright_red().bold()
+                arrows.color(color).bold(),
+                " ALERT ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                alert.unique_dests.len().to_string().color(color).bold()
             );
             println!("  Destinatii: {}{}", dest_list, dest_suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_red());
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!();
         }
-        // Distributed Scan: cyan — vizual distinct, indica atac coordonat din surse multiple.
+        // Distributed Scan: atac coordonat din surse multiple.
         // Afisam sursele unice si porturile vizate pe tinta.
         ScanType::DistributedScan => {
             let src_list: String = alert
@@ -349,30 +557,283 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
                 String::new()
             };
             let target_display = match alert.dest_ip {
-                Some(ip) => format_ip(&ip, hostnames, subnets),
+                Some(ip) => format_ip(&ip, hostnames, subnets, assets),
                 None => "N/A".to_string(),
             };
             println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).cyan());
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!(
                 "{} {} {} [DISTRIBUTED SCAN] {} surse → {} | Porturi: {}",
                 ts.bold().white(),
-                arrows.cyan().bold(),
-                " ALERT ".on_cyan().black().bold(),
-                alert.unique_sources.len().to_string().cyan().bold(),
-                format!("[Target: {}]", target_display).cyan().bold(),
-                alert.unique_ports.len().to_string().cyan().bold()
+                arrows.color(color).bold(),
+                " ALERT ".on_color(color).color(badge_text).bold(),
+                alert.unique_sources.len().to_string().color(color).bold(),
+                format!("[Target: {}]", target_display).color(color).bold(),
+                alert.unique_ports.len().to_string().color(color).bold()
             );
             println!("  Surse:   {}{}", src_list, src_suffix);
             println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).cyan());
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        // Threat intel: badge pe fundal alb — nu e o detectie euristica, ci o
+        // potrivire exacta cu o adresa cunoscuta ca malitioasa.
+        ScanType::KnownBadSource | ScanType::KnownBadDestination => {
+            let (label, peer) = match alert.scan_type {
+                ScanType::KnownBadSource => ("KNOWN BAD SOURCE", src_display.clone()),
+                _ => (
+                    "KNOWN BAD DESTINATION",
+                    match alert.dest_ip {
+                        Some(ip) => format_ip(&ip, hostnames, subnets, assets),
+                        None => "N/A".to_string(),
+                    },
+                ),
+            };
+            let (feed, confidence, indicator) = match alert.threat_intel {
+                Some(ref m) => (m.feed.as_str(), m.confidence, m.indicator.as_str()),
+                None => ("N/A", 0, "N/A"),
+            };
+            println!();
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [{}] {} | feed {} (incredere {}%)",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " IOC ".on_white().color(color).bold(),
+                label,
+                format!("[IP: {}]", peer).color(color).bold(),
+                feed.bold(),
+                confidence
+            );
+            println!("  Sursa: {}  Indicator: {}", src_display, indicator);
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
             println!();
         }
+        // Lant de atac: cel mai vizibil bloc — compromitere probabila.
+        ScanType::AttackChain => {
+            let rule = alert.chain.as_ref().map(|c| c.rule.as_str()).unwrap_or("N/A");
+            println!();
+            println!("{}", "═".repeat(SEPARATOR_WIDTH).color(color).bold());
+            println!(
+                "{} {} {} [ATTACK CHAIN] {} | regula {}",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " CHAIN ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                rule.bold()
+            );
+            if let Some(ref c) = alert.chain {
+                for (i, step) in c.steps.iter().enumerate() {
+                    let target = match (step.dest, step.port) {
+                        (Some(d), Some(p)) => format!(" → {}:{}", format_ip(&d, hostnames, subnets, assets), p),
+                        (Some(d), None) => format!(" → {}", format_ip(&d, hostnames, subnets, assets)),
+                        _ => String::new(),
+                    };
+                    println!(
+                        "  {}. {} {} {}{}",
+                        i + 1,
+                        step.timestamp.format("%H:%M:%S").to_string().dimmed(),
+                        step.label.bold(),
+                        format_ip(&step.source, hostnames, subnets, assets),
+                        target
+                    );
+                }
+            }
+            print_context(alert, assets);
+            println!("{}", "═".repeat(SEPARATOR_WIDTH).color(color).bold());
+            println!();
+        }
+        // Escaladare: mai multe detectii ale aceleiasi surse.
+        ScanType::Escalation => {
+            let (tier, score) = alert
+                .risk
+                .as_ref()
+                .map(|r| (r.tier, r.score))
+                .unwrap_or_default();
+            println!();
+            println!("{}", "═".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [RISK TIER {}] {} | scor risc {:.1}",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " ESCALATION ".on_color(color).color(badge_text).bold(),
+                tier,
+                format!("[IP: {}]", src_display).color(color).bold(),
+                score
+            );
+            if let Some(ref r) = alert.risk {
+                for entry in r.chain.iter() {
+                    println!(
+                        "  {} {} (+{})",
+                        entry.timestamp.format("%H:%M:%S").to_string().dimmed(),
+                        entry.scan_type,
+                        entry.points
+                    );
+                }
+            }
+            print_context(alert, assets);
+            println!("{}", "═".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        // Anomalie: abatere statistica, nu semnatura de atac.
+        ScanType::Anomaly => {
+            let detail = match alert.anomaly {
+                Some(ref a) => format!(
+                    "{} = {:.2} (normal {:.2} ± {:.2}, {:.1} sigma)",
+                    a.metric, a.observed, a.mean, a.stddev, a.sigmas
+                ),
+                None => "N/A".to_string(),
+            };
+            let key = alert
+                .anomaly
+                .as_ref()
+                .map(|a| a.baseline_key.as_str())
+                .unwrap_or("N/A");
+            println!();
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [ANOMALY] {} | {}",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " ALERT ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                detail.color(color).bold()
+            );
+            println!("  Baseline: {}  Porturi: {}{}", key, port_list, suffix);
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        // Brute Force: accept-uri (serviciul raspunde) sau drop-uri repetate pe un port.
+        ScanType::BruteForce => {
+            let dst_display = alert
+                .dest_ip
+                .map(|ip| format_ip(&ip, hostnames, subnets, assets))
+                .unwrap_or_else(|| "N/A".to_string());
+            let (count, accepted, window) = alert
+                .flood
+                .as_ref()
+                .map(|f| (f.count, f.accepted, f.window_secs))
+                .unwrap_or_default();
+            let action = if accepted { "acceptate" } else { "blocate" };
+            println!();
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [BRUTE FORCE] {} → {}:{} | {} conexiuni {} in {}s",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " FLOOD ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                dst_display,
+                port_list,
+                count.to_string().color(color).bold(),
+                action,
+                window
+            );
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        // Beaconing: trafic periodic catre acelasi tuplu (C2).
+        ScanType::Beaconing => {
+            let dst_display = alert
+                .dest_ip
+                .map(|ip| format_ip(&ip, hostnames, subnets, assets))
+                .unwrap_or_else(|| "N/A".to_string());
+            let detail = match alert.beacon {
+                Some(ref b) => format!(
+                    "{} conexiuni la {:.1}s (jitter {:.1}%) in {}s",
+                    b.connections, b.mean_interval_secs, b.jitter * 100.0, b.duration_secs
+                ),
+                None => "N/A".to_string(),
+            };
+            println!();
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [BEACONING] {} → {}:{}",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " C2 ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                dst_display.color(color).bold(),
+                port_list
+            );
+            println!("  {}", detail);
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        // Ping Sweep: recunoastere ICMP, de obicei preludiul unei scanari.
+        ScanType::PingSweep => {
+            let dest_list: String = alert
+                .unique_dests
+                .iter()
+                .take(25)
+                .map(|ip| ip.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let dest_suffix = if alert.unique_dests.len() > 25 {
+                format!(" ... (+{} more)", alert.unique_dests.len() - 25)
+            } else {
+                String::new()
+            };
+            println!();
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [PING SWEEP] {} | {} destinatii sondate ICMP",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " ICMP ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                alert.unique_dests.len().to_string().color(color).bold()
+            );
+            println!("  Destinatii: {}{}", dest_list, dest_suffix);
+            print_context(alert, assets);
+            println!("{}", "─".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        ScanType::PersistentScanner => {
+            let (nth, window) = alert
+                .offence
+                .as_ref()
+                .map(|o| (o.nth, o.window_hours))
+                .unwrap_or_default();
+            println!();
+            println!("{}", "═".repeat(SEPARATOR_WIDTH).color(color));
+            println!(
+                "{} {} {} [PERSISTENT SCANNER] {} | {} alerte in {}h",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " REPEAT ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                nth.to_string().color(color).bold(),
+                window
+            );
+            print_context(alert, assets);
+            println!("{}", "═".repeat(SEPARATOR_WIDTH).color(color));
+            println!();
+        }
+        // Alerta de test: o singura linie, fara context — nu e o detectie.
+        ScanType::TestAlert => {
+            println!(
+                "{} {} {} [TEST ALERT] {} → {} | trimisa prin toate destinatiile",
+                ts.bold().white(),
+                arrows.color(color).bold(),
+                " TEST ".on_color(color).color(badge_text).bold(),
+                format!("[IP: {}]", src_display).color(color).bold(),
+                alert.dest_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "N/A".to_string())
+            );
+        }
     }
 }
 
 /// Confirma ca o alerta a fost transmisa cu succes (verde subtil).
 pub fn log_alert_sent(destination: &str, alert_type: &str) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} Alert '{}' transmis -> {}",
@@ -399,7 +860,11 @@ pub fn log_firewall_event(
     action: &str,
     hostnames: &HashMap<IpAddr, String>,
     subnets: &[SubnetEntry],
+    assets: &AssetInventory,
 ) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
     // Badge dinamic: albastru pentru drop, verde pentru accept.
     // NOTA RUST: `if-else` ca expresie — returneaza ColoredString din ambele ramuri.
@@ -413,7 +878,7 @@ pub fn log_firewall_event(
         "{} {} Src={} DstPort={} Proto={} Action={}",
         ts.dimmed(),
         badge,
-        format_ip(ip, hostnames, subnets).bright_blue(),
+        format_ip(ip, hostnames, subnets, assets).bright_blue(),
         format!("{}", port).bright_blue(),
         protocol.bright_blue(),
         action.bright_blue()
@@ -424,6 +889,9 @@ pub fn log_firewall_event(
 ///
 /// Format: [timestamp] [STAT] 42 IP-uri urmarite | Cleanup: 5 sterse
 pub fn log_stats(tracked_ips: usize, cleaned_ips: usize) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} {} IP-uri urmarite | Cleanup: {} sterse",
@@ -434,8 +902,57 @@ pub fn log_stats(tracked_ips: usize, cleaned_ips: usize) {
     );
 }
 
+/// Numarul de reguli afisate in linia periodica RULE.
+const TOP_RULES: usize = 5;
+
+/// Afiseaza contoarele celor mai lovite reguli de firewall (apelat din cleanup task).
+///
+/// Format: [timestamp] [RULE] 9 (Cleanup rule): 1200 drop / 0 accept / 14 alerte | ...
+pub fn log_rule_stats(stats: &[(String, RuleCounter)]) {
+    if muted() {
+        return;
+    }
+    let ts = timestamp();
+    let top = stats
+        .iter()
+        .take(TOP_RULES)
+        .map(|(label, c)| {
+            format!("{}: {} drop / {} accept / {} alerte", label.bold(), c.drops, c.accepts, c.alerts)
+        })
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let more = if stats.len() > TOP_RULES {
+        format!(" (+{} reguli)", stats.len() - TOP_RULES)
+    } else {
+        String::new()
+    };
+    println!("{} {} {}{}", ts.dimmed(), " RULE ".on_cyan().black().bold(), top, more.dimmed());
+}
+
+/// Afiseaza rezumatul unei sesiuni de scanare inchise (apelat din cleanup task).
+pub fn log_session(session: &SessionSummary) {
+    let who = match session.kind {
+        SessionKind::Source => format!("sursa {}", session.ip),
+        SessionKind::Target => format!("tinta {}", session.ip),
+    };
+    if divert("SESS", &format!("Sesiune inchisa, {}: {}", who, session.summary())) {
+        return;
+    }
+    let ts = timestamp();
+    println!(
+        "{} {} Sesiune inchisa, {}: {}",
+        ts.dimmed(),
+        " SESS ".on_magenta().black().bold(),
+        who.bold(),
+        session.summary()
+    );
+}
+
 /// Confirma reincarcarea configuratiei la SIGHUP (badge cyan).
 pub fn log_reload(message: &str) {
+    if divert("LOAD", message) {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} {}",
@@ -450,6 +967,9 @@ pub fn log_reload(message: &str) {
 /// Format: [timestamp] [ RATE ] 1234 pachete UDP dropate (rate limit)
 /// Badge galben — vizibilitate medie, nu este o eroare ci protectie activa.
 pub fn log_rate_limited(dropped: u64) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} {} pachete UDP dropate (rate limit)",
@@ -465,6 +985,9 @@ pub fn log_rate_limited(dropped: u64) {
 
 /// Afiseaza linia raw primita pe port (mod debug).
 pub fn log_debug_raw(line: &str) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} {}",
@@ -476,20 +999,67 @@ pub fn log_debug_raw(line: &str) {
 
 /// Afiseaza confirmarea parsarii reusite cu campurile extrase (mod debug).
 pub fn log_debug_parse_ok(event: &LogEvent) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
+    // ICMP nu are port: afisam tipul/codul ("-" daca log-ul nu le contine).
+    let target = if event.is_icmp() {
+        let part = |v: Option<u8>| v.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+        format!("icmp={}/{}", part(event.icmp_type), part(event.icmp_code))
+    } else {
+        format!("dpt={}", event.dest_port)
+    };
+    // Campurile optionale (port sursa, flag-uri, NAT, regula, interfata) —
+    // afisate doar cand log-ul le contine.
+    let mut extra = String::new();
+    if let Some(spt) = event.source_port {
+        extra.push_str(&format!(" spt={}", spt));
+    }
+    if let Some(ref flags) = event.tcp_flags {
+        extra.push_str(&format!(" flags={}", flags));
+    }
+    if let Some(ip) = event.xlate_src {
+        extra.push_str(&format!(" xlatesrc={}", ip));
+    }
+    if let Some(ip) = event.xlate_dst {
+        extra.push_str(&format!(" xlatedst={}", ip));
+    }
+    if let Some(port) = event.xlate_sport {
+        extra.push_str(&format!(" xlatesport={}", port));
+    }
+    if let Some(port) = event.xlate_dport {
+        extra.push_str(&format!(" xlatedport={}", port));
+    }
+    if let Some(ref rule) = event.rule {
+        extra.push_str(&format!(" rule={}", rule));
+    }
+    if let Some(ref name) = event.rule_name {
+        extra.push_str(&format!(" rule_name=\"{}\"", name));
+    }
+    if let Some(ref uid) = event.rule_uid {
+        extra.push_str(&format!(" rule_uid={}", uid));
+    }
+    if let Some(ref iface) = event.interface {
+        extra.push_str(&format!(" if={}", iface));
+    }
     println!(
-        "{} {}  src={} dpt={} proto={} action={}",
+        "{} {}  src={} {} proto={} action={}{}",
         ts.bold().white(),
         "  OK  ".on_green().black().bold(),
         event.source_ip.to_string().green(),
-        event.dest_port.to_string().green(),
+        target.green(),
         event.protocol.green(),
-        event.action.green()
+        event.action.green(),
+        extra.dimmed()
     );
 }
 
 /// Afiseaza detalii despre esecul parsarii (mod debug).
 pub fn log_debug_parse_fail(line: &str, parser_name: &str, expected: &str) {
+    if muted() {
+        return;
+    }
     let ts = timestamp();
     println!(
         "{} {} Parsare esuata! (parser: {})",
@@ -522,12 +1092,195 @@ fn timestamp() -> String {
     Local::now().format("[%Y-%m-%d %H:%M:%S]").to_string()
 }
 
-/// Formateaza un IP cu hostname si/sau locatie subnet.
-/// Format: "IP (hostname) [Etaj 1]", "IP [Etaj 1]", "IP (hostname)", sau doar "IP".
-fn format_ip(ip: &IpAddr, hostnames: &HashMap<IpAddr, String>, subnets: &[SubnetEntry]) -> String {
-    let hostname = hostnames.get(ip).map(|s| format!(" ({})", s)).unwrap_or_default();
+// ---------------------------------------------------------------------------
+// `ids-rs alerts` — afisarea istoricului de alerte
+// ---------------------------------------------------------------------------
+
+/// Un IP din istoric cu hostname-ul si locatia salvate la momentul alertei.
+fn format_record_ip(ip: &str, host: &Option<String>, subnet: &Option<String>) -> String {
+    let mut out = ip.to_string();
+    if let Some(ref host) = host {
+        out.push_str(&format!(" ({})", host));
+    }
+    if let Some(ref subnet) = subnet {
+        out.push_str(&format!(" [{}]", subnet));
+    }
+    out
+}
+
+/// Lista `ids-rs alerts list`: o linie per alerta, cele mai noi primele.
+///
+/// Format: #42  2026-10-18 09:12:03  sev 8  fast_scan  10.0.5.20 (ws-05) [Etaj 2] → 10.0.1.10
+///             Fast Scan detectat: 40 porturi unice in 10 secunde
+pub fn print_history_list(records: &[AlertRecord]) {
+    if records.is_empty() {
+        println!("{}", "Nicio alerta in istoric pentru filtrele date.".dimmed());
+        return;
+    }
+    for record in records {
+        let (color, _) = severity_colors(record.severity);
+        let dest = match record.dest {
+            Some(ref dest) => format!(" → {}", format_record_ip(dest, &record.dest_host, &record.dest_subnet)),
+            None => String::new(),
+        };
+        println!(
+            "{} {} {} {} {}{}",
+            format!("#{:<6}", record.id).bold(),
+            record.timestamp.format("%Y-%m-%d %H:%M:%S").to_string().dimmed(),
+            format!("sev {:>2}", record.severity).color(color).bold(),
+            format!("{:<22}", record.scan_type).color(color),
+            format_record_ip(&record.source, &record.source_host, &record.source_subnet),
+            dest,
+        );
+        println!("        {}", record.summary.dimmed());
+    }
+    println!("{}", format!("{} alerte afisate", records.len()).dimmed());
+}
+
+/// Detaliile complete ale unei alerte (`ids-rs alerts show <id>`).
+pub fn print_history_record(record: &AlertRecord) {
+    let (color, _) = severity_colors(record.severity);
+    println!(
+        "{} {} — {}",
+        format!(" ALERTA #{} ", record.id).on_color(color).white().bold(),
+        record.scan_type.color(color).bold(),
+        record.timestamp.format("%Y-%m-%d %H:%M:%S"),
+    );
+    println!("  Severitate: {}", record.severity.to_string().bold());
+    println!(
+        "  Sursa: {}",
+        format_record_ip(&record.source, &record.source_host, &record.source_subnet).bold()
+    );
+    if let Some(ref dest) = record.dest {
+        println!(
+            "  Destinatie: {}",
+            format_record_ip(dest, &record.dest_host, &record.dest_subnet).bold()
+        );
+    }
+    println!("  Descriere: {}", record.summary);
+    if !record.related.is_empty() {
+        println!("  Destinatii/surse unice ({}): {}", record.related.len(), record.related.join(", "));
+    }
+    for (label, value) in &record.details {
+        println!("  {}: {}", label, value);
+    }
+}
+
+/// Totalul si defalcarea pe tip / sursa (`ids-rs alerts count`).
+pub fn print_history_counts(counts: &HistoryCounts) {
+    println!("Total alerte: {}", counts.total.to_string().bold());
+    if let (Some(first), Some(last)) = (counts.first, counts.last) {
+        println!(
+            "Interval: {} — {}",
+            first.format("%Y-%m-%d %H:%M:%S"),
+            last.format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+    if !counts.by_type.is_empty() {
+        println!("\nPe tip:");
+        for (scan_type, n) in &counts.by_type {
+            println!("  {:<24} {}", scan_type, n);
+        }
+    }
+    if !counts.top_sources.is_empty() {
+        println!("\nTop surse:");
+        for (source, n) in &counts.top_sources {
+            println!("  {:<40} {}", source, n);
+        }
+    }
+}
+
+/// Liniile de context ale alertei: profilul `[[detection.profiles]]` folosit,
+/// defalcarea pe protocol (doar scanari mixte TCP + UDP), unealta probabila,
+/// adresele NAT, regulile de firewall lovite, recidiva sursei, activul tinta cel mai critic din
+/// inventar si scorul ponderat al porturilor. Absente pentru pragurile globale / fara ponderi.
+fn print_context(alert: &Alert, assets: &AssetInventory) {
+    if let Some(ref o) = alert.ongoing {
+        println!("  {}: {}", o.label().yellow().bold(), o.summary());
+    }
+    if let Some(ref name) = alert.profile {
+        println!("  Profil detectie: {}", name.cyan());
+    }
+    if alert.protocols.len() > 1 {
+        println!("  Protocoale: {}", alert.protocol_summary(", "));
+    }
+    if let Some(ref fp) = alert.fingerprint {
+        println!("  Unealta probabila: {} ({})", fp.tool.magenta().bold(), fp.evidence.dimmed());
+    }
+    if let Some(ref nat) = alert.nat {
+        println!("  NAT: {}", nat.summary().cyan());
+    }
+    if !alert.rules.is_empty() {
+        println!("  Reguli firewall: {}", alert.rules.join(", ").yellow());
+    }
+    if let Some(ref offence) = alert.offence {
+        if offence.is_repeat() {
+            println!("  Recidiva: {}", offence.summary().yellow());
+            if let Some(last) = offence.last_seen {
+                println!("  Alerta anterioara: {}", last.format("%Y-%m-%d %H:%M:%S"));
+            }
+        }
+    }
+    if !alert.severity.factors.is_empty() {
+        println!(
+            "  Severitate: {} ({})",
+            alert.severity.level.to_string().bold(),
+            alert.severity.factors.join(", ")
+        );
+    }
+    if let Some(target) = assets.most_critical(alert.targets()) {
+        println!("  Activ tinta: {}", target.summary().bold());
+    }
+    if let Some(score) = alert.score {
+        let sensitive = alert
+            .sensitive_ports
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if sensitive.is_empty() {
+            println!("  Scor ponderat: {:.2}", score);
+        } else {
+            println!(
+                "  Scor ponderat: {:.2}  Porturi sensibile: {}",
+                score,
+                sensitive.red().bold()
+            );
+        }
+    }
+}
+
+/// Culoarea alertei si a textului din badge dupa severitate:
+/// 9-10 rosu aprins, 8 rosu, 7 magenta, 6 galben, <=5 cyan.
+fn severity_colors(level: u8) -> (Color, Color) {
+    match level {
+        9.. => (Color::BrightRed, Color::White),
+        8 => (Color::Red, Color::White),
+        7 => (Color::Magenta, Color::White),
+        6 => (Color::Yellow, Color::Black),
+        _ => (Color::Cyan, Color::Black),
+    }
+}
+
+/// Formateaza un IP cu hostname, locatie subnet si eticheta din inventar.
+/// Format: "IP (hostname) [Etaj 1] {Infra · critical · server}", cu partile
+/// lipsa omise. Hostname-ul din `[network.hostnames]` are prioritate fata de
+/// cel din inventar.
+fn format_ip(
+    ip: &IpAddr,
+    hostnames: &HashMap<IpAddr, String>,
+    subnets: &[SubnetEntry],
+    assets: &AssetInventory,
+) -> String {
+    let asset = assets.lookup(ip);
+    let hostname = hostnames
+        .get(ip)
+        .or_else(|| asset.and_then(|a| a.hostname.as_ref()))
+        .map(|s| format!(" ({})", s))
+        .unwrap_or_default();
     let location = SubnetEntry::lookup(subnets, ip)
         .map(|l| format!(" [{}]", l))
         .unwrap_or_default();
-    format!("{}{}{}", ip, hostname, location)
+    let tag = asset.map(|a| format!(" {{{}}}", a.tag())).unwrap_or_default();
+    format!("{}{}{}{}", ip, hostname, location, tag)
 }
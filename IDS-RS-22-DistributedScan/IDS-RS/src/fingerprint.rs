@@ -0,0 +1,250 @@
+// =============================================================================
+// fingerprint.rs - Amprentarea uneltei de scanare (port sursa + flag-uri TCP)
+// =============================================================================
+//
+// Detectoarele spun CA o sursa scaneaza; amprenta incearca sa spuna CU CE.
+// Uneltele de scanare lasa urme caracteristice in pachetele pe care le trimit:
+//
+//   - NULL / FIN / Xmas scan: pachete TCP fara SYN, cu combinatii de flag-uri
+//     pe care nicio stiva TCP normala nu le trimite (niciun flag, doar FIN,
+//     FIN+PSH+URG). Flag-urile singure identifica tehnica.
+//   - nmap SYN scan (-sS): pachetele raw refolosesc ACELASI port sursa pentru
+//     toate porturile sondate.
+//   - masscan: porturi sursa aleatoare, fara ordine.
+//   - connect scan (nmap -sT, scripturi): stiva OS aloca porturi efemere
+//     consecutive — diferente mici, crescatoare, intre pachete succesive.
+//
+// Clasificarea este euristica: opereaza pe hit-urile scanarii din fereastra
+// (port sursa si flag-uri, cand log-ul le contine) si cere un minim de
+// esantioane si o majoritate clara. Fara dovezi suficiente → None.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. BITFLAGS MANUALE
+//    Flag-urile TCP sunt biti dintr-un octet (FIN = 0x01, SYN = 0x02, ...).
+//    Combinatiile se construiesc cu `|` si se testeaza cu egalitate sau `&`,
+//    fara crate extern — un `u8` ocupa un singur octet in PortHit.
+//
+// 2. `&'static str`
+//    Numele uneltelor sunt literali compilati in binar: referinte valabile pe
+//    toata durata programului, fara alocare per alerta.
+//
+// =============================================================================
+
+use std::collections::HashMap;
+
+pub const FIN: u8 = 0x01;
+pub const SYN: u8 = 0x02;
+pub const RST: u8 = 0x04;
+pub const PSH: u8 = 0x08;
+pub const ACK: u8 = 0x10;
+pub const URG: u8 = 0x20;
+
+/// Numarul minim de esantioane (hit-uri cu port sursa / flag-uri) pentru
+/// o clasificare — sub el, orice tipar poate fi coincidenta.
+const MIN_SAMPLES: usize = 5;
+
+/// Proportia minima a tiparului dominant (80%).
+const DOMINANT_SHARE: f64 = 0.8;
+
+/// Diferenta maxima intre porturi sursa succesive considerata "consecutiva"
+/// (stiva OS; alte conexiuni ale host-ului pot consuma porturi intre ele).
+const EPHEMERAL_STEP: u16 = 64;
+
+/// Unealta / tehnica probabila a unei scanari.
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    /// Eticheta scurta (ex: "nmap SYN scan") — in CEF flexString1.
+    pub tool: &'static str,
+    /// Dovada pe care se bazeaza eticheta (ex: "port sursa fix 40000 pe 18/20").
+    pub evidence: String,
+}
+
+/// Un hit al scanarii, asa cum il vede clasificatorul.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub source_port: Option<u16>,
+    pub flags: Option<u8>,
+}
+
+/// Interpreteaza flag-urile TCP din log: nume ("SYN,ACK", "FIN PSH URG"),
+/// litere ("S", "FPU") sau "NULL"/"NONE" pentru niciun flag.
+/// Returneaza None pentru un text gol sau fara niciun flag recunoscut.
+pub fn parse_tcp_flags(raw: &str) -> Option<u8> {
+    let mut flags = 0u8;
+    let mut recognized = false;
+    for token in raw.split(|c: char| !c.is_ascii_alphanumeric()).filter(|t| !t.is_empty()) {
+        let bit = match token.to_ascii_uppercase().as_str() {
+            "FIN" => FIN,
+            "SYN" => SYN,
+            "RST" | "RESET" => RST,
+            "PSH" | "PUSH" => PSH,
+            "ACK" => ACK,
+            "URG" => URG,
+            "NULL" | "NONE" => 0,
+            letters => {
+                // Forma compacta: fiecare litera este un flag (ex: "SA", "FPU").
+                let mut bits = 0u8;
+                for c in letters.chars() {
+                    bits |= match c {
+                        'F' => FIN,
+                        'S' => SYN,
+                        'R' => RST,
+                        'P' => PSH,
+                        'A' => ACK,
+                        'U' => URG,
+                        _ => return None,
+                    };
+                }
+                bits
+            }
+        };
+        flags |= bit;
+        recognized = true;
+    }
+    recognized.then_some(flags)
+}
+
+/// Clasifica o scanare dupa esantioanele ei (in ordinea sosirii).
+///
+/// Ordinea regulilor: flag-urile (cele mai specifice), apoi portul sursa.
+pub fn classify(samples: &[Sample]) -> Option<Fingerprint> {
+    classify_flags(samples).or_else(|| classify_source_ports(samples))
+}
+
+/// NULL / FIN / Xmas scan — tiparul dominant al flag-urilor.
+fn classify_flags(samples: &[Sample]) -> Option<Fingerprint> {
+    let flags: Vec<u8> = samples.iter().filter_map(|s| s.flags).collect();
+    if flags.len() < MIN_SAMPLES {
+        return None;
+    }
+    let (value, count) = dominant(&flags)?;
+    let tool = match value {
+        0 => "NULL scan",
+        FIN => "FIN scan",
+        v if v == FIN | PSH | URG => "Xmas scan",
+        _ => return None,
+    };
+    Some(Fingerprint {
+        tool,
+        evidence: format!("flag-uri {} pe {}/{} pachete", format_flags(value), count, flags.len()),
+    })
+}
+
+/// nmap SYN / masscan / connect scan — tiparul porturilor sursa.
+fn classify_source_ports(samples: &[Sample]) -> Option<Fingerprint> {
+    let ports: Vec<u16> = samples.iter().filter_map(|s| s.source_port).collect();
+    if ports.len() < MIN_SAMPLES {
+        return None;
+    }
+    let total = ports.len();
+
+    if let Some((port, count)) = dominant(&ports) {
+        return Some(Fingerprint {
+            tool: "nmap SYN scan",
+            evidence: format!("port sursa fix {} pe {}/{} pachete", port, count, total),
+        });
+    }
+
+    let mut unique = ports.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    if (unique.len() as f64) < total as f64 * 0.9 {
+        return None;
+    }
+
+    let steps = ports.windows(2).count();
+    let sequential = ports
+        .windows(2)
+        .filter(|w| (1..=EPHEMERAL_STEP).contains(&w[1].wrapping_sub(w[0])))
+        .count();
+    if sequential as f64 >= steps as f64 * 0.7 {
+        Some(Fingerprint {
+            tool: "connect scan",
+            evidence: format!("porturi sursa efemere consecutive ({} pachete)", total),
+        })
+    } else {
+        Some(Fingerprint {
+            tool: "masscan",
+            evidence: format!("porturi sursa aleatoare ({} unice din {})", unique.len(), total),
+        })
+    }
+}
+
+/// Valoarea cea mai frecventa, daca depaseste `DOMINANT_SHARE`.
+fn dominant<T: Copy + Eq + std::hash::Hash>(values: &[T]) -> Option<(T, usize)> {
+    let mut counts: HashMap<T, usize> = HashMap::new();
+    for v in values {
+        *counts.entry(*v).or_default() += 1;
+    }
+    let (value, count) = counts.into_iter().max_by_key(|(_, n)| *n)?;
+    (count as f64 >= values.len() as f64 * DOMINANT_SHARE).then_some((value, count))
+}
+
+/// Forma lizibila a unui octet de flag-uri (ex: "FIN+PSH+URG", "niciunul").
+fn format_flags(flags: u8) -> String {
+    let names: Vec<&str> = [(FIN, "FIN"), (SYN, "SYN"), (RST, "RST"), (PSH, "PSH"), (ACK, "ACK"), (URG, "URG")]
+        .iter()
+        .filter(|(bit, _)| flags & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    if names.is_empty() {
+        "niciunul".to_string()
+    } else {
+        names.join("+")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ports(list: &[u16]) -> Vec<Sample> {
+        list.iter()
+            .map(|p| Sample { source_port: Some(*p), flags: parse_tcp_flags("SYN") })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_tcp_flags_forms() {
+        assert_eq!(parse_tcp_flags("SYN"), Some(SYN));
+        assert_eq!(parse_tcp_flags("FIN,PSH,URG"), Some(FIN | PSH | URG));
+        assert_eq!(parse_tcp_flags("SA"), Some(SYN | ACK));
+        assert_eq!(parse_tcp_flags("NULL"), Some(0));
+        assert_eq!(parse_tcp_flags(""), None);
+        assert_eq!(parse_tcp_flags("xyz"), None);
+    }
+
+    #[test]
+    fn test_xmas_and_null_scans() {
+        let xmas: Vec<Sample> = (0..6)
+            .map(|_| Sample { source_port: None, flags: parse_tcp_flags("FPU") })
+            .collect();
+        assert_eq!(classify(&xmas).unwrap().tool, "Xmas scan");
+
+        let null: Vec<Sample> = (0..6)
+            .map(|_| Sample { source_port: None, flags: Some(0) })
+            .collect();
+        assert_eq!(classify(&null).unwrap().tool, "NULL scan");
+    }
+
+    #[test]
+    fn test_source_port_patterns() {
+        let fixed = ports(&[40000, 40000, 40000, 40000, 40000, 40000]);
+        let fp = classify(&fixed).unwrap();
+        assert_eq!(fp.tool, "nmap SYN scan");
+        assert!(fp.evidence.contains("40000"));
+
+        let sequential = ports(&[51000, 51001, 51003, 51004, 51006, 51007]);
+        assert_eq!(classify(&sequential).unwrap().tool, "connect scan");
+
+        let random = ports(&[61234, 1055, 33012, 48777, 20311, 59001]);
+        assert_eq!(classify(&random).unwrap().tool, "masscan");
+    }
+
+    #[test]
+    fn test_too_few_samples_unclassified() {
+        assert!(classify(&ports(&[40000, 40000, 40000])).is_none());
+        assert!(classify(&[]).is_none());
+    }
+}
@@ -0,0 +1,632 @@
+// =============================================================================
+// history.rs - Istoricul local al alertelor (SQLite) si interogarea lui
+// =============================================================================
+//
+// Dupa ce o alerta pleaca spre SIEM si email, IDS-RS nu mai pastra nimic —
+// la intrebarea "a mai alertat IP-ul asta?" analistul trebuia sa caute in
+// ArcSight. Fiecare alerta, cu tot contextul ei (hostname, locatie, activ,
+// severitate, NAT, reguli...), este scrisa intr-o baza SQLite locala si poate
+// fi interogata direct pe masina:
+//
+//   ids-rs alerts list  --ip 10.0.5.20 --since 7d
+//   ids-rs alerts list  --type fast_scan --subnet "Etaj 2" --limit 20
+//   ids-rs alerts show  42
+//   ids-rs alerts count --since 2026-10-01 --until 2026-10-08
+//
+// Schema (o singura tabela, `alerts`):
+//
+//   id, ts (ms Unix), scan_type (cheia: fast_scan, ...), severity,
+//   source / source_host / source_subnet, dest / dest_host / dest_subnet,
+//   related (",ip,ip," — destinatiile sau sursele unice),
+//   summary (descrierea din CEF msg), details (JSON: [[eticheta, valoare], ...])
+//
+// Contextul este salvat asa cum era la momentul alertei — un hostname sau un
+// subnet redenumit ulterior nu rescrie istoricul.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. Mutex<Connection>
+//    `rusqlite::Connection` este Send dar nu Sync — o singura conexiune nu
+//    poate fi folosita simultan din doua thread-uri. Mutex-ul serializeaza
+//    accesul; scrierile sunt rare (una per alerta) si scurte.
+//
+// 2. params_from_iter + rusqlite::types::Value
+//    Filtrele sunt optionale, deci clauza WHERE se construieste dinamic.
+//    Valorile merg separat, ca parametri `?N` — niciodata concatenate in
+//    SQL (un label de subnet cu ghilimele nu poate injecta SQL).
+//
+// =============================================================================
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OpenFlags, Row};
+use std::net::{IpAddr, Ipv6Addr};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Limita implicita de randuri pentru `ids-rs alerts list`.
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// Prefixele sub care o sursa IPv6 poate fi agregata (`ipv6_source_prefix`,
+/// validat 32 – 128; /128 inseamna adresa completa, fara sufix).
+const IPV6_SOURCE_PREFIXES: std::ops::RangeInclusive<u8> = 32..=127;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS alerts (
+        id            INTEGER PRIMARY KEY AUTOINCREMENT,
+        ts            INTEGER NOT NULL,
+        scan_type     TEXT    NOT NULL,
+        severity      INTEGER NOT NULL,
+        source        TEXT    NOT NULL,
+        source_host   TEXT,
+        source_subnet TEXT,
+        dest          TEXT,
+        dest_host     TEXT,
+        dest_subnet   TEXT,
+        related       TEXT    NOT NULL DEFAULT '',
+        summary       TEXT    NOT NULL,
+        details       TEXT    NOT NULL DEFAULT '[]'
+    );
+    CREATE INDEX IF NOT EXISTS idx_alerts_ts        ON alerts (ts);
+    CREATE INDEX IF NOT EXISTS idx_alerts_source    ON alerts (source);
+    CREATE INDEX IF NOT EXISTS idx_alerts_dest      ON alerts (dest);
+    CREATE INDEX IF NOT EXISTS idx_alerts_scan_type ON alerts (scan_type);
+";
+
+/// O alerta asa cum este salvata in istoric.
+#[derive(Debug, Clone)]
+pub struct AlertRecord {
+    /// Atribuit de SQLite la insert (0 pana atunci).
+    pub id: i64,
+    pub timestamp: DateTime<Local>,
+    /// Cheia tipului (`ScanType::key`), ex: "fast_scan".
+    pub scan_type: String,
+    pub severity: u8,
+    /// IP-ul sursa (sau prefixul IPv6 agregat, ex: "2001:db8::/64").
+    pub source: String,
+    pub source_host: Option<String>,
+    pub source_subnet: Option<String>,
+    pub dest: Option<String>,
+    pub dest_host: Option<String>,
+    pub dest_subnet: Option<String>,
+    /// Destinatiile unice (Lateral Movement, Ping Sweep) sau sursele unice
+    /// (Distributed Scan) — cautate si ele de filtrul `--ip`.
+    pub related: Vec<String>,
+    /// Descrierea alertei, ca in campul `msg` din CEF.
+    pub summary: String,
+    /// Randurile de context, ca in tabelul din email.
+    pub details: Vec<(String, String)>,
+}
+
+/// Filtrele `ids-rs alerts` — toate optionale, combinate cu AND.
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    /// IP-ul cautat ca sursa, destinatie sau in lista de destinatii/surse unice.
+    pub ip: Option<String>,
+    pub scan_type: Option<String>,
+    /// Eticheta de subnet a sursei sau a destinatiei (ex: "Etaj 2").
+    pub subnet: Option<String>,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+}
+
+impl HistoryFilter {
+    /// Clauza WHERE si parametrii ei.
+    fn where_clause(&self) -> (String, Vec<Value>) {
+        let mut clauses = Vec::new();
+        let mut values = Vec::new();
+        if let Some(ref ip) = self.ip {
+            values.push(Value::Text(ip.clone()));
+            let n = values.len();
+            // `%` si `_` din valoare sunt caractere literale, nu wildcard-uri.
+            values.push(Value::Text(ip.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+            let mut clause = format!(
+                "source = ?{n} OR dest = ?{n} OR related LIKE '%,' || ?{} || ',%' ESCAPE '\\'",
+                n + 1
+            );
+            // O sursa IPv6 agregata este salvata ca "2001:db8:1:2::/64" — cautam
+            // prefixul adresei pentru orice `ipv6_source_prefix`, inclusiv unul
+            // configurat anterior.
+            let labels = ipv6_source_labels(ip);
+            if !labels.is_empty() {
+                let first = values.len() + 1;
+                values.extend(labels.into_iter().map(Value::Text));
+                let placeholders: Vec<String> = (first..=values.len()).map(|i| format!("?{}", i)).collect();
+                clause.push_str(&format!(" OR source IN ({})", placeholders.join(", ")));
+            }
+            clauses.push(format!("({})", clause));
+        }
+        if let Some(ref scan_type) = self.scan_type {
+            values.push(Value::Text(scan_type.clone()));
+            clauses.push(format!("scan_type = ?{}", values.len()));
+        }
+        if let Some(ref subnet) = self.subnet {
+            values.push(Value::Text(subnet.clone()));
+            let n = values.len();
+            clauses.push(format!("(source_subnet = ?{n} OR dest_subnet = ?{n})"));
+        }
+        if let Some(since) = self.since {
+            values.push(Value::Integer(since.timestamp_millis()));
+            clauses.push(format!("ts >= ?{}", values.len()));
+        }
+        if let Some(until) = self.until {
+            values.push(Value::Integer(until.timestamp_millis()));
+            clauses.push(format!("ts <= ?{}", values.len()));
+        }
+        if clauses.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", clauses.join(" AND ")), values)
+        }
+    }
+}
+
+/// Etichetele de sursa agregata ("retea/prefix") care contin adresa IPv6
+/// `ip`, una pentru fiecare prefix posibil. Gol pentru IPv4 sau text invalid.
+fn ipv6_source_labels(ip: &str) -> Vec<String> {
+    let Ok(addr) = ip.parse::<Ipv6Addr>() else {
+        return Vec::new();
+    };
+    IPV6_SOURCE_PREFIXES
+        .map(|prefix| {
+            let mask = !0u128 << (128 - prefix);
+            format!("{}/{}", Ipv6Addr::from(u128::from(addr) & mask), prefix)
+        })
+        .collect()
+}
+
+/// Numararile pentru `ids-rs alerts count`.
+#[derive(Debug, Default)]
+pub struct HistoryCounts {
+    pub total: u64,
+    pub first: Option<DateTime<Local>>,
+    pub last: Option<DateTime<Local>>,
+    /// (tip, numar), descrescator.
+    pub by_type: Vec<(String, u64)>,
+    /// (sursa, numar), primele 10 descrescator.
+    pub top_sources: Vec<(String, u64)>,
+}
+
+/// Baza de date a istoricului de alerte.
+pub struct AlertHistory {
+    conn: Mutex<Connection>,
+}
+
+impl AlertHistory {
+    /// Deschide (sau creeaza) baza pentru scriere — folosita de daemon.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Nu pot crea directorul istoricului {:?}", dir))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Nu pot deschide istoricul de alerte {:?}", path))?;
+        // WAL: `ids-rs alerts` citeste in timp ce daemon-ul scrie, fara blocare.
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA).context("Nu pot crea schema istoricului")?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Deschide o baza existenta doar pentru citire — folosita de CLI, nu
+    /// creeaza fisierul daca lipseste.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            bail!("Istoricul de alerte {:?} nu exista (alerting.history dezactivat?)", path);
+        }
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Nu pot deschide istoricul de alerte {:?}", path))?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // Un panic in timpul unui insert nu strica baza (tranzactia nu se
+        // finalizeaza) — continuam cu conexiunea chiar daca mutex-ul e otravit.
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Salveaza o alerta si returneaza id-ul atribuit.
+    pub fn insert(&self, record: &AlertRecord) -> Result<i64> {
+        let related = if record.related.is_empty() {
+            String::new()
+        } else {
+            format!(",{},", record.related.join(","))
+        };
+        let details = serde_json::to_string(&record.details)?;
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO alerts (ts, scan_type, severity, source, source_host, source_subnet,
+                                 dest, dest_host, dest_subnet, related, summary, details)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.timestamp.timestamp_millis(),
+                record.scan_type,
+                record.severity,
+                record.source,
+                record.source_host,
+                record.source_subnet,
+                record.dest,
+                record.dest_host,
+                record.dest_subnet,
+                related,
+                record.summary,
+                details,
+            ],
+        )
+        .context("Nu pot salva alerta in istoric")?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Alertele care corespund filtrului, cele mai noi primele.
+    pub fn list(&self, filter: &HistoryFilter, limit: usize) -> Result<Vec<AlertRecord>> {
+        let (clause, mut values) = filter.where_clause();
+        values.push(Value::Integer(limit as i64));
+        let sql = format!(
+            "SELECT * FROM alerts{} ORDER BY ts DESC, id DESC LIMIT ?{}",
+            clause,
+            values.len()
+        );
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), record_from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().context("Eroare la citirea istoricului")
+    }
+
+    /// O alerta dupa id.
+    pub fn get(&self, id: i64) -> Result<Option<AlertRecord>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT * FROM alerts WHERE id = ?1")?;
+        let mut rows = stmt.query_map([id], record_from_row)?;
+        rows.next().transpose().context("Eroare la citirea istoricului")
+    }
+
+    /// Totalul, intervalul si defalcarea pe tip / sursa pentru filtru.
+    pub fn counts(&self, filter: &HistoryFilter) -> Result<HistoryCounts> {
+        let (clause, values) = filter.where_clause();
+        let conn = self.conn();
+
+        let (total, first, last): (u64, Option<i64>, Option<i64>) = conn.query_row(
+            &format!("SELECT COUNT(*), MIN(ts), MAX(ts) FROM alerts{}", clause),
+            params_from_iter(values.iter()),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        // LIMIT -1 = fara limita in SQLite.
+        let group = |column: &str, limit: i64| -> Result<Vec<(String, u64)>> {
+            let sql = format!(
+                "SELECT {column}, COUNT(*) AS n FROM alerts{clause} GROUP BY {column} \
+                 ORDER BY n DESC, {column} LIMIT {limit}"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        };
+
+        Ok(HistoryCounts {
+            total,
+            first: first.and_then(from_millis),
+            last: last.and_then(from_millis),
+            by_type: group("scan_type", -1)?,
+            top_sources: group("source", 10)?,
+        })
+    }
+
+    /// Sterge alertele mai vechi de `retention_days` zile (0 = pastreaza tot).
+    /// Returneaza numarul de alerte sterse.
+    pub fn prune(&self, retention_days: u64, now: DateTime<Local>) -> Result<usize> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = i64::try_from(retention_days)
+            .ok()
+            .and_then(ChronoDuration::try_days)
+            .and_then(|retention| now.checked_sub_signed(retention))
+            .with_context(|| format!("retention_days = {} este prea mare", retention_days))?;
+        let removed = self
+            .conn()
+            .execute("DELETE FROM alerts WHERE ts < ?1", [cutoff.timestamp_millis()])
+            .context("Nu pot curata istoricul de alerte")?;
+        Ok(removed)
+    }
+}
+
+fn from_millis(ms: i64) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(ms).single()
+}
+
+fn record_from_row(row: &Row<'_>) -> rusqlite::Result<AlertRecord> {
+    let ts: i64 = row.get("ts")?;
+    let related: String = row.get("related")?;
+    let details: String = row.get("details")?;
+    Ok(AlertRecord {
+        id: row.get("id")?,
+        timestamp: from_millis(ts).unwrap_or_else(Local::now),
+        scan_type: row.get("scan_type")?,
+        severity: row.get("severity")?,
+        source: row.get("source")?,
+        source_host: row.get("source_host")?,
+        source_subnet: row.get("source_subnet")?,
+        dest: row.get("dest")?,
+        dest_host: row.get("dest_host")?,
+        dest_subnet: row.get("dest_subnet")?,
+        related: related
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        summary: row.get("summary")?,
+        // Un JSON corupt nu ascunde alerta — doar detaliile lipsesc.
+        details: serde_json::from_str(&details).unwrap_or_default(),
+    })
+}
+
+/// Parseaza un moment pentru `--since` / `--until`:
+///   relativ la `now` — "30m", "24h", "7d"
+///   absolut (ora locala) — "2026-10-01", "2026-10-01 08:30", "2026-10-01T08:30:00"
+pub fn parse_time(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let input = input.trim();
+    if let Some(unit) = input.chars().last().filter(|c| matches!(c, 'm' | 'h' | 'd')) {
+        if let Ok(n) = input[..input.len() - 1].parse::<i64>() {
+            let span = match unit {
+                'm' => ChronoDuration::try_minutes(n),
+                'h' => ChronoDuration::try_hours(n),
+                _ => ChronoDuration::try_days(n),
+            };
+            return span
+                .and_then(|span| now.checked_sub_signed(span))
+                .with_context(|| format!("Moment invalid '{}': intervalul este prea mare", input));
+        }
+    }
+
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(input, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        });
+    match naive.and_then(|n| Local.from_local_datetime(&n).earliest()) {
+        Some(t) => Ok(t),
+        None => bail!(
+            "Moment invalid '{}': foloseste 30m / 24h / 7d sau YYYY-MM-DD [HH:MM[:SS]]",
+            input
+        ),
+    }
+}
+
+/// Valoarea `--ip`: o adresa sau un prefix ("2001:db8::/64"), in forma
+/// canonica in care sunt salvate in istoric.
+fn parse_ip_filter(input: &str) -> Result<String> {
+    let input = input.trim();
+    let invalid = || anyhow::anyhow!("--ip: adresa sau prefix invalid '{}'", input);
+    match input.split_once('/') {
+        Some((addr, prefix)) => {
+            let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+            let max = if addr.is_ipv4() { 32 } else { 128 };
+            match prefix.parse::<u8>() {
+                Ok(prefix) if prefix <= max => Ok(format!("{}/{}", addr, prefix)),
+                _ => Err(invalid()),
+            }
+        }
+        None => input.parse::<IpAddr>().map(|addr| addr.to_string()).map_err(|_| invalid()),
+    }
+}
+
+/// Comanda `ids-rs alerts` — actiunea si filtrele din linia de comanda.
+#[derive(Debug)]
+pub struct AlertsCommand {
+    pub action: AlertsAction,
+    pub filter: HistoryFilter,
+    pub limit: usize,
+    /// Config-ul din care se citeste `alerting.history.path`.
+    pub config_path: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AlertsAction {
+    List,
+    Show(i64),
+    Count,
+}
+
+/// Textul de ajutor pentru `ids-rs alerts`.
+pub const ALERTS_USAGE: &str = "\
+Utilizare: ids-rs alerts [list|show <id>|count] [optiuni]
+
+  list              alertele care corespund filtrelor, cele mai noi primele (implicit)
+  show <id>         toate detaliile unei alerte
+  count             totalul si defalcarea pe tip si pe sursa
+
+Optiuni:
+  --ip <IP>         sursa, destinatia sau una din destinatiile/sursele unice
+  --type <tip>      fast_scan, slow_scan, accept_scan, lateral_movement, ...
+  --subnet <nume>   eticheta din [network.subnets] a sursei sau destinatiei
+  --since <moment>  30m / 24h / 7d sau YYYY-MM-DD [HH:MM[:SS]]
+  --until <moment>  idem
+  --limit <N>       numarul maxim de alerte afisate de list (implicit 50)
+  --config <fisier> config-ul cu [alerting.history] (implicit config.toml)";
+
+impl AlertsCommand {
+    /// Parseaza argumentele de dupa `alerts`.
+    pub fn parse(args: &[String], now: DateTime<Local>) -> Result<Self> {
+        let mut cmd = AlertsCommand {
+            action: AlertsAction::List,
+            filter: HistoryFilter::default(),
+            limit: DEFAULT_LIST_LIMIT,
+            config_path: "config.toml".to_string(),
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = |name: &str| {
+                iter.next()
+                    .cloned()
+                    .with_context(|| format!("{} necesita o valoare", name))
+            };
+            match arg.as_str() {
+                "list" => cmd.action = AlertsAction::List,
+                "count" => cmd.action = AlertsAction::Count,
+                "show" => {
+                    let id = value("show")?;
+                    let id = id.parse().with_context(|| format!("Id invalid: {}", id))?;
+                    cmd.action = AlertsAction::Show(id);
+                }
+                "--ip" => cmd.filter.ip = Some(parse_ip_filter(&value(arg)?)?),
+                "--type" => cmd.filter.scan_type = Some(value(arg)?),
+                "--subnet" => cmd.filter.subnet = Some(value(arg)?),
+                "--since" => cmd.filter.since = Some(parse_time(&value(arg)?, now)?),
+                "--until" => cmd.filter.until = Some(parse_time(&value(arg)?, now)?),
+                "--limit" => {
+                    let limit = value(arg)?;
+                    cmd.limit = limit
+                        .parse()
+                        .with_context(|| format!("--limit invalid: {}", limit))?;
+                }
+                "--config" => cmd.config_path = value(arg)?,
+                other => bail!("Argument necunoscut: {}\n\n{}", other, ALERTS_USAGE),
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(scan_type: &str, source: &str, dest: &str, subnet: &str, age_hours: i64) -> AlertRecord {
+        AlertRecord {
+            id: 0,
+            timestamp: Local::now() - ChronoDuration::hours(age_hours),
+            scan_type: scan_type.to_string(),
+            severity: 7,
+            source: source.to_string(),
+            source_host: None,
+            source_subnet: Some(subnet.to_string()),
+            dest: Some(dest.to_string()),
+            dest_host: Some("srv-dc01".to_string()),
+            dest_subnet: None,
+            related: Vec::new(),
+            summary: format!("{} de la {}", scan_type, source),
+            details: vec![("Porturi".to_string(), "22, 80".to_string())],
+        }
+    }
+
+    fn temp_history(name: &str) -> (AlertHistory, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("ids-rs-history-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        (AlertHistory::open(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn test_ip_filter_matches_aggregated_ipv6_source() {
+        let (history, path) = temp_history("ipv6");
+        history.insert(&record("fast_scan", "2001:db8:1:2::/64", "10.0.1.10", "Etaj 2", 1)).unwrap();
+        history.insert(&record("slow_scan", "2001:db8:1::/56", "10.0.1.10", "Etaj 2", 1)).unwrap();
+        history.insert(&record("fast_scan", "2001:db8:9::/64", "10.0.1.10", "Etaj 2", 1)).unwrap();
+
+        // O adresa din /64 (si din /56) gaseste alertele surselor agregate.
+        let by_ip = HistoryFilter { ip: Some("2001:db8:1:2::abcd".to_string()), ..Default::default() };
+        let listed = history.list(&by_ip, 50).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().all(|r| r.source.starts_with("2001:db8:1:")));
+        let counts = history.counts(&by_ip).unwrap();
+        assert_eq!(counts.total, 2);
+
+        // IPv4 nu genereaza etichete; prefixul cautat direct se potriveste exact.
+        assert!(ipv6_source_labels("10.0.1.10").is_empty());
+        let by_prefix = HistoryFilter { ip: Some("2001:db8:9::/64".to_string()), ..Default::default() };
+        assert_eq!(history.list(&by_prefix, 50).unwrap().len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_insert_list_and_filters() {
+        let (history, path) = temp_history("filters");
+        history.insert(&record("fast_scan", "10.0.5.20", "10.0.1.10", "Etaj 2", 30)).unwrap();
+        history.insert(&record("accept_scan", "10.0.5.20", "10.0.1.11", "Etaj 2", 2)).unwrap();
+        let lateral = AlertRecord {
+            related: vec!["10.0.1.10".to_string(), "10.0.1.12".to_string()],
+            ..record("lateral_movement", "10.0.9.9", "10.0.1.12", "Etaj 9", 1)
+        };
+        let lateral_id = history.insert(&lateral).unwrap();
+
+        // IP cautat ca sursa, destinatie sau in lista de destinatii unice.
+        let by_ip = HistoryFilter { ip: Some("10.0.1.10".to_string()), ..Default::default() };
+        assert_eq!(history.list(&by_ip, 50).unwrap().len(), 2);
+        // "10.0.1.1" nu se potriveste partial cu "10.0.1.10" / "10.0.1.12".
+        let partial = HistoryFilter { ip: Some("10.0.1.1".to_string()), ..Default::default() };
+        assert!(history.list(&partial, 50).unwrap().is_empty());
+        // `%` si `_` sunt literale in LIKE, nu wildcard-uri.
+        for wildcard in ["%", "10.0.1.1_", "10.0.1.%"] {
+            let filter = HistoryFilter { ip: Some(wildcard.to_string()), ..Default::default() };
+            assert!(history.list(&filter, 50).unwrap().is_empty(), "{}", wildcard);
+        }
+
+        let recent = HistoryFilter {
+            subnet: Some("Etaj 2".to_string()),
+            since: Some(parse_time("24h", Local::now()).unwrap()),
+            ..Default::default()
+        };
+        let listed = history.list(&recent, 50).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].scan_type, "accept_scan");
+
+        let shown = history.get(lateral_id).unwrap().unwrap();
+        assert_eq!(shown.related, lateral.related);
+        assert_eq!(shown.details, lateral.details);
+        assert_eq!(shown.dest_host.as_deref(), Some("srv-dc01"));
+
+        let counts = history.counts(&HistoryFilter::default()).unwrap();
+        assert_eq!(counts.total, 3);
+        assert_eq!(counts.top_sources[0], ("10.0.5.20".to_string(), 2));
+
+        // Retentie de o zi: alerta de acum 30h dispare.
+        assert_eq!(history.prune(1, Local::now()).unwrap(), 1);
+        // O retentie absurda este eroare, nu panica.
+        assert!(history.prune(u64::MAX, Local::now()).is_err());
+        assert!(history.prune(u64::MAX / 86_400_000, Local::now()).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_parse_alerts_command() {
+        let now = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let args: Vec<String> = ["show", "42", "--ip", "10.0.5.20", "--since", "7d", "--until", "2026-10-18 08:30"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let cmd = AlertsCommand::parse(&args, now).unwrap();
+        assert_eq!(cmd.action, AlertsAction::Show(42));
+        assert_eq!(cmd.filter.ip.as_deref(), Some("10.0.5.20"));
+        assert_eq!(cmd.filter.since, Some(now - ChronoDuration::days(7)));
+        assert_eq!(
+            cmd.filter.until,
+            Some(Local.with_ymd_and_hms(2026, 10, 18, 8, 30, 0).unwrap())
+        );
+        assert!(parse_time("ieri", now).is_err());
+        assert!(AlertsCommand::parse(&["--bogus".to_string()], now).is_err());
+
+        // Intervale uriase: eroare, nu panica in chrono.
+        for huge in ["99999999999999d", "9223372036854775807h", "-9223372036854775807m", "300000000d"] {
+            let err = parse_time(huge, now).unwrap_err().to_string();
+            assert!(err.contains("prea mare"), "{}: {}", huge, err);
+        }
+
+        // `--ip` este validat si adus la forma canonica; wildcard-urile SQL sunt refuzate.
+        let parse_ip = |ip: &str| {
+            AlertsCommand::parse(&["--ip".to_string(), ip.to_string()], now).map(|cmd| cmd.filter.ip.unwrap())
+        };
+        assert_eq!(parse_ip("2001:DB8:0::1").unwrap(), "2001:db8::1");
+        assert_eq!(parse_ip("2001:db8::/64").unwrap(), "2001:db8::/64");
+        assert_eq!(parse_ip("10.0.0.0/8").unwrap(), "10.0.0.0/8");
+        for bad in ["10.0.%", "10_0_0_1", "%", "10.0.0.0/33", "2001:db8::/129", "fe80::1%eth0"] {
+            assert!(parse_ip(bad).is_err(), "{}", bad);
+        }
+    }
+}
@@ -49,20 +49,50 @@
 // =============================================================================
 
 mod alerter;
+mod api;
+mod assets;
+mod baseline;
+mod beacon;
+mod check;
+mod cli;
 mod config;
+mod control;
+mod cooldown;
+mod correlation;
 mod detector;
 mod display;
+mod fingerprint;
+mod history;
+mod offenders;
 mod parser;
+mod replay;
+mod risk;
+mod rules;
+mod sessions;
+mod severity;
+mod stats;
+mod threat_intel;
+mod tui;
 
 use alerter::Alerter;
+use api::ApiContext;
+use cli::{Cli, Command, PidFile};
 use arc_swap::ArcSwap;
+use assets::AssetInventory;
 use config::{AppConfig, SubnetEntry};
+use control::{ControlContext, Reply};
 use detector::Detector;
+use history::{AlertHistory, AlertsAction, AlertsCommand};
+use stats::LiveStats;
+use threat_intel::ThreatIntel;
+use tui::Dashboard;
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::net::UdpSocket;
+use tokio::signal::unix::Signal;
+use tokio::sync::mpsc;
 
 // =============================================================================
 // TokenBucket — Rate Limiter pentru receptie UDP
@@ -147,6 +177,101 @@ impl TokenBucket {
     }
 }
 
+/// Afiseaza statisticile de incarcare per feed threat intel.
+///
+/// `log` primeste functia de afisare (log_info la pornire, raportul de reload).
+fn log_threat_intel(intel: &ThreatIntel, mut log: impl FnMut(&str)) {
+    for feed in &intel.feeds {
+        let skipped = if feed.skipped > 0 {
+            format!(", {} intrari invalide ignorate", feed.skipped)
+        } else {
+            String::new()
+        };
+        log(&format!(
+            "Threat intel: feed '{}' — {} indicatori{}",
+            feed.name, feed.loaded, skipped
+        ));
+    }
+}
+
+/// Afiseaza statisticile de incarcare ale inventarului de active.
+fn log_assets(assets: &AssetInventory, mut log: impl FnMut(&str)) {
+    let skipped = if assets.skipped > 0 {
+        format!(", {} intrari invalide ignorate", assets.skipped)
+    } else {
+        String::new()
+    };
+    log(&format!("Inventar active: {} intrari{}", assets.loaded, skipped));
+}
+
+/// Parseaza mapping-ul IP→hostname din config.toml.
+/// Functie separata pentru reutilizare la reload SIGHUP (#16) si in `replay`.
+fn parse_hostnames(config: &AppConfig) -> HashMap<IpAddr, String> {
+    config
+        .network
+        .hostnames
+        .iter()
+        .filter_map(|(ip_str, name)| {
+            ip_str.parse::<IpAddr>().ok().map(|ip| (ip, name.clone()))
+        })
+        .collect()
+}
+
+/// Mesajele unui reload: afisate ca pana acum si colectate pentru raportul
+/// intors lui `ids-rs ctl reload`.
+#[derive(Default)]
+struct ReloadReport {
+    lines: Vec<String>,
+}
+
+impl ReloadReport {
+    fn info(&mut self, msg: &str) {
+        display::log_reload(msg);
+        self.lines.push(msg.to_string());
+    }
+
+    fn warn(&mut self, msg: &str) {
+        display::log_warning(msg);
+        self.lines.push(format!("WARN {}", msg));
+    }
+
+    /// Raportul pentru client: mesajele reload-ului reusit sau eroarea de
+    /// validare (toate erorile din config.toml, numerotate).
+    fn finish(self, result: anyhow::Result<()>) -> anyhow::Result<String> {
+        result.map(|()| self.lines.join("\n"))
+    }
+}
+
+/// Urmatoarea cerere de reload: SIGHUP (None) sau `ids-rs ctl reload`
+/// (Some — canalul raportului). Fara socket de control, doar SIGHUP.
+async fn next_reload(sighup: &mut Signal, requests: &mut mpsc::Receiver<Reply>) -> Option<Reply> {
+    tokio::select! {
+        _ = sighup.recv() => None,
+        Some(reply) = requests.recv() => Some(reply),
+    }
+}
+
+/// `ids-rs alerts [list|show <id>|count] [optiuni]` — interogheaza istoricul
+/// local al alertelor (`[alerting.history]`) din config-ul dat cu `--config`.
+fn run_alerts_command(args: &[String]) -> anyhow::Result<()> {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", history::ALERTS_USAGE);
+        return Ok(());
+    }
+    let cmd = AlertsCommand::parse(args, chrono::Local::now())?;
+    let config = AppConfig::load(&cmd.config_path)?;
+    let history = AlertHistory::open_read_only(&config.alerting.history.path)?;
+    match cmd.action {
+        AlertsAction::List => display::print_history_list(&history.list(&cmd.filter, cmd.limit)?),
+        AlertsAction::Show(id) => match history.get(id)? {
+            Some(record) => display::print_history_record(&record),
+            None => anyhow::bail!("Alerta #{} nu exista in istoric", id),
+        },
+        AlertsAction::Count => display::print_history_counts(&history.counts(&cmd.filter)?),
+    }
+    Ok(())
+}
+
 /// Punctul de intrare al aplicatiei.
 ///
 /// NOTA RUST: `-> anyhow::Result<()>`
@@ -168,37 +293,68 @@ async fn main() -> anyhow::Result<()> {
     // Tracing este configurat pentru logging INTERN (debug/erori).
     // Output-ul vizual catre utilizator este gestionat de modulul `display`.
     //
-    // `RUST_LOG` controleaza nivelul:
+    // `--log-level` (cli.rs) are prioritate; altfel `RUST_LOG` controleaza nivelul:
     //   RUST_LOG=debug cargo run    -> vede debug + info + warn + error
     //   RUST_LOG=ids_rs=trace       -> vede tot, inclusiv trace
     //   (fara RUST_LOG)             -> doar warn + error (default)
     //
+    // Linia de comanda se parseaza deci INAINTEA tracing-ului.
+    //
     // NOTA RUST: `unwrap_or_else` cu closure:
     // `.unwrap_or_else(|_| ...)` - daca Err, executa closure-ul.
     // `|_|` = closure cu un parametru pe care il ignoram (eroarea).
     // Closure-ul este evaluat LAZY - doar daca Err.
     //
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = Cli::parse(&args)?;
+    // `--no-color`: sub un manager de servicii codurile ANSI ar ajunge ca
+    // text in journal. `colored` respecta singur si `NO_COLOR`.
+    if cli.no_color {
+        colored::control::set_override(false);
+    }
+    let filter = match cli.log_level {
+        Some(ref level) => tracing_subscriber::EnvFilter::new(format!("ids_rs={}", level)),
+        None => tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "ids_rs=warn".parse().unwrap()),
+    };
     tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "ids_rs=warn".parse().unwrap()),
-        )
+        .with_env_filter(filter)
         .with_target(false)
+        .with_ansi(!cli.no_color)
         .init();
 
     // =========================================================================
     // 2. INCARCARE CONFIGURARE
     // =========================================================================
     //
-    // NOTA RUST: `std::env::args()` returneaza un iterator peste argumentele
-    // liniei de comanda. `.nth(1)` returneaza al doilea argument (index 0 = exe).
-    // `.unwrap_or_else` ofera o valoare default daca nu exista argument.
-    //
-    let config_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "config.toml".to_string());
+    // Subcomenzile offline (`check-config`, `replay`, `parse-test`, `alerts`,
+    // `ctl`, `version`) ies aici — fara banner, fara socket UDP.
+    let opts = match cli.command {
+        Command::Run(opts) => opts,
+        Command::CheckConfig(opts) => return check::run_check_config(&opts),
+        Command::Replay(opts) => return replay::run_replay(&opts).await,
+        Command::ParseTest(opts) => return replay::run_parse_test(&opts),
+        Command::Alerts(args) => return run_alerts_command(&args),
+        Command::Ctl(args) => return control::run_ctl_command(&args).await,
+        Command::Version => {
+            println!("ids-rs {}", env!("CARGO_PKG_VERSION"));
+            println!("Parsere: {}, auto", parser::PARSER_NAMES.join(", "));
+            return Ok(());
+        }
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+    };
 
-    let mut config = AppConfig::load(&config_path)?;
+    // `--tui` inlocuieste log-ul derulant cu dashboard-ul interactiv (tui.rs).
+    let tui_mode = opts.tui;
+    let config_path = opts.config_path.clone();
+    let mut config = opts.overrides.load(&config_path)?;
+
+    // Pidfile-ul se scrie dupa validare (un config gresit nu lasa fisierul in
+    // urma) si este sters la iesirea din main, cand garda este dropata.
+    let _pidfile = opts.pidfile.as_deref().map(PidFile::create).transpose()?;
 
     // =========================================================================
     // 3. BANNER DE START
@@ -209,6 +365,10 @@ async fn main() -> anyhow::Result<()> {
     if debug_mode {
         display::log_warning("Mod DEBUG activ - toate pachetele vor fi afisate");
     }
+    let overrides = opts.overrides.describe();
+    if !overrides.is_empty() {
+        display::log_info(&format!("Suprascrieri din linia de comanda: {}", overrides.join(" ")));
+    }
 
     // =========================================================================
     // 4. INITIALIZARE COMPONENTE
@@ -226,8 +386,12 @@ async fn main() -> anyhow::Result<()> {
     // Alternativa (static dispatch cu generics) ar elimina acest cost
     // dar nu ar permite selectia parser-ului din config la runtime.
     //
-    let parser = parser::create_parser(&config.network.parser)?;
-    display::log_info(&format!("Parser activ: {}", parser.name()));
+    // Parser-ul este in `ArcSwap`, ca hostname-urile: la reload, un parser
+    // nou (alt `network.parser` sau alta ordine `auto_parsers`) il inlocuieste
+    // atomic, fara restart. Main loop-ul si API-ul citesc mereu parser-ul curent.
+    //
+    let parser = Arc::new(ArcSwap::from_pointee(parser::from_config(&config.network)?));
+    display::log_info(&format!("Parser activ: {}", parser.load().name()));
 
     // NOTA RUST - Arc (Atomic Reference Counting):
     //
@@ -245,19 +409,6 @@ async fn main() -> anyhow::Result<()> {
     // Parsam mapping-ul IP→hostname din config.toml.
     // Cheia in TOML este String (IP), o convertim la IpAddr pentru lookup rapid.
     // Validarea cheilor se face in config.rs::validate().
-    /// Parseaza mapping-ul IP→hostname din config.toml.
-    /// Functie separata pentru reutilizare la reload SIGHUP (#16).
-    fn parse_hostnames(config: &AppConfig) -> HashMap<IpAddr, String> {
-        config
-            .network
-            .hostnames
-            .iter()
-            .filter_map(|(ip_str, name)| {
-                ip_str.parse::<IpAddr>().ok().map(|ip| (ip, name.clone()))
-            })
-            .collect()
-    }
-
     let hostnames = Arc::new(ArcSwap::from_pointee(parse_hostnames(&config)));
     let subnets = Arc::new(ArcSwap::from_pointee(SubnetEntry::parse_subnets(&config.network.subnets)));
 
@@ -269,8 +420,43 @@ async fn main() -> anyhow::Result<()> {
         SubnetEntry::parse_subnets(&config.network.subnets),
     )?);
 
+    detector.update_subnets(SubnetEntry::parse_subnets(&config.network.subnets));
     display::log_info("Detector initializat (DashMap thread-safe)");
 
+    // Contoarele live (evenimente, linii neparsate, rate limit, alerte) —
+    // alimentate mereu, citite doar de dashboard-ul `--tui`.
+    let live = Arc::new(LiveStats::new());
+
+    // Baseline-uri persistate: un fisier corupt nu opreste IDS-ul — invatarea
+    // reporneste de la zero, dar detectia pe praguri fixe functioneaza normal.
+    if config.detection.anomaly.enabled {
+        match detector.load_baselines() {
+            Ok(count) => display::log_info(&format!(
+                "Mod anomalie activ: {} baseline-uri incarcate", count
+            )),
+            Err(e) => display::log_warning(&format!(
+                "Baseline-uri neincarcate, invatarea reporneste de la zero: {:#}", e
+            )),
+        }
+    }
+
+    // Threat intel: un feed lipsa/invalid la pornire este eroare fatala —
+    // operatorul trebuie sa stie imediat ca listele CERT nu sunt active.
+    if config.threat_intel.enabled {
+        let intel = ThreatIntel::load(&config.threat_intel)?;
+        log_threat_intel(&intel, display::log_info);
+        detector.update_threat_intel(intel);
+    }
+
+    // Inventarul de active: ca la threat intel, un fisier lipsa/invalid la
+    // pornire este fatal. Partajat intre display (main loop) si alerter.
+    let assets = Arc::new(ArcSwap::from_pointee(AssetInventory::load(&config.assets)?));
+    if config.assets.enabled {
+        log_assets(&assets.load(), display::log_info);
+    }
+    alerter.update_assets(assets.load_full());
+    detector.update_assets(assets.load_full());
+
     // =========================================================================
     // 5. TASK CLEANUP PERIODIC (Background Async Task)
     // =========================================================================
@@ -290,17 +476,32 @@ async fn main() -> anyhow::Result<()> {
     // `detector` original ramane valid (Arc separat) pentru main loop.
     //
     let cleanup_detector = Arc::clone(&detector);
+    let cleanup_alerter = Arc::clone(&alerter);
+    let cleanup_hostnames = Arc::clone(&hostnames);
+    let cleanup_subnets = Arc::clone(&subnets);
+    let cleanup_assets = Arc::clone(&assets);
+    let cleanup_live = Arc::clone(&live);
     let cleanup_interval = config.cleanup.interval_secs;
     let max_age = config.cleanup.max_entry_age_secs;
 
+    // Cererile `ids-rs ctl cleanup` / `reload`: executate de task-ul de
+    // cleanup, respectiv de main loop, care raspund pe canalul primit.
+    let (cleanup_tx, mut cleanup_rx) = mpsc::channel::<Reply>(4);
+    let (reload_tx, mut reload_rx) = mpsc::channel::<Reply>(4);
+
     tokio::spawn(async move {
         // NOTA RUST: `tokio::time::interval()` face primul tick IMEDIAT la creare,
         // ceea ce ar rula un cleanup inutil la startup (cand memoria e goala).
         // Folosim `sleep` intr-un loop simplu: asteapta intai, curata dupa.
         // Pattern: sleep-first loop garanteaza ca primul cleanup are loc abia
         // dupa `cleanup_interval` secunde de la pornire.
+        //
+        // Un `ids-rs ctl cleanup` ruleaza ciclul imediat si reporneste asteptarea.
         loop {
-            tokio::time::sleep(Duration::from_secs(cleanup_interval)).await;
+            let reply = tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(cleanup_interval)) => None,
+                Some(reply) = cleanup_rx.recv() => Some(reply),
+            };
 
             let tracked_before = cleanup_detector.tracked_ips();
             cleanup_detector.cleanup(Duration::from_secs(max_age));
@@ -310,6 +511,66 @@ async fn main() -> anyhow::Result<()> {
             if tracked_after > 0 || cleaned > 0 {
                 display::log_stats(tracked_after, cleaned);
             }
+
+            // Scanarile "ongoing" care s-au oprit — rezumatul "scan ended"
+            // merge pe acelasi drum ca alertele din bucla principala.
+            let finished = cleanup_detector.finished_scans();
+            let finished_count = finished.len();
+            for alert in finished {
+                display::log_alert(
+                    &alert,
+                    &cleanup_hostnames.load(),
+                    &cleanup_subnets.load(),
+                    &cleanup_assets.load(),
+                );
+                cleanup_live.record_alert(&alert);
+                cleanup_alerter.send_alert(&alert).await;
+            }
+
+            // Sesiunile de scanare inactive — rezumatul catre SIEM / JSON.
+            let closed = cleanup_detector.closed_sessions();
+            let closed_count = closed.len();
+            for session in closed {
+                display::log_session(&session);
+                cleanup_alerter.send_session(&session).await;
+            }
+
+            // Contoarele per regula de firewall — cele mai lovite reguli,
+            // pentru revizuirea politicii (absente daca log-urile nu au regula).
+            let rule_stats = cleanup_detector.rule_stats();
+            if !rule_stats.is_empty() {
+                display::log_rule_stats(&rule_stats);
+            }
+
+            // Persistam baseline-urile la fiecare cleanup — o oprire brusca
+            // pierde cel mult un interval de cleanup din invatare.
+            if let Err(e) = cleanup_detector.save_baselines() {
+                display::log_warning(&format!("Nu pot salva baseline-urile: {:#}", e));
+            }
+
+            // Retentia istoricului de alerte (`alerting.history.retention_days`).
+            let pruned = match cleanup_alerter.prune_history().await {
+                Ok(removed) => {
+                    if removed > 0 {
+                        display::log_info(&format!(
+                            "Istoric alerte: {} alerte expirate sterse", removed
+                        ));
+                    }
+                    removed
+                }
+                Err(e) => {
+                    display::log_warning(&format!("Nu pot curata istoricul de alerte: {:#}", e));
+                    0
+                }
+            };
+
+            if let Some(reply) = reply {
+                let _ = reply.send(Ok(format!(
+                    "Cleanup: {} IP-uri urmarite ({} sterse), {} scanari incheiate, \
+                     {} sesiuni inchise, {} alerte expirate sterse din istoric",
+                    tracked_after, cleaned, finished_count, closed_count, pruned
+                )));
+            }
         }
     });
 
@@ -333,9 +594,70 @@ async fn main() -> anyhow::Result<()> {
     );
     let socket = UdpSocket::bind(&bind_addr).await?;
     display::log_info(&format!("Ascult pe UDP {}", bind_addr));
+
+    // Socket-ul de control (`ids-rs ctl`). Fara el, nimeni nu trimite pe
+    // canalele de cerere: cleanup-ul ramane periodic, reload-ul doar la SIGHUP.
+    let control_socket = if config.control.enabled {
+        control::start(
+            &config.control.socket_path,
+            ControlContext {
+                detector: Arc::clone(&detector),
+                alerter: Arc::clone(&alerter),
+                hostnames: Arc::clone(&hostnames),
+                subnets: Arc::clone(&subnets),
+                assets: Arc::clone(&assets),
+                live: Arc::clone(&live),
+                cleanup: cleanup_tx,
+                reload: reload_tx,
+            },
+        )?;
+        display::log_info(&format!("Socket de control: {}", config.control.socket_path));
+        Some(config.control.socket_path.clone())
+    } else {
+        None
+    };
+
+    // API-ul REST citeste config-ul curent (`GET /config`, descrierile
+    // alertelor) din `shared_config`, actualizat la fiecare reload reusit.
+    let shared_config = Arc::new(ArcSwap::from_pointee(config.clone()));
+    if config.api.enabled {
+        api::start(
+            &config.api,
+            ApiContext {
+                detector: Arc::clone(&detector),
+                live: Arc::clone(&live),
+                config: Arc::clone(&shared_config),
+                parser: Arc::clone(&parser),
+                started: Instant::now(),
+                started_at: chrono::Local::now(),
+            },
+        )
+        .await?;
+        display::log_info(&format!(
+            "API REST: http://{}:{}{}",
+            config.api.listen_address,
+            config.api.listen_port,
+            if config.api.token.is_empty() { "" } else { " (token)" }
+        ));
+    }
+
     display::log_info("Astept log-uri de la firewall... (Ctrl+C pentru oprire)");
     display::print_separator();
 
+    // Dashboard-ul preia terminalul de aici inainte; iesirea din el (q) este
+    // notificata prin `tui_quit` si opreste main loop-ul ca un Ctrl+C.
+    let tui_quit = Arc::new(tokio::sync::Notify::new());
+    let dashboard = if tui_mode {
+        Some(Dashboard::start(
+            Arc::clone(&detector),
+            Arc::clone(&live),
+            format!("IDS-RS · {} · UDP {}", parser.load().name(), bind_addr),
+            Arc::clone(&tui_quit),
+        )?)
+    } else {
+        None
+    };
+
     // =========================================================================
     // 7. RATE LIMITER (Token Bucket, optional)
     // =========================================================================
@@ -385,7 +707,8 @@ async fn main() -> anyhow::Result<()> {
     //
     // La primirea SIGHUP-ului:
     //   1. Re-citim config.toml (validate() inclus)
-    //   2. Comparam listen_port/address/parser — daca difera, warning + skip
+    //   2. Comparam listen_port/address — daca difera, warning + skip
+    //      (parser-ul schimbat este reconstruit si inlocuit pe loc)
     //   3. Aplicam noile valori la detector, alerter, hostnames, rate limiter
     //   4. Starea de detectie (DashMap-urile) ramane INTACTA
     //
@@ -459,23 +782,56 @@ async fn main() -> anyhow::Result<()> {
                 break;
             }
 
-            // Branch: Hot reload config la SIGHUP (#16).
-            _ = sighup.recv() => {
-                display::log_reload("SIGHUP primit — reincarc config.toml...");
-                match AppConfig::load(&config_path) {
+            // Branch: iesire din dashboard-ul `--tui` (q / Ctrl+C in dashboard).
+            _ = tui_quit.notified() => {
+                break;
+            }
+
+            // Branch: Hot reload config la SIGHUP (#16) sau `ids-rs ctl reload`.
+            reply = next_reload(&mut sighup, &mut reload_rx) => {
+                let origin = if reply.is_some() { "ids-rs ctl reload" } else { "SIGHUP primit" };
+                display::log_reload(&format!("{} — reincarc config.toml...", origin));
+                let mut report = ReloadReport::default();
+                let result = match opts.overrides.load(&config_path) {
                     Ok(new_config) => {
                         // Verificam campurile care NU pot fi reincarcate (necesita restart).
                         if new_config.network.listen_port != config.network.listen_port
                             || new_config.network.listen_address != config.network.listen_address
                         {
-                            display::log_warning(
-                                "SIGHUP: listen_address/listen_port modificate — necesita restart, ignorat"
+                            report.warn(
+                                "Reload: listen_address/listen_port modificate — necesita restart, ignorat"
                             );
                         }
-                        if new_config.network.parser != config.network.parser {
-                            display::log_warning(
-                                "SIGHUP: parser modificat — necesita restart, ignorat"
-                            );
+                        // Parser: reconstruit doar daca s-a schimbat; la eroare
+                        // il pastram pe cel vechi (si cache-ul lui per expeditor).
+                        if new_config.network.parser != config.network.parser
+                            || new_config.network.auto_parsers != config.network.auto_parsers
+                        {
+                            match parser::from_config(&new_config.network) {
+                                Ok(new_parser) => {
+                                    report.info(&format!(
+                                        "Reload: parser schimbat: {} → {}",
+                                        parser.load().name(),
+                                        new_parser.name()
+                                    ));
+                                    parser.store(Arc::new(new_parser));
+                                }
+                                Err(e) => report.warn(&format!(
+                                    "Reload: parser nereincarcat, il pastrez pe cel vechi: {:#}", e
+                                )),
+                            }
+                        }
+                        if new_config.control.enabled != config.control.enabled
+                            || new_config.control.socket_path != config.control.socket_path
+                        {
+                            report.warn("Reload: [control] modificat — necesita restart, ignorat");
+                        }
+                        if new_config.api.enabled != config.api.enabled
+                            || new_config.api.listen_address != config.api.listen_address
+                            || new_config.api.listen_port != config.api.listen_port
+                            || new_config.api.token != config.api.token
+                        {
+                            report.warn("Reload: [api] modificat — necesita restart, ignorat");
                         }
 
                         // Aplicam noile valori la componentele reincarcabile.
@@ -484,6 +840,33 @@ async fn main() -> anyhow::Result<()> {
                         // Detector: praguri, cooldown, whitelist.
                         detector.update_config(new_config.detection.clone());
 
+                        // Threat intel: re-citim feed-urile de pe disc. La eroare
+                        // (fisier lipsa, JSON invalid) pastram listele vechi.
+                        match ThreatIntel::load(&new_config.threat_intel) {
+                            Ok(intel) => {
+                                log_threat_intel(&intel, |msg| report.info(msg));
+                                detector.update_threat_intel(intel);
+                            }
+                            Err(e) => report.warn(&format!(
+                                "Reload: threat intel nereincarcat, pastrez listele vechi: {:#}", e
+                            )),
+                        }
+
+                        // Inventar active: la eroare pastram inventarul vechi.
+                        match AssetInventory::load(&new_config.assets) {
+                            Ok(inventory) => {
+                                if new_config.assets.enabled {
+                                    log_assets(&inventory, |msg| report.info(msg));
+                                }
+                                let inventory = Arc::new(inventory);
+                                alerter.update_assets(Arc::clone(&inventory));
+                                detector.update_assets(Arc::clone(&inventory));
+                                assets.store(inventory);
+                            }
+                            Err(e) => report.warn(&format!(
+                                "Reload: inventar active nereincarcat, pastrez inventarul vechi: {:#}", e
+                            )),
+                        }
                         // Alerter: SIEM, email, hostnames, subnets.
                         let new_hostnames = parse_hostnames(&new_config);
                         let new_subnets = SubnetEntry::parse_subnets(&new_config.network.subnets);
@@ -494,7 +877,9 @@ async fn main() -> anyhow::Result<()> {
                             new_subnets.clone(),
                         );
 
-                        // Hostnames si subnets partajate (folosite in main loop pentru display).
+                        // Hostnames si subnets partajate (folosite in main loop pentru display
+                        // si de detector pentru gruparea baseline-urilor pe subnet).
+                        detector.update_subnets(new_subnets.clone());
                         hostnames.store(Arc::new(new_hostnames));
                         subnets.store(Arc::new(new_subnets));
 
@@ -503,7 +888,7 @@ async fn main() -> anyhow::Result<()> {
                             || new_config.network.udp_burst_size != config.network.udp_burst_size
                         {
                             rate_limiter = if new_config.network.udp_rate_limit > 0 {
-                                display::log_reload(&format!(
+                                report.info(&format!(
                                     "Rate limiting UDP: {} pachete/s, burst {}",
                                     new_config.network.udp_rate_limit,
                                     new_config.network.udp_burst_size
@@ -513,21 +898,27 @@ async fn main() -> anyhow::Result<()> {
                                     new_config.network.udp_burst_size,
                                 ))
                             } else {
-                                display::log_reload("Rate limiting UDP dezactivat");
+                                report.info("Rate limiting UDP dezactivat");
                                 None
                             };
                         }
 
-                        // Salvam config-ul nou pentru comparatii viitoare la urmatorul SIGHUP.
+                        // Salvam config-ul nou pentru comparatii viitoare la urmatorul reload.
+                        shared_config.store(Arc::new(new_config.clone()));
                         config = new_config;
 
-                        display::log_reload("Config reincarcat cu succes");
+                        report.info("Config reincarcat cu succes");
+                        Ok(())
                     }
                     Err(e) => {
                         display::log_warning(&format!(
-                            "SIGHUP: reload esuat, pastrez config-ul vechi: {:#}", e
+                            "Reload esuat, pastrez config-ul vechi: {:#}", e
                         ));
+                        Err(e.context("Reload esuat, config-ul vechi ramane activ"))
                     }
+                };
+                if let Some(reply) = reply {
+                    let _ = reply.send(report.finish(result));
                 }
             }
 
@@ -544,11 +935,12 @@ async fn main() -> anyhow::Result<()> {
             // Branch: Pachet UDP primit.
             result = socket.recv_from(&mut buf) => {
                 match result {
-                    Ok((len, _addr)) => {
+                    Ok((len, addr)) => {
                         // Rate limiting: verificam daca avem token disponibil.
                         // Daca bucket-ul e gol, dropam pachetul silentios.
                         if let Some(ref mut limiter) = rate_limiter {
                             if !limiter.try_consume() {
+                                live.record_rate_limited();
                                 continue;
                             }
                         }
@@ -574,6 +966,7 @@ async fn main() -> anyhow::Result<()> {
                         // `.lines()` returneaza un iterator care produce &str
                         // pentru fiecare linie, ignorand delimitatorii (\n, \r\n).
                         //
+                        let parser = parser.load();
                         for line in data.lines() {
                             // `.trim()` returneaza un &str fara spatii la inceput/sfarsit.
                             // Nu aloca memorie noua - returneaza un sub-slice.
@@ -587,12 +980,14 @@ async fn main() -> anyhow::Result<()> {
                                 display::log_debug_raw(line);
                             }
 
-                            // Parsam linia cu parser-ul activ (dynamic dispatch).
-                            if let Some(event) = parser.parse(line) {
+                            // Parsam linia cu parser-ul activ (dynamic dispatch);
+                            // expeditorul conteaza doar pentru `parser = "auto"`.
+                            if let Some(event) = parser.parse_from(line, Some(addr.ip())) {
                                 // Debug: afiseaza campurile extrase.
                                 if debug_mode {
                                     display::log_debug_parse_ok(&event);
                                 }
+                                live.record_event(&event.action);
 
                                 // Afisam evenimentul in terminal cu badge dinamic
                                 // (albastru = drop, verde = accept).
@@ -603,6 +998,7 @@ async fn main() -> anyhow::Result<()> {
                                     &event.action,
                                     &hostnames.load(),
                                     &subnets.load(),
+                                    &assets.load(),
                                 );
 
                                 // Pastram log-ul original la nivel debug pentru audit/troubleshooting.
@@ -614,18 +1010,22 @@ async fn main() -> anyhow::Result<()> {
                                 // Procesam alertele generate (daca exista).
                                 for alert in alerts {
                                     // Afisam alerta in terminal (colorat, cu hostname-uri).
-                                    display::log_alert(&alert, &hostnames.load(), &subnets.load());
+                                    display::log_alert(&alert, &hostnames.load(), &subnets.load(), &assets.load());
+                                    live.record_alert(&alert);
 
                                     // Trimitem alerta catre SIEM si email (async).
                                     alerter.send_alert(&alert).await;
                                 }
-                            } else if debug_mode {
-                                // Debug: afiseaza detalii despre esecul parsarii.
-                                display::log_debug_parse_fail(
-                                    line,
-                                    parser.name(),
-                                    parser.expected_format(),
-                                );
+                            } else {
+                                live.record_parse_failure();
+                                if debug_mode {
+                                    // Debug: afiseaza detalii despre esecul parsarii.
+                                    display::log_debug_parse_fail(
+                                        line,
+                                        parser.name(),
+                                        parser.expected_format(),
+                                    );
+                                }
                             }
                         }
                     }
@@ -639,5 +1039,22 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Redam terminalul inainte de mesajele de oprire.
+    if let Some(dashboard) = dashboard {
+        if let Err(e) = dashboard.stop() {
+            display::log_error(&format!("Dashboard: {:#}", e));
+        }
+        display::log_info("Dashboard inchis — oprire gratiosa...");
+    }
+
+    if let Some(path) = control_socket {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // Salvam baseline-urile la oprire (Ctrl+C / SIGTERM / iesire din dashboard).
+    if let Err(e) = detector.save_baselines() {
+        display::log_warning(&format!("Nu pot salva baseline-urile: {:#}", e));
+    }
+
     Ok(())
 }
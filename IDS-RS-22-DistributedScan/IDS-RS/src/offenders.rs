@@ -0,0 +1,310 @@
+// =============================================================================
+// offenders.rs - Recidiva per sursa si scannerele persistente
+// =============================================================================
+//
+// Cooldown-urile (`*_cooldowns`) uita o sursa dupa `alert_cooldown_secs`, iar
+// cleanup-ul pastreaza doar intrarile proaspete. Un host care scaneaza la
+// fiecare 6 minute produce de fiecare data o alerta identica — nimic nu spune
+// ca este a zecea de azi.
+//
+// Istoricul de aici pastreaza alertele fiecarei surse pe un orizont lung
+// (`horizon_hours`, implicit o saptamana): cate au fost, prima si ultima,
+// tipurile. Fiecare alerta primeste contextul
+//
+//   a 4-a alerta in 24h (9 in 168h, prima 2026-10-14 08:12; Fast Scan, Accept Scan)
+//
+// care creste severitatea (factorul de recidiva din severity.rs) si, la
+// `persistent_threshold` alerte in fereastra, produce o alerta separata
+// PersistentScanner — cel mult una per sursa la `persistent_cooldown_hours`.
+//
+// Alertele derivate (Escalation, AttackChain, PersistentScanner) primesc
+// contextul dar nu se numara: ar dubla alertele din care provin.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. Instant + DateTime<Local> in aceeasi inregistrare
+//    `Instant` este monoton (nu sare la schimbarea orei sistemului) — bun
+//    pentru ferestre si expirare. `DateTime<Local>` este ora de perete —
+//    bun pentru afisare ("prima 2026-10-14 08:12"). Le pastram pe amandoua.
+//
+// =============================================================================
+
+use crate::config::RepeatOffendersConfig;
+use crate::detector::ScanType;
+use chrono::{DateTime, Local};
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Numarul maxim de alerte retinute per sursa (cele mai vechi ies primele).
+const MAX_OFFENCES_PER_SOURCE: usize = 256;
+
+/// Contextul de recidiva atasat unei alerte.
+#[derive(Debug, Clone)]
+pub struct OffenceDetail {
+    /// Numarul alertei sursei in fereastra, inclusiv aceasta (1 = prima).
+    pub nth: usize,
+    pub window_hours: u64,
+    /// Alertele sursei pe tot orizontul, inclusiv aceasta.
+    pub total: usize,
+    pub horizon_hours: u64,
+    /// Prima alerta a sursei din orizont.
+    pub first_seen: DateTime<Local>,
+    /// Alerta anterioara a sursei (None la prima).
+    pub last_seen: Option<DateTime<Local>>,
+    /// Tipurile distincte de alerta din orizont, in ordinea aparitiei.
+    pub scan_types: Vec<String>,
+}
+
+impl OffenceDetail {
+    /// "a 4-a alerta in 24h"
+    pub fn headline(&self) -> String {
+        format!("a {}-a alerta in {}h", self.nth, self.window_hours)
+    }
+
+    /// "a 4-a alerta in 24h (9 in 168h, prima 2026-10-14 08:12; Fast Scan, Accept Scan)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({} in {}h, prima {}; {})",
+            self.headline(),
+            self.total,
+            self.horizon_hours,
+            self.first_seen.format("%Y-%m-%d %H:%M"),
+            self.scan_types.join(", ")
+        )
+    }
+
+    /// Sursa a mai alertat in fereastra.
+    pub fn is_repeat(&self) -> bool {
+        self.nth > 1
+    }
+}
+
+/// Tipurile care se numara ca recidiva. Cele derivate din alte alerte nu,
+/// nici alerta de test.
+pub fn counts_as_offence(scan_type: &ScanType) -> bool {
+    !matches!(
+        scan_type,
+        ScanType::Escalation
+            | ScanType::AttackChain
+            | ScanType::PersistentScanner
+            | ScanType::TestAlert
+    )
+}
+
+struct Offence {
+    at: Instant,
+    when: DateTime<Local>,
+    scan_type: String,
+}
+
+#[derive(Default)]
+struct SourceHistory {
+    offences: VecDeque<Offence>,
+    /// Ultima notificare PersistentScanner pentru sursa.
+    last_persistent: Option<Instant>,
+}
+
+/// Istoricul alertelor per sursa pe orizontul configurat.
+pub struct OffenderTracker {
+    sources: DashMap<IpAddr, SourceHistory>,
+}
+
+impl OffenderTracker {
+    pub fn new() -> Self {
+        Self { sources: DashMap::new() }
+    }
+
+    /// Contextul pentru o alerta noua a sursei — alertele deja inregistrate
+    /// plus aceasta (care nu e inca inregistrata).
+    pub fn context(
+        &self,
+        ip: &IpAddr,
+        scan_type: &ScanType,
+        now: Instant,
+        cfg: &RepeatOffendersConfig,
+    ) -> OffenceDetail {
+        let current = Offence { at: now, when: Local::now(), scan_type: scan_type.to_string() };
+        match self.sources.get(ip) {
+            Some(history) => detail(history.offences.iter().chain([&current]), now, cfg),
+            None => detail([&current].into_iter(), now, cfg),
+        }
+    }
+
+    /// Inregistreaza alerta sursei (dupa ce toate alertele evenimentului si-au
+    /// primit contextul, ca alertele simultane sa nu se numere una pe alta).
+    pub fn record(&self, ip: IpAddr, scan_type: &ScanType, now: Instant) {
+        let mut history = self.sources.entry(ip).or_default();
+        history.offences.push_back(Offence {
+            at: now,
+            when: Local::now(),
+            scan_type: scan_type.to_string(),
+        });
+        if history.offences.len() > MAX_OFFENCES_PER_SOURCE {
+            history.offences.pop_front();
+        }
+    }
+
+    /// Contextul PersistentScanner daca sursa a atins `persistent_threshold`
+    /// alerte in fereastra si nu a fost notificata in ultimele
+    /// `persistent_cooldown_hours` ore. Marcheaza notificarea.
+    pub fn persistent(
+        &self,
+        ip: &IpAddr,
+        now: Instant,
+        cfg: &RepeatOffendersConfig,
+    ) -> Option<OffenceDetail> {
+        if cfg.persistent_threshold == 0 {
+            return None;
+        }
+        let mut history = self.sources.get_mut(ip)?;
+        let detail = detail(history.offences.iter(), now, cfg);
+        if detail.nth < cfg.persistent_threshold {
+            return None;
+        }
+        let cooldown = Duration::from_secs(cfg.persistent_cooldown_hours * 3600);
+        if history
+            .last_persistent
+            .is_some_and(|t| now.saturating_duration_since(t) < cooldown)
+        {
+            return None;
+        }
+        history.last_persistent = Some(now);
+        Some(detail)
+    }
+
+    /// Uita istoricul sursei — evacuarea LRU si `ids-rs ctl clear-state`:
+    /// o sursa stearsa reincepe de la prima alerta.
+    pub fn remove(&self, ip: &IpAddr) {
+        self.sources.remove(ip);
+    }
+
+    /// Sterge alertele iesite din orizont si sursele fara alerte.
+    pub fn cleanup(&self, now: Instant, cfg: &RepeatOffendersConfig) {
+        let horizon = Duration::from_secs(cfg.horizon_hours * 3600);
+        self.sources.retain(|_, history| {
+            while history
+                .offences
+                .front()
+                .is_some_and(|o| now.saturating_duration_since(o.at) > horizon)
+            {
+                history.offences.pop_front();
+            }
+            !history.offences.is_empty()
+        });
+    }
+}
+
+/// Contextul calculat din alertele unei surse (cele mai vechi primele).
+fn detail<'a>(
+    offences: impl Iterator<Item = &'a Offence>,
+    now: Instant,
+    cfg: &RepeatOffendersConfig,
+) -> OffenceDetail {
+    let window = Duration::from_secs(cfg.window_hours * 3600);
+    let horizon = Duration::from_secs(cfg.horizon_hours * 3600);
+
+    let mut nth = 0;
+    let mut total = 0;
+    let mut first_seen = None;
+    let mut previous = None;
+    let mut last_seen = None;
+    let mut scan_types: Vec<String> = Vec::new();
+    for offence in offences {
+        let age = now.saturating_duration_since(offence.at);
+        if age > horizon {
+            continue;
+        }
+        total += 1;
+        if age <= window {
+            nth += 1;
+        }
+        first_seen.get_or_insert(offence.when);
+        last_seen = previous;
+        previous = Some(offence.when);
+        if !scan_types.contains(&offence.scan_type) {
+            scan_types.push(offence.scan_type.clone());
+        }
+    }
+
+    OffenceDetail {
+        nth,
+        window_hours: cfg.window_hours,
+        total,
+        horizon_hours: cfg.horizon_hours,
+        first_seen: first_seen.unwrap_or_else(Local::now),
+        last_seen,
+        scan_types,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg() -> RepeatOffendersConfig {
+        RepeatOffendersConfig {
+            window_hours: 1,
+            horizon_hours: 4,
+            persistent_threshold: 3,
+            persistent_cooldown_hours: 2,
+            ..RepeatOffendersConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_context_counts_window_and_horizon() {
+        let tracker = OffenderTracker::new();
+        let cfg = cfg();
+        let ip: IpAddr = "10.0.5.20".parse().unwrap();
+        let start = Instant::now();
+
+        let first = tracker.context(&ip, &ScanType::Fast, start, &cfg);
+        assert_eq!((first.nth, first.total), (1, 1));
+        assert!(!first.is_repeat() && first.last_seen.is_none());
+
+        tracker.record(ip, &ScanType::Fast, start);
+        tracker.record(ip, &ScanType::AcceptScan, start + Duration::from_secs(3600));
+
+        // La 3h: prima alerta e in orizont (4h) dar nu in fereastra (1h).
+        let now = start + Duration::from_secs(3 * 3600);
+        let ctx = tracker.context(&ip, &ScanType::Fast, now, &cfg);
+        assert_eq!((ctx.nth, ctx.total), (1, 3));
+        assert_eq!(ctx.scan_types, vec!["Fast Scan", "Accept Scan"]);
+        assert!(ctx.last_seen.is_some());
+        assert_eq!(ctx.headline(), "a 1-a alerta in 1h");
+
+        // La 5h prima alerta iese din orizont si este stearsa la cleanup.
+        let later = start + Duration::from_secs(5 * 3600);
+        tracker.cleanup(later, &cfg);
+        assert_eq!(tracker.sources.get(&ip).unwrap().offences.len(), 1);
+        tracker.cleanup(start + Duration::from_secs(7 * 3600), &cfg);
+        assert!(tracker.sources.is_empty());
+    }
+
+    #[test]
+    fn test_persistent_threshold_and_cooldown() {
+        let tracker = OffenderTracker::new();
+        let cfg = cfg();
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let start = Instant::now();
+
+        for i in 0..2 {
+            tracker.record(ip, &ScanType::Fast, start + Duration::from_secs(i * 600));
+        }
+        assert!(tracker.persistent(&ip, start + Duration::from_secs(600), &cfg).is_none());
+
+        tracker.record(ip, &ScanType::Slow, start + Duration::from_secs(1200));
+        let hit = tracker.persistent(&ip, start + Duration::from_secs(1200), &cfg).unwrap();
+        assert_eq!(hit.nth, 3);
+
+        // Cooldown de 2h: a 4-a alerta nu renotifica.
+        tracker.record(ip, &ScanType::Fast, start + Duration::from_secs(1800));
+        assert!(tracker.persistent(&ip, start + Duration::from_secs(1800), &cfg).is_none());
+
+        // Dezactivat cu prag 0.
+        let off = RepeatOffendersConfig { persistent_threshold: 0, ..cfg };
+        assert!(tracker.persistent(&ip, start + Duration::from_secs(9 * 3600), &off).is_none());
+    }
+}
@@ -0,0 +1,164 @@
+// =============================================================================
+// parser/auto.rs - Detectia automata a formatului, per linie (`parser = "auto"`)
+// =============================================================================
+//
+// In timpul unei migrari, colectorul primeste amestecat Gaia raw, LEA prin
+// ArcSight si CEF generic. `AutoParser` incearca parserele in ordinea din
+// `network.auto_parsers`; prima care recunoaste linia castiga.
+//
+// Un firewall trimite de regula un singur format, deci ultimul parser reusit
+// este retinut per expeditor (adresa sursa a pachetului UDP) si incercat
+// primul la urmatoarea linie — pentru traficul normal, o singura incercare
+// de parsare per linie, ca la un parser fix.
+//
+// Contoarele per parser (`match_counts`) arata cat trafic vine in fiecare
+// format — in `GET /stats`, `ids-rs replay` si `ids-rs check-config --sample`.
+//
+// NOTA RUST - `AtomicU64` fara Mutex:
+// `parse` primeste `&self` (trait-ul cere Send + Sync, parser-ul este
+// partajat). Contoarele se incrementeaza cu `fetch_add` — atomic, fara lock;
+// `Ordering::Relaxed` ajunge, fiindca sunt doar statistici independente.
+//
+// =============================================================================
+
+use super::{create_parser, LogEvent, LogParser, PARSER_NAMES};
+use anyhow::{bail, Result};
+use dashmap::DashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Expeditorii retinuti; la depasire cache-ul este golit (colectorul are de
+/// obicei cateva firewall-uri, nu mii).
+const MAX_CACHED_SENDERS: usize = 4096;
+
+pub struct AutoParser {
+    /// (cheia din config, parser-ul), in ordinea incercarilor.
+    parsers: Vec<(&'static str, Box<dyn LogParser>)>,
+    /// Liniile recunoscute de fiecare parser (acelasi index ca `parsers`).
+    matches: Vec<AtomicU64>,
+    /// Ultimul parser reusit per expeditor (index in `parsers`).
+    last_match: DashMap<IpAddr, usize>,
+    name: String,
+    expected: String,
+}
+
+impl AutoParser {
+    /// Parserele in ordinea data; cheile sunt cele din `network.parser`.
+    pub fn new<S: AsRef<str>>(order: &[S]) -> Result<Self> {
+        if order.is_empty() {
+            bail!("parser auto: lista de parsere este goala");
+        }
+        let mut parsers = Vec::with_capacity(order.len());
+        for key in order {
+            let key = key.as_ref();
+            let Some(&name) = PARSER_NAMES.iter().find(|&&n| n == key) else {
+                bail!("parser auto: '{}' nu este un parser concret ({})", key, PARSER_NAMES.join(", "));
+            };
+            parsers.push((name, create_parser(name)?));
+        }
+        let keys: Vec<&str> = parsers.iter().map(|(key, _)| *key).collect();
+        let expected = parsers
+            .iter()
+            .map(|(key, p)| format!("{}: {}", key, p.expected_format()))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        Ok(AutoParser {
+            matches: parsers.iter().map(|_| AtomicU64::new(0)).collect(),
+            parsers,
+            last_match: DashMap::new(),
+            name: format!("Auto ({})", keys.join(" → ")),
+            expected,
+        })
+    }
+
+    /// Incearca parser-ul `index`; la succes numara potrivirea.
+    fn try_parser(&self, index: usize, line: &str) -> Option<LogEvent> {
+        let event = self.parsers[index].1.parse(line)?;
+        self.matches[index].fetch_add(1, Ordering::Relaxed);
+        Some(event)
+    }
+}
+
+impl LogParser for AutoParser {
+    fn parse(&self, line: &str) -> Option<LogEvent> {
+        self.parse_from(line, None)
+    }
+
+    fn parse_from(&self, line: &str, sender: Option<IpAddr>) -> Option<LogEvent> {
+        let cached = sender.and_then(|ip| self.last_match.get(&ip).map(|index| *index));
+        if let Some(index) = cached {
+            if let Some(event) = self.try_parser(index, line) {
+                return Some(event);
+            }
+        }
+        for index in (0..self.parsers.len()).filter(|&i| Some(i) != cached) {
+            if let Some(event) = self.try_parser(index, line) {
+                if let Some(ip) = sender {
+                    if self.last_match.len() >= MAX_CACHED_SENDERS {
+                        self.last_match.clear();
+                    }
+                    self.last_match.insert(ip, index);
+                }
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn expected_format(&self) -> &str {
+        &self.expected
+    }
+
+    fn match_counts(&self) -> Vec<(&'static str, u64)> {
+        self.parsers
+            .iter()
+            .zip(&self.matches)
+            .map(|((key, _), count)| (*key, count.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAIA: &str = "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop 192.168.11.7 >eth8 \
+                        rule: 113; src: 192.168.11.7; dst: 10.0.0.5; proto: tcp; service: 22; s_port: 40000;";
+    const LEA: &str = "time=\"1777777777777\" action=\"Drop\" src=\"10.0.0.9\" dst=\"10.0.0.5\" \
+                       service=\"23\" proto=\"6\" s_port=\"50000\"";
+    const CEF: &str = "<134>Feb 17 11:32:44 fw CEF:0|Vendor|FW|1.0|100|Deny|5|src=10.0.0.7 dst=10.0.0.5 \
+                       dpt=443 proto=TCP act=drop";
+
+    #[test]
+    fn test_auto_parser_mixed_formats_and_sender_cache() {
+        let parser = AutoParser::new(&PARSER_NAMES).unwrap();
+        let sender: IpAddr = "192.168.99.1".parse().unwrap();
+
+        // Fiecare format este recunoscut pe linia lui, in acelasi flux.
+        let gaia = parser.parse_from(GAIA, Some(sender)).unwrap();
+        assert_eq!(gaia.source_ip.to_string(), "192.168.11.7");
+        let lea = parser.parse_from(LEA, Some(sender)).unwrap();
+        assert_eq!(lea.source_ip.to_string(), "10.0.0.9");
+        let cef = parser.parse_from(CEF, None).unwrap();
+        assert_eq!(cef.source_ip.to_string(), "10.0.0.7");
+        assert_eq!(cef.dest_port, 443);
+        assert!(parser.parse_from("linie fara format", Some(sender)).is_none());
+
+        // Expeditorul retine ultimul format reusit (gaia_cef, dupa linia LEA);
+        // CEF-ul fara expeditor nu intra in cache.
+        assert_eq!(parser.last_match.get(&sender).map(|i| *i), Some(1));
+        assert_eq!(parser.last_match.len(), 1);
+        assert_eq!(parser.match_counts(), vec![("gaia", 1), ("gaia_cef", 1), ("cef", 1)]);
+
+        // Ordinea configurata este respectata; cheile necunoscute sunt refuzate.
+        let only_cef = AutoParser::new(&["cef"]).unwrap();
+        assert!(only_cef.parse(GAIA).is_none());
+        assert_eq!(only_cef.name(), "Auto (cef)");
+        assert!(AutoParser::new(&["auto"]).is_err());
+        assert!(AutoParser::new::<&str>(&[]).is_err());
+    }
+}
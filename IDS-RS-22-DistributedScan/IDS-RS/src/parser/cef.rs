@@ -12,9 +12,18 @@
 // Extensiile sunt perechi cheie=valoare separate prin spatiu:
 //   src  = IP sursa
 //   dst  = IP destinatie
-//   dpt  = port destinatie
+//          (IPv4 sau IPv6, inclusiv "[2001:db8::1]"; c6a2 / c6a3 = IPv6 ArcSight)
+//   dpt  = port destinatie (lipseste la ICMP → port 0)
 //   proto= protocol
 //   act  = actiune
+//   icmpType / icmpCode = tip/cod ICMP (optionale)
+//   spt  = port sursa, tcpFlags = flag-uri TCP (optionale)
+//   sourceTranslatedAddress / destinationTranslatedAddress /
+//   sourceTranslatedPort / destinationTranslatedPort = NAT (optionale)
+//   rule / ruleUid / ruleName = regula firewall, deviceInboundInterface = interfata
+//
+// Valorile pot contine spatii (ex: ruleName=Cleanup rule) — o valoare se
+// termina la urmatorul cuvant de forma `cheie=`.
 //
 // NOTA: Acesta este un SCHELET functional. Parseaza formatul CEF de baza,
 // dar va trebui adaptat cand integrarea ArcSight reala va fi disponibila.
@@ -22,7 +31,7 @@
 //
 // =============================================================================
 
-use super::{LogEvent, LogParser};
+use super::{parse_ip, parse_nat_ip, parse_nat_port, LogEvent, LogParser, Protocol};
 use std::net::IpAddr;
 
 /// Parser pentru log-uri in format CEF (Common Event Format).
@@ -41,6 +50,35 @@ impl CefParser {
     }
 }
 
+impl CefParser {
+    /// Imparte extensiile in perechi (cheie, valoare). Valorile pot contine
+    /// spatii (ex: `ruleName=Cleanup rule`): o valoare se termina la urmatorul
+    /// cuvant de forma `cheie=`.
+    fn extension_pairs(extension: &str) -> Vec<(&str, &str)> {
+        // Pozitia de start a fiecarui token `cheie=...` din extensii.
+        let mut starts: Vec<(usize, usize)> = Vec::new();
+        let mut offset = 0;
+        for token in extension.split(' ') {
+            if let Some(eq) = token.find('=') {
+                let key = &token[..eq];
+                if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    starts.push((offset, offset + eq));
+                }
+            }
+            offset += token.len() + 1;
+        }
+
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, eq))| {
+                let end = starts.get(i + 1).map_or(extension.len(), |next| next.0);
+                (&extension[start..eq], extension[eq + 1..end].trim())
+            })
+            .collect()
+    }
+}
+
 impl LogParser for CefParser {
     /// Parseaza o linie CEF si extrage campurile relevante.
     ///
@@ -88,6 +126,18 @@ impl LogParser for CefParser {
         let mut dest_port: Option<u16> = None;
         let mut protocol = String::from("tcp");
         let mut action = String::new();
+        let mut icmp_type: Option<u8> = None;
+        let mut icmp_code: Option<u8> = None;
+        let mut source_port: Option<u16> = None;
+        let mut tcp_flags: Option<String> = None;
+        let mut xlate_src: Option<IpAddr> = None;
+        let mut xlate_dst: Option<IpAddr> = None;
+        let mut xlate_sport: Option<u16> = None;
+        let mut xlate_dport: Option<u16> = None;
+        let mut rule: Option<String> = None;
+        let mut rule_uid: Option<String> = None;
+        let mut rule_name: Option<String> = None;
+        let mut interface: Option<String> = None;
 
         // Parcurgem perechile cheie=valoare din extensii.
         //
@@ -97,26 +147,48 @@ impl LogParser for CefParser {
         // care prinde tot ce nu a fost tratat explicit.
         //
         // Match pe &str compara string-uri la nivel de continut (nu pointeri).
-        for token in extension.split_whitespace() {
-            // `splitn(2, '=')` imparte in maxim 2 parti la primul '='.
-            let kv: Vec<&str> = token.splitn(2, '=').collect();
-            if kv.len() == 2 {
-                match kv[0] {
-                    "src" => source_ip = kv[1].parse().ok(),
-                    "dst" => dest_ip = kv[1].parse().ok(),
-                    "dpt" => dest_port = kv[1].parse().ok(),
-                    "proto" => protocol = kv[1].to_lowercase(),
-                    "act" => action = kv[1].to_lowercase(),
-                    // Ignoram cheile necunoscute (extensibil pe viitor).
-                    _ => {}
-                }
+        for (key, value) in Self::extension_pairs(extension) {
+            match key {
+                "src" => source_ip = parse_ip(value),
+                "dst" => dest_ip = parse_ip(value),
+                // ArcSight muta adresele IPv6 in campurile dedicate c6a2/c6a3
+                // cand src/dst sunt rezervate pentru IPv4.
+                "c6a2" => source_ip = source_ip.or_else(|| parse_ip(value)),
+                "c6a3" => dest_ip = dest_ip.or_else(|| parse_ip(value)),
+                "dpt" => dest_port = value.parse().ok(),
+                "proto" => protocol = value.to_lowercase(),
+                "act" => action = value.to_lowercase(),
+                "icmpType" | "icmp_type" => icmp_type = value.parse().ok(),
+                "icmpCode" | "icmp_code" => icmp_code = value.parse().ok(),
+                "spt" => source_port = value.parse().ok(),
+                "tcpFlags" | "tcp_flags" => tcp_flags = Some(value.to_string()),
+                "sourceTranslatedAddress" | "xlatesrc" => xlate_src = parse_nat_ip(value),
+                "destinationTranslatedAddress" | "xlatedst" => xlate_dst = parse_nat_ip(value),
+                "sourceTranslatedPort" | "xlatesport" => xlate_sport = parse_nat_port(value),
+                "destinationTranslatedPort" | "xlatedport" => xlate_dport = parse_nat_port(value),
+                "rule" => rule = Some(value.to_string()),
+                "ruleUid" | "rule_uid" => rule_uid = Some(value.to_string()),
+                "ruleName" | "rule_name" => rule_name = Some(value.to_string()),
+                "deviceInboundInterface" | "ifname" => interface = Some(value.to_string()),
+                // Ignoram cheile necunoscute (extensibil pe viitor).
+                _ => {}
             }
         }
 
         // Extragem valorile din Option-uri cu `?`.
         // Daca oricare este None, intreaga functie returneaza None.
+        // ICMP nu are dpt — admis cu port 0 (pentru detectia Ping Sweep).
         let source_ip = source_ip?;
-        let dest_port = dest_port?;
+        let is_icmp = Protocol::from_name(&protocol) == Protocol::Icmp;
+        let dest_port = match dest_port {
+            Some(port) => port,
+            None if is_icmp => 0,
+            None => return None,
+        };
+        if !is_icmp {
+            icmp_type = None;
+            icmp_code = None;
+        }
 
         // Filtram: procesam "drop" si "accept" (similar cu parser-ul Gaia).
         //   drop   = conexiune BLOCATA de firewall → Fast/Slow Scan
@@ -126,12 +198,19 @@ impl LogParser for CefParser {
         }
 
         Some(LogEvent {
-            source_ip,
-            dest_ip,
-            dest_port,
-            protocol,
-            action,
-            raw_log: line.to_string(),
+            icmp_type,
+            icmp_code,
+            source_port,
+            tcp_flags,
+            xlate_src,
+            xlate_dst,
+            xlate_sport,
+            xlate_dport,
+            rule,
+            rule_uid,
+            rule_name,
+            interface,
+            ..LogEvent::new(source_ip, dest_ip, dest_port, protocol, action, line.to_string())
         })
     }
 
@@ -199,12 +278,54 @@ mod tests {
         assert_eq!(event.action, "drop");
     }
 
+    #[test]
+    fn test_parse_cef_rule_name_with_spaces() {
+        let parser = CefParser::new();
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|src=192.168.11.7 dst=10.0.0.1 dpt=23 proto=TCP \
+            act=drop rule=133 ruleName=Cleanup rule ruleUid=gec";
+
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.dest_port, 23);
+        assert_eq!(event.rule.as_deref(), Some("133"));
+        assert_eq!(event.rule_name.as_deref(), Some("Cleanup rule"));
+        assert_eq!(event.rule_uid.as_deref(), Some("gec"));
+        assert_eq!(event.rule_label().as_deref(), Some("133 (Cleanup rule)"));
+    }
+
+    #[test]
+    fn test_parse_cef_ipv6_forms() {
+        let parser = CefParser::new();
+
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|src=[2001:db8::7] dst=2001:0db8:0:0:0:0:0:1 dpt=22 proto=TCP act=drop";
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.source_ip, "2001:db8::7".parse::<IpAddr>().unwrap());
+        assert_eq!(event.dest_ip, Some("2001:db8::1".parse().unwrap()));
+
+        // ArcSight: IPv6 in c6a2/c6a3; IPv4 mapat → IPv4.
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|c6a2=fe80::1%eth0 c6a3=::ffff:10.0.0.1 dpt=22 proto=TCP act=drop";
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.source_ip, "fe80::1".parse::<IpAddr>().unwrap());
+        assert_eq!(event.dest_ip, Some("10.0.0.1".parse().unwrap()));
+    }
+
     #[test]
     fn test_reject_non_cef() {
         let parser = CefParser::new();
         assert!(parser.parse("not a CEF log").is_none());
     }
 
+    #[test]
+    fn test_parse_cef_icmp_without_dpt() {
+        let parser = CefParser::new();
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|src=192.168.11.7 dst=10.0.0.1 proto=ICMP icmpType=8 icmpCode=0 act=drop";
+
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.dest_port, 0);
+        assert!(event.is_icmp());
+        assert_eq!(event.icmp_type, Some(8));
+        assert_eq!(event.icmp_code, Some(0));
+    }
+
     #[test]
     fn test_incomplete_cef_fields() {
         let parser = CefParser::new();
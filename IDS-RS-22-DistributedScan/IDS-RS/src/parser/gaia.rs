@@ -19,6 +19,9 @@
 //   - IP sursa: din "src: <IP>" (cel care scaneaza)
 //   - Port destinatie: din "service: <port>" (portul scanat)
 //   - Protocol: din "proto: <proto>"
+//   - ICMP: din "ICMP Type: <n>; ICMP Code: <n>" (fara service → port 0)
+//   - Optionale: s_port, tcp_flags, xlatesrc/xlatedst/xlatesport/xlatedport, rule, rule_uid,
+//     rule_name si interfata din header (">eth8" dupa IP-ul gateway-ului)
 //
 // CONCEPTE RUST EXPLICATE:
 //
@@ -35,7 +38,7 @@
 //
 // =============================================================================
 
-use super::{LogEvent, LogParser};
+use super::{parse_ip, parse_nat_ip, parse_nat_port, LogEvent, LogParser, Protocol};
 use regex::Regex;
 use std::net::IpAddr;
 
@@ -65,8 +68,10 @@ impl GaiaParser {
         //   Checkpoint:\s+ = literalul "Checkpoint:" urmat de spatii
         //   \S+\s+\S+\s+  = checkpoint date + time (ex: "3Sep2007 15:10:28")
         //   (accept|drop|reject) = actiunea (grup 1 capturat)
+        //   (?:\S+\s+[<>](\S+)\s+)? = optional: gateway + interfata (grup 2,
+        //                  ex: "192.168.11.7 >eth8"; '>' = intrare, '<' = iesire)
         let header_re = Regex::new(
-            r"(?i)Checkpoint:\s+\S+\s+\S+\s+(accept|drop|reject)\s+"
+            r"(?i)Checkpoint:\s+\S+\s+\S+\s+(accept|drop|reject)\s+(?:\S+\s+[<>](\S+)\s+)?"
         )?;
 
         Ok(Self { header_re })
@@ -91,6 +96,14 @@ impl GaiaParser {
         }
         None
     }
+
+    /// Primul camp numeric ICMP gasit dintre variantele de nume acceptate
+    /// (SmartView foloseste "ICMP Type", exporturile mai noi "icmp-type").
+    fn extract_icmp(extensions: &str, keys: &[&str]) -> Option<u8> {
+        keys.iter()
+            .find_map(|k| Self::extract_field(extensions, k))
+            .and_then(|v| v.parse().ok())
+    }
 }
 
 /// Implementarea trait-ului LogParser pentru GaiaParser.
@@ -147,12 +160,11 @@ impl LogParser for GaiaParser {
         // Extragem source_ip din "src: <IP>".
         // Log-urile broadcast (fara "src:") sunt ignorate - return None.
         let src_str = Self::extract_field(extensions, "src")?;
-        let source_ip: IpAddr = src_str.parse().ok()?;
+        let source_ip: IpAddr = parse_ip(src_str)?;
 
         // Extragem dest_ip din "dst: <IP>" (tinta atacului).
         // Option<> - unele log-uri pot lipsi campul dst.
-        let dest_ip: Option<IpAddr> = Self::extract_field(extensions, "dst")
-            .and_then(|s| s.parse().ok());
+        let dest_ip: Option<IpAddr> = Self::extract_field(extensions, "dst").and_then(parse_ip);
 
         // Extragem protocolul din "proto: <proto>".
         let protocol = Self::extract_field(extensions, "proto")
@@ -160,20 +172,45 @@ impl LogParser for GaiaParser {
             .to_lowercase();
 
         // Extragem portul destinatie din "service: <port>".
-        // Log-urile ICMP (fara "service:") sunt ignorate - return None.
-        let service_str = Self::extract_field(extensions, "service")?;
-        let dest_port: u16 = service_str.parse().ok()?;
+        // ICMP nu are porturi: log-urile ICMP fara "service:" sunt admise cu
+        // port 0 si tip/cod ICMP (pentru detectia Ping Sweep). Orice alt
+        // protocol fara "service:" este ignorat - return None.
+        let is_icmp = Protocol::from_name(&protocol) == Protocol::Icmp;
+        let dest_port: u16 = match Self::extract_field(extensions, "service") {
+            Some(service_str) => service_str.parse().ok()?,
+            None if is_icmp => 0,
+            None => return None,
+        };
+        let (icmp_type, icmp_code) = if is_icmp {
+            (
+                Self::extract_icmp(extensions, &["ICMP Type", "icmp-type", "icmp_type"]),
+                Self::extract_icmp(extensions, &["ICMP Code", "icmp-code", "icmp_code"]),
+            )
+        } else {
+            (None, None)
+        };
+
+        // Campuri optionale: amprentare, NAT, regula, interfata.
+        let owned = |key: &str| Self::extract_field(extensions, key).map(str::to_string);
+        let ip_field = |key: &str| Self::extract_field(extensions, key).and_then(parse_nat_ip);
 
         // Construim LogEvent-ul. `line.to_string()` creaza un String owned
         // din &str (copiaza datele pe heap). Necesar deoarece LogEvent
         // trebuie sa fie independent de buffer-ul original.
         Some(LogEvent {
-            source_ip,
-            dest_ip,
-            dest_port,
-            protocol,
-            action,
-            raw_log: line.to_string(),
+            icmp_type,
+            icmp_code,
+            source_port: Self::extract_field(extensions, "s_port").and_then(|s| s.parse().ok()),
+            tcp_flags: owned("tcp_flags").or_else(|| owned("TCP flags")),
+            xlate_src: ip_field("xlatesrc"),
+            xlate_dst: ip_field("xlatedst"),
+            xlate_sport: Self::extract_field(extensions, "xlatesport").and_then(parse_nat_port),
+            xlate_dport: Self::extract_field(extensions, "xlatedport").and_then(parse_nat_port),
+            rule: owned("rule"),
+            rule_uid: owned("rule_uid").map(|u| u.trim_matches(|c| c == '{' || c == '}').to_string()),
+            rule_name: owned("rule_name"),
+            interface: caps.get(2).map(|m| m.as_str().to_string()),
+            ..LogEvent::new(source_ip, dest_ip, dest_port, protocol, action, line.to_string())
         })
     }
 
@@ -211,6 +248,59 @@ mod tests {
         assert_eq!(event.dest_port, 80);
         assert_eq!(event.protocol, "tcp");
         assert_eq!(event.action, "drop");
+        assert_eq!(event.source_port, Some(2854));
+        assert_eq!(event.rule.as_deref(), Some("113"));
+        assert_eq!(event.rule_uid.as_deref(), Some("AAAAAAAA-9999-8888-FFCF33A92D27"));
+        assert_eq!(event.interface.as_deref(), Some("eth8"));
+    }
+
+    #[test]
+    fn test_parse_flags_and_nat() {
+        let parser = GaiaParser::new().unwrap();
+        let log = "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop \
+            192.168.11.7 <eth1 rule: 7; rule_name: Block Scanners; src: 10.1.1.5; dst: 4.23.34.126; \
+            proto: tcp; service: 443; s_port: 40000; tcp_flags: FIN,PSH,URG; xlatesrc: 198.51.100.7;";
+
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.tcp_flags.as_deref(), Some("FIN,PSH,URG"));
+        assert_eq!(event.xlate_src, Some("198.51.100.7".parse().unwrap()));
+        assert!(event.xlate_dst.is_none());
+        assert_eq!(event.interface.as_deref(), Some("eth1"));
+        assert_eq!(event.rule_name.as_deref(), Some("Block Scanners"));
+        assert_eq!(event.rule_label().as_deref(), Some("7 (Block Scanners)"));
+    }
+
+    #[test]
+    fn test_parse_port_forward_nat() {
+        let parser = GaiaParser::new().unwrap();
+        let log = "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 accept \
+            192.168.11.7 <eth0 rule: 12; src: 203.0.113.50; dst: 198.51.100.10; proto: tcp; \
+            service: 8443; s_port: 51000; xlatesrc: 0.0.0.0; xlatedst: 10.0.0.20; \
+            xlatesport: 0; xlatedport: 443;";
+
+        let event = parser.parse(log).unwrap();
+        // 0.0.0.0 / 0 inseamna "netradus" pe Checkpoint.
+        assert!(event.xlate_src.is_none());
+        assert!(event.xlate_sport.is_none());
+        assert_eq!(event.xlate_dst, Some("10.0.0.20".parse().unwrap()));
+        assert_eq!(event.xlate_dport, Some(443));
+
+        let translated = event.translated();
+        assert_eq!(translated.source_ip, event.source_ip);
+        assert_eq!(translated.dest_ip, Some("10.0.0.20".parse().unwrap()));
+        assert_eq!(translated.dest_port, 443);
+    }
+
+    #[test]
+    fn test_parse_ipv6() {
+        let parser = GaiaParser::new().unwrap();
+        let log = "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop \
+            192.168.11.7 >eth8 rule: 7; src: 2001:db8:1:2::a; dst: [2001:db8::80]; proto: tcp; \
+            service: 443; s_port: 40000;";
+
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.source_ip, "2001:db8:1:2::a".parse::<IpAddr>().unwrap());
+        assert_eq!(event.dest_ip, Some("2001:db8::80".parse().unwrap()));
     }
 
     #[test]
@@ -246,13 +336,29 @@ mod tests {
 
     #[test]
     fn test_icmp_drop_no_service() {
-        // Drop ICMP fara "service:" - trebuie ignorat (return None).
+        // Drop ICMP fara "service:" - admis cu port 0 si tip/cod ICMP.
         let parser = GaiaParser::new().unwrap();
         let log = "Sep 3 15:12:56 192.168.99.1 Checkpoint: 3Sep2007 15:13:53 drop \
             192.168.11.7 >eth2 rule: 134; rule_uid: {11111111-2222-3333-BD17-711F536C7C33}; \
             ICMP: Echo Request; src: 203.193.149.227; dst: 64.129.8.245; proto: icmp; \
             ICMP Type: 8; ICMP Code: 0; product: VPN-1 & FireWall-1;";
 
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.source_ip.to_string(), "203.193.149.227");
+        assert_eq!(event.dest_port, 0);
+        assert!(event.is_icmp());
+        assert_eq!(event.icmp_type, Some(8));
+        assert_eq!(event.icmp_code, Some(0));
+    }
+
+    #[test]
+    fn test_non_icmp_without_service_ignored() {
+        // TCP fara "service:" - portul lipseste, evenimentul este ignorat.
+        let parser = GaiaParser::new().unwrap();
+        let log = "Sep 3 15:12:56 192.168.99.1 Checkpoint: 3Sep2007 15:13:53 drop \
+            192.168.11.7 >eth2 rule: 134; src: 203.193.149.227; dst: 64.129.8.245; \
+            proto: tcp; product: VPN-1 & FireWall-1;";
+
         assert!(parser.parse(log).is_none());
     }
 
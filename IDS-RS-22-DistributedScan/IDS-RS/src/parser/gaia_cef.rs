@@ -30,8 +30,11 @@
 //   action  = "Drop" / "Accept"
 //   src     = IP sursa (atacatorul)
 //   dst     = IP destinatie (tinta)
-//   service = port destinatie (numeric)
-//   proto   = numar protocol IANA (6=tcp, 17=udp, 1=icmp)
+//   service = port destinatie (numeric; lipseste la ICMP → port 0)
+//   proto   = numar protocol IANA (6=tcp, 17=udp, 1=icmp, 58=ipv6-icmp)
+//   icmp_type / icmp_code = tip/cod ICMP (optionale, doar pentru ICMP)
+//   s_port, tcp_flags, xlatesrc/xlatedst/xlatesport/xlatedport, rule (sau match_id), rule_uid,
+//   rule_name, ifname = optionale (amprentare, NAT, regula, interfata)
 //
 // CAPCANE (rezolvate prin boundary check):
 //   rule_action="Drop"   ← NU e action="Drop"  (precedat de '_', nu spatiu)
@@ -40,7 +43,7 @@
 //
 // =============================================================================
 
-use super::{LogEvent, LogParser};
+use super::{parse_ip, parse_nat_ip, parse_nat_port, LogEvent, LogParser, Protocol};
 use std::net::IpAddr;
 
 /// Parser pentru blob-uri LEA de la Checkpoint Gaia (via ArcSight).
@@ -105,6 +108,7 @@ impl GaiaCefParser {
             "6" => "tcp".to_string(),
             "17" => "udp".to_string(),
             "1" => "icmp".to_string(),
+            "58" => "ipv6-icmp".to_string(),
             other => other.to_lowercase(),
         }
     }
@@ -218,28 +222,52 @@ impl GaiaCefParser {
 
         // Extragem IP sursa (obligatoriu).
         let src_str = Self::extract_lea_field(blob, "src")?;
-        let source_ip: IpAddr = src_str.parse().ok()?;
+        let source_ip: IpAddr = parse_ip(src_str)?;
 
         // Extragem IP destinatie (optional).
-        let dest_ip: Option<IpAddr> = Self::extract_lea_field(blob, "dst")
-            .and_then(|s| s.parse().ok());
-
-        // Extragem portul destinatie din "service" (obligatoriu).
-        let service_str = Self::extract_lea_field(blob, "service")?;
-        let dest_port: u16 = service_str.parse().ok()?;
+        let dest_ip: Option<IpAddr> = Self::extract_lea_field(blob, "dst").and_then(parse_ip);
 
         // Extragem protocolul (optional, default tcp).
         let protocol = Self::extract_lea_field(blob, "proto")
             .map(Self::map_protocol)
             .unwrap_or_else(|| "tcp".to_string());
 
+        // Extragem portul destinatie din "service" — obligatoriu, cu exceptia
+        // ICMP (fara porturi): acolo lipsa lui inseamna port 0.
+        let is_icmp = Protocol::from_name(&protocol) == Protocol::Icmp;
+        let dest_port: u16 = match Self::extract_lea_field(blob, "service") {
+            Some(service_str) => service_str.parse().ok()?,
+            None if is_icmp => 0,
+            None => return None,
+        };
+        let icmp_field = |keys: [&str; 2]| -> Option<u8> {
+            keys.iter()
+                .find_map(|k| Self::extract_lea_field(blob, k))
+                .and_then(|v| v.parse().ok())
+        };
+        let (icmp_type, icmp_code) = if is_icmp {
+            (icmp_field(["icmp_type", "icmp-type"]), icmp_field(["icmp_code", "icmp-code"]))
+        } else {
+            (None, None)
+        };
+
+        let owned = |key: &str| Self::extract_lea_field(blob, key).map(str::to_string);
+        let ip_field = |key: &str| Self::extract_lea_field(blob, key).and_then(parse_nat_ip);
+
         Some(LogEvent {
-            source_ip,
-            dest_ip,
-            dest_port,
-            protocol,
-            action,
-            raw_log: raw_log.to_string(),
+            icmp_type,
+            icmp_code,
+            source_port: Self::extract_lea_field(blob, "s_port").and_then(|s| s.parse().ok()),
+            tcp_flags: owned("tcp_flags"),
+            xlate_src: ip_field("xlatesrc"),
+            xlate_dst: ip_field("xlatedst"),
+            xlate_sport: Self::extract_lea_field(blob, "xlatesport").and_then(parse_nat_port),
+            xlate_dport: Self::extract_lea_field(blob, "xlatedport").and_then(parse_nat_port),
+            rule: owned("rule").or_else(|| owned("match_id")),
+            rule_uid: owned("rule_uid").map(|u| u.trim_matches(|c| c == '{' || c == '}').to_string()),
+            rule_name: owned("rule_name"),
+            interface: owned("ifname"),
+            ..LogEvent::new(source_ip, dest_ip, dest_port, protocol, action, raw_log.to_string())
         })
     }
 }
@@ -298,6 +326,19 @@ mod tests {
         assert_eq!(event.action, "drop");
     }
 
+    #[test]
+    fn test_parse_ipv6() {
+        // IPv6 comprimat si expandat; ICMPv6 (58) → icmp.
+        let parser = GaiaCefParser::new();
+        let log = "<134>Feb 17 11:32:44 gw CEF:0|CheckPoint|FW-1|R77|100|action=\"Drop\" src=\"2001:db8:1:2::a\" dst=\"2001:0db8:0000:0000:0000:0000:0000:0001\" proto=\"58\" icmp_type=\"128\"|5|";
+
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.source_ip.to_string(), "2001:db8:1:2::a");
+        assert_eq!(event.dest_ip.unwrap().to_string(), "2001:db8::1");
+        assert!(event.is_icmp());
+        assert_eq!(event.icmp_type, Some(128));
+    }
+
     #[test]
     fn test_parse_valid_accept() {
         // Accept complet — pentru detectia Accept Scan.
@@ -351,6 +392,19 @@ mod tests {
         assert_eq!(event.dest_port, 0);
     }
 
+    #[test]
+    fn test_icmp_without_service() {
+        // ICMP fara service — admis cu port 0, tip/cod extrase.
+        let parser = GaiaCefParser::new();
+        let log = "<134>Feb 17 11:32:44 gw CEF:0|CheckPoint|FW-1|R77|100|action=\"Drop\" src=\"192.168.11.7\" dst=\"10.0.0.1\" proto=\"1\" icmp_type=\"8\" icmp_code=\"0\"|5|";
+
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.dest_port, 0);
+        assert!(event.is_icmp());
+        assert_eq!(event.icmp_type, Some(8));
+        assert_eq!(event.icmp_code, Some(0));
+    }
+
     #[test]
     fn test_case_insensitive_action() {
         // action="DROP" (uppercase) — trebuie normalizat la "drop".
@@ -417,6 +471,12 @@ mod tests {
         assert_eq!(event.dest_port, 23);
         assert_eq!(event.protocol, "tcp");
         assert_eq!(event.action, "drop");
+        // s_port invalid (> 65535) — ignorat; parent_rule nu e confundat cu rule.
+        assert!(event.source_port.is_none());
+        assert_eq!(event.rule.as_deref(), Some("133"));
+        assert_eq!(event.rule_uid.as_deref(), Some("gec"));
+        assert_eq!(event.rule_name.as_deref(), Some("Cleanup rule"));
+        assert_eq!(event.interface.as_deref(), Some("bound.2"));
     }
 
     #[test]
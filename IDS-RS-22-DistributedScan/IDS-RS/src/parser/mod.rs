@@ -42,10 +42,12 @@
 //
 // =============================================================================
 
+pub mod auto;
 pub mod cef;
 pub mod gaia;
 pub mod gaia_cef;
 
+use crate::config::NetworkConfig;
 use std::net::IpAddr;
 
 /// Eveniment de log parsabil - structura comuna pentru toate formatele.
@@ -69,18 +71,193 @@ pub struct LogEvent {
     pub dest_ip: Option<IpAddr>,
 
     /// Portul destinatie care a fost scanat / accesat.
+    /// 0 pentru ICMP (protocolul nu are porturi — vezi icmp_type/icmp_code).
     pub dest_port: u16,
 
     /// Protocolul (tcp, udp, icmp, etc.).
     pub protocol: String,
 
+    /// Tipul si codul ICMP (ex: 8/0 = echo request). None pentru TCP/UDP
+    /// sau cand log-ul ICMP nu le contine.
+    pub icmp_type: Option<u8>,
+    pub icmp_code: Option<u8>,
+
     /// Actiunea firewall-ului (drop, reject, accept, etc.).
     pub action: String,
 
+    /// Portul sursa (`s_port` / `spt`). Folosit la amprentarea uneltei de
+    /// scanare: nmap -sS refoloseste acelasi port sursa, masscan il randomizeaza.
+    pub source_port: Option<u16>,
+
+    /// Flag-urile TCP asa cum apar in log (ex: "SYN", "FIN,PSH,URG", "S").
+    /// Interpretate de `fingerprint::parse_tcp_flags`.
+    pub tcp_flags: Option<String>,
+
+    /// Adresele si porturile dupa NAT (`xlatesrc` / `xlatedst` / `xlatesport` /
+    /// `xlatedport`), daca gateway-ul a tradus. `src` / `dst` raman cele originale.
+    pub xlate_src: Option<IpAddr>,
+    pub xlate_dst: Option<IpAddr>,
+    pub xlate_sport: Option<u16>,
+    pub xlate_dport: Option<u16>,
+
+    /// Regula de firewall care a decis: numarul (`rule`), UID-ul (`rule_uid`)
+    /// si numele din politica (`rule_name`).
+    pub rule: Option<String>,
+    pub rule_uid: Option<String>,
+    pub rule_name: Option<String>,
+
+    /// Interfata gateway-ului pe care a intrat pachetul (ex: "eth8").
+    pub interface: Option<String>,
+
     /// Log-ul original brut - pastrat pentru audit/debugging.
     pub raw_log: String,
 }
 
+impl LogEvent {
+    /// Eveniment cu campurile obligatorii; cele optionale (ICMP, port sursa,
+    /// flag-uri, NAT, regula, interfata) sunt None.
+    ///
+    /// NOTA RUST: ca la `Alert::new`, parserii completeaza campurile optionale
+    /// prin struct update syntax: `LogEvent { source_port, ..LogEvent::new(...) }`.
+    pub fn new(
+        source_ip: IpAddr,
+        dest_ip: Option<IpAddr>,
+        dest_port: u16,
+        protocol: String,
+        action: String,
+        raw_log: String,
+    ) -> Self {
+        Self {
+            source_ip,
+            dest_ip,
+            dest_port,
+            protocol,
+            icmp_type: None,
+            icmp_code: None,
+            action,
+            source_port: None,
+            tcp_flags: None,
+            xlate_src: None,
+            xlate_dst: None,
+            xlate_sport: None,
+            xlate_dport: None,
+            rule: None,
+            rule_uid: None,
+            rule_name: None,
+            interface: None,
+            raw_log,
+        }
+    }
+
+    /// Protocolul evenimentului, normalizat.
+    pub fn proto(&self) -> Protocol {
+        Protocol::from_name(&self.protocol)
+    }
+
+    /// Evenimentul este ICMP (fara port destinatie real).
+    pub fn is_icmp(&self) -> bool {
+        self.proto() == Protocol::Icmp
+    }
+
+    /// Gateway-ul a tradus cel putin o adresa sau un port (NAT).
+    pub fn has_nat(&self) -> bool {
+        self.xlate_src.is_some()
+            || self.xlate_dst.is_some()
+            || self.xlate_sport.is_some()
+            || self.xlate_dport.is_some()
+    }
+
+    /// Evenimentul vazut prin NAT: adresele si porturile traduse inlocuiesc
+    /// originalele (cele netraduse raman). Campurile xlate_* se pastreaza.
+    pub fn translated(&self) -> LogEvent {
+        LogEvent {
+            source_ip: self.xlate_src.unwrap_or(self.source_ip),
+            dest_ip: self.xlate_dst.or(self.dest_ip),
+            dest_port: self.xlate_dport.unwrap_or(self.dest_port),
+            source_port: self.xlate_sport.or(self.source_port),
+            ..self.clone()
+        }
+    }
+
+    /// Eticheta regulii de firewall pentru context si contoare:
+    /// "113 (Stealth)", "113", "Stealth" sau UID-ul cand lipsesc celelalte.
+    pub fn rule_label(&self) -> Option<String> {
+        match (&self.rule, &self.rule_name) {
+            (Some(number), Some(name)) => Some(format!("{} ({})", number, name)),
+            (Some(number), None) => Some(number.clone()),
+            (None, Some(name)) => Some(name.clone()),
+            (None, None) => self.rule_uid.clone(),
+        }
+    }
+}
+
+/// Parseaza o adresa IP din log, in orice forma intalnita la firewall-uri:
+///   - IPv4 si IPv6 comprimat / expandat ("2001:db8::1", "2001:0db8:0:0::1")
+///   - IPv6 intre paranteze, optional cu port ("[2001:db8::1]", "[2001:db8::1]:443")
+///   - zona de scope ("fe80::1%eth0") — eliminata
+///   - IPv4 mapat in IPv6 ("::ffff:10.0.0.5") — normalizat la IPv4, astfel
+///     incat whitelist-ul, subnetele si contoarele IPv4 se aplica la fel
+pub fn parse_ip(raw: &str) -> Option<IpAddr> {
+    let raw = raw.trim();
+    let addr = match raw.strip_prefix('[') {
+        Some(rest) => &rest[..rest.find(']')?],
+        None => raw,
+    };
+    let addr = addr.split('%').next().unwrap_or(addr);
+    addr.parse::<IpAddr>().ok().map(|ip| ip.to_canonical())
+}
+
+/// Adresa NAT din log: ca `parse_ip`, dar "0.0.0.0" / "::" (Checkpoint le
+/// logheaza cand nu s-a tradus nimic) inseamna "fara traducere".
+pub fn parse_nat_ip(raw: &str) -> Option<IpAddr> {
+    parse_ip(raw).filter(|ip| !ip.is_unspecified())
+}
+
+/// Port NAT din log — 0 inseamna "fara traducere".
+pub fn parse_nat_port(raw: &str) -> Option<u16> {
+    raw.trim().parse().ok().filter(|port| *port != 0)
+}
+
+/// Protocolul de transport normalizat — folosit de detector pentru a
+/// separa porturile TCP de cele UDP (80/tcp si 80/udp sunt porturi diferite).
+///
+/// NOTA RUST: `Copy` — enum fara date, se copiaza implicit ca un intreg.
+/// `PartialOrd + Ord` permit sortarea perechilor (protocol, port).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Icmp,
+    Other,
+}
+
+impl Protocol {
+    /// Mapeaza numele (sau numarul IANA) protocolului din log.
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "tcp" | "6" => Protocol::Tcp,
+            "udp" | "17" => Protocol::Udp,
+            "icmp" | "1" | "icmp6" | "icmpv6" | "ipv6-icmp" | "58" => Protocol::Icmp,
+            _ => Protocol::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+            Protocol::Icmp => "icmp",
+            Protocol::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Trait-ul central de parsing - contractul pe care orice parser trebuie
 /// sa il respecte.
 ///
@@ -93,7 +270,8 @@ pub struct LogEvent {
 /// `Option<LogEvent>` = tipul returnat. Option este un enum:
 ///   - Some(event) = parsare reusita
 ///   - None        = linia nu a putut fi parsata sau nu ne intereseaza
-///     Rust nu are null - Option este mecanismul safe de a reprezenta
+///
+/// Rust nu are null - Option este mecanismul safe de a reprezenta
 /// absenta unei valori.
 pub trait LogParser: Send + Sync {
     /// Parseaza o linie de log si returneaza un LogEvent daca este relevanta.
@@ -104,8 +282,29 @@ pub trait LogParser: Send + Sync {
 
     /// Returneaza un exemplu de format valid (pentru debug/diagnostic).
     fn expected_format(&self) -> &str;
+
+    /// Ca `parse`, cu adresa expeditorului pachetului UDP (None = linie din
+    /// fisier). Parserele cu un singur format o ignora; `AutoParser` retine
+    /// per expeditor ultimul format recunoscut.
+    ///
+    /// NOTA RUST - DEFAULT METHODS: o metoda cu corp in trait este mostenita
+    /// de toate implementarile care nu o suprascriu.
+    fn parse_from(&self, line: &str, _sender: Option<IpAddr>) -> Option<LogEvent> {
+        self.parse(line)
+    }
+
+    /// Liniile recunoscute de fiecare format, pentru `parser = "auto"`.
+    /// Gol pentru parserele cu un singur format.
+    fn match_counts(&self) -> Vec<(&'static str, u64)> {
+        Vec::new()
+    }
 }
 
+/// Parserele concrete, in ordinea implicita a `parser = "auto"` (afisate de
+/// `ids-rs version`): formatul Gaia raw este cel mai specific, CEF generic
+/// ultimul — un CEF cu blob LEA trebuie sa ajunga la gaia_cef.
+pub const PARSER_NAMES: [&str; 3] = ["gaia", "gaia_cef", "cef"];
+
 /// Factory function - creeaza parser-ul potrivit pe baza configurarii.
 ///
 /// NOTA RUST: Returneaza `Result<Box<dyn LogParser>>`:
@@ -124,6 +323,17 @@ pub fn create_parser(parser_type: &str) -> anyhow::Result<Box<dyn LogParser>> {
         "gaia" => Ok(Box::new(gaia::GaiaParser::new()?)),
         "cef" => Ok(Box::new(cef::CefParser::new())),
         "gaia_cef" => Ok(Box::new(gaia_cef::GaiaCefParser::new())),
-        _ => anyhow::bail!("Parser necunoscut: '{}'. Optiuni valide: gaia, cef, gaia_cef", parser_type),
+        "auto" => Ok(Box::new(auto::AutoParser::new(&PARSER_NAMES)?)),
+        _ => anyhow::bail!("Parser necunoscut: '{}'. Optiuni valide: gaia, cef, gaia_cef, auto", parser_type),
+    }
+}
+
+/// Parser-ul din `[network]`: `parser = "auto"` foloseste ordinea din
+/// `auto_parsers`, celelalte valori merg la `create_parser`.
+pub fn from_config(network: &NetworkConfig) -> anyhow::Result<Box<dyn LogParser>> {
+    if network.parser == "auto" {
+        Ok(Box::new(auto::AutoParser::new(&network.auto_parsers)?))
+    } else {
+        create_parser(&network.parser)
     }
 }
@@ -0,0 +1,269 @@
+// =============================================================================
+// replay.rs - Log-uri din fisier prin parser si detector (`replay`, `parse-test`)
+// =============================================================================
+//
+// `ids-rs replay <log>` trece un fisier de log-uri (sau stdin) prin acelasi
+// parser si acelasi detector ca procesul pornit — fara socket UDP — si
+// afiseaza alertele rezultate. Util pentru a verifica pragurile noi pe un
+// incident real sau pe o captura de la firewall, inainte de deploy.
+//
+// `ids-rs parse-test` afiseaza campurile extrase din fiecare linie (ca modul
+// debug) — pentru a diagnostica un format de log nou.
+//
+// Limitari ale replay-ului:
+//   - Detectorul masoara timpul la procesare (`Instant::now()`), nu din
+//     timestamp-ul log-ului: un fisier reluat in cateva secunde comprima
+//     timpul, deci evenimentele cad in aceeasi fereastra Fast Scan.
+//   - Baseline-urile (`[detection.anomaly]`) nu sunt incarcate si nu sunt
+//     salvate — replay-ul nu modifica starea procesului de productie.
+//   - Fara `--send`, alertele apar doar in consola.
+//
+// NOTA RUST - `Box<dyn BufRead>`:
+// Fisierul (`BufReader<File>`) si stdin (`StdinLock`) sunt tipuri diferite;
+// un trait object le unifica intr-o singura variabila, citita linie cu linie
+// fara a incarca tot fisierul in memorie.
+//
+// =============================================================================
+
+use crate::alerter::Alerter;
+use crate::assets::AssetInventory;
+use crate::cli::{ParseTestOptions, ReplayOptions};
+use crate::config::SubnetEntry;
+use crate::detector::{Alert, Detector};
+use crate::display;
+use crate::parser::{self, LogParser};
+use crate::threat_intel::ThreatIntel;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+
+/// Contoarele unui replay.
+#[derive(Debug, Default)]
+struct ReplayStats {
+    lines: usize,
+    parsed: usize,
+    /// Alertele pe tip (`ScanType::key`).
+    alerts: BTreeMap<&'static str, usize>,
+}
+
+/// Parseaza o linie si o trece prin detector; intoarce alertele generate.
+fn replay_line(parser: &dyn LogParser, detector: &Detector, line: &str, stats: &mut ReplayStats) -> Vec<Alert> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Vec::new();
+    }
+    stats.lines += 1;
+    let Some(event) = parser.parse(line) else {
+        return Vec::new();
+    };
+    stats.parsed += 1;
+    let alerts = detector.process_event(&event);
+    for alert in &alerts {
+        *stats.alerts.entry(alert.scan_type.key()).or_insert(0) += 1;
+    }
+    alerts
+}
+
+/// Fisierul dat sau stdin ("-").
+fn open_input(path: &str) -> Result<Box<dyn BufRead>> {
+    if path == "-" {
+        return Ok(Box::new(std::io::stdin().lock()));
+    }
+    let file = File::open(path).with_context(|| format!("Nu pot deschide {:?}", path))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// `ids-rs replay <log> [<config>] [--send]`.
+pub async fn run_replay(opts: &ReplayOptions) -> Result<()> {
+    let config = opts.overrides.load(&opts.config_path)?;
+    let parser = parser::from_config(&config.network)?;
+    let hostnames = crate::parse_hostnames(&config);
+    let subnets = SubnetEntry::parse_subnets(&config.network.subnets);
+
+    // Aceleasi componente ca la pornire: subnete, threat intel, inventar —
+    // alertele si severitatea lor trebuie sa fie cele din productie.
+    let detector = Detector::new(config.detection.clone());
+    detector.update_subnets(subnets.clone());
+    if config.threat_intel.enabled {
+        detector.update_threat_intel(ThreatIntel::load(&config.threat_intel)?);
+    }
+    let assets = Arc::new(AssetInventory::load(&config.assets)?);
+    detector.update_assets(Arc::clone(&assets));
+
+    let alerter = if opts.send {
+        let alerter = Alerter::new(
+            config.alerting.clone(),
+            config.detection.clone(),
+            hostnames.clone(),
+            subnets.clone(),
+        )?;
+        alerter.update_assets(Arc::clone(&assets));
+        Some(alerter)
+    } else {
+        None
+    };
+
+    println!(
+        "Replay: {} — parser {}{}",
+        opts.log_path.bold(),
+        parser.name(),
+        if opts.send { ", alertele sunt trimise" } else { "" }
+    );
+
+    let mut input = open_input(&opts.log_path)?;
+    let mut stats = ReplayStats::default();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = input
+            .read_until(b'\n', &mut buf)
+            .with_context(|| format!("Eroare la citirea {:?}", opts.log_path))?;
+        if read == 0 {
+            break;
+        }
+        let line = String::from_utf8_lossy(&buf);
+        for alert in replay_line(&*parser, &detector, &line, &mut stats) {
+            display::log_alert(&alert, &hostnames, &subnets, &assets);
+            if let Some(ref alerter) = alerter {
+                alerter.send_alert(&alert).await;
+            }
+        }
+    }
+
+    let failed = stats.lines - stats.parsed;
+    println!(
+        "\n{} linii, {} parsate, {} nerecunoscute",
+        stats.lines,
+        stats.parsed.to_string().green(),
+        if failed > 0 { failed.to_string().yellow() } else { failed.to_string().normal() }
+    );
+    let matches = parser.match_counts();
+    if !matches.is_empty() {
+        let matches: Vec<String> = matches.iter().map(|(p, n)| format!("{} {}", p, n)).collect();
+        println!("Formate: {}", matches.join(", "));
+    }
+    let total: usize = stats.alerts.values().sum();
+    let by_type: Vec<String> = stats.alerts.iter().map(|(t, n)| format!("{} {}", t, n)).collect();
+    if total > 0 {
+        println!("Alerte: {} ({})", total.to_string().bold(), by_type.join(", "));
+    } else {
+        println!("Alerte: 0");
+    }
+    Ok(())
+}
+
+/// `ids-rs parse-test [<linie>...]` — exit 1 daca o linie nu este recunoscuta.
+pub fn run_parse_test(opts: &ParseTestOptions) -> Result<()> {
+    // Cu `--parser` nu avem nevoie de config.toml ("auto" foloseste atunci
+    // ordinea implicita, `PARSER_NAMES`).
+    let parser = match opts.overrides.parser {
+        Some(ref name) => parser::create_parser(name)?,
+        None => parser::from_config(&opts.overrides.load(&opts.config_path)?.network)?,
+    };
+
+    let lines: Vec<String> = if opts.lines.is_empty() {
+        std::io::stdin()
+            .lock()
+            .lines()
+            .collect::<std::io::Result<_>>()
+            .context("Eroare la citirea stdin")?
+    } else {
+        opts.lines.clone()
+    };
+
+    let (mut total, mut failed) = (0usize, 0usize);
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        total += 1;
+        match parser.parse(line) {
+            Some(event) => display::log_debug_parse_ok(&event),
+            None => {
+                failed += 1;
+                display::log_debug_parse_fail(line, parser.name(), parser.expected_format());
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} din {} linii nerecunoscute de parser-ul {}", failed, total, parser.name());
+    }
+    println!("{} linii parsate cu {}", total, parser.name());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[test]
+    fn test_replay_lines_through_detector() {
+        let config: AppConfig = toml::from_str(include_str!("../config.toml")).unwrap();
+        let threshold = config.detection.fast_scan.port_threshold;
+        let parser = parser::create_parser("gaia").unwrap();
+        let detector = Detector::new(config.detection.clone());
+
+        let mut stats = ReplayStats::default();
+        let mut alerts = Vec::new();
+        for port in 0..threshold + 1 {
+            let line = format!(
+                "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop 192.168.11.7 >eth8 \
+                 rule: 113; src: 192.168.11.7; dst: 10.0.0.5; proto: tcp; service: {}; s_port: 40000;",
+                1000 + port
+            );
+            alerts.extend(replay_line(&*parser, &detector, &line, &mut stats));
+        }
+        replay_line(&*parser, &detector, "   ", &mut stats);
+        replay_line(&*parser, &detector, "linie fara format", &mut stats);
+
+        assert_eq!(stats.lines, threshold + 2, "liniile goale nu se numara");
+        assert_eq!(stats.parsed, threshold + 1);
+        assert_eq!(stats.alerts.get("fast_scan"), Some(&1), "o singura alerta — cooldown-ul se aplica");
+        assert_eq!(alerts.len(), stats.alerts.values().sum::<usize>());
+    }
+
+    /// Un log de proba din `tester/` prin replay, cu un detector nou.
+    fn replay_fixture(parser_name: &str, log: &str) -> (ReplayStats, Vec<Alert>) {
+        let config: AppConfig = toml::from_str(include_str!("../config.toml")).unwrap();
+        let parser = parser::create_parser(parser_name).unwrap();
+        let detector = Detector::new(config.detection.clone());
+        let mut stats = ReplayStats::default();
+        let alerts = log
+            .lines()
+            .flat_map(|line| replay_line(&*parser, &detector, line, &mut stats))
+            .collect();
+        (stats, alerts)
+    }
+
+    #[test]
+    fn test_replay_fixture_logs() {
+        // 20 porturi unice in ~10s de la 192.168.11.34 → o alerta Fast Scan.
+        for (name, log) in [
+            ("gaia", include_str!("../tester/sample_fast_gaia.log")),
+            ("cef", include_str!("../tester/sample_fast_cef.log")),
+        ] {
+            let (stats, alerts) = replay_fixture(name, log);
+            assert_eq!((stats.lines, stats.parsed), (20, 20), "{}", name);
+            assert_eq!(stats.alerts, BTreeMap::from([("fast_scan", 1)]), "{}", name);
+            assert_eq!(alerts[0].source_ip.to_string(), "192.168.11.34");
+            assert!(alerts[0].unique_ports.contains(&41906), "{}", name);
+        }
+
+        // Scanare lenta: Fast Scan pe primele porturi, apoi Slow Scan la prag.
+        let (stats, alerts) = replay_fixture("gaia", include_str!("../tester/sample_slow_gaia.log"));
+        assert_eq!(stats.parsed, 35);
+        assert_eq!(stats.alerts, BTreeMap::from([("fast_scan", 1), ("slow_scan", 1)]));
+        assert!(alerts.iter().all(|a| a.source_ip.to_string() == "192.168.11.34"));
+
+        // Trafic normal: parsat integral, fara alerte.
+        let (stats, alerts) = replay_fixture("gaia", include_str!("../tester/sample_normal_gaia.log"));
+        assert_eq!((stats.lines, stats.parsed), (5, 5));
+        assert!(alerts.is_empty());
+
+        // Parser-ul gresit pentru format: nimic parsat, nimic detectat.
+        let (stats, alerts) = replay_fixture("cef", include_str!("../tester/sample_fast_gaia.log"));
+        assert_eq!((stats.lines, stats.parsed), (20, 0));
+        assert!(alerts.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+// =============================================================================
+// risk.rs - Scor de risc per sursa si escaladare
+// =============================================================================
+//
+// Detectoarele (Fast, Accept, Lateral etc.) emit alerte independente, fiecare
+// cu propriul cooldown. Un host care face Fast Scan, apoi Accept Scan, apoi
+// Lateral Movement produce trei alerte fara legatura intre ele.
+//
+// Acest modul leaga alertele aceleiasi surse:
+//   1. Fiecare alerta adauga puncte la scorul sursei (`[detection.risk.weights]`)
+//   2. Scorul scade exponential in timp (timp de injumatatire configurabil)
+//   3. La depasirea unui prag din `tiers` → alerta Escalation cu lantul
+//      alertelor care au contribuit
+//
+// Fiecare nivel se raporteaza o singura data. Dupa ce scorul scade sub un
+// prag (sursa s-a linistit), acel nivel poate fi raportat din nou.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. DECAY EXPONENTIAL FARA TIMER
+//    Nu avem un task care scade scorurile periodic. Scorul stocat este valabil
+//    la momentul `updated`; la urmatoarea citire il aducem la zi:
+//        scor_acum = scor * 0.5^(secunde_trecute / half_life)
+//    Cost zero pentru sursele inactive — calculul se face doar la acces.
+//
+// 2. VecDeque
+//    Coada dubla (ring buffer): `push_back` + `pop_front` in O(1). Folosita
+//    pentru lantul de alerte limitat la ultimele MAX_CHAIN intrari.
+//
+// =============================================================================
+
+use crate::config::{RiskConfig, RiskWeights};
+use crate::detector::{Alert, ScanType};
+use chrono::{DateTime, Local};
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::time::Instant;
+
+/// Numarul maxim de alerte pastrate in lantul unei surse.
+const MAX_CHAIN: usize = 20;
+
+/// Sub acest scor starea sursei este considerata stinsa si este stearsa.
+const MIN_SCORE: f64 = 0.5;
+
+/// O alerta care a contribuit la scorul de risc.
+#[derive(Debug, Clone)]
+pub struct ChainEntry {
+    pub scan_type: ScanType,
+    pub timestamp: DateTime<Local>,
+    pub points: f64,
+}
+
+/// Detaliile unei escaladari — atasate alertei Escalation.
+#[derive(Debug, Clone)]
+pub struct RiskDetail {
+    /// Scorul curent (dupa decay si dupa alerta care a declansat escaladarea).
+    pub score: f64,
+    /// Nivelul atins, numerotat de la 1.
+    pub tier: usize,
+    /// Pragul nivelului atins.
+    pub threshold: f64,
+    /// Alertele care au contribuit, in ordine cronologica.
+    pub chain: Vec<ChainEntry>,
+}
+
+impl RiskDetail {
+    /// Lantul formatat: `HH:MM:SS Fast Scan (+10)` separate prin `sep`.
+    pub fn format_chain(&self, sep: &str) -> String {
+        self.chain
+            .iter()
+            .map(|e| format!("{} {} (+{})", e.timestamp.format("%H:%M:%S"), e.scan_type, e.points))
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+}
+
+/// Starea de risc a unei surse.
+struct RiskState {
+    score: f64,
+    updated: Instant,
+    /// Cel mai inalt nivel deja raportat (0 = niciunul).
+    tier: usize,
+    chain: VecDeque<ChainEntry>,
+}
+
+/// Scorurile de risc ale tuturor surselor.
+///
+/// NOTA RUST: la fel ca in Detector, DashMap permite actualizari prin `&self`
+/// din task-uri concurente — `RiskTracker` nu are nevoie de `&mut`.
+pub struct RiskTracker {
+    states: DashMap<IpAddr, RiskState>,
+}
+
+/// Scorul adus la momentul `now` (decay exponential).
+fn decayed(score: f64, since: Instant, now: Instant, half_life_secs: u64) -> f64 {
+    let elapsed = now.saturating_duration_since(since).as_secs_f64();
+    score * 0.5f64.powf(elapsed / half_life_secs as f64)
+}
+
+/// Numarul de praguri depasite de scor (0 = sub primul prag).
+fn tier_for(score: f64, tiers: &[f64]) -> usize {
+    tiers.iter().filter(|t| score >= **t).count()
+}
+
+/// Punctele de risc ale unui tip de alerta.
+fn points(weights: &RiskWeights, scan_type: &ScanType) -> f64 {
+    match scan_type {
+        ScanType::Fast => weights.fast_scan,
+        ScanType::Slow => weights.slow_scan,
+        ScanType::AcceptScan => weights.accept_scan,
+        ScanType::LateralMovement => weights.lateral_movement,
+        ScanType::DistributedScan => weights.distributed_scan,
+        ScanType::KnownBadSource => weights.known_bad_source,
+        ScanType::KnownBadDestination => weights.known_bad_destination,
+        ScanType::Anomaly => weights.anomaly,
+        ScanType::BruteForce => weights.brute_force,
+        ScanType::Beaconing => weights.beaconing,
+        ScanType::PingSweep => weights.ping_sweep,
+        ScanType::AttackChain => weights.attack_chain,
+        // Escaladarea este rezultatul scorului, nu o contributie la el.
+        ScanType::Escalation => 0.0,
+        // Recidiva repeta alerte deja punctate.
+        ScanType::PersistentScanner => 0.0,
+        ScanType::TestAlert => 0.0,
+    }
+}
+
+impl RiskTracker {
+    pub fn new() -> Self {
+        Self {
+            states: DashMap::new(),
+        }
+    }
+
+    /// Adauga punctele unei alerte la scorul sursei ei.
+    ///
+    /// Returneaza `Some` daca sursa a urcat intr-un nivel de risc nou.
+    pub fn record(&self, alert: &Alert, now: Instant, cfg: &RiskConfig) -> Option<RiskDetail> {
+        let pts = points(&cfg.weights, &alert.scan_type);
+        if pts <= 0.0 {
+            return None;
+        }
+
+        let mut state = self.states.entry(alert.source_ip).or_insert_with(|| RiskState {
+            score: 0.0,
+            updated: now,
+            tier: 0,
+            chain: VecDeque::new(),
+        });
+
+        // Aducem scorul la zi. Nivelurile sub care scorul a coborat devin din
+        // nou raportabile; o sursa complet linistita isi pierde lantul.
+        state.score = decayed(state.score, state.updated, now, cfg.half_life_secs);
+        if state.score < MIN_SCORE {
+            state.chain.clear();
+        }
+        state.tier = state.tier.min(tier_for(state.score, &cfg.tiers));
+
+        state.score += pts;
+        state.updated = now;
+        state.chain.push_back(ChainEntry {
+            scan_type: alert.scan_type.clone(),
+            timestamp: alert.timestamp,
+            points: pts,
+        });
+        if state.chain.len() > MAX_CHAIN {
+            state.chain.pop_front();
+        }
+
+        let tier = tier_for(state.score, &cfg.tiers);
+        if tier <= state.tier {
+            return None;
+        }
+        state.tier = tier;
+        Some(RiskDetail {
+            score: state.score,
+            tier,
+            threshold: cfg.tiers[tier - 1],
+            chain: state.chain.iter().cloned().collect(),
+        })
+    }
+
+    /// Sterge sursele al caror scor a scazut sub MIN_SCORE.
+    pub fn cleanup(&self, now: Instant, cfg: &RiskConfig) {
+        self.states
+            .retain(|_, s| decayed(s.score, s.updated, now, cfg.half_life_secs) >= MIN_SCORE);
+    }
+
+    /// Sterge starea unei surse (LRU eviction in Detector).
+    pub fn remove(&self, ip: &IpAddr) {
+        self.states.remove(ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn cfg() -> RiskConfig {
+        RiskConfig {
+            enabled: true,
+            half_life_secs: 60,
+            tiers: vec![20.0, 50.0],
+            weights: RiskWeights::default(),
+        }
+    }
+
+    fn alert(scan_type: ScanType) -> Alert {
+        Alert::new(scan_type, "10.0.0.9".parse().unwrap(), None)
+    }
+
+    #[test]
+    fn test_escalates_once_per_tier() {
+        let tracker = RiskTracker::new();
+        let now = Instant::now();
+
+        // Fast (10) → sub prag; Accept (15) → 25 ≥ 20 → nivelul 1.
+        assert!(tracker.record(&alert(ScanType::Fast), now, &cfg()).is_none());
+        let d = tracker.record(&alert(ScanType::AcceptScan), now, &cfg()).unwrap();
+        assert_eq!(d.tier, 1);
+        assert_eq!(d.threshold, 20.0);
+        assert_eq!(d.chain.len(), 2);
+
+        // Inca 10 puncte (35) — acelasi nivel, fara escaladare noua.
+        assert!(tracker.record(&alert(ScanType::Fast), now, &cfg()).is_none());
+        // Lateral (30) → 65 ≥ 50 → nivelul 2, lantul complet.
+        let d = tracker.record(&alert(ScanType::LateralMovement), now, &cfg()).unwrap();
+        assert_eq!(d.tier, 2);
+        assert_eq!(d.chain.len(), 4);
+        assert!(matches!(d.chain[3].scan_type, ScanType::LateralMovement));
+    }
+
+    #[test]
+    fn test_score_decays_and_tier_rearms() {
+        let tracker = RiskTracker::new();
+        let t0 = Instant::now();
+
+        assert!(tracker.record(&alert(ScanType::LateralMovement), t0, &cfg()).is_some());
+
+        // Dupa 2 timpi de injumatatire: 30 → 7.5, sub pragul 20 → nivelul se rearmeaza.
+        let t1 = t0 + Duration::from_secs(120);
+        let d = tracker.record(&alert(ScanType::AcceptScan), t1, &cfg()).unwrap();
+        assert_eq!(d.tier, 1);
+        assert!((d.score - 22.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cleanup_removes_quiet_sources() {
+        let tracker = RiskTracker::new();
+        let t0 = Instant::now();
+        tracker.record(&alert(ScanType::Fast), t0, &cfg());
+
+        tracker.cleanup(t0 + Duration::from_secs(60), &cfg());
+        assert_eq!(tracker.states.len(), 1);
+        // 10 * 0.5^10 ≈ 0.01 < MIN_SCORE.
+        tracker.cleanup(t0 + Duration::from_secs(600), &cfg());
+        assert!(tracker.states.is_empty());
+    }
+}
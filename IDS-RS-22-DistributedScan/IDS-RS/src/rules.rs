@@ -0,0 +1,166 @@
+// =============================================================================
+// rules.rs - Regulile de firewall lovite (context per sursa + contoare)
+// =============================================================================
+//
+// Prima intrebare a analistului la o alerta: "ce regula a prins scanarea?".
+// Log-urile Checkpoint contin numarul regulii, UID-ul si numele ei; acest
+// modul le pastreaza in doua vederi:
+//
+//   1. Per sursa urmarita — multimea regulilor lovite (cu numarul de
+//      evenimente), atasata alertelor sursei (email, CEF flexString2, CLI).
+//   2. Per regula — contoare cumulative drop/accept/alerte de la pornire,
+//      afisate periodic in linia STAT pentru revizuirea politicii
+//      (o regula "cleanup" care prinde toate scanarile, o regula accept
+//      prin care trec scanari care ar trebui blocate).
+//
+// Starea per sursa urmeaza ciclul de viata al sursei in detector (LRU,
+// cleanup). Contoarele per regula sunt globale si limitate la
+// MAX_TRACKED_RULES — numele regulilor vin din log-uri, deci pot fi falsificate.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. DashMap<String, RuleCounter> cu `entry().or_default()`
+//    Inserarea si incrementarea se fac sub acelasi lock de shard — doua
+//    task-uri care lovesc aceeasi regula nu pierd incrementari.
+//
+// 2. `#[derive(Default)]` pe RuleCounter
+//    Contoarele pornesc de la zero fara un constructor scris de mana;
+//    `or_default()` foloseste exact acest `Default`.
+//
+// =============================================================================
+
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// Numarul maxim de reguli distincte pastrate per sursa.
+const MAX_RULES_PER_SOURCE: usize = 64;
+
+/// Numarul maxim de reguli distincte cu contoare globale.
+const MAX_TRACKED_RULES: usize = 10_000;
+
+/// Contoarele cumulative ale unei reguli (de la pornire).
+#[derive(Debug, Clone, Default)]
+pub struct RuleCounter {
+    /// Evenimente blocate (drop) de regula.
+    pub drops: u64,
+    /// Evenimente permise (accept) de regula.
+    pub accepts: u64,
+    /// Alerte emise pentru surse care au lovit regula.
+    pub alerts: u64,
+}
+
+impl RuleCounter {
+    pub fn total(&self) -> u64 {
+        self.drops + self.accepts
+    }
+}
+
+/// Regulile lovite per sursa si contoarele per regula.
+pub struct RuleTracker {
+    /// Sursa → (eticheta regulii → evenimente).
+    per_source: DashMap<IpAddr, HashMap<String, u64>>,
+    /// Eticheta regulii → contoare cumulative.
+    counters: DashMap<String, RuleCounter>,
+}
+
+impl RuleTracker {
+    pub fn new() -> Self {
+        Self {
+            per_source: DashMap::new(),
+            counters: DashMap::new(),
+        }
+    }
+
+    /// Inregistreaza un eveniment al sursei `ip` decis de regula `label`.
+    pub fn record(&self, ip: IpAddr, label: &str, accepted: bool) {
+        {
+            let mut rules = self.per_source.entry(ip).or_default();
+            if let Some(n) = rules.get_mut(label) {
+                *n += 1;
+            } else if rules.len() < MAX_RULES_PER_SOURCE {
+                rules.insert(label.to_string(), 1);
+            }
+        }
+
+        if !self.counters.contains_key(label) && self.counters.len() >= MAX_TRACKED_RULES {
+            return;
+        }
+        let mut counter = self.counters.entry(label.to_string()).or_default();
+        if accepted {
+            counter.accepts += 1;
+        } else {
+            counter.drops += 1;
+        }
+    }
+
+    /// Regulile lovite de `ip`, cele mai frecvente primele.
+    pub fn rules_for(&self, ip: &IpAddr) -> Vec<String> {
+        let Some(rules) = self.per_source.get(ip) else {
+            return Vec::new();
+        };
+        let mut sorted: Vec<(&String, &u64)> = rules.iter().collect();
+        sorted.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        sorted.into_iter().map(|(label, _)| label.clone()).collect()
+    }
+
+    /// Numara o alerta pentru fiecare regula atasata ei.
+    pub fn count_alert(&self, rules: &[String]) {
+        for label in rules {
+            if let Some(mut counter) = self.counters.get_mut(label) {
+                counter.alerts += 1;
+            }
+        }
+    }
+
+    /// Contoarele tuturor regulilor, cele mai lovite primele.
+    pub fn stats(&self) -> Vec<(String, RuleCounter)> {
+        let mut stats: Vec<(String, RuleCounter)> = self
+            .counters
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+        stats.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then_with(|| a.0.cmp(&b.0)));
+        stats
+    }
+
+    /// Pastreaza doar sursele pentru care `keep` returneaza true (cleanup).
+    pub fn retain_sources(&self, keep: impl Fn(&IpAddr) -> bool) {
+        self.per_source.retain(|ip, _| keep(ip));
+    }
+
+    /// Sterge regulile unei surse (LRU eviction).
+    pub fn remove(&self, ip: &IpAddr) {
+        self.per_source.remove(ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_sorted_by_hits_and_counted() {
+        let tracker = RuleTracker::new();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        tracker.record(ip, "113 (Stealth)", false);
+        tracker.record(ip, "9 (Cleanup rule)", false);
+        tracker.record(ip, "9 (Cleanup rule)", false);
+        tracker.record(ip, "20 (Web)", true);
+
+        assert_eq!(tracker.rules_for(&ip), vec!["9 (Cleanup rule)", "113 (Stealth)", "20 (Web)"]);
+
+        tracker.count_alert(&tracker.rules_for(&ip));
+        let stats = tracker.stats();
+        assert_eq!(stats[0].0, "9 (Cleanup rule)");
+        assert_eq!(stats[0].1.drops, 2);
+        assert_eq!(stats[0].1.alerts, 1);
+        let web = stats.iter().find(|(label, _)| label == "20 (Web)").unwrap();
+        assert_eq!((web.1.drops, web.1.accepts), (0, 1));
+
+        // Evictia sursei nu sterge contoarele globale.
+        tracker.remove(&ip);
+        assert!(tracker.rules_for(&ip).is_empty());
+        assert_eq!(tracker.stats().len(), 3);
+    }
+}
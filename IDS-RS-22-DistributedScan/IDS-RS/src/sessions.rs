@@ -0,0 +1,356 @@
+// =============================================================================
+// sessions.rs - Sesiuni de scanare: durata si amploarea reala a unui incident
+// =============================================================================
+//
+// O alerta este o fotografie a ferestrei detectorului ("15 porturi in 10 s").
+// Raportul de incident are nevoie de altceva: cand a inceput scanarea, cand
+// s-a oprit, cate porturi si destinatii a atins in total, cat a trecut de
+// firewall.
+//
+// Prima alerta de scanare a unei surse (Fast/Slow/Accept Scan, Lateral
+// Movement, Ping Sweep) sau a unei tinte (Distributed Scan) deschide o
+// sesiune. Sesiunea porneste din hit-urile deja in memorie — inceputul real
+// al scanarii, nu momentul alertei — si inregistreaza apoi fiecare eveniment
+// al cheii: porturile in ordinea sondarii, destinatiile (sau sursele, pentru
+// o tinta), drop / accept, alertele primite. Dupa `idle_timeout_secs` fara
+// evenimente sesiunea se inchide si devine o inregistrare de rezumat
+// (SIEM, fisier JSON, CLI).
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. Instant → DateTime<Local>
+//    Hit-urile poarta doar `Instant` (monoton). Ora de perete a unui hit se
+//    reconstituie din "acum": `Local::now() - (now - seen_at)`.
+//    `chrono::Duration::from_std` converteste durata std in durata chrono.
+//
+// 2. `serde_json::json!`
+//    Macro-ul construieste un `serde_json::Value` cu sintaxa JSON direct in
+//    cod — fara un struct `#[derive(Serialize)]` doar pentru iesire.
+//
+// =============================================================================
+
+use crate::config::SessionsConfig;
+use crate::cooldown::format_span;
+use crate::detector::{Alert, ScanType};
+use chrono::{DateTime, Local};
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Cheia sesiunii: sursa care scaneaza sau tinta scanata din surse multiple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionKind {
+    Source,
+    Target,
+}
+
+impl SessionKind {
+    pub fn key(&self) -> &'static str {
+        match self {
+            SessionKind::Source => "source",
+            SessionKind::Target => "target",
+        }
+    }
+}
+
+/// Sesiunea pe care o deschide alerta, daca este o alerta de scanare.
+pub fn opens_session(alert: &Alert) -> Option<(SessionKind, IpAddr)> {
+    match alert.scan_type {
+        ScanType::Fast
+        | ScanType::Slow
+        | ScanType::AcceptScan
+        | ScanType::LateralMovement
+        | ScanType::PingSweep => Some((SessionKind::Source, alert.source_ip)),
+        ScanType::DistributedScan => alert.dest_ip.map(|dest| (SessionKind::Target, dest)),
+        _ => None,
+    }
+}
+
+/// Un eveniment vazut din perspectiva sesiunii.
+#[derive(Debug, Clone, Copy)]
+pub struct Probe {
+    pub at: Instant,
+    /// Portul destinatie (None pentru ICMP).
+    pub port: Option<u16>,
+    /// Destinatia (sesiune de sursa) sau sursa (sesiune de tinta).
+    pub peer: Option<IpAddr>,
+    pub accept: bool,
+}
+
+/// Rezumatul unei sesiuni inchise.
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub kind: SessionKind,
+    pub ip: IpAddr,
+    pub first_seen: DateTime<Local>,
+    pub last_seen: DateTime<Local>,
+    pub duration: Duration,
+    pub events: u64,
+    pub drops: u64,
+    pub accepts: u64,
+    /// Porturile in ordinea primei sondari (cel mult `max_ports`).
+    pub ports: Vec<u16>,
+    pub unique_ports: usize,
+    /// Destinatii unice (sesiune de sursa) sau surse unice (sesiune de tinta).
+    pub peers: usize,
+    pub alerts: usize,
+    pub scan_types: Vec<String>,
+    /// Cea mai mare severitate a alertelor din sesiune.
+    pub severity: u8,
+}
+
+impl SessionSummary {
+    /// "destinatii" / "surse" — ce numara `peers`.
+    pub fn peer_label(&self) -> &'static str {
+        match self.kind {
+            SessionKind::Source => "destinatii",
+            SessionKind::Target => "surse",
+        }
+    }
+
+    /// "12m 05s (08:12:40 - 08:24:45), 1240 evenimente (1100 drop / 140 accept),
+    /// 380 porturi unice, 3 destinatii, 2 alerte: Fast Scan, Slow Scan"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({} - {}), {} evenimente ({} drop / {} accept), {} porturi unice, {} {}, {} alerte: {}",
+            format_span(self.duration),
+            self.first_seen.format("%H:%M:%S"),
+            self.last_seen.format("%H:%M:%S"),
+            self.events,
+            self.drops,
+            self.accepts,
+            self.unique_ports,
+            self.peers,
+            self.peer_label(),
+            self.alerts,
+            self.scan_types.join(", ")
+        )
+    }
+
+    /// Inregistrarea JSON (o linie in fisierul `alerting.sessions.json_path`).
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "kind": self.kind.key(),
+            "ip": self.ip.to_string(),
+            "first_seen": self.first_seen.to_rfc3339(),
+            "last_seen": self.last_seen.to_rfc3339(),
+            "duration_secs": self.duration.as_secs(),
+            "events": self.events,
+            "drops": self.drops,
+            "accepts": self.accepts,
+            "unique_ports": self.unique_ports,
+            "ports": self.ports,
+            "ports_truncated": self.unique_ports > self.ports.len(),
+            "peers": self.peers,
+            "alerts": self.alerts,
+            "scan_types": self.scan_types,
+            "severity": self.severity,
+        })
+    }
+}
+
+struct Session {
+    first_seen: Instant,
+    last_seen: Instant,
+    /// Ora de perete a lui `first_seen` (reconstituita la deschidere).
+    first_seen_at: DateTime<Local>,
+    events: u64,
+    drops: u64,
+    accepts: u64,
+    ports: Vec<u16>,
+    port_set: HashSet<u16>,
+    peers: HashSet<IpAddr>,
+    alerts: usize,
+    scan_types: Vec<String>,
+    severity: u8,
+}
+
+impl Session {
+    fn record(&mut self, probe: &Probe, max_ports: usize) {
+        self.events += 1;
+        if probe.accept {
+            self.accepts += 1;
+        } else {
+            self.drops += 1;
+        }
+        if let Some(port) = probe.port {
+            if self.port_set.insert(port) && self.ports.len() < max_ports {
+                self.ports.push(port);
+            }
+        }
+        if let Some(peer) = probe.peer {
+            if self.peers.len() < MAX_PEERS {
+                self.peers.insert(peer);
+            }
+        }
+        self.first_seen = self.first_seen.min(probe.at);
+        self.last_seen = self.last_seen.max(probe.at);
+    }
+}
+
+/// Limita destinatiilor / surselor retinute per sesiune (memorie).
+const MAX_PEERS: usize = 65_536;
+
+/// Sesiunile deschise, per (tip, IP).
+pub struct SessionTracker {
+    sessions: DashMap<(SessionKind, IpAddr), Session>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self { sessions: DashMap::new() }
+    }
+
+    pub fn is_open(&self, kind: SessionKind, ip: IpAddr) -> bool {
+        self.sessions.contains_key(&(kind, ip))
+    }
+
+    /// Tipurile sesiunilor deschise pentru `ip` (ca sursa si/sau tinta).
+    pub fn open_for(&self, ip: IpAddr) -> Vec<SessionKind> {
+        [SessionKind::Source, SessionKind::Target]
+            .into_iter()
+            .filter(|kind| self.is_open(*kind, ip))
+            .collect()
+    }
+
+    /// Renunta la sesiunile lui `ip` (ca sursa si ca tinta), fara rezumat.
+    pub fn remove(&self, ip: IpAddr) {
+        self.remove_source(&ip);
+        self.sessions.remove(&(SessionKind::Target, ip));
+    }
+
+    /// Renunta la sesiunea sursei `ip`, fara rezumat — sursa evacuata (LRU).
+    pub fn remove_source(&self, ip: &IpAddr) {
+        self.sessions.remove(&(SessionKind::Source, *ip));
+    }
+
+    /// Inregistreaza evenimentul in sesiunea cheii, daca exista.
+    pub fn record(&self, kind: SessionKind, ip: IpAddr, probe: &Probe, cfg: &SessionsConfig) {
+        if let Some(mut session) = self.sessions.get_mut(&(kind, ip)) {
+            session.record(probe, cfg.max_ports);
+        }
+    }
+
+    /// Deschide sesiunea din hit-urile deja in memorie (`seed`, in orice ordine).
+    pub fn open(&self, kind: SessionKind, ip: IpAddr, mut seed: Vec<Probe>, now: Instant, cfg: &SessionsConfig) {
+        seed.sort_by_key(|p| p.at);
+        let first = seed.first().map(|p| p.at).unwrap_or(now);
+        let mut session = Session {
+            first_seen: first,
+            last_seen: first,
+            first_seen_at: wall_clock(first, now),
+            events: 0,
+            drops: 0,
+            accepts: 0,
+            ports: Vec::new(),
+            port_set: HashSet::new(),
+            peers: HashSet::new(),
+            alerts: 0,
+            scan_types: Vec::new(),
+            severity: 0,
+        };
+        for probe in &seed {
+            session.record(probe, cfg.max_ports);
+        }
+        self.sessions.entry((kind, ip)).or_insert(session);
+    }
+
+    /// Adauga alerta la sesiunea cheii, daca exista.
+    pub fn note_alert(&self, kind: SessionKind, ip: IpAddr, alert: &Alert) {
+        if let Some(mut session) = self.sessions.get_mut(&(kind, ip)) {
+            session.alerts += 1;
+            session.severity = session.severity.max(alert.severity.level);
+            let label = alert.scan_type.to_string();
+            if !session.scan_types.contains(&label) {
+                session.scan_types.push(label);
+            }
+        }
+    }
+
+    /// Inchide sesiunile fara evenimente de `idle_timeout_secs` si intoarce
+    /// rezumatele lor, cele mai vechi primele.
+    pub fn close_idle(&self, now: Instant, cfg: &SessionsConfig) -> Vec<SessionSummary> {
+        let idle = Duration::from_secs(cfg.idle_timeout_secs);
+        let mut closed = Vec::new();
+        self.sessions.retain(|(kind, ip), session| {
+            if now.saturating_duration_since(session.last_seen) < idle {
+                return true;
+            }
+            let duration = session.last_seen.saturating_duration_since(session.first_seen);
+            closed.push(SessionSummary {
+                kind: *kind,
+                ip: *ip,
+                first_seen: session.first_seen_at,
+                last_seen: wall_clock(session.last_seen, now),
+                duration,
+                events: session.events,
+                drops: session.drops,
+                accepts: session.accepts,
+                ports: std::mem::take(&mut session.ports),
+                unique_ports: session.port_set.len(),
+                peers: session.peers.len(),
+                alerts: session.alerts,
+                scan_types: std::mem::take(&mut session.scan_types),
+                severity: session.severity,
+            });
+            false
+        });
+        closed.sort_by_key(|s| s.first_seen);
+        closed
+    }
+}
+
+/// Ora de perete a momentului `at`, masurata fata de `now`.
+fn wall_clock(at: Instant, now: Instant) -> DateTime<Local> {
+    let ago = chrono::Duration::from_std(now.saturating_duration_since(at)).unwrap_or_default();
+    Local::now() - ago
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(at: Instant, port: u16, dest: &str, accept: bool) -> Probe {
+        Probe { at, port: Some(port), peer: Some(dest.parse().unwrap()), accept }
+    }
+
+    #[test]
+    fn test_session_seed_record_and_close() {
+        let tracker = SessionTracker::new();
+        let cfg = SessionsConfig { enabled: true, idle_timeout_secs: 60, max_ports: 3 };
+        let ip: IpAddr = "203.0.113.50".parse().unwrap();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // Seed-ul vine neordonat (drop-uri si accept-uri din map-uri diferite).
+        let seed = vec![
+            probe(at(5), 443, "10.0.0.5", true),
+            probe(at(0), 22, "10.0.0.5", false),
+            probe(at(2), 80, "10.0.0.6", false),
+        ];
+        tracker.open(SessionKind::Source, ip, seed, at(5), &cfg);
+        assert!(tracker.is_open(SessionKind::Source, ip));
+        // A doua deschidere nu reseteaza sesiunea.
+        tracker.open(SessionKind::Source, ip, Vec::new(), at(6), &cfg);
+
+        tracker.record(SessionKind::Source, ip, &probe(at(30), 8080, "10.0.0.7", false), &cfg);
+        tracker.record(SessionKind::Source, ip, &probe(at(40), 22, "10.0.0.7", true), &cfg);
+        let alert = Alert::new(ScanType::Fast, ip, None);
+        tracker.note_alert(SessionKind::Source, ip, &alert);
+        tracker.note_alert(SessionKind::Source, ip, &alert);
+
+        assert!(tracker.close_idle(at(90), &cfg).is_empty());
+        let closed = tracker.close_idle(at(100), &cfg);
+        assert_eq!(closed.len(), 1);
+        let s = &closed[0];
+        assert_eq!(s.duration, Duration::from_secs(40));
+        assert_eq!((s.events, s.drops, s.accepts), (5, 3, 2));
+        // Ordinea sondarii, trunchiata la max_ports; totalul ramane corect.
+        assert_eq!(s.ports, vec![22, 80, 443]);
+        assert_eq!((s.unique_ports, s.peers, s.alerts), (4, 3, 2));
+        assert_eq!(s.scan_types, vec!["Fast Scan"]);
+        assert_eq!(s.to_json()["ports_truncated"], true);
+        assert!(!tracker.is_open(SessionKind::Source, ip));
+    }
+}
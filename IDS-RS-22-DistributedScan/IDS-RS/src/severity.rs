@@ -0,0 +1,210 @@
+// =============================================================================
+// severity.rs - Severitatea dinamica a alertelor
+// =============================================================================
+//
+// Severitatea CEF era fixa per tip (Fast 7, Slow 6, Accept 5, ...): un Fast
+// Scan de 16 porturi de pe o statie de test avea aceeasi severitate ca unul de
+// 2000 de porturi pe controller-ul de domeniu, de la o sursa care a mai alertat
+// de cinci ori azi.
+//
+// Severitatea porneste de la baza tipului si creste cu factorii din
+// `[detection.severity]` (plafonat la 10):
+//
+//   prag depasit x4      → +2 (o treapta per dublare a pragului)
+//   tinta critical       → `[assets]` critical_boost / high_boost
+//   sursa interna        → +1 (host compromis in retea, nu zgomot de Internet)
+//   porturi sensibile    → +1
+//   a 3-a alerta in 24h  → +1 per alerta anterioara, plafonat (offenders.rs)
+//
+// Aceeasi severitate alimenteaza campul CEF, nivelul si culoarea email-ului
+// si culoarea alertei in consola. Factorii aplicati insotesc alerta (msg CEF,
+// rand email, CLI) — analistul vede DE CE alerta are 9, nu doar ca are 9.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. `u8::saturating_add`
+//    Suma factorilor nu poate depasi 255 (overflow ar fi panic in debug);
+//    plafonul la 10 se aplica dupa.
+//
+// =============================================================================
+
+use crate::assets::AssetInventory;
+use crate::config::{SeverityConfig, SubnetEntry};
+use crate::detector::{Alert, ScanType};
+
+/// Severitatea maxima CEF.
+const MAX_LEVEL: u8 = 10;
+
+/// Severitatea unei alerte si factorii care au ridicat-o.
+#[derive(Debug, Clone, Default)]
+pub struct Severity {
+    /// 1-10, ca in header-ul CEF.
+    pub level: u8,
+    /// Factorii aplicati peste baza, ex: "prag depasit x4 (+2)".
+    pub factors: Vec<String>,
+}
+
+impl Severity {
+    /// Nivelul afisat in email.
+    pub fn label(&self) -> &'static str {
+        match self.level {
+            8.. => "CRITICA",
+            7 => "RIDICATA",
+            6 => "MEDIE",
+            _ => "MEDIE-MICA",
+        }
+    }
+
+    /// Emoji-ul din subiectul email-ului (rosu / portocaliu / galben / albastru).
+    pub fn emoji(&self) -> &'static str {
+        match self.level {
+            8.. => "\u{1F534}",
+            7 => "\u{1F7E0}",
+            6 => "\u{1F7E1}",
+            _ => "\u{1F535}",
+        }
+    }
+}
+
+/// Severitatea de baza a tipului de alerta (fosta severitate fixa CEF).
+///
+/// Escalation creste cu nivelul atins (1 → 8, 2 → 9, 3+ → 10); Brute Force
+/// cu accept-uri (serviciul raspunde) este 8, cu drop-uri 7.
+pub fn base_level(alert: &Alert) -> u8 {
+    match alert.scan_type {
+        ScanType::Fast => 7,
+        ScanType::Slow => 6,
+        ScanType::AcceptScan => 5,
+        ScanType::LateralMovement => 8,
+        ScanType::DistributedScan => 7,
+        ScanType::KnownBadSource => 8,
+        ScanType::KnownBadDestination => 9,
+        ScanType::Anomaly => 6,
+        ScanType::Escalation => alert.risk.as_ref().map(|r| (7 + r.tier).min(10) as u8).unwrap_or(8),
+        ScanType::AttackChain => 10,
+        ScanType::Beaconing => 7,
+        ScanType::BruteForce => {
+            if alert.flood.as_ref().is_some_and(|f| f.accepted) { 8 } else { 7 }
+        }
+        ScanType::PingSweep => 5,
+        ScanType::PersistentScanner => 7,
+        ScanType::TestAlert => 1,
+    }
+}
+
+/// Calculeaza severitatea alertei. Recidiva vine din contextul `offence`
+/// atasat de detector (alertele anterioare ale sursei in fereastra).
+pub fn assess(
+    alert: &Alert,
+    cfg: &SeverityConfig,
+    assets: &AssetInventory,
+    subnets: &[SubnetEntry],
+) -> Severity {
+    let base = base_level(alert);
+    if !cfg.enabled {
+        return Severity { level: base, factors: Vec::new() };
+    }
+
+    let mut level = base;
+    let mut factors = Vec::new();
+    let mut add = |bonus: u8, reason: String| {
+        if bonus > 0 {
+            level = level.saturating_add(bonus);
+            factors.push(format!("{} (+{})", reason, bonus));
+        }
+    };
+
+    // Depasirea pragului: o treapta per dublare (x2 → 1, x4 → 2, ...).
+    if let (Some(observed), Some(threshold)) = (alert.observed(), alert.threshold) {
+        if threshold > 0 {
+            let ratio = observed / threshold as f64;
+            let doublings = ratio.log2().floor().max(0.0) as u8;
+            let bonus = doublings.saturating_mul(cfg.excess_per_doubling).min(cfg.max_excess);
+            add(bonus, format!("prag depasit x{:.0}", ratio.floor()));
+        }
+    }
+
+    if let Some(target) = assets.most_critical(alert.targets()) {
+        add(assets.severity_boost(target.criticality), format!("tinta {}", target.criticality));
+    }
+
+    if SubnetEntry::lookup(subnets, &alert.source_ip).is_some() {
+        add(cfg.internal_source, "sursa interna".to_string());
+    } else {
+        add(cfg.external_source, "sursa externa".to_string());
+    }
+
+    if !alert.sensitive_ports.is_empty() {
+        add(cfg.sensitive_ports, "porturi sensibile".to_string());
+    }
+
+    // PersistentScanner este chiar notificarea recidivei — nu o numaram de doua ori.
+    if let Some(ref offence) = alert.offence {
+        if offence.is_repeat() && !matches!(alert.scan_type, ScanType::PersistentScanner) {
+            let prior = (offence.nth - 1).min(u8::MAX as usize) as u8;
+            let bonus = prior.saturating_mul(cfg.repeat_per_offence).min(cfg.max_repeat);
+            add(bonus, offence.headline());
+        }
+    }
+
+    Severity { level: level.min(MAX_LEVEL), factors }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offenders::OffenceDetail;
+    use chrono::Local;
+
+    fn fast_alert(src: &str, ports: usize, threshold: usize) -> Alert {
+        Alert {
+            unique_ports: (1..=ports as u16).collect(),
+            threshold: Some(threshold),
+            ..Alert::new(ScanType::Fast, src.parse().unwrap(), Some("10.0.0.1".parse().unwrap()))
+        }
+    }
+
+    fn offence(nth: usize) -> OffenceDetail {
+        OffenceDetail {
+            nth,
+            window_hours: 24,
+            total: nth,
+            horizon_hours: 168,
+            first_seen: Local::now(),
+            last_seen: None,
+            scan_types: vec!["Fast Scan".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_excess_zone_and_repeat_factors() {
+        let cfg = SeverityConfig::default();
+        let subnets = SubnetEntry::parse_subnets(
+            &[("10.0.0.0/8".to_string(), "LAN".to_string())].into(),
+        );
+        let assets = AssetInventory::default();
+
+        // Extern, exact la prag: severitatea de baza.
+        let sev = assess(&fast_alert("203.0.113.5", 15, 15), &cfg, &assets, &subnets);
+        assert_eq!(sev.level, 7);
+        assert!(sev.factors.is_empty());
+
+        // x4 peste prag (+2, plafonat) si sursa interna (+1).
+        let sev = assess(&fast_alert("10.1.1.5", 200, 15), &cfg, &assets, &subnets);
+        assert_eq!(sev.level, 10);
+        assert_eq!(sev.factors, vec!["prag depasit x13 (+2)", "sursa interna (+1)"]);
+
+        // Recidiva: a 2-a alerta in 24h (+1); a 5-a plafonata la max_repeat (+2).
+        let repeat = Alert { offence: Some(offence(2)), ..fast_alert("203.0.113.5", 15, 15) };
+        let sev = assess(&repeat, &cfg, &assets, &subnets);
+        assert_eq!((sev.level, sev.label()), (8, "CRITICA"));
+        assert_eq!(sev.factors, vec!["a 2-a alerta in 24h (+1)"]);
+        let repeat = Alert { offence: Some(offence(5)), ..fast_alert("203.0.113.5", 15, 15) };
+        assert_eq!(assess(&repeat, &cfg, &assets, &subnets).level, 9);
+
+        // Dezactivat → severitatea fixa de baza.
+        let off = SeverityConfig { enabled: false, ..SeverityConfig::default() };
+        let sev = assess(&fast_alert("10.1.1.5", 200, 15), &off, &assets, &subnets);
+        assert_eq!((sev.level, sev.label()), (7, "RIDICATA"));
+    }
+}
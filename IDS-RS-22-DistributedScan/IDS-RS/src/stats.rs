@@ -0,0 +1,162 @@
+// =============================================================================
+// stats.rs - Contoarele live ale procesului si alertele recente
+// =============================================================================
+//
+// Main loop-ul numara evenimentele parsate (per actiune), liniile neparsate,
+// pachetele dropate de TokenBucket si alertele, si pastreaza ultimele alerte.
+// Le citesc dashboard-ul (`--tui`), socket-ul de control si API-ul REST —
+// niciunul nu depinde de celalalt, toate depind doar de `LiveStats`.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. ATOMICS (`AtomicU64`, `Ordering::Relaxed`)
+//    Contoarele sunt incrementate din main loop si citite din alte task-uri
+//    sau thread-uri. `fetch_add` este o operatie atomica — fara Mutex.
+//    `Relaxed` ajunge: fiecare contor este independent, nu sincronizam alte
+//    date prin el.
+//
+// =============================================================================
+
+use crate::detector::Alert;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Alertele recente pastrate (panoul dashboard-ului, `GET /alerts/recent`).
+const MAX_RECENT_ALERTS: usize = 200;
+
+/// Contoarele live ale main loop-ului, citite de dashboard si de API.
+pub struct LiveStats {
+    drops: AtomicU64,
+    accepts: AtomicU64,
+    other: AtomicU64,
+    parse_failures: AtomicU64,
+    rate_limited: AtomicU64,
+    alerts_total: AtomicU64,
+    alerts: Mutex<VecDeque<Alert>>,
+}
+
+impl LiveStats {
+    pub fn new() -> Self {
+        Self {
+            drops: AtomicU64::new(0),
+            accepts: AtomicU64::new(0),
+            other: AtomicU64::new(0),
+            parse_failures: AtomicU64::new(0),
+            rate_limited: AtomicU64::new(0),
+            alerts_total: AtomicU64::new(0),
+            alerts: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Un eveniment parsat, numarat dupa actiune.
+    pub fn record_event(&self, action: &str) {
+        let counter = match action {
+            "drop" => &self.drops,
+            "accept" => &self.accepts,
+            _ => &self.other,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// O linie pe care parser-ul activ nu a recunoscut-o.
+    pub fn record_parse_failure(&self) {
+        self.parse_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Un pachet UDP dropat de TokenBucket.
+    pub fn record_rate_limited(&self) {
+        self.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_alert(&self, alert: &Alert) {
+        self.alerts_total.fetch_add(1, Ordering::Relaxed);
+        let mut alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        if alerts.len() == MAX_RECENT_ALERTS {
+            alerts.pop_back();
+        }
+        alerts.push_front(alert.clone());
+    }
+
+    pub fn counters(&self) -> Counters {
+        Counters {
+            drops: self.drops.load(Ordering::Relaxed),
+            accepts: self.accepts.load(Ordering::Relaxed),
+            other: self.other.load(Ordering::Relaxed),
+            parse_failures: self.parse_failures.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            alerts: self.alerts_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Alertele recente, cele mai noi primele.
+    pub fn recent_alerts(&self) -> Vec<Alert> {
+        self.alerts.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+}
+
+/// Valorile contoarelor la un moment dat.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counters {
+    pub drops: u64,
+    pub accepts: u64,
+    pub other: u64,
+    pub parse_failures: u64,
+    pub rate_limited: u64,
+    pub alerts: u64,
+}
+
+impl Counters {
+    pub fn events(&self) -> u64 {
+        self.drops + self.accepts + self.other
+    }
+
+    /// Rata pe secunda a fiecarui contor intre `earlier` si `self`.
+    pub fn rate_since(&self, earlier: &Counters, elapsed: Duration) -> Counters {
+        let secs = elapsed.as_secs_f64().max(0.001);
+        let per_sec = |now: u64, before: u64| (now.saturating_sub(before) as f64 / secs).round() as u64;
+        Counters {
+            drops: per_sec(self.drops, earlier.drops),
+            accepts: per_sec(self.accepts, earlier.accepts),
+            other: per_sec(self.other, earlier.other),
+            parse_failures: per_sec(self.parse_failures, earlier.parse_failures),
+            rate_limited: per_sec(self.rate_limited, earlier.rate_limited),
+            alerts: per_sec(self.alerts, earlier.alerts),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    #[test]
+    fn test_live_stats_counters_and_rates() {
+        let live = LiveStats::new();
+        for action in ["drop", "drop", "accept", "reject"] {
+            live.record_event(action);
+        }
+        live.record_parse_failure();
+        live.record_rate_limited();
+        let first = live.counters();
+        assert_eq!((first.drops, first.accepts, first.other, first.events()), (2, 1, 1, 4));
+
+        for _ in 0..10 {
+            live.record_event("drop");
+        }
+        let rates = live.counters().rate_since(&first, Duration::from_secs(2));
+        assert_eq!((rates.drops, rates.accepts, rates.events()), (5, 0, 5));
+
+        // Alertele recente: cele mai noi primele, limitate la MAX_RECENT_ALERTS.
+        for i in 0..=MAX_RECENT_ALERTS {
+            let src = format!("10.0.{}.{}", i / 256, i % 256).parse().unwrap();
+            live.record_alert(&Alert::new(ScanType::Fast, src, None));
+        }
+        let recent = live.recent_alerts();
+        assert_eq!(recent.len(), MAX_RECENT_ALERTS);
+        assert_eq!(recent[0].source_ip.to_string(), "10.0.0.200");
+        assert_eq!(live.counters().alerts, MAX_RECENT_ALERTS as u64 + 1);
+    }
+}
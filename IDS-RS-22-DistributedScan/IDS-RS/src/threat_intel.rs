@@ -0,0 +1,376 @@
+// =============================================================================
+// threat_intel.rs - Liste IOC (Indicators of Compromise) din fisiere locale
+// =============================================================================
+//
+// Listele de IP-uri/subretele cunoscute ca malitioase vin offline (ex: pe USB
+// de la CERT-ul national) si sunt incarcate din fisiere locale:
+//
+//   plain → un IP sau CIDR pe linie, comentarii cu `#`
+//   csv   → indicator[,confidence[,descriere]] — header-ul optional e ignorat
+//   stix  → bundle STIX 2.x simplificat: obiecte `indicator` cu pattern
+//           `[ipv4-addr:value = '...']` si obiecte `ipv4-addr`/`ipv6-addr`
+//
+// Orice eveniment de la/catre o adresa listata declanseaza imediat alerta
+// KnownBadSource / KnownBadDestination in detector (fara praguri).
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. HashMap + Vec (doua niveluri de cautare)
+//    Majoritatea indicatorilor sunt IP-uri individuale → HashMap<IpAddr, _>
+//    ofera lookup O(1). Subretele CIDR sunt putine → Vec parcurs linear.
+//    Lookup-ul se face de 2 ori per eveniment (sursa + destinatie), deci
+//    o lista liniara de zeci de mii de IP-uri ar fi prea lenta.
+//
+// 2. Arc<str>
+//    Numele feed-ului este partajat de toti indicatorii lui. `Arc<str>` este
+//    un string imutabil cu reference counting — clonarea costa un increment
+//    atomic, nu o alocare noua.
+//
+// =============================================================================
+
+use crate::config::{SubnetEntry, ThreatFeedConfig, ThreatIntelConfig};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Rezultatul unei potriviri IOC — transmis in alerta (CEF cs4/cs5, email).
+#[derive(Debug, Clone)]
+pub struct IocMatch {
+    /// Numele feed-ului din care provine indicatorul.
+    pub feed: String,
+    /// Increderea (0-100) — din fisier sau valoarea implicita a feed-ului.
+    pub confidence: u8,
+    /// Indicatorul exact care s-a potrivit (IP sau CIDR, ca in fisier).
+    pub indicator: String,
+}
+
+/// Indicator intern (dupa parsare, inainte de indexare).
+#[derive(Debug, Clone)]
+struct IocRecord {
+    feed: Arc<str>,
+    confidence: u8,
+    indicator: String,
+}
+
+impl IocRecord {
+    fn to_match(&self) -> IocMatch {
+        IocMatch {
+            feed: self.feed.to_string(),
+            confidence: self.confidence,
+            indicator: self.indicator.clone(),
+        }
+    }
+}
+
+/// Indicator brut extras dintr-un fisier (inainte de validare IP/CIDR).
+#[derive(Debug)]
+struct RawIndicator {
+    value: String,
+    confidence: Option<u8>,
+}
+
+/// Statistici de incarcare per feed — afisate la pornire si la SIGHUP.
+#[derive(Debug, Clone)]
+pub struct FeedSummary {
+    pub name: String,
+    pub loaded: usize,
+    pub skipped: usize,
+}
+
+/// Baza de indicatori IOC, indexata pentru lookup rapid.
+#[derive(Debug, Default)]
+pub struct ThreatIntel {
+    /// IP-uri individuale (/32 sau /128) — lookup O(1).
+    exact: HashMap<IpAddr, IocRecord>,
+    /// Subretele CIDR — parcurse linear, longest prefix match.
+    cidrs: Vec<(SubnetEntry, IocRecord)>,
+    /// Statistici per feed (ordinea din config.toml).
+    pub feeds: Vec<FeedSummary>,
+}
+
+impl ThreatIntel {
+    /// Incarca toate feed-urile din configurare.
+    ///
+    /// Daca threat intel este dezactivat, returneaza o baza goala.
+    /// Un fisier lipsa sau un JSON invalid este eroare (fatal la pornire,
+    /// warning la SIGHUP — listele vechi raman active).
+    /// Liniile invalide individuale sunt doar numarate in `skipped`.
+    pub fn load(config: &ThreatIntelConfig) -> Result<Self> {
+        let mut intel = ThreatIntel::default();
+        if !config.enabled {
+            return Ok(intel);
+        }
+
+        for feed in &config.feeds {
+            let content = std::fs::read_to_string(&feed.path).with_context(|| {
+                format!("Nu pot citi feed-ul threat intel '{}': {:?}", feed.name, feed.path)
+            })?;
+            let summary = intel.add_feed(feed, &content)?;
+            intel.feeds.push(summary);
+        }
+
+        Ok(intel)
+    }
+
+    /// Parseaza continutul unui feed si il adauga in index.
+    fn add_feed(&mut self, feed: &ThreatFeedConfig, content: &str) -> Result<FeedSummary> {
+        let (raw, mut skipped) = match feed.format.as_str() {
+            "csv" => parse_csv(content),
+            "stix" => parse_stix(content)
+                .with_context(|| format!("Feed-ul '{}' nu este un JSON STIX valid", feed.name))?,
+            _ => parse_plain(content),
+        };
+
+        let feed_name: Arc<str> = Arc::from(feed.name.as_str());
+        let mut loaded = 0;
+        for item in raw {
+            let Some(subnet) = SubnetEntry::parse_ip_or_cidr(&item.value) else {
+                skipped += 1;
+                continue;
+            };
+            let record = IocRecord {
+                feed: Arc::clone(&feed_name),
+                confidence: item.confidence.unwrap_or(feed.confidence).min(100),
+                indicator: item.value,
+            };
+            loaded += 1;
+
+            // IP individual (/32 sau /128) → HashMap, cheia fiind chiar IP-ul.
+            // La duplicat (acelasi IP in mai multe feed-uri), pastram increderea maxima.
+            let host_ip = record
+                .indicator
+                .split('/')
+                .next()
+                .and_then(|ip| ip.parse::<IpAddr>().ok())
+                .filter(|ip| subnet.prefix_len() == if ip.is_ipv4() { 32 } else { 128 });
+            match host_ip {
+                Some(ip) => {
+                    let keep_existing = self
+                        .exact
+                        .get(&ip)
+                        .is_some_and(|existing| existing.confidence >= record.confidence);
+                    if !keep_existing {
+                        self.exact.insert(ip, record);
+                    }
+                }
+                None => self.cidrs.push((subnet, record)),
+            }
+        }
+
+        Ok(FeedSummary { name: feed.name.clone(), loaded, skipped })
+    }
+
+    /// Cauta un IP in toate feed-urile.
+    ///
+    /// IP-ul exact are prioritate (cel mai lung prefix posibil). Altfel se alege
+    /// subnetul cu prefixul cel mai lung; la egalitate, increderea cea mai mare.
+    pub fn lookup(&self, ip: &IpAddr) -> Option<IocMatch> {
+        if let Some(record) = self.exact.get(ip) {
+            return Some(record.to_match());
+        }
+        self.cidrs
+            .iter()
+            .filter(|(subnet, _)| subnet.matches(ip))
+            .max_by_key(|(subnet, record)| (subnet.prefix_len(), record.confidence))
+            .map(|(_, record)| record.to_match())
+    }
+
+    /// Baza goala (threat intel dezactivat sau feed-uri fara indicatori valizi).
+    pub fn is_empty(&self) -> bool {
+        self.exact.is_empty() && self.cidrs.is_empty()
+    }
+}
+
+/// Format plain: un indicator pe linie. Comentariile (`#`) si liniile goale
+/// sunt ignorate; orice text dupa primul spatiu este ignorat (ex: "1.2.3.4 botnet").
+fn parse_plain(content: &str) -> (Vec<RawIndicator>, usize) {
+    let raw = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|value| RawIndicator { value: value.to_string(), confidence: None })
+        .collect();
+    (raw, 0)
+}
+
+/// Format CSV: `indicator[,confidence[,descriere]]`.
+///
+/// Prima linie este considerata header daca primul camp nu este IP/CIDR valid.
+/// O valoare de confidence invalida nu invalideaza linia — se aplica valoarea
+/// implicita a feed-ului.
+fn parse_csv(content: &str) -> (Vec<RawIndicator>, usize) {
+    let mut raw = Vec::new();
+    let mut skipped = 0;
+    let mut first = true;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(',').map(|f| f.trim().trim_matches('"'));
+        let value = fields.next().unwrap_or_default();
+
+        if first {
+            first = false;
+            if SubnetEntry::parse_ip_or_cidr(value).is_none() {
+                continue; // header
+            }
+        }
+        if value.is_empty() {
+            skipped += 1;
+            continue;
+        }
+
+        let confidence = fields.next().and_then(|c| c.parse::<u8>().ok());
+        raw.push(RawIndicator { value: value.to_string(), confidence });
+    }
+
+    (raw, skipped)
+}
+
+/// Format STIX-lite: bundle JSON `{"type": "bundle", "objects": [...]}` sau
+/// direct un array de obiecte.
+///
+/// Obiecte recunoscute:
+///   - `indicator` cu `pattern` care contine `ipv4-addr:value = '...'` sau
+///     `ipv6-addr:value = '...'` (pot fi mai multe, unite prin OR)
+///   - `ipv4-addr` / `ipv6-addr` (observabile) cu campul `value`
+///
+/// Campul `confidence` (STIX 2.1, 0-100) este preluat daca exista.
+/// Celelalte tipuri de obiecte (malware, relationship, etc.) sunt ignorate.
+fn parse_stix(content: &str) -> Result<(Vec<RawIndicator>, usize)> {
+    let root: serde_json::Value = serde_json::from_str(content)?;
+    let objects = match &root {
+        serde_json::Value::Array(items) => items.as_slice(),
+        other => other
+            .get("objects")
+            .and_then(|o| o.as_array())
+            .map(|a| a.as_slice())
+            .unwrap_or_default(),
+    };
+
+    let pattern_re = Regex::new(r"(?:ipv4-addr|ipv6-addr):value\s*=\s*'([^']+)'")
+        .expect("regex STIX valid");
+
+    let mut raw = Vec::new();
+    let mut skipped = 0;
+    for obj in objects {
+        let confidence = obj
+            .get("confidence")
+            .and_then(|c| c.as_u64())
+            .map(|c| c.min(100) as u8);
+
+        match obj.get("type").and_then(|t| t.as_str()) {
+            Some("indicator") => {
+                let pattern = obj.get("pattern").and_then(|p| p.as_str()).unwrap_or_default();
+                let before = raw.len();
+                for cap in pattern_re.captures_iter(pattern) {
+                    raw.push(RawIndicator { value: cap[1].to_string(), confidence });
+                }
+                // Indicator fara adrese IP (ex: hash de fisier, domeniu) — nu il putem folosi.
+                if raw.len() == before {
+                    skipped += 1;
+                }
+            }
+            Some("ipv4-addr") | Some("ipv6-addr") => {
+                match obj.get("value").and_then(|v| v.as_str()) {
+                    Some(value) => raw.push(RawIndicator { value: value.to_string(), confidence }),
+                    None => skipped += 1,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((raw, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(name: &str, format: &str, confidence: u8) -> ThreatFeedConfig {
+        ThreatFeedConfig {
+            name: name.to_string(),
+            path: String::new(),
+            format: format.to_string(),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_plain_feed_with_comments() {
+        let mut intel = ThreatIntel::default();
+        let content = "# lista CERT\n203.0.113.5\n\n198.51.100.0/24  # botnet C2\nnu-e-ip\n";
+        let summary = intel.add_feed(&feed("cert-ro", "plain", 80), content).unwrap();
+
+        assert_eq!(summary.loaded, 2);
+        assert_eq!(summary.skipped, 1);
+        let hit = intel.lookup(&"203.0.113.5".parse().unwrap()).unwrap();
+        assert_eq!(hit.feed, "cert-ro");
+        assert_eq!(hit.confidence, 80);
+        let hit = intel.lookup(&"198.51.100.77".parse().unwrap()).unwrap();
+        assert_eq!(hit.indicator, "198.51.100.0/24");
+        assert!(intel.lookup(&"10.0.0.1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_csv_feed_header_and_confidence() {
+        let mut intel = ThreatIntel::default();
+        let content = "indicator,confidence,description\n\
+                       203.0.113.5,95,scanner\n\
+                       2001:db8::/32,abc,retea\n\
+                       ,10,gol\n";
+        let summary = intel.add_feed(&feed("csv", "csv", 40), content).unwrap();
+
+        assert_eq!(summary.loaded, 2);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(intel.lookup(&"203.0.113.5".parse().unwrap()).unwrap().confidence, 95);
+        // Confidence invalid → valoarea implicita a feed-ului.
+        assert_eq!(intel.lookup(&"2001:db8::1".parse().unwrap()).unwrap().confidence, 40);
+    }
+
+    #[test]
+    fn test_stix_bundle() {
+        let mut intel = ThreatIntel::default();
+        let content = r#"{
+            "type": "bundle",
+            "objects": [
+                {"type": "indicator", "confidence": 90,
+                 "pattern": "[ipv4-addr:value = '203.0.113.5' OR ipv4-addr:value = '198.51.100.0/24']"},
+                {"type": "indicator", "pattern": "[file:hashes.MD5 = 'abc']"},
+                {"type": "ipv6-addr", "value": "2001:db8::66"},
+                {"type": "malware", "name": "x"}
+            ]
+        }"#;
+        let summary = intel.add_feed(&feed("stix", "stix", 50), content).unwrap();
+
+        assert_eq!(summary.loaded, 3);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(intel.lookup(&"198.51.100.9".parse().unwrap()).unwrap().confidence, 90);
+        assert_eq!(intel.lookup(&"2001:db8::66".parse().unwrap()).unwrap().confidence, 50);
+    }
+
+    #[test]
+    fn test_stix_invalid_json_is_error() {
+        let mut intel = ThreatIntel::default();
+        assert!(intel.add_feed(&feed("stix", "stix", 50), "{ nu e json").is_err());
+    }
+
+    #[test]
+    fn test_lookup_longest_prefix_then_confidence() {
+        let mut intel = ThreatIntel::default();
+        intel.add_feed(&feed("larg", "plain", 99), "10.0.0.0/8\n").unwrap();
+        intel.add_feed(&feed("ingust", "plain", 30), "10.1.0.0/16\n").unwrap();
+        intel.add_feed(&feed("dublura", "plain", 60), "10.1.0.0/16\n").unwrap();
+
+        let hit = intel.lookup(&"10.1.2.3".parse().unwrap()).unwrap();
+        assert_eq!(hit.indicator, "10.1.0.0/16");
+        assert_eq!(hit.feed, "dublura");
+        assert_eq!(intel.lookup(&"10.2.0.1".parse().unwrap()).unwrap().feed, "larg");
+    }
+}
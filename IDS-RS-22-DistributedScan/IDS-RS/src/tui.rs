@@ -0,0 +1,530 @@
+// =============================================================================
+// tui.rs - Dashboard interactiv in terminal (`--tui`)
+// =============================================================================
+//
+// La rate mari de evenimente, log-ul derulant din display.rs (o linie per
+// eveniment, banner per alerta) nu mai poate fi citit. Cu `--tui`, terminalul
+// devine un dashboard cu panouri actualizate live:
+//
+//   - evenimente/secunda per actiune (drop / accept / alte) + istoric
+//   - contoare: linii neparsate, pachete dropate de TokenBucket, IP-uri urmarite
+//   - top surse dupa porturi unice, top tinte dupa evenimente
+//   - cooldown-urile active per detector
+//   - alertele recente, cu detalii (porturi, destinatii, surse) la Enter
+//   - mesajele INFO / WARN / ERR pe care display.rs nu le mai afiseaza
+//
+// Sursele de date: `LiveStats` (stats.rs, contoare alimentate din main loop) si
+// instantanee din acelasi `Detector` folosit pentru detectie — dashboard-ul
+// nu tine stare de detectie proprie.
+//
+// Taste: q / Ctrl+C iesire, ↑↓ (j/k) selectie, Enter detalii, Esc inchide.
+//
+// CONCEPTE RUST EXPLICATE:
+//
+// 1. THREAD DEDICAT PENTRU UI
+//    `event::poll(timeout)` din crossterm este blocant. Rulat intr-un task
+//    tokio, ar ocupa un worker al runtime-ului; un `std::thread` separat
+//    deseneaza si citeste tastatura fara sa atinga main loop-ul async.
+//
+// 2. IMMEDIATE-MODE RENDERING (ratatui)
+//    Nu exista widget-uri persistente: la fiecare frame `draw` construieste
+//    tot ecranul din stare, iar ratatui trimite la terminal doar diferentele.
+//
+// =============================================================================
+
+use crate::cooldown::format_span;
+use crate::detector::{ActiveCooldown, Alert, Detector, SourceActivity, TargetActivity};
+use crate::display;
+use crate::stats::{Counters, LiveStats};
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Randurile din panourile "Top surse" / "Top tinte".
+const TOP_ROWS: usize = 10;
+
+/// Secundele de istoric din grafic.
+const RATE_HISTORY: usize = 120;
+
+/// Intervalul dintre doua frame-uri (si timeout-ul citirii tastaturii).
+const FRAME: Duration = Duration::from_millis(250);
+
+/// Dashboard-ul pornit: thread-ul UI si semnalul de oprire.
+pub struct Dashboard {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Result<()>>,
+}
+
+impl Dashboard {
+    /// Preia terminalul (ecran alternativ, raw mode), opreste afisarea din
+    /// display.rs si porneste thread-ul UI. La iesirea din dashboard (q /
+    /// Ctrl+C sau eroare), `quit` este notificat — main loop-ul se opreste.
+    pub fn start(detector: Arc<Detector>, live: Arc<LiveStats>, title: String, quit: Arc<Notify>) -> Result<Self> {
+        let terminal = ratatui::try_init().context("Nu pot initializa terminalul pentru --tui")?;
+        display::set_muted(true);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::Builder::new()
+            .name("ids-rs-tui".to_string())
+            .spawn(move || {
+                let mut terminal = terminal;
+                let result = App::new(detector, live, title).run(&mut terminal, &thread_stop);
+                ratatui::restore();
+                quit.notify_one();
+                result
+            })
+            .context("Nu pot porni thread-ul dashboard-ului")?;
+        Ok(Self { stop, handle })
+    }
+
+    /// Opreste dashboard-ul, reda terminalul si reporneste afisarea normala.
+    /// Intoarce eroarea thread-ului UI, daca a existat.
+    pub fn stop(self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        let result = match self.handle.join() {
+            Ok(result) => result,
+            Err(_) => {
+                ratatui::restore();
+                Err(anyhow::anyhow!("Thread-ul dashboard-ului s-a oprit cu panic"))
+            }
+        };
+        display::set_muted(false);
+        result
+    }
+}
+
+/// Starea dashboard-ului intre frame-uri.
+struct App {
+    detector: Arc<Detector>,
+    live: Arc<LiveStats>,
+    title: String,
+    started: Instant,
+    sampled_at: Instant,
+    sampled: Counters,
+    rates: Counters,
+    history: VecDeque<u64>,
+    sources: Vec<SourceActivity>,
+    targets: Vec<TargetActivity>,
+    cooldowns: Vec<ActiveCooldown>,
+    tracked_ips: usize,
+    alerts: Vec<Alert>,
+    selection: TableState,
+    /// Alerta deschisa la Enter — copiata, ca sa nu se schimbe sub cursor.
+    detail: Option<Alert>,
+}
+
+impl App {
+    fn new(detector: Arc<Detector>, live: Arc<LiveStats>, title: String) -> Self {
+        let now = Instant::now();
+        let sampled = live.counters();
+        let mut app = Self {
+            detector,
+            live,
+            title,
+            started: now,
+            sampled_at: now,
+            sampled,
+            rates: Counters::default(),
+            history: VecDeque::with_capacity(RATE_HISTORY),
+            sources: Vec::new(),
+            targets: Vec::new(),
+            cooldowns: Vec::new(),
+            tracked_ips: 0,
+            alerts: Vec::new(),
+            selection: TableState::default(),
+            detail: None,
+        };
+        app.refresh_detector();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal, stop: &AtomicBool) -> Result<()> {
+        while !stop.load(Ordering::Relaxed) {
+            if self.sampled_at.elapsed() >= Duration::from_secs(1) {
+                self.sample();
+            }
+            self.alerts = self.live.recent_alerts();
+            self.clamp_selection();
+
+            terminal.draw(|frame| self.draw(frame)).context("Eroare desenare dashboard")?;
+
+            if event::poll(FRAME).context("Eroare citire tastatura")? {
+                if let Event::Key(key) = event::read().context("Eroare citire tastatura")? {
+                    if key.kind == KeyEventKind::Press && !self.on_key(key) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// O data pe secunda: ratele si instantaneele detectorului.
+    fn sample(&mut self) {
+        let now = Instant::now();
+        let counters = self.live.counters();
+        self.rates = counters.rate_since(&self.sampled, now - self.sampled_at);
+        self.sampled = counters;
+        self.sampled_at = now;
+        if self.history.len() == RATE_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.rates.events());
+        self.refresh_detector();
+    }
+
+    fn refresh_detector(&mut self) {
+        self.sources = self.detector.top_sources(TOP_ROWS);
+        self.targets = self.detector.top_targets(TOP_ROWS);
+        self.cooldowns = self.detector.active_cooldowns();
+        self.tracked_ips = self.detector.tracked_ips();
+    }
+
+    fn clamp_selection(&mut self) {
+        match (self.selection.selected(), self.alerts.len()) {
+            (_, 0) => self.selection.select(None),
+            (None, _) => self.selection.select(Some(0)),
+            (Some(i), len) if i >= len => self.selection.select(Some(len - 1)),
+            _ => {}
+        }
+    }
+
+    /// Trateaza o tasta. Intoarce `false` pentru iesire.
+    fn on_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc => self.detail = None,
+            KeyCode::Enter => {
+                self.detail = self.selection.selected().and_then(|i| self.alerts.get(i)).cloned();
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.detail.is_none() => self.selection.select_next(),
+            KeyCode::Up | KeyCode::Char('k') if self.detail.is_none() => self.selection.select_previous(),
+            KeyCode::Home if self.detail.is_none() => self.selection.select_first(),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, top, middle, alerts, notices] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(8),
+            Constraint::Min(8),
+            Constraint::Min(8),
+            Constraint::Length(5),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(format!(" {} ", self.title), Style::new().bold().reversed()),
+                Span::raw(format!("  uptime {}  ", format_span(self.started.elapsed()))),
+                Span::styled("q iesire · ↑↓ selectie · Enter detalii · Esc inchide", Style::new().dark_gray()),
+            ])),
+            header,
+        );
+
+        let [rates, counters] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(top);
+        self.draw_rates(frame, rates);
+        self.draw_counters(frame, counters);
+
+        let [sources, targets, cooldowns] = Layout::horizontal([
+            Constraint::Percentage(35),
+            Constraint::Percentage(30),
+            Constraint::Percentage(35),
+        ])
+        .areas(middle);
+        self.draw_sources(frame, sources);
+        self.draw_targets(frame, targets);
+        self.draw_cooldowns(frame, cooldowns);
+
+        self.draw_alerts(frame, alerts);
+        draw_notices(frame, notices);
+
+        if let Some(ref alert) = self.detail {
+            draw_detail(frame, alert);
+        }
+    }
+
+    fn draw_rates(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::new().borders(Borders::ALL).title(" Evenimente / secunda ");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [line, graph] = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(inner);
+        let r = &self.rates;
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(format!("drop {}/s", r.drops), Style::new().fg(Color::Blue).bold()),
+                Span::raw("   "),
+                Span::styled(format!("accept {}/s", r.accepts), Style::new().fg(Color::Green).bold()),
+                Span::raw("   "),
+                Span::raw(format!("alte {}/s", r.other)),
+                Span::raw("   "),
+                Span::styled(format!("total {}/s", r.events()), Style::new().bold()),
+            ])),
+            line,
+        );
+        // Cele mai recente secunde, aliniate la dreapta graficului.
+        let width = graph.width as usize;
+        let data: Vec<u64> = self.history.iter().skip(self.history.len().saturating_sub(width)).copied().collect();
+        frame.render_widget(Sparkline::default().data(&data).style(Style::new().fg(Color::Cyan)), graph);
+    }
+
+    fn draw_counters(&self, frame: &mut Frame, area: Rect) {
+        let c = &self.sampled;
+        let lines = vec![
+            Line::from(format!(
+                "Evenimente: {} ({} drop / {} accept / {} alte)",
+                c.events(),
+                c.drops,
+                c.accepts,
+                c.other
+            )),
+            Line::from(format!("Alerte: {}", c.alerts)),
+            Line::from(format!("Linii neparsate: {}", c.parse_failures)),
+            Line::from(format!("Dropate de rate limit: {}", c.rate_limited)),
+            Line::from(format!("IP-uri urmarite: {}", self.tracked_ips)),
+            Line::from(format!("Cooldown-uri active: {}", self.cooldowns.len())),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(" Contoare ")),
+            area,
+        );
+    }
+
+    fn draw_sources(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.sources.iter().map(|s| {
+            Row::new(vec![
+                Cell::from(s.ip.to_string()),
+                Cell::from(s.unique_ports.to_string()),
+                Cell::from(s.drops.to_string()).style(Style::new().fg(Color::Blue)),
+                Cell::from(s.accepts.to_string()).style(Style::new().fg(Color::Green)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [Constraint::Min(15), Constraint::Length(7), Constraint::Length(7), Constraint::Length(7)],
+        )
+        .header(Row::new(vec!["Sursa", "Porturi", "Drop", "Accept"]).style(Style::new().bold()))
+        .block(Block::new().borders(Borders::ALL).title(" Top surse (porturi unice) "));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_targets(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.targets.iter().map(|t| {
+            Row::new(vec![t.ip.to_string(), t.unique_sources.to_string(), t.events.to_string()])
+        });
+        let table = Table::new(rows, [Constraint::Min(15), Constraint::Length(6), Constraint::Length(10)])
+            .header(Row::new(vec!["Tinta", "Surse", "Evenimente"]).style(Style::new().bold()))
+            .block(Block::new().borders(Borders::ALL).title(" Top tinte "));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_cooldowns(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.cooldowns.iter().map(|c| {
+            Row::new(vec![
+                c.detector.to_string(),
+                c.key.to_string(),
+                format_span(c.remaining),
+                c.mode.as_str().to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [Constraint::Length(16), Constraint::Min(15), Constraint::Length(8), Constraint::Length(7)],
+        )
+        .header(Row::new(vec!["Detector", "Cheie", "Ramas", "Mod"]).style(Style::new().bold()))
+        .block(Block::new().borders(Borders::ALL).title(" Cooldown-uri active "));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_alerts(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.alerts.iter().map(|a| {
+            let dest = a.dest_ip.map(|ip| ip.to_string()).unwrap_or_default();
+            Row::new(vec![
+                Cell::from(a.timestamp.format("%H:%M:%S").to_string()),
+                Cell::from(a.severity.level.to_string()).style(severity_style(a.severity.level)),
+                Cell::from(a.scan_type.to_string()),
+                Cell::from(a.source_label()),
+                Cell::from(dest),
+                Cell::from(extent(a)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(3),
+                Constraint::Length(22),
+                Constraint::Length(24),
+                Constraint::Length(16),
+                Constraint::Min(10),
+            ],
+        )
+        .header(Row::new(vec!["Ora", "Sev", "Tip", "Sursa", "Destinatie", "Amploare"]).style(Style::new().bold()))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(format!(" Alerte recente ({}) ", self.alerts.len())),
+        );
+        frame.render_stateful_widget(table, area, &mut self.selection);
+    }
+}
+
+/// Mesajele INFO / WARN / ERR retinute de display.rs, cele mai noi jos.
+fn draw_notices(frame: &mut Frame, area: Rect) {
+    let notices = display::recent_notices();
+    let visible = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = notices
+        .iter()
+        .skip(notices.len().saturating_sub(visible))
+        .map(|n| {
+            let style = if n.contains(" ERR ") {
+                Style::new().fg(Color::Red)
+            } else if n.contains(" WARN ") {
+                Style::new().fg(Color::Yellow)
+            } else {
+                Style::new()
+            };
+            Line::styled(n.clone(), style)
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(" Mesaje ")),
+        area,
+    );
+}
+
+/// Fereastra cu detaliile alertei selectate.
+fn draw_detail(frame: &mut Frame, alert: &Alert) {
+    let area = centered(frame.area(), 80, 80);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(alert.scan_type.to_string(), Style::new().bold()),
+            Span::raw(format!("  {}", alert.timestamp.format("%Y-%m-%d %H:%M:%S"))),
+        ]),
+        Line::from(format!("Sursa: {}", alert.source_label())),
+    ];
+    if let Some(dest) = alert.dest_ip {
+        lines.push(Line::from(format!("Destinatie: {}", dest)));
+    }
+    lines.push(Line::from(vec![
+        Span::raw("Severitate: "),
+        Span::styled(alert.severity.level.to_string(), severity_style(alert.severity.level)),
+        Span::raw(format!(" ({})", alert.severity.factors.join(", "))),
+    ]));
+    if let Some(ref o) = alert.ongoing {
+        lines.push(Line::from(format!("{}: {}", o.label(), o.summary())));
+    }
+    if let Some(ref name) = alert.profile {
+        lines.push(Line::from(format!("Profil: {}", name)));
+    }
+    if let Some(threshold) = alert.threshold {
+        lines.push(Line::from(format!("Prag: {}", threshold)));
+    }
+    if !alert.protocols.is_empty() {
+        lines.push(Line::from(format!("Protocoale: {}", alert.protocol_summary(", "))));
+    }
+    if let Some(ref fp) = alert.fingerprint {
+        lines.push(Line::from(format!("Unealta probabila: {} ({})", fp.tool, fp.evidence)));
+    }
+    if let Some(ref nat) = alert.nat {
+        lines.push(Line::from(format!("NAT: {}", nat.summary())));
+    }
+    if !alert.rules.is_empty() {
+        lines.push(Line::from(format!("Reguli firewall: {}", alert.rules.join(", "))));
+    }
+    if let Some(ref offence) = alert.offence {
+        if offence.is_repeat() {
+            lines.push(Line::from(format!("Recidiva: {}", offence.summary())));
+        }
+    }
+    for (label, values) in [
+        ("Porturi", join(&alert.unique_ports)),
+        ("Destinatii", join(&alert.unique_dests)),
+        ("Surse", join(&alert.unique_sources)),
+    ] {
+        if let Some((count, list)) = values {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(format!("{} ({}):", label, count), Style::new().bold()));
+            lines.push(Line::from(list));
+        }
+    }
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::new().borders(Borders::ALL).title(" Detalii alerta (Esc inchide) ")),
+        area,
+    );
+}
+
+/// Amploarea alertei pe un rand: porturi, destinatii sau surse.
+fn extent(alert: &Alert) -> String {
+    let mut parts = Vec::new();
+    if !alert.unique_ports.is_empty() {
+        parts.push(format!("{} porturi", alert.unique_ports.len()));
+    }
+    if !alert.unique_dests.is_empty() {
+        parts.push(format!("{} destinatii", alert.unique_dests.len()));
+    }
+    if !alert.unique_sources.is_empty() {
+        parts.push(format!("{} surse", alert.unique_sources.len()));
+    }
+    parts.join(", ")
+}
+
+/// "(3, "22, 80, 443")" — None pentru o lista goala.
+fn join<T: ToString>(values: &[T]) -> Option<(usize, String)> {
+    if values.is_empty() {
+        return None;
+    }
+    let list = values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+    Some((values.len(), list))
+}
+
+/// Aceleasi praguri de culoare ca alertele din display.rs.
+fn severity_style(level: u8) -> Style {
+    let color = match level {
+        9.. => Color::LightRed,
+        8 => Color::Red,
+        7 => Color::Magenta,
+        6 => Color::Yellow,
+        _ => Color::Cyan,
+    };
+    Style::new().fg(color).bold()
+}
+
+/// Dreptunghiul centrat de `percent_x` × `percent_y` din `area`.
+fn centered(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .areas(area);
+    let [_, center, _] = Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .areas(middle);
+    center
+}